@@ -10,6 +10,15 @@
 //! - **7z**: `sevenz-rust2` - pure Rust, multi-threaded LZMA2 decompression
 //! - **RAR**: `unrar` crate with 7z binary fallback for reference records
 //!
+//! The format is always detected from the file's magic bytes, never its
+//! extension. 7z archives are extracted with the 7z binary when one is
+//! installed (much faster on big solid LZMA2 archives) and with
+//! `sevenz-rust2` otherwise. There is no pure-Rust RAR decoder: RAR goes
+//! through the unrar library, and RAR5 archives with reference records can
+//! only be extracted with the 7z binary. Without one, those fail with an
+//! error saying so. Anything that isn't ZIP, 7z or RAR is handed to 7z
+//! as-is and reported as an unsupported format if 7z can't open it either.
+//!
 //! # Archive Ordering
 //!
 //! For optimal extraction performance, archives should be processed in order:
@@ -33,7 +42,7 @@ use std::collections::HashMap;
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader, BufWriter, Read};
 use std::path::{Component, Path, PathBuf};
-use std::process::{Command, Stdio};
+use std::process::{Command, Output, Stdio};
 use unicode_normalization::UnicodeNormalization;
 
 /// Run a Command and capture output, using spawn() instead of output().
@@ -51,6 +60,54 @@ fn spawn_output(cmd: &mut Command) -> std::io::Result<std::process::Output> {
         .wait_with_output()
 }
 
+/// Like [`spawn_output`], but hands each stdout line to `on_line` while the
+/// process runs. Used with 7z's `-bb1`, which prints `- <path>` for every
+/// extracted file.
+fn spawn_output_lines(cmd: &mut Command, mut on_line: impl FnMut(&str)) -> std::io::Result<Output> {
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Drain stderr on its own thread so a chatty 7z can't block on a full pipe.
+    let mut stderr_pipe = child.stderr.take().expect("piped stderr");
+    let stderr_reader = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stderr_pipe.read_to_end(&mut buf);
+        buf
+    });
+
+    let mut stdout = Vec::new();
+    let mut reader = BufReader::new(child.stdout.take().expect("piped stdout"));
+    let mut line = Vec::new();
+    while reader.read_until(b'\n', &mut line)? > 0 {
+        on_line(String::from_utf8_lossy(&line).trim_end_matches(['\r', '\n']));
+        stdout.append(&mut line);
+    }
+
+    let status = child.wait()?;
+    let stderr = stderr_reader.join().unwrap_or_default();
+    Ok(Output {
+        status,
+        stdout,
+        stderr,
+    })
+}
+
+/// Run a 7z extraction, feeding its per-file output to `tracker` when it
+/// reports progress (the command must then have been given `-bb1`).
+fn run_7z_extract(cmd: &mut Command, tracker: &mut EntryTracker) -> std::io::Result<Output> {
+    if !tracker.is_active() {
+        return spawn_output(cmd);
+    }
+    spawn_output_lines(cmd, |line| {
+        if let Some(path) = line.strip_prefix("- ") {
+            tracker.entry_done(path);
+        }
+    })
+}
+
 /// Windows file attribute flag for reparse points (symlinks, junctions).
 const FILE_ATTRIBUTE_REPARSE_POINT: u32 = 0x0400;
 
@@ -126,6 +183,78 @@ pub struct ArchiveEntry {
     pub is_dir: bool,
 }
 
+/// Entries and bytes written so far by one extraction call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExtractProgress {
+    pub entries_done: usize,
+    pub entries_total: usize,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+}
+
+/// Called with the running totals each time an entry finishes extracting.
+pub type ExtractProgressFn<'a> = &'a (dyn Fn(ExtractProgress) + Sync);
+
+/// Turns "entry finished" notifications from the extraction backends into
+/// [`ExtractProgress`] updates, sized from the archive listing.
+struct EntryTracker<'a> {
+    /// Normalized path -> size of entries not finished yet.
+    pending: HashMap<String, u64>,
+    progress: ExtractProgress,
+    on_progress: Option<ExtractProgressFn<'a>>,
+}
+
+impl<'a> EntryTracker<'a> {
+    /// Track the listed entries that are going to be extracted.
+    fn new<'e>(
+        entries: impl IntoIterator<Item = &'e ArchiveEntry>,
+        on_progress: ExtractProgressFn<'a>,
+    ) -> Self {
+        let pending: HashMap<String, u64> = entries
+            .into_iter()
+            .filter(|e| !e.is_dir)
+            .map(|e| (normalize_path(&e.path), e.size))
+            .collect();
+        let progress = ExtractProgress {
+            entries_total: pending.len(),
+            bytes_total: pending.values().sum(),
+            ..ExtractProgress::default()
+        };
+        Self {
+            pending,
+            progress,
+            on_progress: Some(on_progress),
+        }
+    }
+
+    /// A tracker that reports nothing.
+    fn none() -> Self {
+        Self {
+            pending: HashMap::new(),
+            progress: ExtractProgress::default(),
+            on_progress: None,
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        self.on_progress.is_some()
+    }
+
+    /// Record that `path` was written. Unknown paths and repeats (a fallback
+    /// backend extracting the same entry again) are ignored.
+    fn entry_done(&mut self, path: &str) {
+        let Some(on_progress) = self.on_progress else {
+            return;
+        };
+        let Some(size) = self.pending.remove(&normalize_path(path)) else {
+            return;
+        };
+        self.progress.entries_done += 1;
+        self.progress.bytes_done += size;
+        on_progress(self.progress);
+    }
+}
+
 // ============================================================================
 // Public API - dispatches to native crates by archive type
 // ============================================================================
@@ -146,22 +275,62 @@ pub fn list_archive(archive_path: &Path) -> Result<Vec<ArchiveEntry>> {
         ArchiveType::Rar => {
             list_rar(archive_path).or_else(|_| list_archive_7z_binary(archive_path))
         }
-        _ => list_archive_7z_binary(archive_path),
+        _ => list_archive_7z_binary(archive_path).map_err(|e| unsupported_format(e, archive_path)),
     }
 }
 
+/// Whether a 7z binary is installed. 7z archives use it when it is and the
+/// native decoder otherwise.
+fn has_7z_binary() -> bool {
+    get_7z_path().is_ok()
+}
+
+/// Retry a failed native ZIP/RAR extraction with the 7z binary, or say why
+/// that isn't possible.
+fn retry_with_7z<T>(
+    native_err: anyhow::Error,
+    archive_path: &Path,
+    retry: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    if !has_7z_binary() {
+        return Err(native_err.context(format!(
+            "Cannot extract {} natively and no 7z binary is installed to retry with \
+             (RAR5 archives with reference records need one). Install 7-Zip (7zz) or p7zip",
+            archive_path.display()
+        )));
+    }
+    retry()
+}
+
+/// Error for an archive whose magic bytes matched no supported format and
+/// that 7z couldn't open either.
+fn unsupported_format(err: anyhow::Error, archive_path: &Path) -> anyhow::Error {
+    err.context(format!(
+        "Unsupported archive format: {} is not a ZIP, 7z or RAR archive and 7z could not open it",
+        archive_path.display()
+    ))
+}
+
 /// Extract a single file from an archive to memory.
 pub fn extract_file(archive_path: &Path, file_path: &str) -> Result<Vec<u8>> {
     let archive_type = detect_archive_type(archive_path).unwrap_or(ArchiveType::Unknown);
 
     match archive_type {
-        ArchiveType::Zip => extract_zip_file(archive_path, file_path)
-            .or_else(|_| extract_file_7z_binary(archive_path, file_path)),
+        ArchiveType::Zip => extract_zip_file(archive_path, file_path).or_else(|e| {
+            retry_with_7z(e, archive_path, || {
+                extract_file_7z_binary(archive_path, file_path)
+            })
+        }),
         // Match Wabbajack behavior: use external 7z process for 7z extraction.
-        ArchiveType::SevenZ => extract_file_7z_binary(archive_path, file_path),
-        ArchiveType::Rar => extract_rar_file(archive_path, file_path)
-            .or_else(|_| extract_file_7z_binary(archive_path, file_path)),
-        _ => extract_file_7z_binary(archive_path, file_path),
+        ArchiveType::SevenZ if has_7z_binary() => extract_file_7z_binary(archive_path, file_path),
+        ArchiveType::SevenZ => extract_7z_native_file(archive_path, file_path),
+        ArchiveType::Rar => extract_rar_file(archive_path, file_path).or_else(|e| {
+            retry_with_7z(e, archive_path, || {
+                extract_file_7z_binary(archive_path, file_path)
+            })
+        }),
+        _ => extract_file_7z_binary(archive_path, file_path)
+            .map_err(|e| unsupported_format(e, archive_path)),
     }
 }
 
@@ -187,6 +356,15 @@ pub fn extract_file_case_insensitive(archive_path: &Path, file_path: &str) -> Re
 
 /// Extract multiple files from an archive.
 pub fn extract_files(archive_path: &Path, files: &[&str], output_dir: &Path) -> Result<()> {
+    extract_files_tracked(archive_path, files, output_dir, &mut EntryTracker::none())
+}
+
+fn extract_files_tracked(
+    archive_path: &Path,
+    files: &[&str],
+    output_dir: &Path,
+    tracker: &mut EntryTracker,
+) -> Result<()> {
     if files.is_empty() {
         return Ok(());
     }
@@ -194,12 +372,30 @@ pub fn extract_files(archive_path: &Path, files: &[&str], output_dir: &Path) ->
     let archive_type = detect_archive_type(archive_path).unwrap_or(ArchiveType::Unknown);
 
     match archive_type {
-        ArchiveType::Zip => extract_zip_files(archive_path, files, output_dir)
-            .or_else(|_| extract_files_7z_binary(archive_path, files, output_dir)),
-        ArchiveType::SevenZ => extract_files_7z_binary(archive_path, files, output_dir),
-        ArchiveType::Rar => extract_rar_files(archive_path, files, output_dir)
-            .or_else(|_| extract_files_7z_binary(archive_path, files, output_dir)),
-        _ => extract_files_7z_binary(archive_path, files, output_dir),
+        ArchiveType::Zip => {
+            extract_zip_files(archive_path, files, output_dir, tracker).or_else(|e| {
+                retry_with_7z(e, archive_path, || {
+                    extract_files_7z_binary(archive_path, files, output_dir, tracker)
+                })
+            })
+        }
+        ArchiveType::SevenZ if has_7z_binary() => {
+            extract_files_7z_binary(archive_path, files, output_dir, tracker)
+        }
+        ArchiveType::SevenZ => {
+            let wanted: std::collections::HashSet<String> =
+                files.iter().map(|f| normalize_path(f)).collect();
+            extract_7z_native(archive_path, Some(&wanted), output_dir, tracker).map(|_| ())
+        }
+        ArchiveType::Rar => {
+            extract_rar_files(archive_path, files, output_dir, tracker).or_else(|e| {
+                retry_with_7z(e, archive_path, || {
+                    extract_files_7z_binary(archive_path, files, output_dir, tracker)
+                })
+            })
+        }
+        _ => extract_files_7z_binary(archive_path, files, output_dir, tracker)
+            .map_err(|e| unsupported_format(e, archive_path)),
     }
 }
 
@@ -211,6 +407,26 @@ pub fn extract_files_case_insensitive(
     archive_path: &Path,
     files: &[String],
     output_dir: &Path,
+) -> Result<usize> {
+    extract_files_case_insensitive_impl(archive_path, files, output_dir, None)
+}
+
+/// [`extract_files_case_insensitive`], reporting each finished entry to
+/// `on_progress`. Lets callers show movement inside one huge archive.
+pub fn extract_files_case_insensitive_with_progress(
+    archive_path: &Path,
+    files: &[String],
+    output_dir: &Path,
+    on_progress: ExtractProgressFn,
+) -> Result<usize> {
+    extract_files_case_insensitive_impl(archive_path, files, output_dir, Some(on_progress))
+}
+
+fn extract_files_case_insensitive_impl(
+    archive_path: &Path,
+    files: &[String],
+    output_dir: &Path,
+    on_progress: Option<ExtractProgressFn>,
 ) -> Result<usize> {
     if files.is_empty() {
         return Ok(0);
@@ -264,31 +480,70 @@ pub fn extract_files_case_insensitive(
         );
     }
 
+    let mut tracker = match on_progress {
+        Some(on_progress) => {
+            let wanted: std::collections::HashSet<&str> =
+                resolved.iter().map(String::as_str).collect();
+            EntryTracker::new(
+                entries.iter().filter(|e| wanted.contains(e.path.as_str())),
+                on_progress,
+            )
+        }
+        None => EntryTracker::none(),
+    };
     let resolved_refs: Vec<&str> = resolved.iter().map(|s| s.as_str()).collect();
-    extract_files(archive_path, &resolved_refs, output_dir)?;
+    extract_files_tracked(archive_path, &resolved_refs, output_dir, &mut tracker)?;
     Ok(resolved_refs.len())
 }
 
 pub fn extract_all(archive_path: &Path, output_dir: &Path) -> Result<usize> {
+    extract_all_tracked(archive_path, output_dir, &mut EntryTracker::none())
+}
+
+/// [`extract_all`], reporting each finished entry to `on_progress`. Lists
+/// the archive first for the totals.
+pub fn extract_all_with_progress(
+    archive_path: &Path,
+    output_dir: &Path,
+    on_progress: ExtractProgressFn,
+) -> Result<usize> {
+    let entries = list_archive(archive_path)?;
+    let mut tracker = EntryTracker::new(&entries, on_progress);
+    extract_all_tracked(archive_path, output_dir, &mut tracker)
+}
+
+fn extract_all_tracked(
+    archive_path: &Path,
+    output_dir: &Path,
+    tracker: &mut EntryTracker,
+) -> Result<usize> {
     let archive_type = detect_archive_type(archive_path).unwrap_or(ArchiveType::Unknown);
 
     match archive_type {
-        ArchiveType::Zip => extract_zip_all(archive_path, output_dir).or_else(|e| {
+        ArchiveType::Zip => extract_zip_all(archive_path, output_dir, tracker).or_else(|e| {
             tracing::warn!(
                 "Native ZIP extraction failed, falling back to 7z binary: {}",
                 e
             );
-            extract_all_7z_binary(archive_path, output_dir)
+            retry_with_7z(e, archive_path, || {
+                extract_all_7z_binary(archive_path, output_dir, tracker)
+            })
         }),
-        ArchiveType::SevenZ => extract_all_7z_binary(archive_path, output_dir),
-        ArchiveType::Rar => extract_rar_all(archive_path, output_dir).or_else(|e| {
+        ArchiveType::SevenZ if has_7z_binary() => {
+            extract_all_7z_binary(archive_path, output_dir, tracker)
+        }
+        ArchiveType::SevenZ => extract_7z_native(archive_path, None, output_dir, tracker),
+        ArchiveType::Rar => extract_rar_all(archive_path, output_dir, tracker).or_else(|e| {
             tracing::warn!(
                 "Native RAR extraction failed, falling back to 7z binary: {}",
                 e
             );
-            extract_all_7z_binary(archive_path, output_dir)
+            retry_with_7z(e, archive_path, || {
+                extract_all_7z_binary(archive_path, output_dir, tracker)
+            })
         }),
-        _ => extract_all_7z_binary(archive_path, output_dir),
+        _ => extract_all_7z_binary(archive_path, output_dir, tracker)
+            .map_err(|e| unsupported_format(e, archive_path)),
     }
 }
 
@@ -576,7 +831,12 @@ fn extract_zip_file(archive_path: &Path, file_path: &str) -> Result<Vec<u8>> {
 }
 
 /// Extract specific files from a ZIP archive to a directory.
-fn extract_zip_files(archive_path: &Path, files: &[&str], output_dir: &Path) -> Result<()> {
+fn extract_zip_files(
+    archive_path: &Path,
+    files: &[&str],
+    output_dir: &Path,
+    tracker: &mut EntryTracker,
+) -> Result<()> {
     let file = File::open(archive_path)?;
     let reader = BufReader::new(file);
     let mut archive = zip::ZipArchive::new(reader)?;
@@ -609,6 +869,7 @@ fn extract_zip_files(archive_path: &Path, files: &[&str], output_dir: &Path) ->
             }
             let mut outfile = BufWriter::with_capacity(65536, File::create(&output_path)?);
             std::io::copy(&mut entry, &mut outfile)?;
+            tracker.entry_done(entry.name());
         }
     }
 
@@ -616,7 +877,11 @@ fn extract_zip_files(archive_path: &Path, files: &[&str], output_dir: &Path) ->
 }
 
 /// Extract all files from a ZIP archive to a directory.
-fn extract_zip_all(archive_path: &Path, output_dir: &Path) -> Result<usize> {
+fn extract_zip_all(
+    archive_path: &Path,
+    output_dir: &Path,
+    tracker: &mut EntryTracker,
+) -> Result<usize> {
     let file = File::open(archive_path)?;
     let reader = BufReader::new(file);
     let mut archive = zip::ZipArchive::new(reader)?;
@@ -641,6 +906,7 @@ fn extract_zip_all(archive_path: &Path, output_dir: &Path) -> Result<usize> {
             }
             let mut outfile = BufWriter::with_capacity(65536, File::create(&output_path)?);
             std::io::copy(&mut entry, &mut outfile)?;
+            tracker.entry_done(entry.name());
             count += 1;
         }
     }
@@ -663,9 +929,7 @@ fn list_7z_native(archive_path: &Path) -> Result<Vec<ArchiveEntry>> {
             continue;
         }
         // Skip reparse point entries
-        if file_entry.has_windows_attributes
-            && (file_entry.windows_attributes & FILE_ATTRIBUTE_REPARSE_POINT) != 0
-        {
+        if is_7z_reparse_point(file_entry) {
             continue;
         }
         // Include files with no stream (empty/zero-byte files)
@@ -677,6 +941,89 @@ fn list_7z_native(archive_path: &Path) -> Result<Vec<ArchiveEntry>> {
     }
     Ok(entries)
 }
+
+/// Whether a 7z entry is a reparse point (symlink/junction) we don't extract.
+fn is_7z_reparse_point(entry: &sevenz_rust2::ArchiveEntry) -> bool {
+    entry.has_windows_attributes && (entry.windows_attributes & FILE_ATTRIBUTE_REPARSE_POINT) != 0
+}
+
+/// Extract a 7z archive with the native decoder, for systems without a 7z
+/// binary. `wanted` (normalized paths) limits which entries are written;
+/// solid blocks are still decoded in full.
+fn extract_7z_native(
+    archive_path: &Path,
+    wanted: Option<&std::collections::HashSet<String>>,
+    output_dir: &Path,
+    tracker: &mut EntryTracker,
+) -> Result<usize> {
+    tracing::info!("[7z-native] {}", archive_path.display());
+    fs::create_dir_all(output_dir)?;
+
+    let mut reader =
+        sevenz_rust2::ArchiveReader::open(archive_path, sevenz_rust2::Password::empty())
+            .with_context(|| format!("Failed to open 7z: {}", archive_path.display()))?;
+
+    let mut count = 0usize;
+    reader
+        .for_each_entries(|entry, data| {
+            let relative = entry.name.replace('\\', "/");
+            let safe = Path::new(&relative)
+                .components()
+                .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+            let skip = entry.is_directory
+                || !safe
+                || is_7z_reparse_point(entry)
+                || wanted.is_some_and(|w| !w.contains(&normalize_path(&entry.name)));
+            if skip {
+                std::io::copy(data, &mut std::io::sink())?;
+                return Ok(true);
+            }
+
+            let output_path = output_dir.join(&relative);
+            if let Some(parent) = output_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut outfile = BufWriter::with_capacity(65536, File::create(&output_path)?);
+            std::io::copy(data, &mut outfile)?;
+            tracker.entry_done(&entry.name);
+            count += 1;
+            Ok(true)
+        })
+        .with_context(|| format!("7z extraction failed: {}", archive_path.display()))?;
+
+    Ok(count)
+}
+
+/// Read one file out of a 7z archive with the native decoder.
+fn extract_7z_native_file(archive_path: &Path, file_path: &str) -> Result<Vec<u8>> {
+    let target = normalize_path(file_path);
+    let mut reader =
+        sevenz_rust2::ArchiveReader::open(archive_path, sevenz_rust2::Password::empty())
+            .with_context(|| format!("Failed to open 7z: {}", archive_path.display()))?;
+
+    let mut found = None;
+    reader
+        .for_each_entries(|entry, data| {
+            if entry.is_directory || normalize_path(&entry.name) != target {
+                std::io::copy(data, &mut std::io::sink())?;
+                return Ok(true);
+            }
+            let mut buf = Vec::with_capacity(entry.size as usize);
+            data.read_to_end(&mut buf)?;
+            found = Some(buf);
+            Ok(false)
+        })
+        .with_context(|| format!("7z extraction failed: {}", archive_path.display()))?;
+
+    found.with_context(|| {
+        format!(
+            "File '{}' not found in 7z '{}'",
+            file_path,
+            archive_path.display()
+        )
+    })
+}
+
 // ============================================================================
 // Native RAR implementation (using `unrar` crate)
 // ============================================================================
@@ -741,7 +1088,12 @@ fn extract_rar_file(archive_path: &Path, file_path: &str) -> Result<Vec<u8>> {
 }
 
 /// Extract specific files from a RAR archive to a directory.
-fn extract_rar_files(archive_path: &Path, files: &[&str], output_dir: &Path) -> Result<()> {
+fn extract_rar_files(
+    archive_path: &Path,
+    files: &[&str],
+    output_dir: &Path,
+    tracker: &mut EntryTracker,
+) -> Result<()> {
     let target_set: std::collections::HashSet<String> =
         files.iter().map(|f| normalize_path(f)).collect();
 
@@ -766,6 +1118,7 @@ fn extract_rar_files(archive_path: &Path, files: &[&str], output_dir: &Path) ->
                     anyhow::anyhow!("RAR extract error: {:?}", e)
                 }
             })?;
+            tracker.entry_done(&entry_path);
         } else {
             archive = header
                 .skip()
@@ -777,7 +1130,11 @@ fn extract_rar_files(archive_path: &Path, files: &[&str], output_dir: &Path) ->
 }
 
 /// Extract all files from a RAR archive to a directory.
-fn extract_rar_all(archive_path: &Path, output_dir: &Path) -> Result<usize> {
+fn extract_rar_all(
+    archive_path: &Path,
+    output_dir: &Path,
+    tracker: &mut EntryTracker,
+) -> Result<usize> {
     fs::create_dir_all(output_dir)?;
 
     let mut archive = unrar::Archive::new(archive_path)
@@ -791,6 +1148,7 @@ fn extract_rar_all(archive_path: &Path, output_dir: &Path) -> Result<usize> {
         .map_err(|e| anyhow::anyhow!("RAR read_header error: {:?}", e))?
     {
         if header.entry().is_file() {
+            let name = header.entry().filename.to_string_lossy().into_owned();
             archive = header.extract_with_base(output_dir).map_err(|e| {
                 if e.code == unrar::error::Code::EReference {
                     anyhow::anyhow!("RAR5 reference record - falling back to 7z binary")
@@ -798,6 +1156,7 @@ fn extract_rar_all(archive_path: &Path, output_dir: &Path) -> Result<usize> {
                     anyhow::anyhow!("RAR extract error: {:?}", e)
                 }
             })?;
+            tracker.entry_done(&name);
             count += 1;
         } else {
             let entry_path = Path::new(&header.entry().filename);
@@ -910,7 +1269,12 @@ fn extract_file_7z_binary(archive_path: &Path, file_path: &str) -> Result<Vec<u8
 }
 
 /// Extract specific files using 7z binary.
-fn extract_files_7z_binary(archive_path: &Path, files: &[&str], output_dir: &Path) -> Result<()> {
+fn extract_files_7z_binary(
+    archive_path: &Path,
+    files: &[&str],
+    output_dir: &Path,
+    tracker: &mut EntryTracker,
+) -> Result<()> {
     if files.is_empty() {
         return Ok(());
     }
@@ -933,8 +1297,11 @@ fn extract_files_7z_binary(archive_path: &Path, files: &[&str], output_dir: &Pat
         .arg("-y")
         .arg("-aoa")
         .arg("-scsUTF-8")
-        .arg("-mmt=1")
-        .arg(format!("-o{}", output_dir.display()))
+        .arg("-mmt=1");
+    if tracker.is_active() {
+        cmd.arg("-bb1");
+    }
+    cmd.arg(format!("-o{}", output_dir.display()))
         .arg(archive_path)
         .arg("--");
 
@@ -942,7 +1309,7 @@ fn extract_files_7z_binary(archive_path: &Path, files: &[&str], output_dir: &Pat
         cmd.arg(file);
     }
 
-    let output = spawn_output(&mut cmd)
+    let output = run_7z_extract(&mut cmd, tracker)
         .with_context(|| format!("Failed to extract files from {}", archive_path.display()))?;
 
     if !output.status.success() {
@@ -959,6 +1326,9 @@ fn extract_files_7z_binary(archive_path: &Path, files: &[&str], output_dir: &Pat
                 .arg("-scsUTF-8")
                 .arg("-mmt=1")
                 .arg(format!("-o{}", output_dir.display()));
+            if tracker.is_active() {
+                retry.arg("-bb1");
+            }
 
             for path in &reparse_paths {
                 retry.arg(format!("-x!{}", path));
@@ -968,7 +1338,7 @@ fn extract_files_7z_binary(archive_path: &Path, files: &[&str], output_dir: &Pat
                 retry.arg(file);
             }
 
-            let retry_output = spawn_output(&mut retry)?;
+            let retry_output = run_7z_extract(&mut retry, tracker)?;
             if retry_output.status.success() {
                 return Ok(());
             }
@@ -1001,7 +1371,11 @@ fn extract_files_7z_binary(archive_path: &Path, files: &[&str], output_dir: &Pat
 }
 
 /// Extract all files using 7z binary.
-fn extract_all_7z_binary(archive_path: &Path, output_dir: &Path) -> Result<usize> {
+fn extract_all_7z_binary(
+    archive_path: &Path,
+    output_dir: &Path,
+    tracker: &mut EntryTracker,
+) -> Result<usize> {
     let sz_path = get_7z_path()?;
     fs::create_dir_all(output_dir)?;
 
@@ -1022,11 +1396,14 @@ fn extract_all_7z_binary(archive_path: &Path, output_dir: &Path) -> Result<usize
         .arg("-aoa")
         .arg("-scsUTF-8")
         .arg(mmt_arg);
+    if tracker.is_active() {
+        cmd.arg("-bb1");
+    }
 
     cmd.arg(format!("-o{}", output_dir.display()))
         .arg(archive_path);
 
-    let output = spawn_output(&mut cmd)
+    let output = run_7z_extract(&mut cmd, tracker)
         .with_context(|| format!("Failed to extract {}", archive_path.display()))?;
 
     if !output.status.success() {
@@ -1043,13 +1420,16 @@ fn extract_all_7z_binary(archive_path: &Path, output_dir: &Path) -> Result<usize
                 .arg("-scsUTF-8")
                 .arg(mmt_arg);
 
+            if tracker.is_active() {
+                retry.arg("-bb1");
+            }
             retry.arg(format!("-o{}", output_dir.display()));
             for path in &reparse_paths {
                 retry.arg(format!("-x!{}", path));
             }
             retry.arg(archive_path);
 
-            let retry_output = spawn_output(&mut retry)?;
+            let retry_output = run_7z_extract(&mut retry, tracker)?;
             if retry_output.status.success() {
                 let count = walkdir::WalkDir::new(output_dir)
                     .into_iter()
@@ -1562,7 +1942,7 @@ Listing "Example"
             zip.finish()?;
         }
 
-        let count = extract_zip_all(&zip_path, &output_dir)?;
+        let count = extract_zip_all(&zip_path, &output_dir, &mut EntryTracker::none())?;
         assert_eq!(count, 2);
         assert!(output_dir.join("file1.txt").exists());
         assert!(output_dir.join("file2.txt").exists());
@@ -1596,7 +1976,12 @@ Listing "Example"
             zip.finish()?;
         }
 
-        extract_zip_files(&zip_path, &["file1.txt", "subdir/file3.txt"], &output_dir)?;
+        extract_zip_files(
+            &zip_path,
+            &["file1.txt", "subdir/file3.txt"],
+            &output_dir,
+            &mut EntryTracker::none(),
+        )?;
 
         assert!(output_dir.join("file1.txt").exists());
         assert!(!output_dir.join("file2.txt").exists()); // not extracted
@@ -1605,6 +1990,58 @@ Listing "Example"
         Ok(())
     }
 
+    #[test]
+    fn test_extract_with_progress_reports_entries() -> Result<()> {
+        let dir = tempdir()?;
+        let zip_path = dir.path().join("test.zip");
+
+        {
+            let file = File::create(&zip_path)?;
+            let mut zip = zip::ZipWriter::new(file);
+            let options = zip::write::SimpleFileOptions::default();
+
+            zip.start_file("Textures/a.dds", options)?;
+            zip.write_all(b"aaaa")?;
+
+            zip.start_file("textures/b.dds", options)?;
+            zip.write_all(b"bb")?;
+
+            zip.start_file("skip.txt", options)?;
+            zip.write_all(b"skipped")?;
+
+            zip.finish()?;
+        }
+
+        let updates = std::sync::Mutex::new(Vec::new());
+        let on_progress = |p: ExtractProgress| updates.lock().unwrap().push(p);
+        extract_files_case_insensitive_with_progress(
+            &zip_path,
+            &["textures/A.dds".to_string(), "Textures/B.DDS".to_string()],
+            &dir.path().join("selective"),
+            &on_progress,
+        )?;
+        let updates = updates.into_inner().unwrap();
+        assert_eq!(updates.len(), 2);
+        assert_eq!(
+            updates.last(),
+            Some(&ExtractProgress {
+                entries_done: 2,
+                entries_total: 2,
+                bytes_done: 6,
+                bytes_total: 6,
+            })
+        );
+
+        let last = std::sync::Mutex::new(ExtractProgress::default());
+        let on_progress = |p: ExtractProgress| *last.lock().unwrap() = p;
+        extract_all_with_progress(&zip_path, &dir.path().join("all"), &on_progress)?;
+        let last = last.into_inner().unwrap();
+        assert_eq!((last.entries_done, last.entries_total), (3, 3));
+        assert_eq!(last.bytes_done, 13);
+
+        Ok(())
+    }
+
     #[test]
     fn test_dispatch_zip_no_7z_needed() -> Result<()> {
         let dir = tempdir()?;
@@ -1769,6 +2206,16 @@ Listing "Example"
         Ok(())
     }
 
+    #[test]
+    fn test_unknown_format_reported_as_unsupported() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("notes.zip");
+        fs::write(&path, b"definitely not an archive").unwrap();
+
+        let err = extract_all(&path, &dir.path().join("out")).unwrap_err();
+        assert!(format!("{:#}", err).contains("not a ZIP, 7z or RAR"));
+    }
+
     #[test]
     fn test_detect_archive_type_magic() -> Result<()> {
         let dir = tempdir()?;
@@ -5,29 +5,60 @@
 //! for managing API keys, GPU selection, and default directories.
 
 use crate::downloaders::{LoversLabDownloader, NexusDownloader};
-use crate::game_finder::{detect_all_games, find_by_gog_id, find_by_steam_id, Launcher};
-use crate::modlist::browser::{ModlistBrowser, ModlistMetadata, SearchIndex};
+use crate::game_finder::{
+    cached_games, cached_protons, refresh_scan_cache, validate_game_path, GameScanResult, GameType,
+};
+use crate::installer::clean::{find_stale, known_archives, remove_stale, StaleEntry};
+use crate::installer::integrity::{check_install, CheckProgress, IntegrityReport, IssueKind};
+use crate::installer::metered::{
+    network_manager_metered, METERED_CONCURRENT_DOWNLOADS, METERED_SPEED_LIMIT,
+};
+use crate::installer::profiles::{read_profiles, ModlistProfiles};
+use crate::installer::setup_check::{run_setup_checks, SetupCheck, SetupCheckInput};
+use crate::installer::status::interrupted_install;
+use crate::installer::InstallStatus;
+use crate::modlist::browser::{
+    format_age, FetchFailure, ModlistBrowser, ModlistMetadata, ModlistSort, SearchIndex,
+    CACHE_MAX_AGE_SECS, FETCH_RETRY_DELAYS_SECS,
+};
 use crate::settings::{BrowserListPaths, Settings};
-use crate::textures::{list_gpus, GpuInfo};
+use crate::textures::{list_gpus, run_benchmark, BenchResult, EncoderMode, GpuInfo};
 use eframe::egui;
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tokio_util::sync::CancellationToken;
 
 /// How many images to fetch concurrently during background loading.
 const IMAGE_BATCH_SIZE: usize = 12;
 
-/// How many downloaded images to decode/upload on the UI thread per frame.
+/// How many images to decode at once. Each full-size decode briefly holds a
+/// large RGBA buffer, so this bounds the peak.
+const IMAGE_DECODE_CONCURRENCY: usize = 4;
+
+/// How many decoded thumbnails to upload as textures per frame.
 const IMAGE_CONVERSIONS_PER_FRAME: usize = 2;
 
+/// egui id of the browser search box. Keyboard navigation stays active while
+/// it has focus so you can type, arrow down and press Enter.
+const SEARCH_BOX_ID: &str = "browser_search";
+
 /// List row thumbnail size.
 const THUMB_WIDTH: f32 = 200.0;
 const THUMB_HEIGHT: f32 = 113.0; // ~16:9
 const ROW_HEIGHT: f32 = 120.0;
 
+/// Log files listed in Settings → Logs.
+const RECENT_LOGS: usize = 20;
+
 /// Launch the browser GUI window. Blocks until the window is closed.
-pub fn launch_browser() -> Result<(), eframe::Error> {
+/// `offline` serves the gallery and images from cache only and passes
+/// `--offline` to installs. `session_log` is this process's log file; the
+/// Settings tab's log tools default to the newest log before it, which is
+/// usually the install the browser launched.
+pub fn launch_browser(offline: bool, session_log: PathBuf) -> Result<(), eframe::Error> {
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_title("CLF3 — Modlist Browser")
@@ -42,7 +73,7 @@ pub fn launch_browser() -> Result<(), eframe::Error> {
         Box::new(|cc| {
             // Install image loaders so egui_extras can decode PNG/JPEG/etc.
             egui_extras::install_image_loaders(&cc.egui_ctx);
-            Ok(Box::new(BrowserApp::new(cc)))
+            Ok(Box::new(BrowserApp::new(cc, offline, session_log)))
         }),
     )
 }
@@ -51,8 +82,8 @@ pub fn launch_browser() -> Result<(), eframe::Error> {
 enum ImageState {
     /// Download in flight.
     Loading,
-    /// Raw image bytes ready to be turned into a texture.
-    Downloaded(Vec<u8>),
+    /// Decoded and downscaled, ready to be uploaded as a texture.
+    Decoded(egui::ColorImage),
     /// Texture uploaded to GPU.
     Texture(egui::TextureHandle),
     /// Failed to load.
@@ -67,8 +98,64 @@ struct SharedState {
     images: HashMap<String, ImageState>,
     fetch_done: bool,
     fetch_error: Option<String>,
+    /// When the gallery shown was fetched (the cache file's mtime for a
+    /// cached one).
+    gallery_updated: Option<SystemTime>,
+    /// A fresh gallery is being fetched while the old one stays up.
+    refreshing: bool,
+    /// Why the last refresh failed; the old gallery stays up. Stops
+    /// automatic refreshes until the user asks for one.
+    refresh_error: Option<String>,
+    /// Set while a failed fetch waits to be retried.
+    fetch_retry: Option<String>,
+    /// Machine names the gallery dropped since they were cached.
+    removed_modlists: HashSet<String>,
+    /// Set when a refresh replaced the gallery, so the UI thread loads
+    /// thumbnails for new lists.
+    gallery_refreshed: bool,
     search_index: Option<Arc<SearchIndex>>,
     search_index_error: Option<String>,
+    /// Result of the background launcher scan, taken by the UI thread.
+    installed_games: Option<InstalledGames>,
+}
+
+/// Games found by the launcher scan, keyed by lowercase canonical Wabbajack
+/// `GameType` string.
+struct InstalledGames {
+    types: HashSet<String>,
+    dirs: HashMap<String, PathBuf>,
+    /// Launcher installs detected, including ones that failed validation.
+    count: usize,
+}
+
+impl InstalledGames {
+    /// Map each detected game back to its canonical Wabbajack `GameType`
+    /// string (by app ID, or by its files for localized and Epic installs),
+    /// so modlists can be filtered by "do you own this game in any launcher".
+    fn from_scan(scan: &GameScanResult) -> Self {
+        let mut types = HashSet::new();
+        let mut dirs = HashMap::new();
+        for game in &scan.games {
+            let Some(gt) = game.game_type() else {
+                continue;
+            };
+            // A leftover or half-deleted install folder doesn't count as
+            // owning the game.
+            if let Err(e) = validate_game_path(gt, &game.install_path) {
+                tracing::warn!("Ignoring detected {}: {}", game.name, e);
+                continue;
+            }
+            let wj = gt.wabbajack_name();
+            types.insert(wj.to_lowercase());
+            dirs.entry(wj.to_lowercase())
+                .or_insert_with(|| game.install_path.clone());
+        }
+        Self {
+            types,
+            dirs,
+            count: scan.games.len(),
+        }
+    }
 }
 
 /// Top-level navigation between the modlist browser and the settings editor.
@@ -79,6 +166,93 @@ enum Tab {
     Settings,
 }
 
+/// Status of the install panel's "Validate Setup" run.
+enum SetupCheckStatus {
+    Idle,
+    Running,
+    Done(Vec<SetupCheck>),
+}
+
+/// Status of the install panel's "Verify Install" check.
+enum VerifyStatus {
+    Idle,
+    Running {
+        progress: CheckProgress,
+        cancel: CancellationToken,
+    },
+    /// `report` is partial if the check was cancelled.
+    Done {
+        name: String,
+        report: IntegrityReport,
+    },
+    Failed(String),
+}
+
+/// Status of the Settings tab's texture encoder benchmark.
+enum BenchmarkStatus {
+    Idle,
+    Running,
+    Done(Vec<BenchResult>),
+}
+
+/// Status of the Settings tab's disk cleanup.
+enum CleanStatus {
+    Idle,
+    Scanning,
+    /// Found leftovers, waiting for the user to confirm deleting them.
+    Scanned(Vec<StaleEntry>),
+    Cleaning,
+    Done {
+        removed: usize,
+        freed: u64,
+    },
+    Failed(String),
+}
+
+/// MO2 profiles read from the selected list's .wabbajack.
+enum ProfileScan {
+    Idle,
+    Scanning(PathBuf),
+    Done {
+        path: PathBuf,
+        profiles: ModlistProfiles,
+        /// Profile picked for this modlist on an earlier install.
+        cached: Option<String>,
+    },
+    Failed(PathBuf),
+}
+
+/// .wabbajack, downloads dir and install dir a resume check ran for.
+type ResumeKey = (PathBuf, PathBuf, PathBuf);
+
+/// Unfinished earlier install of the selected list into the chosen dirs.
+enum ResumeCheck {
+    Idle,
+    Checking(ResumeKey),
+    /// `None` when there's nothing to resume.
+    Done(ResumeKey, Option<InstallStatus>),
+}
+
+impl ResumeCheck {
+    fn key(&self) -> Option<&ResumeKey> {
+        match self {
+            ResumeCheck::Idle => None,
+            ResumeCheck::Checking(key) | ResumeCheck::Done(key, _) => Some(key),
+        }
+    }
+}
+
+impl ProfileScan {
+    fn path(&self) -> Option<&Path> {
+        match self {
+            ProfileScan::Idle => None,
+            ProfileScan::Scanning(path)
+            | ProfileScan::Done { path, .. }
+            | ProfileScan::Failed(path) => Some(path),
+        }
+    }
+}
+
 /// Async credential-validation status for the Settings tab.
 #[derive(Clone)]
 enum ValidationStatus {
@@ -92,6 +266,11 @@ struct BrowserApp {
     shared: Arc<Mutex<SharedState>>,
     /// Search query string.
     search: String,
+    /// Focus the search box on the next frame (set at startup).
+    focus_search: bool,
+    /// Card highlighted by arrow-key navigation, as an index into the
+    /// filtered list. Separate from `selected`: Enter selects it.
+    focused_index: Option<usize>,
     /// Selected game filter (empty = all).
     game_filter: String,
     /// Show NSFW lists.
@@ -100,17 +279,28 @@ struct BrowserApp {
     show_unavailable: bool,
     /// Show only modlists for games the user has installed (Steam or Heroic/GOG).
     show_installed_only: bool,
+    /// Show only starred modlists.
+    show_favorites_only: bool,
+    /// List order; applied after filtering so it holds across filter changes.
+    sort: ModlistSort,
     /// Exact MO2 mod names selected from Wabbajack's search index.
     must_include_mods: Vec<String>,
     must_exclude_mods: Vec<String>,
     include_mod_query: String,
     exclude_mod_query: String,
     /// Set of canonical Wabbajack `GameType` strings the user has installed.
-    /// Populated once at startup via `detect_all_games()`. Case-insensitive
+    /// Filled in once the background `cached_games()` scan finishes. Case-insensitive
     /// match to `ModlistMetadata.game` in `filtered_modlists`.
     installed_game_types: HashSet<String>,
     /// Count of launcher installs detected, shown next to the checkbox.
     installed_game_count: usize,
+    /// Install directory of each detected game that passed validation, keyed
+    /// like `installed_game_types`. Used by "Validate Setup".
+    installed_game_dirs: HashMap<String, PathBuf>,
+    /// The launcher scan has been kicked off; the fields above are empty
+    /// until it lands in `SharedState::installed_games`.
+    game_scan_started: bool,
+    game_scan_done: bool,
     /// Currently selected modlist machine_name (if any).
     selected: Option<String>,
     /// A pre-downloaded `.wabbajack` file the user browsed to. When set, the
@@ -126,6 +316,8 @@ struct BrowserApp {
     generated_command: Option<String>,
     /// Whether we've kicked off the initial fetch.
     fetch_started: bool,
+    /// `--offline`: never touch the network.
+    offline: bool,
     /// tokio runtime for async operations. Held as `Option` so `Drop` can
     /// take ownership and call `shutdown_background()` — otherwise dropping
     /// the runtime while reqwest's connection pool is still tearing down
@@ -144,6 +336,9 @@ struct BrowserApp {
     settings: Settings,
     /// Async status for "Save & Verify Nexus key".
     nexus_status: Arc<Mutex<ValidationStatus>>,
+    /// The Nexus key came from `NEXUS_API_KEY`; verify it (without saving)
+    /// on the first frame.
+    verify_env_key: bool,
     /// Async status for "Save & Verify LoversLab login".
     ll_status: Arc<Mutex<ValidationStatus>>,
     /// Whether to render the Nexus key in plain text.
@@ -158,6 +353,44 @@ struct BrowserApp {
     run_status: Option<(bool, String)>,
     /// Whether we've tried to restore the last browser selection after metadata loaded.
     selection_restore_attempted: bool,
+    /// Checklist from the last "Validate Setup" click.
+    setup_checks: Arc<Mutex<SetupCheckStatus>>,
+    /// Texture encoder benchmark (Settings → GPU).
+    benchmark: Arc<Mutex<BenchmarkStatus>>,
+    /// Result of the last "Verify Install" on the install dir.
+    verify: Arc<Mutex<VerifyStatus>>,
+    /// Outcome of exporting the verify report.
+    verify_export_message: Option<(bool, String)>,
+    /// Append `--purge-downloads` so unused archives are deleted after a
+    /// successful install. Off by default; downloads are reused on updates.
+    purge_downloads: bool,
+    /// Profiles of the selected list (install panel).
+    profile_scan: Arc<Mutex<ProfileScan>>,
+    /// .wabbajack `selected_profile` was filled in for.
+    profile_pick_for: Option<PathBuf>,
+    /// Appended as `--profile`. None keeps the author's default.
+    selected_profile: Option<String>,
+    /// Earlier install of the selected list that didn't finish.
+    resume_check: Arc<Mutex<ResumeCheck>>,
+    /// "Start Fresh" picked over resuming: appends `--fresh`.
+    fresh_install: bool,
+    /// NetworkManager's metered flag, read when detection is turned on.
+    network_metered: Option<bool>,
+    /// Download size confirmed for a metered connection: appends
+    /// `--accept-metered`.
+    metered_confirmed: bool,
+    /// Disk cleanup (Settings → Disk cleanup).
+    clean: Arc<Mutex<CleanStatus>>,
+    /// Include archives no known modlist uses in the cleanup.
+    clean_unreferenced: bool,
+    /// This process's log file.
+    session_log: PathBuf,
+    /// Log picked in Settings → Logs; None until the list is first read.
+    selected_log: Option<PathBuf>,
+    /// Recent log files, newest first (Settings → Logs).
+    recent_logs: Vec<PathBuf>,
+    /// Outcome of the last copy/save of a log.
+    log_message: Option<(bool, String)>,
 }
 
 impl Drop for BrowserApp {
@@ -172,37 +405,25 @@ impl Drop for BrowserApp {
 }
 
 impl BrowserApp {
-    fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        let image_cache_dir = dirs::cache_dir()
-            .unwrap_or_else(|| PathBuf::from("/tmp"))
-            .join("clf3")
-            .join("images");
+    fn new(_cc: &eframe::CreationContext<'_>, offline: bool, session_log: PathBuf) -> Self {
+        let image_cache_dir =
+            crate::paths::image_cache_dir().unwrap_or_else(|| PathBuf::from("/tmp/clf3/images"));
         let _ = std::fs::create_dir_all(&image_cache_dir);
 
-        // Detect all installed games (Steam, Heroic/GOG, Epic) once at startup.
-        // Map each detected Game's app_id back to its canonical Wabbajack
-        // `GameType` string via KNOWN_GAMES, so we can filter modlists by
-        // "do you own this game in any launcher".
-        let detected = detect_all_games();
-        let installed_game_count = detected.games.len();
-        let mut installed_game_types: HashSet<String> = HashSet::new();
-        for game in &detected.games {
-            let known = match game.launcher {
-                Launcher::Steam { .. } => find_by_steam_id(&game.app_id),
-                Launcher::Heroic { .. } => find_by_gog_id(&game.app_id),
-            };
-            if let Some(k) = known {
-                if let Some(wj) = k.wabbajack_type {
-                    installed_game_types.insert(wj.to_lowercase());
-                }
-            }
-        }
-
         // Load saved settings and pre-fill the install panel's directory
-        // fields with the user's defaults (if any).
-        let settings = Settings::load();
+        // fields with the user's defaults (if any). Empty fields fall back to
+        // the same environment variables the CLI reads.
+        let mut settings = Settings::load();
+        for var in settings.apply_env_fallbacks() {
+            tracing::info!("Using {} from the environment", var);
+        }
+        let verify_env_key = !offline && settings.is_from_env("NEXUS_API_KEY");
         let downloads_dir = settings.default_downloads_dir.clone();
         let install_dir = settings.default_install_dir.clone();
+        let network_metered = settings
+            .detect_metered_connection
+            .then(network_manager_metered)
+            .flatten();
 
         Self {
             shared: Arc::new(Mutex::new(SharedState {
@@ -211,26 +432,41 @@ impl BrowserApp {
                 images: HashMap::new(),
                 fetch_done: false,
                 fetch_error: None,
+                gallery_updated: None,
+                refreshing: false,
+                refresh_error: None,
+                fetch_retry: None,
+                removed_modlists: HashSet::new(),
+                gallery_refreshed: false,
                 search_index: None,
                 search_index_error: None,
+                installed_games: None,
             })),
             search: String::new(),
+            focus_search: true,
+            focused_index: None,
             game_filter: settings.browser_game_filter.clone(),
             show_nsfw: settings.browser_show_nsfw,
             show_unavailable: settings.browser_show_unavailable,
             show_installed_only: settings.browser_show_installed_only,
+            show_favorites_only: settings.browser_show_favorites_only,
+            sort: settings.browser_sort,
             must_include_mods: Vec::new(),
             must_exclude_mods: Vec::new(),
             include_mod_query: String::new(),
             exclude_mod_query: String::new(),
-            installed_game_types,
-            installed_game_count,
+            installed_game_types: HashSet::new(),
+            installed_game_count: 0,
+            installed_game_dirs: HashMap::new(),
+            game_scan_started: false,
+            game_scan_done: false,
             selected: None,
             local_wabbajack: None,
             downloads_dir,
             install_dir,
             generated_command: None,
             fetch_started: false,
+            offline,
             rt: Some(tokio::runtime::Runtime::new().expect("Failed to create tokio runtime")),
             image_cache_dir,
             image_load_started: false,
@@ -244,6 +480,25 @@ impl BrowserApp {
             settings_save_message: None,
             run_status: None,
             selection_restore_attempted: false,
+            setup_checks: Arc::new(Mutex::new(SetupCheckStatus::Idle)),
+            benchmark: Arc::new(Mutex::new(BenchmarkStatus::Idle)),
+            verify: Arc::new(Mutex::new(VerifyStatus::Idle)),
+            verify_export_message: None,
+            purge_downloads: false,
+            profile_scan: Arc::new(Mutex::new(ProfileScan::Idle)),
+            profile_pick_for: None,
+            selected_profile: None,
+            resume_check: Arc::new(Mutex::new(ResumeCheck::Idle)),
+            fresh_install: false,
+            network_metered,
+            metered_confirmed: false,
+            clean: Arc::new(Mutex::new(CleanStatus::Idle)),
+            clean_unreferenced: false,
+            session_log,
+            selected_log: None,
+            recent_logs: Vec::new(),
+            log_message: None,
+            verify_env_key,
         }
     }
 
@@ -253,6 +508,29 @@ impl BrowserApp {
         self.rt.as_ref().expect("runtime taken outside Drop")
     }
 
+    /// Scan launchers for installed games (and warm the Proton cache used by
+    /// "Validate Setup") off the UI thread. `refresh` drops the cached scan
+    /// first so newly installed games show up.
+    fn start_game_scan(&mut self, ctx: &egui::Context, refresh: bool) {
+        if self.game_scan_started && !refresh {
+            return;
+        }
+        self.game_scan_started = true;
+        self.game_scan_done = false;
+
+        let shared = Arc::clone(&self.shared);
+        let ctx = ctx.clone();
+        self.rt().spawn_blocking(move || {
+            if refresh {
+                refresh_scan_cache();
+            }
+            let installed = InstalledGames::from_scan(&cached_games());
+            shared.lock().expect("lock shared state").installed_games = Some(installed);
+            ctx.request_repaint();
+            cached_protons();
+        });
+    }
+
     /// Kick off async modlist fetch in the background.
     fn start_fetch(&mut self, ctx: &egui::Context) {
         if self.fetch_started {
@@ -262,6 +540,7 @@ impl BrowserApp {
 
         let shared = Arc::clone(&self.shared);
         let ctx = ctx.clone();
+        let offline = self.offline;
 
         self.rt().spawn(async move {
             let mut browser = match ModlistBrowser::new() {
@@ -277,11 +556,15 @@ impl BrowserApp {
 
             // This index is independent of gallery metadata. Failure only
             // disables mod-name filters; the rest of the browser still works.
-            let cached_index = ModlistBrowser::has_recent_search_index_cache()
+            let cached_index = (offline || ModlistBrowser::has_recent_search_index_cache())
                 .then(ModlistBrowser::load_search_index_cache)
                 .and_then(Result::ok);
             let index = if let Some(index) = cached_index {
                 Some(index)
+            } else if offline {
+                shared.lock().expect("lock shared state").search_index_error =
+                    Some("Mod filters unavailable offline (nothing cached)".to_string());
+                None
             } else {
                 match browser.fetch_search_index().await {
                     Ok(index) => {
@@ -300,47 +583,88 @@ impl BrowserApp {
                 ctx.request_repaint();
             }
 
-            // Try cache first
-            if ModlistBrowser::has_recent_cache() {
-                if let Ok(true) = browser.load_cache() {
-                    let mut state = shared.lock().expect("lock shared state");
-                    state.games = browser.games().into_iter().map(String::from).collect();
-                    state.modlists = browser.modlists().to_vec();
-                    state.fetch_done = true;
-                    ctx.request_repaint();
+            // Try cache first. Offline, any cached gallery will do; online,
+            // an old one is shown while the fresh gallery loads.
+            let cache_age = ModlistBrowser::cache_age_secs();
+            if let Ok(true) = browser.load_cache() {
+                let stale = !offline && !ModlistBrowser::has_recent_cache();
+                let mut state = shared.lock().expect("lock shared state");
+                state.games = browser.games().into_iter().map(String::from).collect();
+                state.modlists = browser.modlists().to_vec();
+                state.gallery_updated =
+                    cache_age.map(|age| SystemTime::now() - Duration::from_secs(age));
+                state.refreshing = stale;
+                state.fetch_done = true;
+                drop(state);
+                ctx.request_repaint();
+                if !stale {
                     return;
                 }
             }
 
-            // Fetch from network
-            match browser.fetch_modlists().await {
-                Ok(_) => {
-                    let _ = browser.save_cache();
-                    let mut state = shared.lock().expect("lock shared state");
-                    state.games = browser.games().into_iter().map(String::from).collect();
-                    state.modlists = browser.modlists().to_vec();
-                    state.fetch_done = true;
-                }
+            if offline {
+                let mut state = shared.lock().expect("lock shared state");
+                state.fetch_error = Some(
+                    "Offline and no cached modlist gallery. Start CLF3 online once.".to_string(),
+                );
+                state.fetch_done = true;
+                ctx.request_repaint();
+                return;
+            }
+
+            fetch_gallery(&mut browser, &shared, &ctx).await;
+            ctx.request_repaint();
+        });
+    }
+
+    /// Fetch the gallery again in the background, keeping the current one
+    /// up until it lands. Lists it no longer has are noted as removed.
+    fn start_gallery_refresh(&mut self, ctx: &egui::Context) {
+        {
+            let mut state = self.shared.lock().expect("lock shared state");
+            if state.refreshing {
+                return;
+            }
+            state.refreshing = true;
+            state.refresh_error = None;
+        }
+
+        let shared = Arc::clone(&self.shared);
+        let ctx = ctx.clone();
+        self.rt().spawn(async move {
+            let mut browser = match ModlistBrowser::new() {
+                Ok(b) => b,
                 Err(e) => {
                     let mut state = shared.lock().expect("lock shared state");
-                    state.fetch_error = Some(format!("Failed to fetch modlists: {}", e));
-                    state.fetch_done = true;
+                    state.refreshing = false;
+                    state.refresh_error = Some(e.to_string());
+                    ctx.request_repaint();
+                    return;
                 }
-            }
+            };
+            // The cache holds what's on screen, to compare the fresh
+            // gallery against.
+            let _ = browser.load_cache();
+            fetch_gallery(&mut browser, &shared, &ctx).await;
             ctx.request_repaint();
         });
     }
 
-    /// Kick off background image downloads for all modlists that have image URLs.
+    /// Whether a modlist's thumbnail should be fetched. Lists hidden by the
+    /// NSFW/unavailable filters are skipped until the filter is turned off.
+    fn wants_thumbnail(&self, modlist: &ModlistMetadata) -> bool {
+        (self.show_nsfw || !modlist.nsfw) && (self.show_unavailable || modlist.is_available())
+    }
+
+    /// Kick off background image downloads for modlists that have image URLs
+    /// and aren't loaded yet. Called again when a filter change reveals more.
     fn start_image_loading(&mut self, ctx: &egui::Context) {
-        if self.image_load_started {
-            return;
-        }
         self.image_load_started = true;
 
         let shared = Arc::clone(&self.shared);
         let ctx = ctx.clone();
         let cache_dir = self.image_cache_dir.clone();
+        let offline = self.offline;
 
         // Collect what needs loading.
         let to_load: Vec<(String, String)> = {
@@ -350,7 +674,8 @@ impl BrowserApp {
                 .iter()
                 .filter_map(|m| {
                     let key = m.machine_name.clone();
-                    if key.is_empty() {
+                    if key.is_empty() || state.images.contains_key(&key) || !self.wants_thumbnail(m)
+                    {
                         return None;
                     }
                     let url = m.image_url()?.to_string();
@@ -365,12 +690,17 @@ impl BrowserApp {
             }
             items
         };
+        if to_load.is_empty() {
+            return;
+        }
 
         self.rt().spawn(async move {
             let client = reqwest::Client::builder()
+                .user_agent(crate::downloaders::user_agent())
                 .timeout(std::time::Duration::from_secs(15))
                 .build()
                 .unwrap();
+            let decode_slots = Arc::new(tokio::sync::Semaphore::new(IMAGE_DECODE_CONCURRENCY));
 
             // Process in batches.
             for batch in to_load.chunks(IMAGE_BATCH_SIZE) {
@@ -381,31 +711,22 @@ impl BrowserApp {
                     let key = key.clone();
                     let url = url.clone();
                     let cache_dir = cache_dir.clone();
+                    let decode_slots = Arc::clone(&decode_slots);
 
                     handles.push(tokio::spawn(async move {
-                        // Check disk cache first.
-                        let cached_path = cache_dir.join(&key);
-                        if cached_path.exists() {
-                            if let Ok(bytes) = std::fs::read(&cached_path) {
-                                if !bytes.is_empty() {
-                                    return (key, Ok(bytes));
-                                }
-                            }
-                        }
-
-                        // Download.
-                        match client.get(&url).send().await {
-                            Ok(resp) if resp.status().is_success() => match resp.bytes().await {
-                                Ok(bytes) if !bytes.is_empty() => {
-                                    let _ = std::fs::write(&cached_path, &bytes);
-                                    (key, Ok(bytes.to_vec()))
-                                }
-                                Ok(_) => (key, Err("Empty response".to_string())),
-                                Err(e) => (key, Err(e.to_string())),
-                            },
-                            Ok(resp) => (key, Err(format!("HTTP {}", resp.status()))),
-                            Err(e) => (key, Err(e.to_string())),
-                        }
+                        let bytes =
+                            match fetch_image_bytes(&client, &url, &cache_dir, &key, offline).await
+                            {
+                                Ok(bytes) => bytes,
+                                Err(e) => return (key, Err(e)),
+                            };
+                        let _slot = decode_slots.acquire().await;
+                        let decoded = tokio::task::spawn_blocking(move || decode_thumbnail(&bytes))
+                            .await
+                            .ok()
+                            .flatten()
+                            .ok_or_else(|| "Decode failed".to_string());
+                        (key, decoded)
                     }));
                 }
 
@@ -414,8 +735,8 @@ impl BrowserApp {
                     if let Ok((key, result)) = handle.await {
                         let mut state = shared.lock().expect("lock shared state");
                         match result {
-                            Ok(bytes) => {
-                                state.images.insert(key, ImageState::Downloaded(bytes));
+                            Ok(image) => {
+                                state.images.insert(key, ImageState::Decoded(image));
                             }
                             Err(_) => {
                                 state.images.insert(key, ImageState::Failed);
@@ -432,7 +753,7 @@ impl BrowserApp {
     /// Build the filtered list of modlists to display.
     fn filtered_modlists(&self) -> Vec<ModlistMetadata> {
         let state = self.shared.lock().expect("lock shared state");
-        state
+        let mut filtered: Vec<ModlistMetadata> = state
             .modlists
             .iter()
             .filter(|m| {
@@ -442,7 +763,13 @@ impl BrowserApp {
                 if !self.show_nsfw && m.nsfw {
                     return false;
                 }
+                if self.show_favorites_only && !self.is_favorite(&m.machine_name) {
+                    return false;
+                }
+                // Until the launcher scan lands, show everything rather than
+                // an empty list.
                 if self.show_installed_only
+                    && self.game_scan_done
                     && !self.installed_game_types.contains(&m.game.to_lowercase())
                 {
                     return false;
@@ -470,7 +797,11 @@ impl BrowserApp {
                 true
             })
             .cloned()
-            .collect()
+            .collect();
+        self.sort.apply(&mut filtered);
+        // Favorites first; the sort is stable so the chosen order holds otherwise.
+        filtered.sort_by_key(|m| !self.is_favorite(&m.machine_name));
+        filtered
     }
 
     /// Format bytes into a human-readable string.
@@ -488,32 +819,22 @@ impl BrowserApp {
     }
 
     /// Format game name for display.
+    /// Supported games come from `GameType`; the rest are gallery-only games
+    /// CLF3 can't install yet but still lists.
     fn format_game_name(game: &str) -> String {
+        if let Some(game_type) = GameType::from_wabbajack_name(game) {
+            return game_type.short_name().into();
+        }
         match game.to_lowercase().as_str() {
-            "skyrimspecialedition" => "Skyrim SE".into(),
-            "skyrim" => "Skyrim LE".into(),
-            "skyrimvr" => "Skyrim VR".into(),
-            "fallout4" => "Fallout 4".into(),
-            "falloutnewvegas" => "Fallout NV".into(),
-            "fallout3" => "Fallout 3".into(),
-            "oblivion" => "Oblivion".into(),
             "oblivionremastered" => "Oblivion Remastered".into(),
-            "morrowind" => "Morrowind".into(),
-            "enderal" | "enderalspecialedition" => "Enderal".into(),
-            "starfield" => "Starfield".into(),
-            "nierautomata" => "NieR: Automata".into(),
             "mountandblade2bannerlord" => "Mount & Blade II".into(),
             "nomanssky" => "No Man's Sky".into(),
             "sevendaystodie" => "7 Days to Die".into(),
             "stardewvalley" => "Stardew Valley".into(),
-            "cyberpunk2077" => "Cyberpunk 2077".into(),
             "dragonage" | "dragonageorigins" => "Dragon Age".into(),
-            "baldursgate3" => "Baldur's Gate 3".into(),
             "dishonored" => "Dishonored".into(),
             "dragonsdogma" | "dragonsdogma2" => "Dragon's Dogma".into(),
-            "fallout4vr" => "Fallout 4 VR".into(),
             "vtmb" => "Vampire: The Masquerade".into(),
-            "witcher3" => "The Witcher 3".into(),
             _ => game.to_string(),
         }
     }
@@ -521,12 +842,12 @@ impl BrowserApp {
     /// Generate the CLI command for the selected modlist.
     fn generate_command(&self, modlist: &ModlistMetadata) -> Option<String> {
         let url = modlist.download_url()?;
-        self.build_install_command(url)
+        self.build_install_command(url, modlist.download_hash())
     }
 
     /// Generate the CLI command for a local .wabbajack path.
     fn generate_command_for_local(&self, path: &Path) -> Option<String> {
-        self.build_install_command(&path.display().to_string())
+        self.build_install_command(&path.display().to_string(), None)
     }
 
     fn selected_list_key(&self) -> Option<String> {
@@ -563,24 +884,111 @@ impl BrowserApp {
         self.settings.browser_show_nsfw = self.show_nsfw;
         self.settings.browser_show_unavailable = self.show_unavailable;
         self.settings.browser_show_installed_only = self.show_installed_only;
+        self.settings.browser_show_favorites_only = self.show_favorites_only;
+        self.settings.browser_sort = self.sort;
         self.settings.browser_last_selected_modlist = self.selected.clone();
         let _ = self.settings.save();
     }
 
+    fn is_favorite(&self, machine_name: &str) -> bool {
+        self.settings
+            .browser_favorite_modlists
+            .iter()
+            .any(|name| name == machine_name)
+    }
+
+    /// Star or unstar a modlist. Saved right away, keyed by machine_name so
+    /// it survives gallery refreshes.
+    fn toggle_favorite(&mut self, machine_name: String) {
+        let favorites = &mut self.settings.browser_favorite_modlists;
+        if let Some(pos) = favorites.iter().position(|name| *name == machine_name) {
+            favorites.remove(pos);
+        } else {
+            favorites.push(machine_name);
+        }
+        let _ = self.settings.save();
+    }
+
     fn select_modlist(&mut self, name: String) {
         self.remember_current_list_paths();
         self.selected = Some(name.clone());
         self.local_wabbajack = None;
         self.generated_command = None;
         self.run_status = None;
+        *self.setup_checks.lock().expect("lock setup checks") = SetupCheckStatus::Idle;
+        self.reset_verify();
+        self.fresh_install = false;
+        self.metered_confirmed = false;
         self.apply_paths_for_list(&name);
         self.settings.browser_last_selected_modlist = Some(name);
         let _ = self.settings.save();
     }
 
+    /// Close the install panel (the "X Close" button and Escape).
+    fn close_selection(&mut self) {
+        self.remember_current_list_paths();
+        self.selected = None;
+        self.local_wabbajack = None;
+        self.generated_command = None;
+        self.run_status = None;
+        *self.setup_checks.lock().expect("lock setup checks") = SetupCheckStatus::Idle;
+        self.reset_verify();
+        self.fresh_install = false;
+        self.metered_confirmed = false;
+        self.settings.browser_last_selected_modlist = None;
+        let _ = self.settings.save();
+    }
+
+    /// Arrow keys move the highlighted card, Enter selects it and Escape
+    /// closes the install panel. Only active when no other text field has
+    /// focus. Returns true when the highlight moved, so the list can scroll
+    /// it into view.
+    fn handle_list_keys(&mut self, ctx: &egui::Context, filtered: &[ModlistMetadata]) -> bool {
+        let focus = ctx.memory(|m| m.focused());
+        if focus.is_some_and(|id| id != egui::Id::new(SEARCH_BOX_ID)) {
+            return false;
+        }
+        let (down, up, enter, escape) = ctx.input(|i| {
+            (
+                i.key_pressed(egui::Key::ArrowDown),
+                i.key_pressed(egui::Key::ArrowUp),
+                i.key_pressed(egui::Key::Enter),
+                i.key_pressed(egui::Key::Escape),
+            )
+        });
+
+        let last = filtered.len().checked_sub(1);
+        self.focused_index = self.focused_index.zip(last).map(|(i, last)| i.min(last));
+        let previous = self.focused_index;
+        if down {
+            self.focused_index = match (self.focused_index, last) {
+                (_, None) => None,
+                (None, Some(_)) => Some(0),
+                (Some(i), Some(last)) => Some((i + 1).min(last)),
+            };
+        }
+        if up {
+            self.focused_index = self.focused_index.map(|i| i.saturating_sub(1));
+        }
+
+        if enter {
+            if let Some(modlist) = self.focused_index.and_then(|i| filtered.get(i)) {
+                if modlist.download_url().is_some() {
+                    self.select_modlist(modlist.machine_name.clone());
+                }
+            }
+        }
+        if escape && (self.selected.is_some() || self.local_wabbajack.is_some()) {
+            self.close_selection();
+        }
+
+        self.focused_index != previous
+    }
+
     /// Shared command builder — both modlist-URL and local-path callers
     /// produce the same `clf3 install <src> <downloads> <install>` shape.
-    fn build_install_command(&self, source: &str) -> Option<String> {
+    /// Gallery lists pass their listed hash so the download gets checked.
+    fn build_install_command(&self, source: &str, wabbajack_hash: Option<&str>) -> Option<String> {
         if self.downloads_dir.is_empty() || self.install_dir.is_empty() {
             return None;
         }
@@ -594,13 +1002,40 @@ impl BrowserApp {
             shell_quote(&self.downloads_dir),
             shell_quote(&self.install_dir),
         ];
+        if let Some(hash) = wabbajack_hash {
+            parts.push("--wabbajack-hash".to_string());
+            parts.push(shell_quote(hash));
+        }
+        if self.purge_downloads {
+            parts.push("--purge-downloads".to_string());
+        }
+        if let Some(profile) = self.profile_arg() {
+            parts.push("--profile".to_string());
+            parts.push(shell_quote(&profile));
+        }
+        if self.offline {
+            parts.push("--offline".to_string());
+        }
+        if self.fresh_install {
+            parts.push("--fresh".to_string());
+        }
+        if self.is_metered() {
+            parts.push("--metered".to_string());
+        }
+        if self.metered_confirmed {
+            parts.push("--accept-metered".to_string());
+        }
         Some(parts.join(" "))
     }
 
     /// Build the install command as a list of args (exe + args) for direct
     /// spawning, alongside the display string. Returns `None` when the user
     /// hasn't supplied both directories yet.
-    fn build_install_args(&self, source: &str) -> Option<(PathBuf, Vec<String>)> {
+    fn build_install_args(
+        &self,
+        source: &str,
+        wabbajack_hash: Option<&str>,
+    ) -> Option<(PathBuf, Vec<String>)> {
         if self.downloads_dir.is_empty() || self.install_dir.is_empty() {
             return None;
         }
@@ -611,34 +1046,409 @@ impl BrowserApp {
             self.downloads_dir.clone(),
             self.install_dir.clone(),
         ];
+        if let Some(hash) = wabbajack_hash {
+            args.push("--wabbajack-hash".into());
+            args.push(hash.into());
+        }
+        if self.purge_downloads {
+            args.push("--purge-downloads".into());
+        }
+        if let Some(profile) = self.profile_arg() {
+            args.push("--profile".into());
+            args.push(profile);
+        }
+        if self.offline {
+            args.push("--offline".into());
+        }
+        if self.fresh_install {
+            args.push("--fresh".into());
+        }
+        if self.is_metered() {
+            args.push("--metered".into());
+        }
+        if self.metered_confirmed {
+            args.push("--accept-metered".into());
+        }
         Some((exe, args))
     }
+
+    /// .wabbajack to read MO2 profiles and saved install progress from: the
+    /// local file, or the gallery list's cached download from an earlier
+    /// install.
+    fn profile_source(&self, modlist: Option<&ModlistMetadata>) -> Option<PathBuf> {
+        if let Some(path) = &self.local_wabbajack {
+            return Some(path.clone());
+        }
+        let url = modlist?.download_url()?;
+        let path =
+            crate::paths::modlist_cache_dir()?.join(crate::cache_filename_from_wabbajack_url(url));
+        path.is_file().then_some(path)
+    }
+
+    /// Read the profiles of `path` in the background, once per file.
+    fn start_profile_scan(&self, ctx: &egui::Context, path: PathBuf) {
+        {
+            let mut scan = self.profile_scan.lock().expect("lock profile scan");
+            if scan.path() == Some(path.as_path()) {
+                return;
+            }
+            *scan = ProfileScan::Scanning(path.clone());
+        }
+        let scan = Arc::clone(&self.profile_scan);
+        let ctx = ctx.clone();
+        self.rt().spawn(async move {
+            let read = tokio::task::spawn_blocking({
+                let path = path.clone();
+                move || read_profile_choice(&path)
+            })
+            .await;
+            let mut state = scan.lock().expect("lock profile scan");
+            // Another list was selected meanwhile.
+            if state.path() != Some(path.as_path()) {
+                return;
+            }
+            *state = match read {
+                Ok(Ok((profiles, cached))) => ProfileScan::Done {
+                    path,
+                    profiles,
+                    cached,
+                },
+                _ => ProfileScan::Failed(path),
+            };
+            ctx.request_repaint();
+        });
+    }
+
+    /// Look for an unfinished install of `wabbajack` into the chosen dirs in
+    /// the background, once per file and dir pair.
+    fn start_resume_check(&self, ctx: &egui::Context, wabbajack: PathBuf) {
+        if self.downloads_dir.trim().is_empty() || self.install_dir.trim().is_empty() {
+            *self.resume_check.lock().expect("lock resume check") = ResumeCheck::Idle;
+            return;
+        }
+        let key: ResumeKey = (
+            wabbajack,
+            PathBuf::from(self.downloads_dir.trim()),
+            PathBuf::from(self.install_dir.trim()),
+        );
+        {
+            let mut check = self.resume_check.lock().expect("lock resume check");
+            if check.key() == Some(&key) {
+                return;
+            }
+            *check = ResumeCheck::Checking(key.clone());
+        }
+        let check = Arc::clone(&self.resume_check);
+        let ctx = ctx.clone();
+        self.rt().spawn(async move {
+            let found = tokio::task::spawn_blocking({
+                let (wabbajack, downloads, install) = key.clone();
+                move || interrupted_install(&wabbajack, &downloads, &install)
+            })
+            .await;
+            let found = match found {
+                Ok(Ok(found)) => found,
+                Ok(Err(e)) => {
+                    tracing::warn!("Could not check for an unfinished install: {:#}", e);
+                    None
+                }
+                Err(_) => None,
+            };
+            let mut state = check.lock().expect("lock resume check");
+            // Another list or dir was picked meanwhile.
+            if state.key() != Some(&key) {
+                return;
+            }
+            *state = ResumeCheck::Done(key, found);
+            ctx.request_repaint();
+        });
+    }
+
+    /// "Resume" / "Start Fresh" choice when the chosen dirs hold an
+    /// unfinished install of this list.
+    fn render_resume_choice(&mut self, ui: &mut egui::Ui) {
+        let status = match &*self.resume_check.lock().expect("lock resume check") {
+            ResumeCheck::Done(_, Some(status)) => status.clone(),
+            _ => {
+                if self.fresh_install {
+                    self.fresh_install = false;
+                    self.generated_command = None;
+                }
+                return;
+            }
+        };
+
+        let percent = |done: usize, total: usize| {
+            if total == 0 {
+                100
+            } else {
+                done * 100 / total
+            }
+        };
+        let mut progress = format!(
+            "An earlier install of this list didn't finish: {} of {} files installed ({}%)",
+            status.directives_done,
+            status.directives_total,
+            percent(status.directives_done, status.directives_total)
+        );
+        if !status.missing_archives.is_empty() {
+            progress.push_str(&format!(
+                ", {} of {} archives downloaded ({} left)",
+                status.archives_present,
+                status.archives_total,
+                Self::format_size(status.download_bytes_remaining)
+            ));
+        }
+        progress.push('.');
+
+        ui.label(
+            egui::RichText::new(progress)
+                .size(11.0)
+                .color(egui::Color32::from_rgb(220, 180, 60)),
+        );
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            changed |= ui
+                .radio_value(&mut self.fresh_install, false, "Resume")
+                .on_hover_text("Pick up where it stopped. Finished files are kept.")
+                .changed();
+            changed |= ui
+                .radio_value(&mut self.fresh_install, true, "Start Fresh")
+                .on_hover_text(
+                    "Forget the saved progress and check every existing file by hash. \
+                     Downloads are kept.",
+                )
+                .changed();
+        });
+        if changed {
+            self.generated_command = None;
+        }
+    }
+
+    /// Whether installs count as metered: the setting, or NetworkManager
+    /// when detection is on. Mirrors the CLI's own check.
+    fn is_metered(&self) -> bool {
+        !self.offline && (self.settings.metered_connection || self.network_metered == Some(true))
+    }
+
+    /// On a metered connection, show how much the install will download and
+    /// ask for confirmation. Returns whether Run may start the install.
+    ///
+    /// The size comes from an unfinished install's progress, else the
+    /// gallery. When neither knows it (a local file with nothing downloaded
+    /// yet), the install itself shows it and asks in the terminal.
+    fn render_metered_confirm(
+        &mut self,
+        ui: &mut egui::Ui,
+        modlist: Option<&ModlistMetadata>,
+    ) -> bool {
+        if !self.is_metered() {
+            if self.metered_confirmed {
+                self.metered_confirmed = false;
+                self.generated_command = None;
+            }
+            return true;
+        }
+
+        let remaining = match &*self.resume_check.lock().expect("lock resume check") {
+            ResumeCheck::Done(_, Some(status)) => Some(status.download_bytes_remaining),
+            _ => None,
+        };
+        let size = remaining.or_else(|| modlist.map(|m| m.download_size()));
+        let source = if self.settings.metered_connection {
+            "Metered connection"
+        } else {
+            "Metered connection (reported by NetworkManager)"
+        };
+        ui.label(
+            egui::RichText::new(format!(
+                "{}: downloads run {} at a time at up to {}/s.",
+                source,
+                METERED_CONCURRENT_DOWNLOADS,
+                Self::format_size(METERED_SPEED_LIMIT)
+            ))
+            .size(11.0)
+            .color(egui::Color32::from_rgb(220, 180, 60)),
+        );
+
+        let Some(size) = size else {
+            ui.label(
+                egui::RichText::new(
+                    "The install will show the download size and ask before downloading.",
+                )
+                .size(11.0)
+                .color(egui::Color32::from_gray(160)),
+            );
+            return true;
+        };
+        let confirm = ui.checkbox(
+            &mut self.metered_confirmed,
+            format!(
+                "Download up to {} over this connection",
+                Self::format_size(size)
+            ),
+        );
+        if confirm.changed() {
+            self.generated_command = None;
+        }
+        self.metered_confirmed
+    }
+
+    /// Profile to pass as `--profile`. When "Author default" is picked the
+    /// recommended profile is passed explicitly, otherwise the installer
+    /// would reuse the one remembered from the last install.
+    fn profile_arg(&self) -> Option<String> {
+        let scan = self.profile_scan.lock().expect("lock profile scan");
+        let ProfileScan::Done { path, profiles, .. } = &*scan else {
+            return None;
+        };
+        if self.profile_pick_for.as_deref() != Some(path.as_path()) || !profiles.has_choice() {
+            return None;
+        }
+        self.selected_profile
+            .clone()
+            .or_else(|| profiles.recommended.clone())
+    }
+
+    /// "MO2 profile" dropdown for lists that ship presets.
+    fn render_profile_picker(&mut self, ui: &mut egui::Ui) {
+        let (path, profiles, cached) = match &*self.profile_scan.lock().expect("lock profile scan")
+        {
+            ProfileScan::Done {
+                path,
+                profiles,
+                cached,
+            } => (path.clone(), profiles.clone(), cached.clone()),
+            ProfileScan::Scanning(_) => {
+                ui.label(
+                    egui::RichText::new("Reading MO2 profiles...")
+                        .size(11.0)
+                        .color(egui::Color32::from_gray(160)),
+                );
+                return;
+            }
+            ProfileScan::Idle | ProfileScan::Failed(_) => return,
+        };
+        if self.profile_pick_for.as_ref() != Some(&path) {
+            self.selected_profile = cached;
+            self.profile_pick_for = Some(path);
+            self.generated_command = None;
+        }
+        if !profiles.has_choice() {
+            return;
+        }
+
+        let author = format!(
+            "Author default ({})",
+            profiles.recommended.as_deref().unwrap_or("as shipped")
+        );
+        let selected_text = self
+            .selected_profile
+            .clone()
+            .unwrap_or_else(|| author.clone());
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("MO2 profile:");
+            egui::ComboBox::from_id_salt("mo2_profile")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    changed |= ui
+                        .selectable_value(&mut self.selected_profile, None, author)
+                        .changed();
+                    for name in &profiles.names {
+                        changed |= ui
+                            .selectable_value(&mut self.selected_profile, Some(name.clone()), name)
+                            .changed();
+                    }
+                })
+                .response
+                .on_hover_text(
+                    "This modlist ships several presets as MO2 profiles. The one \
+                     picked here is what MO2 opens with after the install.",
+                );
+        });
+        if changed {
+            self.generated_command = None;
+        }
+    }
+}
+
+/// Profiles of a .wabbajack and the one picked for it last time.
+fn read_profile_choice(path: &Path) -> anyhow::Result<(ModlistProfiles, Option<String>)> {
+    let modlist = crate::modlist::parse_wabbajack_file(path)?;
+    let profiles = read_profiles(path, &modlist)?;
+    let cached = crate::installer::config_cache::ConfigCache::open()
+        .and_then(|cache| cache.get_latest_config(&modlist.name))
+        .ok()
+        .flatten()
+        .and_then(|c| c.profile_name)
+        .filter(|name| profiles.names.contains(name));
+    Ok((profiles, cached))
 }
 
 impl eframe::App for BrowserApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // Kick off fetch on first frame.
+        // Kick off fetch and the launcher scan on first frame.
         self.start_fetch(ctx);
+        self.start_game_scan(ctx, false);
+        if std::mem::take(&mut self.verify_env_key) {
+            self.verify_nexus(ctx, false);
+        }
+
+        let scanned = self
+            .shared
+            .lock()
+            .expect("lock shared state")
+            .installed_games
+            .take();
+        if let Some(installed) = scanned {
+            self.installed_game_types = installed.types;
+            self.installed_game_dirs = installed.dirs;
+            self.installed_game_count = installed.count;
+            self.game_scan_done = true;
+        }
+
+        // Refetch a gallery that got older than the cache max age while
+        // the browser was open.
+        if !self.offline {
+            let due = {
+                let state = self.shared.lock().expect("lock shared state");
+                state.fetch_done
+                    && state.fetch_error.is_none()
+                    && !state.refreshing
+                    && state.refresh_error.is_none()
+                    && state
+                        .gallery_updated
+                        .and_then(|t| t.elapsed().ok())
+                        .is_some_and(|age| age.as_secs() >= CACHE_MAX_AGE_SECS)
+            };
+            if due {
+                self.start_gallery_refresh(ctx);
+            }
+        }
 
         // Once modlists are loaded, start image loading.
         {
-            let state = self.shared.lock().expect("lock shared state");
+            let mut state = self.shared.lock().expect("lock shared state");
+            if std::mem::take(&mut state.gallery_refreshed) {
+                self.image_load_started = false;
+            }
             if state.fetch_done && !state.modlists.is_empty() && !self.image_load_started {
                 drop(state);
                 self.start_image_loading(ctx);
             }
         }
 
-        // Convert a small number of downloaded images to GPU textures each
-        // frame. Decoding every ready image in one pass makes the browser feel
-        // frozen on launch when many thumbnails arrive together.
-        let images_to_convert: Vec<(String, Vec<u8>)> = {
+        // Upload a small number of decoded thumbnails as GPU textures each
+        // frame. Uploading every ready image in one pass makes the browser
+        // feel frozen on launch when many thumbnails arrive together.
+        let images_to_convert: Vec<(String, egui::ColorImage)> = {
             let mut state = self.shared.lock().expect("lock shared state");
             let keys_to_convert: Vec<String> = state
                 .images
                 .iter()
                 .filter_map(|(k, v)| {
-                    if matches!(v, ImageState::Downloaded(_)) {
+                    if matches!(v, ImageState::Decoded(_)) {
                         Some(k.clone())
                     } else {
                         None
@@ -650,7 +1460,7 @@ impl eframe::App for BrowserApp {
             keys_to_convert
                 .into_iter()
                 .filter_map(|key| match state.images.remove(&key) {
-                    Some(ImageState::Downloaded(bytes)) => Some((key, bytes)),
+                    Some(ImageState::Decoded(image)) => Some((key, image)),
                     other => {
                         if let Some(state_value) = other {
                             state.images.insert(key, state_value);
@@ -661,28 +1471,16 @@ impl eframe::App for BrowserApp {
                 .collect()
         };
 
-        for (key, bytes) in images_to_convert {
-            let converted = match image::load_from_memory(&bytes) {
-                Ok(img) => {
-                    let rgba = img.to_rgba8();
-                    let size = [rgba.width() as usize, rgba.height() as usize];
-                    let pixels = rgba.into_raw();
-                    let color_image = egui::ColorImage::from_rgba_unmultiplied(size, &pixels);
-                    ImageState::Texture(ctx.load_texture(
-                        &key,
-                        color_image,
-                        egui::TextureOptions::LINEAR,
-                    ))
-                }
-                Err(_) => ImageState::Failed,
-            };
+        for (key, image) in images_to_convert {
+            let converted =
+                ImageState::Texture(ctx.load_texture(&key, image, egui::TextureOptions::LINEAR));
 
             let mut state = self.shared.lock().expect("lock shared state");
             state.images.insert(key, converted);
             if state
                 .images
                 .values()
-                .any(|v| matches!(v, ImageState::Downloaded(_)))
+                .any(|v| matches!(v, ImageState::Decoded(_)))
             {
                 ctx.request_repaint();
             }
@@ -737,6 +1535,13 @@ impl eframe::App for BrowserApp {
             });
             self.select_modlist(name);
         }
+
+        let pending_favorite: Option<String> =
+            ctx.memory_mut(|mem| mem.data.get_temp(egui::Id::new("pending_favorite")));
+        if let Some(name) = pending_favorite {
+            ctx.memory_mut(|mem| mem.data.remove::<String>(egui::Id::new("pending_favorite")));
+            self.toggle_favorite(name);
+        }
     }
 }
 
@@ -748,11 +1553,20 @@ impl BrowserApp {
             let old_show_nsfw = self.show_nsfw;
             let old_show_unavailable = self.show_unavailable;
             let old_show_installed_only = self.show_installed_only;
+            let old_show_favorites_only = self.show_favorites_only;
+            let old_sort = self.sort;
 
             ui.add_space(8.0);
             ui.horizontal(|ui| {
                 ui.label("Search:");
-                ui.add(egui::TextEdit::singleline(&mut self.search).desired_width(300.0));
+                let search = ui.add(
+                    egui::TextEdit::singleline(&mut self.search)
+                        .id(egui::Id::new(SEARCH_BOX_ID))
+                        .desired_width(300.0),
+                );
+                if std::mem::take(&mut self.focus_search) {
+                    search.request_focus();
+                }
 
                 ui.separator();
 
@@ -766,7 +1580,7 @@ impl BrowserApp {
                             // When the installed-only filter is on, hide games
                             // from the dropdown that the user doesn't actually
                             // own — dropdown and list view stay in sync.
-                            if self.show_installed_only {
+                            if self.show_installed_only && self.game_scan_done {
                                 self.installed_game_types.contains(&g.to_lowercase())
                             } else {
                                 true
@@ -804,14 +1618,32 @@ impl BrowserApp {
                         }
                     });
 
+                ui.label("Sort:");
+                egui::ComboBox::from_id_salt("modlist_sort")
+                    .selected_text(self.sort.name())
+                    .show_ui(ui, |ui| {
+                        for sort in ModlistSort::ALL {
+                            ui.selectable_value(&mut self.sort, sort, sort.name());
+                        }
+                    });
+
                 ui.separator();
                 ui.checkbox(&mut self.show_nsfw, "NSFW");
                 ui.checkbox(&mut self.show_unavailable, "Unavailable");
+                ui.checkbox(
+                    &mut self.show_favorites_only,
+                    format!(
+                        "Favorites only ({})",
+                        self.settings.browser_favorite_modlists.len()
+                    ),
+                );
 
                 // "Installed only" is disabled when we didn't detect any
                 // supported launcher installs — keeps the hint visible to the
                 // user but prevents toggling into an empty list.
-                let installed_label = if self.installed_game_count > 0 {
+                let installed_label = if !self.game_scan_done {
+                    "Installed only (scanning...)".to_string()
+                } else if self.installed_game_count > 0 {
                     format!("Installed only ({} games)", self.installed_game_types.len())
                 } else {
                     "Installed only (no games detected)".to_string()
@@ -820,9 +1652,16 @@ impl BrowserApp {
                     !self.installed_game_types.is_empty(),
                     egui::Checkbox::new(&mut self.show_installed_only, installed_label),
                 );
-                if self.installed_game_types.is_empty() {
+                if self.game_scan_done && self.installed_game_types.is_empty() {
                     self.show_installed_only = false;
                 }
+                if ui
+                    .add_enabled(self.game_scan_done, egui::Button::new("Rescan"))
+                    .on_hover_text("Scan Steam and Heroic again for newly installed games.")
+                    .clicked()
+                {
+                    self.start_game_scan(ctx, true);
+                }
 
                 ui.separator();
 
@@ -880,9 +1719,19 @@ impl BrowserApp {
                 || self.show_nsfw != old_show_nsfw
                 || self.show_unavailable != old_show_unavailable
                 || self.show_installed_only != old_show_installed_only
+                || self.show_favorites_only != old_show_favorites_only
+                || self.sort != old_sort
             {
                 self.remember_browser_state();
             }
+
+            // Thumbnails of lists these filters hid were never fetched.
+            if self.image_load_started
+                && (self.show_nsfw != old_show_nsfw
+                    || self.show_unavailable != old_show_unavailable)
+            {
+                self.start_image_loading(ctx);
+            }
         });
     }
 
@@ -992,17 +1841,34 @@ impl BrowserApp {
                         ui.label("Local .wabbajack file");
                     }
 
-                    if ui.button("X Close").clicked() {
-                        self.remember_current_list_paths();
-                        self.selected = None;
-                        self.local_wabbajack = None;
-                        self.generated_command = None;
-                        self.run_status = None;
-                        self.settings.browser_last_selected_modlist = None;
-                        let _ = self.settings.save();
+                    if ui.button("X Close").on_hover_text("Esc").clicked() {
+                        self.close_selection();
                     }
                 });
 
+                if selected_modlist.is_none() && self.local_wabbajack.is_none() {
+                    if let Some(name) = &self.selected {
+                        let removed = self
+                            .shared
+                            .lock()
+                            .expect("lock shared state")
+                            .removed_modlists
+                            .contains(name);
+                        let msg = if removed {
+                            format!(
+                                "{} is no longer available in the Wabbajack gallery; its \
+                                 author may have taken it down. Pick another list, or use \
+                                 \"Open .wabbajack file...\" if you still have the file.",
+                                name
+                            )
+                        } else {
+                            format!("{} is not in the modlist gallery.", name)
+                        };
+                        ui.colored_label(egui::Color32::from_rgb(220, 140, 50), msg);
+                        self.generated_command = None;
+                    }
+                }
+
                 if let Some(path) = &self.local_wabbajack {
                     ui.label(
                         egui::RichText::new(path.display().to_string())
@@ -1065,6 +1931,36 @@ impl BrowserApp {
                     }
                 });
 
+                let purge = ui
+                    .checkbox(
+                        &mut self.purge_downloads,
+                        "Delete unused downloads after a successful install",
+                    )
+                    .on_hover_text(
+                        "Frees space by deleting files in the downloads folder this \
+                         modlist doesn't use. The space freed is shown in the install \
+                         log. Leave off if the folder is shared with other modlists.",
+                    );
+                if purge.changed() {
+                    self.generated_command = None;
+                }
+
+                match self.profile_source(selected_modlist.as_ref()) {
+                    Some(source) => {
+                        self.start_profile_scan(ctx, source.clone());
+                        self.render_profile_picker(ui);
+                        self.start_resume_check(ctx, source);
+                    }
+                    None => {
+                        *self.profile_scan.lock().expect("lock profile scan") = ProfileScan::Idle;
+                        *self.resume_check.lock().expect("lock resume check") = ResumeCheck::Idle;
+                        self.profile_pick_for = None;
+                        self.selected_profile = None;
+                    }
+                }
+                self.render_resume_choice(ui);
+                let metered_ready = self.render_metered_confirm(ui, selected_modlist.as_ref());
+
                 ui.add_space(4.0);
 
                 // Build the install command from whichever source is set.
@@ -1074,7 +1970,7 @@ impl BrowserApp {
                     let source = path.display().to_string();
                     (
                         self.generate_command_for_local(&path),
-                        self.build_install_args(&source),
+                        self.build_install_args(&source, None),
                     )
                 } else if let Some(modlist) = &selected_modlist {
                     let source_owned = modlist.download_url().map(|s| s.to_string());
@@ -1083,7 +1979,7 @@ impl BrowserApp {
                         .and_then(|_| self.generate_command(modlist));
                     let args = source_owned
                         .as_ref()
-                        .and_then(|s| self.build_install_args(s));
+                        .and_then(|s| self.build_install_args(s, modlist.download_hash()));
                     (display, args)
                 } else {
                     (None, None)
@@ -1094,16 +1990,20 @@ impl BrowserApp {
                 }
 
                 if let Some(ref cmd) = self.generated_command.clone() {
+                    let mut validate_clicked = false;
                     ui.horizontal(|ui| {
                         ui.label("Command:");
                         ui.add(
                             egui::TextEdit::singleline(&mut cmd.clone())
-                                .desired_width(ui.available_width() - 220.0)
+                                .desired_width(ui.available_width() - 340.0)
                                 .font(egui::TextStyle::Monospace),
                         );
 
                         let run_clicked = ui
-                            .add_enabled(spawn_args.is_some(), egui::Button::new("Run"))
+                            .add_enabled(
+                                spawn_args.is_some() && metered_ready,
+                                egui::Button::new("Run"),
+                            )
                             .on_hover_text(
                                 "Launches the install in a new terminal window and closes \
                                  the browser. Falls back to the parent terminal if no \
@@ -1116,6 +2016,18 @@ impl BrowserApp {
                             self.run_status = Some((true, "Copied to clipboard.".into()));
                         }
 
+                        let checks_running = matches!(
+                            *self.setup_checks.lock().expect("lock setup checks"),
+                            SetupCheckStatus::Running
+                        );
+                        validate_clicked = ui
+                            .add_enabled(!checks_running, egui::Button::new("Validate Setup"))
+                            .on_hover_text(
+                                "Checks the game install, directories, disk space, Nexus \
+                                 key and Proton without starting the install.",
+                            )
+                            .clicked();
+
                         if run_clicked {
                             if let Some((exe, args)) = spawn_args {
                                 match launch_install(&exe, &args) {
@@ -1147,24 +2059,526 @@ impl BrowserApp {
                         }
                     });
 
-                    if let Some((ok, ref msg)) = self.run_status {
-                        let color = if ok {
-                            egui::Color32::from_rgb(50, 180, 50)
-                        } else {
-                            egui::Color32::RED
-                        };
-                        ui.colored_label(color, msg);
+                    if let Some((ok, ref msg)) = self.run_status {
+                        let color = if ok {
+                            egui::Color32::from_rgb(50, 180, 50)
+                        } else {
+                            egui::Color32::RED
+                        };
+                        ui.colored_label(color, msg);
+                    }
+
+                    if validate_clicked {
+                        self.start_setup_checks(ctx, selected_modlist.as_ref());
+                    }
+                    self.render_setup_checks(ui);
+                } else if !self.downloads_dir.is_empty() || !self.install_dir.is_empty() {
+                    ui.label("Fill in both directories to generate the install command.");
+                } else {
+                    ui.label(
+                        "Enter your downloads and install directories to generate the command.",
+                    );
+                }
+
+                self.render_verify_install(ui);
+
+                ui.add_space(4.0);
+            });
+    }
+
+    fn reset_verify(&mut self) {
+        let mut verify = self.verify.lock().expect("lock verify");
+        if !matches!(*verify, VerifyStatus::Running { .. }) {
+            *verify = VerifyStatus::Idle;
+        }
+        self.verify_export_message = None;
+    }
+
+    /// Re-hash a finished install against its modlist in the background.
+    /// The result also goes to the session log.
+    fn start_verify_install(&mut self, ctx: &egui::Context, install_dir: PathBuf) {
+        let cancel = CancellationToken::new();
+        *self.verify.lock().expect("lock verify") = VerifyStatus::Running {
+            progress: CheckProgress::default(),
+            cancel: cancel.clone(),
+        };
+        self.verify_export_message = None;
+        let status = Arc::clone(&self.verify);
+        let ctx = ctx.clone();
+        self.rt().spawn(async move {
+            let progress_status = Arc::clone(&status);
+            let progress_ctx = ctx.clone();
+            let dir = install_dir.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                check_install(
+                    &dir,
+                    None,
+                    &|p| {
+                        if let VerifyStatus::Running { progress, .. } =
+                            &mut *progress_status.lock().expect("lock verify")
+                        {
+                            *progress = p;
+                        }
+                        progress_ctx.request_repaint();
+                    },
+                    &cancel,
+                )
+            })
+            .await;
+
+            let next = match result {
+                Ok(Ok((name, report))) => {
+                    tracing::info!(
+                        "Verify Install of {}:\n{}",
+                        install_dir.display(),
+                        report.render(&name)
+                    );
+                    VerifyStatus::Done { name, report }
+                }
+                Ok(Err(e)) => {
+                    tracing::warn!(
+                        "Verify Install of {} failed: {:#}",
+                        install_dir.display(),
+                        e
+                    );
+                    VerifyStatus::Failed(format!("{:#}", e))
+                }
+                Err(e) => VerifyStatus::Failed(format!("Verify task failed: {}", e)),
+            };
+            *status.lock().expect("lock verify") = next;
+            ctx.request_repaint();
+        });
+    }
+
+    /// "Verify Install" for an install dir CLF3 finished: progress while
+    /// hashing, then pass/fail counts and the files that don't match.
+    fn render_verify_install(&mut self, ui: &mut egui::Ui) {
+        let install_dir = PathBuf::from(self.install_dir.trim());
+        if self.install_dir.trim().is_empty()
+            || !install_dir
+                .join(crate::modlist::MANIFEST_FILENAME)
+                .is_file()
+        {
+            return;
+        }
+
+        ui.add_space(4.0);
+        ui.separator();
+        let running = matches!(
+            *self.verify.lock().expect("lock verify"),
+            VerifyStatus::Running { .. }
+        );
+        if ui
+            .add_enabled(!running, egui::Button::new("Verify Install"))
+            .on_hover_text(
+                "Re-hash the installed files against the modlist. Read-only: \
+                 nothing is changed. Results are also written to the log.",
+            )
+            .clicked()
+        {
+            self.start_verify_install(ui.ctx(), install_dir.clone());
+        }
+
+        let mut export: Option<String> = None;
+        match &*self.verify.lock().expect("lock verify") {
+            VerifyStatus::Idle => {}
+            VerifyStatus::Running { progress, cancel } => {
+                let fraction = if progress.bytes_total > 0 {
+                    progress.bytes_done as f32 / progress.bytes_total as f32
+                } else {
+                    0.0
+                };
+                ui.horizontal(|ui| {
+                    ui.add(egui::ProgressBar::new(fraction).text(format!(
+                        "Hashing {} / {} ({} / {} files)",
+                        Self::format_size(progress.bytes_done),
+                        Self::format_size(progress.bytes_total),
+                        progress.files_done,
+                        progress.files_total
+                    )));
+                    if cancel.is_cancelled() {
+                        ui.label("Stopping...");
+                    } else if ui
+                        .button("Cancel")
+                        .on_hover_text("Stop hashing and show the files checked so far")
+                        .clicked()
+                    {
+                        cancel.cancel();
+                    }
+                });
+            }
+            VerifyStatus::Failed(msg) => {
+                ui.colored_label(egui::Color32::RED, format!("Verify failed: {}", msg));
+            }
+            VerifyStatus::Done { name, report } => {
+                let bad = report.issues.len();
+                ui.horizontal(|ui| {
+                    if report.unchecked > 0 {
+                        ui.colored_label(
+                            egui::Color32::from_rgb(220, 140, 50),
+                            format!(
+                                "Cancelled: {} of {} files checked.",
+                                report.checked,
+                                report.checked + report.unchecked
+                            ),
+                        );
+                    }
+                    if bad == 0 {
+                        ui.colored_label(
+                            egui::Color32::from_rgb(50, 180, 50),
+                            format!("All {} files match the modlist.", report.checked),
+                        );
+                    } else {
+                        ui.colored_label(
+                            egui::Color32::RED,
+                            format!(
+                                "{} OK, {} bad: {} missing, {} corrupted, {} edited after install",
+                                report.checked - bad,
+                                bad,
+                                report.count(IssueKind::Missing),
+                                report.count(IssueKind::Corrupted),
+                                report.count(IssueKind::Modified)
+                            ),
+                        );
+                    }
+                    if ui.button("Export...").clicked() {
+                        export = Some(report.render(name));
+                    }
+                });
+                if bad > 0 {
+                    egui::ScrollArea::vertical()
+                        .id_salt("verify_issues")
+                        .max_height(160.0)
+                        .show(ui, |ui| {
+                            for issue in &report.issues {
+                                let kind = match issue.kind {
+                                    IssueKind::Missing => "missing",
+                                    IssueKind::Corrupted => "corrupted",
+                                    IssueKind::Modified => "edited",
+                                };
+                                ui.label(
+                                    egui::RichText::new(format!("{:<10} {}", kind, issue.path))
+                                        .monospace()
+                                        .size(11.0),
+                                );
+                            }
+                        });
+                    ui.label(
+                        egui::RichText::new(
+                            "Run the install again to restore missing and corrupted files.",
+                        )
+                        .color(egui::Color32::GRAY),
+                    );
+                }
+            }
+        }
+
+        if let Some(text) = export {
+            if let Some(path) = rfd::FileDialog::new()
+                .set_file_name("clf3-verify.txt")
+                .set_directory(&install_dir)
+                .save_file()
+            {
+                self.verify_export_message = Some(match std::fs::write(&path, text) {
+                    Ok(()) => (true, format!("Saved to {}", path.display())),
+                    Err(e) => (false, format!("Export failed: {}", e)),
+                });
+            }
+        }
+        if let Some((ok, msg)) = &self.verify_export_message {
+            let color = if *ok {
+                egui::Color32::from_rgb(50, 180, 50)
+            } else {
+                egui::Color32::RED
+            };
+            ui.colored_label(color, msg);
+        }
+    }
+
+    /// Run the setup checks for the current selection in the background.
+    /// Local `.wabbajack` files are parsed first to get the game and sizes.
+    fn start_setup_checks(&mut self, ctx: &egui::Context, modlist: Option<&ModlistMetadata>) {
+        let default_game_dir = self.settings.default_game_path();
+        let game_dirs = self.installed_game_dirs.clone();
+        let game_dir_for = move |wj: &str| {
+            default_game_dir
+                .clone()
+                .or_else(|| game_dirs.get(&wj.to_lowercase()).cloned())
+        };
+
+        let mut input = SetupCheckInput {
+            downloads_dir: PathBuf::from(self.downloads_dir.trim()),
+            install_dir: PathBuf::from(self.install_dir.trim()),
+            nexus_api_key: self.settings.nexus_api_key.clone(),
+            ttw_configured: self.settings.has_ttw_config(),
+            ..Default::default()
+        };
+        if let Some(m) = modlist {
+            input.game = GameType::from_wabbajack_name(&m.game);
+            input.game_dir = game_dir_for(&m.game);
+            input.download_size = m.download_size();
+            input.install_size = m.installed_size();
+            input.file_count = m
+                .download_metadata
+                .as_ref()
+                .map_or(0, |d| d.number_of_installed_files.into());
+            input.requires_ttw = m.tags.iter().any(|t| t.eq_ignore_ascii_case("ttw"));
+            input.runtimes = crate::installer::requirements::runtimes_in_text(&m.description);
+        }
+        let local = self.local_wabbajack.clone();
+
+        *self.setup_checks.lock().expect("lock setup checks") = SetupCheckStatus::Running;
+        let status = Arc::clone(&self.setup_checks);
+        let ctx = ctx.clone();
+        self.rt().spawn(async move {
+            if let Some(path) = local {
+                let parsed = tokio::task::spawn_blocking(move || {
+                    crate::modlist::parse_wabbajack_file(&path)
+                })
+                .await;
+                if let Ok(Ok(modlist)) = parsed {
+                    input.apply_modlist(&modlist);
+                    input.game_dir = game_dir_for(&modlist.game_type);
+                }
+            }
+            let checks = run_setup_checks(&input).await;
+            *status.lock().expect("lock setup checks") = SetupCheckStatus::Done(checks);
+            ctx.request_repaint();
+        });
+    }
+
+    /// Run the texture encoder benchmark in the background.
+    fn start_benchmark(&self, ctx: &egui::Context) {
+        *self.benchmark.lock().expect("lock benchmark") = BenchmarkStatus::Running;
+        let status = Arc::clone(&self.benchmark);
+        let ctx = ctx.clone();
+        self.rt().spawn(async move {
+            let results = tokio::task::spawn_blocking(run_benchmark)
+                .await
+                .unwrap_or_default();
+            *status.lock().expect("lock benchmark") = BenchmarkStatus::Done(results);
+            ctx.request_repaint();
+        });
+    }
+
+    /// Scan for leftovers in the downloads dir and the install dirs of the
+    /// panel and of recorded installs.
+    fn start_clean_scan(&self, ctx: &egui::Context) {
+        let downloads = match self.downloads_dir.trim() {
+            "" => self.settings.default_downloads_path(),
+            dir => Some(PathBuf::from(dir)),
+        };
+        let Some(downloads) = downloads else {
+            *self.clean.lock().expect("lock clean") =
+                CleanStatus::Failed("Set a downloads directory first".into());
+            return;
+        };
+        let mut temp_dirs: Vec<PathBuf> = self
+            .settings
+            .installed_modlists
+            .values()
+            .map(|record| PathBuf::from(&record.install_dir))
+            .chain(
+                Some(PathBuf::from(self.install_dir.trim())).filter(|d| !d.as_os_str().is_empty()),
+            )
+            .collect();
+        temp_dirs.sort();
+        temp_dirs.dedup();
+        temp_dirs.retain(|dir| *dir != downloads);
+        let settings = self.settings.clone();
+        let unreferenced = self.clean_unreferenced;
+
+        *self.clean.lock().expect("lock clean") = CleanStatus::Scanning;
+        let status = Arc::clone(&self.clean);
+        let ctx = ctx.clone();
+        self.rt().spawn(async move {
+            let result = tokio::task::spawn_blocking(move || {
+                let known = known_archives(&[], &settings);
+                if unreferenced && known.is_empty() {
+                    anyhow::bail!(
+                        "No recorded installs to keep archives for; \
+                         unused archives can't be told apart"
+                    );
+                }
+                let temp_dirs: Vec<&Path> = temp_dirs.iter().map(|d| d.as_path()).collect();
+                Ok(find_stale(&downloads, &temp_dirs, &known, unreferenced)?)
+            })
+            .await;
+            let next = match result {
+                Ok(Ok(stale)) => CleanStatus::Scanned(stale),
+                Ok(Err(e)) => CleanStatus::Failed(format!("{:#}", e)),
+                Err(e) => CleanStatus::Failed(format!("Scan task failed: {}", e)),
+            };
+            *status.lock().expect("lock clean") = next;
+            ctx.request_repaint();
+        });
+    }
+
+    /// Delete what the last scan found.
+    fn start_clean(&self, ctx: &egui::Context, stale: Vec<StaleEntry>) {
+        *self.clean.lock().expect("lock clean") = CleanStatus::Cleaning;
+        let status = Arc::clone(&self.clean);
+        let ctx = ctx.clone();
+        self.rt().spawn(async move {
+            let next = match tokio::task::spawn_blocking(move || remove_stale(&stale)).await {
+                Ok((removed, freed)) => CleanStatus::Done { removed, freed },
+                Err(e) => CleanStatus::Failed(format!("Cleanup task failed: {}", e)),
+            };
+            *status.lock().expect("lock clean") = next;
+            ctx.request_repaint();
+        });
+    }
+
+    /// "Scan" button, then what was found and a button to delete it.
+    fn render_clean(&mut self, ui: &mut egui::Ui) {
+        let busy = matches!(
+            *self.clean.lock().expect("lock clean"),
+            CleanStatus::Scanning | CleanStatus::Cleaning
+        );
+        ui.checkbox(
+            &mut self.clean_unreferenced,
+            "Also delete archives no installed modlist uses",
+        );
+        ui.horizontal(|ui| {
+            if ui.add_enabled(!busy, egui::Button::new("Scan")).clicked() {
+                self.start_clean_scan(ui.ctx());
+            }
+            if busy {
+                ui.spinner();
+            }
+        });
+
+        let mut delete = None;
+        match &*self.clean.lock().expect("lock clean") {
+            CleanStatus::Idle | CleanStatus::Scanning | CleanStatus::Cleaning => {}
+            CleanStatus::Scanned(stale) if stale.is_empty() => {
+                ui.label("Nothing to clean.");
+            }
+            CleanStatus::Scanned(stale) => {
+                let total: u64 = stale.iter().map(|e| e.size).sum();
+                egui::ScrollArea::vertical()
+                    .id_salt("clean_entries")
+                    .max_height(160.0)
+                    .show(ui, |ui| {
+                        for entry in stale {
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "{}  ({}, {})",
+                                    entry.path.display(),
+                                    entry.kind,
+                                    Self::format_size(entry.size)
+                                ))
+                                .size(11.0),
+                            );
+                        }
+                    });
+                if ui
+                    .button(format!(
+                        "Delete {} entries ({})",
+                        stale.len(),
+                        Self::format_size(total)
+                    ))
+                    .clicked()
+                {
+                    delete = Some(stale.clone());
+                }
+            }
+            CleanStatus::Done { removed, freed } => {
+                ui.colored_label(
+                    egui::Color32::from_rgb(50, 180, 50),
+                    format!(
+                        "Deleted {} entries, freed {}",
+                        removed,
+                        Self::format_size(*freed)
+                    ),
+                );
+            }
+            CleanStatus::Failed(msg) => {
+                ui.colored_label(egui::Color32::RED, msg);
+            }
+        }
+        if let Some(stale) = delete {
+            self.start_clean(ui.ctx(), stale);
+        }
+    }
+
+    /// "Benchmark" button plus the throughput of each device from the last run.
+    fn render_benchmark(&self, ui: &mut egui::Ui) {
+        let running = matches!(
+            *self.benchmark.lock().expect("lock benchmark"),
+            BenchmarkStatus::Running
+        );
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(!running, egui::Button::new("Benchmark"))
+                .on_hover_text("Encode a sample texture on the CPU and each GPU")
+                .clicked()
+            {
+                self.start_benchmark(ui.ctx());
+            }
+            if running {
+                ui.spinner();
+                ui.label("Benchmarking...");
+            }
+        });
+        if let BenchmarkStatus::Done(results) = &*self.benchmark.lock().expect("lock benchmark") {
+            for r in results {
+                ui.horizontal_wrapped(|ui| match &r.error {
+                    None => {
+                        ui.label(
+                            egui::RichText::new(format!("{:.1} MP/s", r.megapixels_per_sec))
+                                .strong(),
+                        );
+                        ui.label(&r.device);
                     }
-                } else if !self.downloads_dir.is_empty() || !self.install_dir.is_empty() {
-                    ui.label("Fill in both directories to generate the install command.");
-                } else {
-                    ui.label(
-                        "Enter your downloads and install directories to generate the command.",
-                    );
-                }
+                    Some(e) => {
+                        ui.colored_label(egui::Color32::RED, "failed");
+                        ui.label(format!("{}: {}", r.device, e));
+                    }
+                });
+            }
+        }
+    }
 
+    /// Checklist from the last "Validate Setup" run, if any.
+    fn render_setup_checks(&self, ui: &mut egui::Ui) {
+        let status = self.setup_checks.lock().expect("lock setup checks");
+        match &*status {
+            SetupCheckStatus::Idle => {}
+            SetupCheckStatus::Running => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Checking setup...");
+                });
+            }
+            SetupCheckStatus::Done(checks) => {
                 ui.add_space(4.0);
-            });
+                for check in checks {
+                    let (mark, color) = if check.passed {
+                        ("OK", egui::Color32::from_rgb(50, 180, 50))
+                    } else {
+                        ("FAIL", egui::Color32::RED)
+                    };
+                    ui.horizontal_wrapped(|ui| {
+                        ui.colored_label(color, egui::RichText::new(mark).strong());
+                        ui.label(egui::RichText::new(check.name).strong());
+                        ui.label(&check.detail);
+                    });
+                    if let Some(fix) = &check.fix {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.add_space(24.0);
+                            ui.label(
+                                egui::RichText::new(format!("Fix: {}", fix))
+                                    .color(egui::Color32::GRAY),
+                            );
+                            if let Some(url) = check.fix_url {
+                                ui.hyperlink_to("More info", url);
+                            }
+                        });
+                    }
+                }
+            }
+        }
     }
 
     /// Browser tab: filters + install panel + modlist grid.
@@ -1173,31 +2587,111 @@ impl BrowserApp {
         self.render_install_panel(ctx);
 
         egui::CentralPanel::default().show(ctx, |ui| {
-            let fetch_done = {
+            let (fetch_done, fetch_error, fetch_retry) = {
                 let state = self.shared.lock().expect("lock shared state");
-                if let Some(ref err) = state.fetch_error {
-                    ui.colored_label(egui::Color32::RED, format!("Error: {}", err));
-                    return;
-                }
-                state.fetch_done
+                (
+                    state.fetch_done,
+                    state.fetch_error.clone(),
+                    state.fetch_retry.clone(),
+                )
             };
+            if let Some(err) = fetch_error {
+                ui.colored_label(egui::Color32::RED, format!("Error: {}", err));
+                if !self.offline && ui.button("Retry").clicked() {
+                    {
+                        let mut state = self.shared.lock().expect("lock shared state");
+                        state.fetch_error = None;
+                        state.fetch_done = false;
+                    }
+                    self.start_gallery_refresh(ctx);
+                }
+                return;
+            }
 
             if !fetch_done {
-                ui.centered_and_justified(|ui| {
+                ui.vertical_centered(|ui| {
+                    ui.add_space(ui.available_height() / 2.0 - 20.0);
                     ui.spinner();
+                    if let Some(retry) = fetch_retry {
+                        ui.label(
+                            egui::RichText::new(retry)
+                                .size(11.0)
+                                .color(egui::Color32::from_gray(160)),
+                        );
+                    }
                 });
                 return;
             }
 
             let filtered = self.filtered_modlists();
-            ui.label(format!("{} modlists", filtered.len()));
+            let focus_moved = self.handle_list_keys(ctx, &filtered);
+            let (updated, refreshing, refresh_error) = {
+                let state = self.shared.lock().expect("lock shared state");
+                (
+                    state.gallery_updated,
+                    state.refreshing,
+                    state.refresh_error.clone(),
+                )
+            };
+            let mut refresh_clicked = false;
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "{} modlists{}  (Up/Down to browse, Enter to select)",
+                    filtered.len(),
+                    if self.offline {
+                        " (offline, cached)"
+                    } else {
+                        ""
+                    }
+                ));
+                if let Some(age) = updated.and_then(|t| t.elapsed().ok()) {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "Gallery updated {}",
+                            format_age(age.as_secs())
+                        ))
+                        .size(11.0)
+                        .color(egui::Color32::from_gray(160)),
+                    );
+                }
+                if refreshing {
+                    ui.spinner();
+                    if let Some(retry) = &fetch_retry {
+                        ui.label(
+                            egui::RichText::new(retry)
+                                .size(11.0)
+                                .color(egui::Color32::from_rgb(220, 140, 50)),
+                        );
+                    }
+                } else if !self.offline {
+                    refresh_clicked = ui
+                        .small_button("Refresh")
+                        .on_hover_text("Fetch the modlist gallery again")
+                        .clicked();
+                }
+                if let Some(err) = refresh_error {
+                    let (reason, detail) = err.split_once(": ").unwrap_or((err.as_str(), ""));
+                    ui.colored_label(
+                        egui::Color32::from_rgb(220, 140, 50),
+                        format!("Showing cached gallery, live fetch failed: {}", reason),
+                    )
+                    .on_hover_text(detail);
+                }
+            });
+            if refresh_clicked {
+                self.start_gallery_refresh(ctx);
+            }
             ui.add_space(4.0);
 
             egui::ScrollArea::vertical()
                 .auto_shrink([false, false])
                 .show(ui, |ui| {
-                    for modlist in &filtered {
-                        self.render_row(ui, modlist);
+                    for (index, modlist) in filtered.iter().enumerate() {
+                        let focused = self.focused_index == Some(index);
+                        let row = self.render_row(ui, modlist, focused);
+                        if focused && focus_moved {
+                            row.scroll_to_me(Some(egui::Align::Center));
+                        }
                         ui.add_space(4.0);
                     }
                 });
@@ -1240,6 +2734,80 @@ impl BrowserApp {
                     let _ = self.settings.save();
                 }
 
+                let mut notifications = !self.settings.disable_notifications;
+                ui.checkbox(&mut notifications, "Notify when an install finishes")
+                    .on_hover_text(
+                        "Show a desktop notification when an install completes, fails, \
+                         or is waiting on files you have to download in a browser.",
+                    );
+                if notifications == self.settings.disable_notifications {
+                    self.settings.disable_notifications = !notifications;
+                    let _ = self.settings.save();
+                }
+
+                let prev_metered = (
+                    self.settings.metered_connection,
+                    self.settings.detect_metered_connection,
+                );
+                ui.checkbox(&mut self.settings.metered_connection, "Metered connection")
+                    .on_hover_text(
+                        "Show the download size and ask before an install downloads \
+                         anything, and keep downloads slow and few so a data cap isn't \
+                         used up by surprise.",
+                    );
+                ui.horizontal(|ui| {
+                    ui.checkbox(
+                        &mut self.settings.detect_metered_connection,
+                        "Detect with NetworkManager",
+                    )
+                    .on_hover_text(
+                        "Treat the connection as metered whenever NetworkManager marks \
+                         it metered.",
+                    );
+                    if self.settings.detect_metered_connection {
+                        let state = match self.network_metered {
+                            Some(true) => "current connection is metered",
+                            Some(false) => "current connection is not metered",
+                            None => "NetworkManager can't tell",
+                        };
+                        ui.label(
+                            egui::RichText::new(state)
+                                .size(11.0)
+                                .color(egui::Color32::from_gray(160)),
+                        );
+                    }
+                });
+                if (
+                    self.settings.metered_connection,
+                    self.settings.detect_metered_connection,
+                ) != prev_metered
+                {
+                    self.network_metered = self
+                        .settings
+                        .detect_metered_connection
+                        .then(network_manager_metered)
+                        .flatten();
+                    self.generated_command = None;
+                    let _ = self.settings.save();
+                }
+
+                ui.horizontal(|ui| {
+                    let prev_steam_install = self.settings.steam_install.clone();
+                    ui.label("Steam install:").on_hover_text(
+                        "Which Steam to use for Protons and shortcuts when both a native \
+                         and a Flatpak Steam are installed. Auto picks the running one, \
+                         else the one used last. Applies the next time CLF3 starts.",
+                    );
+                    for (value, label) in
+                        [("", "Auto"), ("native", "Native"), ("flatpak", "Flatpak")]
+                    {
+                        ui.radio_value(&mut self.settings.steam_install, value.to_string(), label);
+                    }
+                    if self.settings.steam_install != prev_steam_install {
+                        let _ = self.settings.save();
+                    }
+                });
+
                 ui.add_space(12.0);
 
                 // --- Default Directories ---
@@ -1247,7 +2815,8 @@ impl BrowserApp {
                     ui.heading("Default Directories");
                     ui.label(
                         egui::RichText::new(
-                            "Pre-fill the install panel each time you launch the browser.",
+                            "Pre-fill the install panel each time you launch the browser. \
+                             `clf3 install` uses the same defaults when arguments are omitted.",
                         )
                         .size(11.0)
                         .color(egui::Color32::from_gray(160)),
@@ -1278,6 +2847,19 @@ impl BrowserApp {
                             }
                         }
                     });
+                    ui.horizontal(|ui| {
+                        ui.label("Game:       ");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.settings.default_game_dir)
+                                .hint_text("auto-detect")
+                                .desired_width(400.0),
+                        );
+                        if ui.button("Browse...").clicked() {
+                            if let Some(p) = rfd::FileDialog::new().pick_folder() {
+                                self.settings.default_game_dir = p.display().to_string();
+                            }
+                        }
+                    });
 
                     ui.add_space(4.0);
                     if ui.button("Save default directories").clicked() {
@@ -1316,10 +2898,20 @@ impl BrowserApp {
                     ui.add_space(4.0);
                     ui.horizontal(|ui| {
                         if ui.button("Save & Verify").clicked() {
-                            self.verify_and_save_nexus(ctx);
+                            self.verify_nexus(ctx, true);
                         }
                         render_validation_status(ui, &self.nexus_status);
                     });
+                    if self.settings.is_from_env("NEXUS_API_KEY") {
+                        ui.label(
+                            egui::RichText::new(
+                                "Using NEXUS_API_KEY from the environment. It is not \
+                                 written to settings.json unless you change it.",
+                            )
+                            .size(11.0)
+                            .color(egui::Color32::from_gray(160)),
+                        );
+                    }
                 });
 
                 ui.add_space(12.0);
@@ -1378,6 +2970,30 @@ impl BrowserApp {
                     );
                     ui.add_space(4.0);
 
+                    ui.horizontal(|ui| {
+                        ui.label("Encoder:");
+                        for (mode, label) in [
+                            (EncoderMode::Auto, "Auto"),
+                            (EncoderMode::Gpu, "GPU only"),
+                            (EncoderMode::Cpu, "CPU only"),
+                        ] {
+                            ui.radio_value(&mut self.settings.texture_encoder, mode, label);
+                        }
+                    });
+                    let mode_help = match self.settings.texture_encoder {
+                        EncoderMode::Auto => {
+                            "Uses the GPU below, falling back to the CPU if it fails."
+                        }
+                        EncoderMode::Gpu => "Install refuses to start without a working GPU.",
+                        EncoderMode::Cpu => "Slower, but needs no GPU or Vulkan driver.",
+                    };
+                    ui.label(
+                        egui::RichText::new(mode_help)
+                            .size(11.0)
+                            .color(egui::Color32::from_gray(160)),
+                    );
+                    ui.add_space(4.0);
+
                     let gpus = self.available_gpus.clone().unwrap_or_default();
                     let current_text = match self.settings.gpu_index {
                         None => "auto (recommended)".to_string(),
@@ -1391,32 +3007,38 @@ impl BrowserApp {
                             }
                         ),
                     };
-                    egui::ComboBox::from_id_salt("gpu_select")
-                        .selected_text(current_text)
-                        .width(500.0)
-                        .show_ui(ui, |ui| {
-                            if ui
-                                .selectable_label(
-                                    self.settings.gpu_index.is_none(),
-                                    "auto (recommended)",
-                                )
-                                .clicked()
-                            {
-                                self.settings.gpu_index = None;
-                                self.settings.gpu_name = String::new();
-                            }
-                            for gpu in &gpus {
-                                let label = format!(
-                                    "[{}] {} ({}, {})",
-                                    gpu.adapter_index, gpu.name, gpu.backend, gpu.device_type
-                                );
-                                let selected = self.settings.gpu_index == Some(gpu.adapter_index);
-                                if ui.selectable_label(selected, &label).clicked() {
-                                    self.settings.gpu_index = Some(gpu.adapter_index);
-                                    self.settings.gpu_name = gpu.name.clone();
+                    ui.add_enabled_ui(self.settings.texture_encoder != EncoderMode::Cpu, |ui| {
+                        egui::ComboBox::from_id_salt("gpu_select")
+                            .selected_text(current_text)
+                            .width(500.0)
+                            .show_ui(ui, |ui| {
+                                if ui
+                                    .selectable_label(
+                                        self.settings.gpu_index.is_none(),
+                                        "auto (recommended)",
+                                    )
+                                    .clicked()
+                                {
+                                    self.settings.gpu_index = None;
+                                    self.settings.gpu_name = String::new();
                                 }
-                            }
-                        });
+                                for gpu in &gpus {
+                                    let label = format!(
+                                        "[{}] {} ({}, {})",
+                                        gpu.adapter_index, gpu.name, gpu.backend, gpu.device_type
+                                    );
+                                    let selected =
+                                        self.settings.gpu_index == Some(gpu.adapter_index);
+                                    if ui.selectable_label(selected, &label).clicked() {
+                                        self.settings.gpu_index = Some(gpu.adapter_index);
+                                        self.settings.gpu_name = gpu.name.clone();
+                                    }
+                                }
+                            });
+                    });
+
+                    ui.add_space(4.0);
+                    self.render_benchmark(ui);
 
                     ui.add_space(4.0);
                     if ui.button("Save GPU selection").clicked() {
@@ -1455,6 +3077,41 @@ impl BrowserApp {
                     }
                 });
 
+                ui.add_space(12.0);
+
+                // --- Disk cleanup ---
+                ui.group(|ui| {
+                    ui.heading("Disk cleanup");
+                    ui.label(
+                        egui::RichText::new(
+                            "Find leftovers of interrupted runs: partial downloads, archives \
+                             of the wrong size and extraction temp dirs in the downloads and \
+                             install directories. Don't run while an install is in progress.",
+                        )
+                        .size(11.0)
+                        .color(egui::Color32::from_gray(160)),
+                    );
+                    ui.add_space(4.0);
+                    self.render_clean(ui);
+                });
+
+                ui.add_space(12.0);
+
+                // --- Logs ---
+                ui.group(|ui| {
+                    ui.heading("Logs");
+                    ui.label(
+                        egui::RichText::new(
+                            "Every run writes its own log. When asking for help, share the \
+                             log of the run that failed.",
+                        )
+                        .size(11.0)
+                        .color(egui::Color32::from_gray(160)),
+                    );
+                    ui.add_space(4.0);
+                    self.render_logs(ui);
+                });
+
                 ui.add_space(16.0);
                 ui.separator();
                 ui.add_space(4.0);
@@ -1476,6 +3133,118 @@ impl BrowserApp {
         });
     }
 
+    /// Re-read the log directory. Keeps the picked log if it's still there,
+    /// else picks the newest log before this session's.
+    fn refresh_logs(&mut self) {
+        let dir = self
+            .session_log
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        self.recent_logs = recent_logs(&dir, RECENT_LOGS);
+        if self
+            .selected_log
+            .as_ref()
+            .is_some_and(|log| self.recent_logs.contains(log))
+        {
+            return;
+        }
+        self.selected_log = self
+            .recent_logs
+            .iter()
+            .find(|log| **log != self.session_log)
+            .or_else(|| self.recent_logs.first())
+            .cloned();
+    }
+
+    /// Log picker with "Copy Log" and "Save Log As..." buttons.
+    fn render_logs(&mut self, ui: &mut egui::Ui) {
+        if self.selected_log.is_none() {
+            self.refresh_logs();
+        }
+        let label = |log: &Path, session: &Path| {
+            let name = log
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            if log == session {
+                format!("{} (this session)", name)
+            } else {
+                name
+            }
+        };
+
+        ui.horizontal(|ui| {
+            ui.label("Log:");
+            let selected = self
+                .selected_log
+                .as_deref()
+                .map(|log| label(log, &self.session_log))
+                .unwrap_or_else(|| "(no logs found)".into());
+            egui::ComboBox::from_id_salt("log_select")
+                .selected_text(selected)
+                .width(360.0)
+                .show_ui(ui, |ui| {
+                    for log in &self.recent_logs {
+                        let text = label(log, &self.session_log);
+                        let is_selected = self.selected_log.as_ref() == Some(log);
+                        if ui.selectable_label(is_selected, text).clicked() {
+                            self.selected_log = Some(log.clone());
+                        }
+                    }
+                });
+            if ui.button("Refresh").clicked() {
+                self.refresh_logs();
+            }
+        });
+
+        let Some(log) = self.selected_log.clone() else {
+            return;
+        };
+        ui.horizontal(|ui| {
+            if ui
+                .button("Copy Log")
+                .on_hover_text("Copy the whole log to the clipboard")
+                .clicked()
+            {
+                self.log_message = Some(match std::fs::read(&log) {
+                    Ok(bytes) => {
+                        ui.ctx()
+                            .copy_text(String::from_utf8_lossy(&bytes).into_owned());
+                        (
+                            true,
+                            format!(
+                                "Copied {} to the clipboard.",
+                                Self::format_size(bytes.len() as u64)
+                            ),
+                        )
+                    }
+                    Err(e) => (false, format!("Failed to read {}: {}", log.display(), e)),
+                });
+            }
+            if ui.button("Save Log As...").clicked() {
+                let file_name = log
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "clf3.log".into());
+                if let Some(dest) = rfd::FileDialog::new().set_file_name(file_name).save_file() {
+                    self.log_message = Some(match std::fs::copy(&log, &dest) {
+                        Ok(_) => (true, format!("Saved to {}", dest.display())),
+                        Err(e) => (false, format!("Save failed: {}", e)),
+                    });
+                }
+            }
+        });
+        if let Some((ok, ref msg)) = self.log_message {
+            let color = if ok {
+                egui::Color32::from_rgb(50, 180, 50)
+            } else {
+                egui::Color32::RED
+            };
+            ui.colored_label(color, msg);
+        }
+    }
+
     /// Persist the current `self.settings` to disk and record a status message.
     fn do_save_settings(&mut self, success_msg: &str) {
         match self.settings.save() {
@@ -1484,10 +3253,10 @@ impl BrowserApp {
         }
     }
 
-    /// Validate the Nexus API key against the live API, then save settings on
-    /// success. The status field is updated from the background task and
-    /// triggers a repaint when done.
-    fn verify_and_save_nexus(&mut self, ctx: &egui::Context) {
+    /// Validate the Nexus API key against the live API, then (if `save`) save
+    /// settings on success. The status field is updated from the background
+    /// task and triggers a repaint when done.
+    fn verify_nexus(&mut self, ctx: &egui::Context, save: bool) {
         let key = self.settings.nexus_api_key.clone();
         if key.is_empty() {
             *self.nexus_status.lock().unwrap() = ValidationStatus::Err("API key is empty".into());
@@ -1505,7 +3274,7 @@ impl BrowserApp {
             };
             let mut s = status.lock().unwrap();
             match result {
-                Ok(info) => match settings_snapshot.save() {
+                Ok(info) => match save.then(|| settings_snapshot.save()).transpose() {
                     Ok(_) => {
                         *s = ValidationStatus::Ok(format!(
                             "Verified as {} (Premium: {})",
@@ -1557,7 +3326,124 @@ impl BrowserApp {
     }
 }
 
-fn shell_quote(value: &str) -> String {
+/// Fetch the gallery into `shared`, retrying network blips. When one is
+/// already shown, a failure leaves it up and is reported as a failed
+/// refresh.
+async fn fetch_gallery(
+    browser: &mut ModlistBrowser,
+    shared: &Mutex<SharedState>,
+    ctx: &egui::Context,
+) {
+    let result = browser
+        .fetch_modlists_with_retry(|retry, delay, failure| {
+            shared.lock().expect("lock shared state").fetch_retry = Some(format!(
+                "{}; retrying in {}s ({}/{})",
+                failure.message(),
+                delay.as_secs(),
+                retry,
+                FETCH_RETRY_DELAYS_SECS.len()
+            ));
+            ctx.request_repaint();
+        })
+        .await
+        .map(|_| ());
+    if result.is_ok() {
+        let _ = browser.save_cache();
+    }
+    let mut state = shared.lock().expect("lock shared state");
+    state.refreshing = false;
+    state.fetch_retry = None;
+    let result = result.map_err(|e| format!("{}: {:#}", FetchFailure::of(&e).message(), e));
+    match result {
+        Ok(()) => {
+            state.games = browser.games().into_iter().map(String::from).collect();
+            state.modlists = browser.modlists().to_vec();
+            let removed = browser.removed_modlists().iter().cloned();
+            state.removed_modlists.extend(removed);
+            // A list that came back is available again.
+            for modlist in browser.modlists() {
+                state.removed_modlists.remove(&modlist.machine_name);
+            }
+            state.gallery_updated = Some(SystemTime::now());
+            state.refresh_error = None;
+            state.gallery_refreshed = true;
+        }
+        Err(e) if state.fetch_done => state.refresh_error = Some(e),
+        Err(e) => state.fetch_error = Some(e),
+    }
+    state.fetch_done = true;
+}
+
+/// Read a gallery image from the disk cache, or download and cache it
+/// (unless `offline`).
+async fn fetch_image_bytes(
+    client: &reqwest::Client,
+    url: &str,
+    cache_dir: &Path,
+    key: &str,
+    offline: bool,
+) -> Result<Vec<u8>, String> {
+    let cached_path = cache_dir.join(key);
+    if let Ok(bytes) = std::fs::read(&cached_path) {
+        if !bytes.is_empty() {
+            return Ok(bytes);
+        }
+    }
+    if offline {
+        return Err("Not cached".to_string());
+    }
+
+    let resp = client.get(url).send().await.map_err(|e| e.to_string())?;
+    if !resp.status().is_success() {
+        return Err(format!("HTTP {}", resp.status()));
+    }
+    let bytes = resp.bytes().await.map_err(|e| e.to_string())?;
+    if bytes.is_empty() {
+        return Err("Empty response".to_string());
+    }
+    let _ = std::fs::write(&cached_path, &bytes);
+    Ok(bytes.to_vec())
+}
+
+/// Decode a gallery image and shrink it to twice the thumbnail size (crisp
+/// on HiDPI), so only small RGBA buffers are kept until upload.
+/// The newest `limit` CLF3 log files in `dir`, newest first. The names
+/// carry the start time, so they sort chronologically.
+fn recent_logs(dir: &Path, limit: usize) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut logs: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("clf3-") && n.ends_with(".log"))
+        })
+        .collect();
+    logs.sort_by(|a, b| b.cmp(a));
+    logs.truncate(limit);
+    logs
+}
+
+fn decode_thumbnail(bytes: &[u8]) -> Option<egui::ColorImage> {
+    let img = image::load_from_memory(bytes).ok()?;
+    let (max_w, max_h) = ((THUMB_WIDTH * 2.0) as u32, (THUMB_HEIGHT * 2.0) as u32);
+    let img = if img.width() > max_w || img.height() > max_h {
+        img.thumbnail(max_w, max_h)
+    } else {
+        img
+    };
+    let rgba = img.to_rgba8();
+    let size = [rgba.width() as usize, rgba.height() as usize];
+    Some(egui::ColorImage::from_rgba_unmultiplied(
+        size,
+        rgba.as_raw(),
+    ))
+}
+
+pub(crate) fn shell_quote(value: &str) -> String {
     if value.is_empty() {
         return "''".to_string();
     }
@@ -1673,7 +3559,13 @@ fn render_validation_status(ui: &mut egui::Ui, status: &Arc<Mutex<ValidationStat
 }
 
 impl BrowserApp {
-    fn render_row(&self, ui: &mut egui::Ui, modlist: &ModlistMetadata) {
+    /// One modlist card. `focused` marks the keyboard-highlighted card.
+    fn render_row(
+        &self,
+        ui: &mut egui::Ui,
+        modlist: &ModlistMetadata,
+        focused: bool,
+    ) -> egui::Response {
         let is_selected = self
             .selected
             .as_ref()
@@ -1682,6 +3574,8 @@ impl BrowserApp {
 
         let stroke = if is_selected {
             egui::Stroke::new(2.0, egui::Color32::from_rgb(100, 149, 237))
+        } else if focused {
+            egui::Stroke::new(2.0, egui::Color32::from_gray(200))
         } else {
             egui::Stroke::new(1.0, egui::Color32::from_gray(60))
         };
@@ -1708,7 +3602,7 @@ impl BrowserApp {
                         let state = self.shared.lock().expect("lock shared state");
                         matches!(
                             state.images.get(&modlist.machine_name),
-                            Some(ImageState::Loading) | Some(ImageState::Downloaded(_)) | None
+                            Some(ImageState::Loading) | Some(ImageState::Decoded(_)) | None
                         )
                     };
 
@@ -1742,6 +3636,30 @@ impl BrowserApp {
                     ui.vertical(|ui| {
                         // Title row with badges.
                         ui.horizontal(|ui| {
+                            let favorite = self.is_favorite(&modlist.machine_name);
+                            let star = egui::RichText::new(if favorite { "★" } else { "☆" })
+                                .size(16.0)
+                                .color(if favorite {
+                                    egui::Color32::from_rgb(240, 200, 60)
+                                } else {
+                                    egui::Color32::from_gray(120)
+                                });
+                            if ui
+                                .add(egui::Button::new(star).frame(false))
+                                .on_hover_text(if favorite {
+                                    "Remove from favorites"
+                                } else {
+                                    "Add to favorites (listed first)"
+                                })
+                                .clicked()
+                            {
+                                ui.ctx().memory_mut(|mem| {
+                                    mem.data.insert_temp(
+                                        egui::Id::new("pending_favorite"),
+                                        modlist.machine_name.clone(),
+                                    );
+                                });
+                            }
                             ui.label(egui::RichText::new(&modlist.title).strong().size(16.0));
                             if modlist.nsfw {
                                 ui.label(
@@ -1829,6 +3747,7 @@ impl BrowserApp {
                         });
                     });
                 });
-            });
+            })
+            .response
     }
 }
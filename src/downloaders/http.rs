@@ -2,21 +2,32 @@
 
 use anyhow::{bail, Context, Result};
 use futures::StreamExt;
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::fs::{File, OpenOptions};
 use tokio::io::AsyncWriteExt;
-use tokio::sync::watch;
 use tracing::{debug, warn};
 
-/// Stall detection: timeout if no progress for this duration.
+/// Stall detection: a connection is considered stalled when it averages
+/// less than [`MIN_THROUGHPUT`] over this long. Overridable per client with
+/// [`HttpClient::with_stall_timeout`] (`--stall-timeout`).
 ///
 /// Nexus and CDN downloads can legitimately pause for short windows on
 /// congested/slow links, especially for multi-GB archives. Keep this lenient
 /// enough to avoid false "stalled" failures.
-const STALL_TIMEOUT: Duration = Duration::from_secs(180);
+pub const STALL_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// Throughput floor for stall detection (bytes/sec). A connection trickling
+/// along below this is dropped and resumed rather than left to run forever.
+const MIN_THROUGHPUT: u64 = 10 * 1024;
+
+/// Most concurrent downloads a `bytes_per_sec` speed cap can feed at the
+/// stall detection floor each.
+pub fn max_concurrent_for_speed(bytes_per_sec: u64) -> usize {
+    (bytes_per_sec / MIN_THROUGHPUT).max(1) as usize
+}
 
 /// Progress check interval
 const PROGRESS_CHECK_INTERVAL: Duration = Duration::from_secs(5);
@@ -24,15 +35,134 @@ const PROGRESS_CHECK_INTERVAL: Duration = Duration::from_secs(5);
 /// How often to invoke the progress callback (in milliseconds)
 const CALLBACK_INTERVAL_MS: u128 = 250;
 
-/// Maximum retries for resumable download recovery.
-const MAX_RESUME_RETRIES: u32 = 8;
+/// Default maximum retries for resumable download recovery.
+pub const MAX_RESUME_RETRIES: u32 = 8;
 
 /// Base delay between resumable retries.
 const RESUME_RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
 
+/// The server is sending a different amount of data than the modlist
+/// expects — almost always a replaced or wrong file. Retrying the same URL
+/// won't fix it.
+#[derive(Debug, thiserror::Error)]
+#[error("Size mismatch: expected {expected} bytes, got {actual}")]
+pub struct SizeMismatch {
+    pub expected: u64,
+    pub actual: u64,
+}
+
+/// The server refused the request with 401/403: the file is behind a login
+/// or referer check this request didn't satisfy.
+#[derive(Debug, thiserror::Error)]
+#[error("HTTP {status} - {host} requires a login to download")]
+pub struct AuthRequired {
+    pub status: u16,
+    pub host: String,
+}
+
+/// Extra request headers per host, from `http_headers` in settings.json
+/// (host → `"Name: value"` lines, e.g. a session `Cookie` or a `Referer`).
+/// A host entry also applies to its subdomains.
+#[derive(Clone, Default)]
+pub struct HostHeaders {
+    by_host: HashMap<String, Vec<(String, String)>>,
+}
+
+impl HostHeaders {
+    /// Lines that aren't `Name: value` are skipped with a warning.
+    pub fn new(config: &HashMap<String, Vec<String>>) -> Self {
+        let by_host = config
+            .iter()
+            .map(|(host, lines)| (host.trim().to_lowercase(), parse_header_lines(lines)))
+            .filter(|(host, headers)| !host.is_empty() && !headers.is_empty())
+            .collect();
+        Self { by_host }
+    }
+
+    /// Headers configured for the host of `url` (empty if none).
+    pub fn for_url(&self, url: &str) -> &[(String, String)] {
+        let Some(host) = url_host(url) else {
+            return &[];
+        };
+        self.by_host
+            .iter()
+            .find(|(configured, _)| {
+                host == **configured || host.ends_with(&format!(".{}", configured))
+            })
+            .map(|(_, headers)| headers.as_slice())
+            .unwrap_or(&[])
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_host.is_empty()
+    }
+}
+
+/// Only the hosts; the values are credentials.
+impl std::fmt::Debug for HostHeaders {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.by_host.keys()).finish()
+    }
+}
+
+/// Parse `"Name: value"` header lines (settings, or a modlist's own
+/// `Headers` for an HTTP source).
+pub fn parse_header_lines(lines: &[String]) -> Vec<(String, String)> {
+    lines
+        .iter()
+        .filter_map(|line| {
+            let parsed = line
+                .split_once(':')
+                .map(|(name, value)| (name.trim(), value.trim()))
+                .filter(|(name, _)| reqwest::header::HeaderName::try_from(*name).is_ok());
+            if parsed.is_none() {
+                warn!("Ignoring malformed HTTP header line: {:?}", line);
+            }
+            parsed.map(|(name, value)| (name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Lowercase host of `url`, if it parses.
+pub fn url_host(url: &str) -> Option<String> {
+    reqwest::Url::parse(url)
+        .ok()?
+        .host_str()
+        .map(|host| host.to_lowercase())
+}
+
 /// Global HTTP client
 pub struct HttpClient {
     client: reqwest::Client,
+    stall_timeout: Duration,
+    max_resume_retries: u32,
+    rate_limit: Option<RateLimit>,
+}
+
+/// Total speed cap shared by every download made through one client
+/// (`--max-download-speed`, metered connections). Each chunk books the next
+/// slot on a shared timeline and waits for it, so concurrent downloads split
+/// the budget between them.
+struct RateLimit {
+    bytes_per_sec: u64,
+    next_free: std::sync::Mutex<Instant>,
+}
+
+impl RateLimit {
+    fn new(bytes_per_sec: u64) -> Self {
+        Self {
+            bytes_per_sec: bytes_per_sec.max(1),
+            next_free: std::sync::Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Book `len` bytes and return how long to wait before writing them.
+    fn reserve(&self, len: u64, now: Instant) -> Duration {
+        let mut next_free = self.next_free.lock().expect("lock rate limit");
+        let start = (*next_free).max(now);
+        *next_free = start + Duration::from_secs_f64(len as f64 / self.bytes_per_sec as f64);
+        start - now
+    }
 }
 
 /// Connection timeout: time to establish TCP connection
@@ -51,14 +181,40 @@ const REQUEST_TIMEOUT: Duration = Duration::from_secs(4 * 60 * 60);
 impl HttpClient {
     pub fn new() -> Result<Self> {
         let client = reqwest::Client::builder()
-            .user_agent(concat!("clf3/", env!("CARGO_PKG_VERSION")))
+            .user_agent(super::user_agent())
             .connect_timeout(CONNECT_TIMEOUT)
             .read_timeout(READ_TIMEOUT)
             .timeout(REQUEST_TIMEOUT)
             .build()
             .context("Failed to create HTTP client")?;
 
-        Ok(Self { client })
+        Ok(Self {
+            client,
+            stall_timeout: STALL_TIMEOUT,
+            max_resume_retries: MAX_RESUME_RETRIES,
+            rate_limit: None,
+        })
+    }
+
+    /// Use a different stall window for downloads made through this client.
+    pub fn with_stall_timeout(mut self, stall_timeout: Duration) -> Self {
+        self.stall_timeout = stall_timeout.max(Duration::from_secs(1));
+        self
+    }
+
+    /// Cap resume/reconnect attempts per download (0 = fail on the first
+    /// error).
+    pub fn with_max_retries(mut self, max_resume_retries: u32) -> Self {
+        self.max_resume_retries = max_resume_retries;
+        self
+    }
+
+    /// Cap the combined speed of downloads through this client (bytes/sec,
+    /// `None` = unlimited). Time spent waiting on the cap doesn't count
+    /// towards stall detection.
+    pub fn with_rate_limit(mut self, bytes_per_sec: Option<u64>) -> Self {
+        self.rate_limit = bytes_per_sec.map(RateLimit::new);
+        self
     }
 
     /// Get the underlying reqwest client
@@ -73,10 +229,9 @@ impl Default for HttpClient {
     }
 }
 
-/// Shared progress state for stall detection and speed calculation
+/// Progress state for speed calculation
 struct DownloadProgress {
     bytes_downloaded: AtomicU64,
-    last_progress_time: std::sync::Mutex<Instant>,
     start_time: Instant,
 }
 
@@ -84,24 +239,18 @@ impl DownloadProgress {
     fn new(initial_bytes: u64) -> Self {
         Self {
             bytes_downloaded: AtomicU64::new(initial_bytes),
-            last_progress_time: std::sync::Mutex::new(Instant::now()),
             start_time: Instant::now(),
         }
     }
 
     fn add_bytes(&self, count: u64) {
         self.bytes_downloaded.fetch_add(count, Ordering::Relaxed);
-        *self.last_progress_time.lock().unwrap() = Instant::now();
     }
 
     fn total_bytes(&self) -> u64 {
         self.bytes_downloaded.load(Ordering::Relaxed)
     }
 
-    fn time_since_progress(&self) -> Duration {
-        self.last_progress_time.lock().unwrap().elapsed()
-    }
-
     /// Calculate average bytes per second since start
     fn bytes_per_second(&self) -> f64 {
         let elapsed = self.start_time.elapsed().as_secs_f64();
@@ -113,6 +262,51 @@ impl DownloadProgress {
     }
 }
 
+/// Sliding throughput window for stall detection.
+struct StallWindow {
+    started: Instant,
+    bytes_at_start: u64,
+    timeout: Duration,
+    /// Time this window spent held back by the speed cap, which says
+    /// nothing about the connection.
+    throttled: Duration,
+}
+
+impl StallWindow {
+    fn new(bytes: u64, timeout: Duration, now: Instant) -> Self {
+        Self {
+            started: now,
+            bytes_at_start: bytes,
+            timeout,
+            throttled: Duration::ZERO,
+        }
+    }
+
+    /// Leave `wait` spent on the speed cap out of the window.
+    fn add_throttled(&mut self, wait: Duration) {
+        self.throttled += wait;
+    }
+
+    /// True once a full window of unthrottled time has passed averaging
+    /// below [`MIN_THROUGHPUT`]. A healthy window starts the next one.
+    fn is_stalled(&mut self, bytes: u64, now: Instant) -> bool {
+        let elapsed = now
+            .saturating_duration_since(self.started)
+            .saturating_sub(self.throttled);
+        if elapsed < self.timeout {
+            return false;
+        }
+        let rate = bytes.saturating_sub(self.bytes_at_start) as f64 / elapsed.as_secs_f64();
+        if rate < MIN_THROUGHPUT as f64 {
+            return true;
+        }
+        self.started = now;
+        self.bytes_at_start = bytes;
+        self.throttled = Duration::ZERO;
+        false
+    }
+}
+
 /// Progress callback type for GUI updates
 pub type ProgressCallback = Box<dyn Fn(u64, u64, f64) + Send + Sync>;
 
@@ -145,6 +339,27 @@ pub async fn download_file_with_callback(
     output_path: &Path,
     expected_size: Option<u64>,
     progress_callback: Option<&ProgressCallback>,
+) -> Result<u64> {
+    download_file_with_headers(
+        client,
+        url,
+        &[],
+        output_path,
+        expected_size,
+        progress_callback,
+    )
+    .await
+}
+
+/// [`download_file_with_callback`] with extra request headers (cookies,
+/// referer). A 401/403 response fails with [`AuthRequired`].
+pub async fn download_file_with_headers(
+    client: &HttpClient,
+    url: &str,
+    headers: &[(String, String)],
+    output_path: &Path,
+    expected_size: Option<u64>,
+    progress_callback: Option<&ProgressCallback>,
 ) -> Result<u64> {
     if let Some(parent) = output_path.parent() {
         tokio::fs::create_dir_all(parent).await?;
@@ -176,6 +391,9 @@ pub async fn download_file_with_callback(
 
     loop {
         let mut request = client.inner().get(url);
+        for (name, value) in headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
         if offset > 0 {
             request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
         }
@@ -183,7 +401,7 @@ pub async fn download_file_with_callback(
         let response = match request.send().await {
             Ok(resp) => resp,
             Err(e) => {
-                if attempts < MAX_RESUME_RETRIES {
+                if attempts < client.max_resume_retries {
                     attempts += 1;
                     let delay = RESUME_RETRY_BASE_DELAY
                         .as_secs()
@@ -193,7 +411,7 @@ pub async fn download_file_with_callback(
                         "Request failed for {} (attempt {}/{}), retrying in {}s: {}",
                         truncate_url(url),
                         attempts,
-                        MAX_RESUME_RETRIES,
+                        client.max_resume_retries,
                         delay,
                         e
                     );
@@ -223,7 +441,7 @@ pub async fn download_file_with_callback(
             let _ = tokio::fs::remove_file(output_path).await;
             offset = 0;
             append_mode = false;
-            if attempts < MAX_RESUME_RETRIES {
+            if attempts < client.max_resume_retries {
                 attempts += 1;
                 tokio::time::sleep(RESUME_RETRY_BASE_DELAY).await;
                 continue;
@@ -231,6 +449,14 @@ pub async fn download_file_with_callback(
             bail!("HTTP 416 - resume range rejected");
         }
 
+        if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+            return Err(AuthRequired {
+                status: status.as_u16(),
+                host: url_host(url).unwrap_or_else(|| truncate_url(url)),
+            }
+            .into());
+        }
+
         if !status.is_success() {
             let body = response.text().await.unwrap_or_default();
             bail!("HTTP {} - {}", status.as_u16(), truncate_error(&body));
@@ -246,9 +472,19 @@ pub async fn download_file_with_callback(
             append_mode = false;
         }
 
-        let total_size = expected_size
-            .or_else(|| response.content_length().map(|len| len + offset))
-            .unwrap_or(0);
+        // Catch a wrong/replaced file from its headers instead of after
+        // downloading all of it. Chunked responses have no length and are
+        // checked once the body is done.
+        let reported_size = response.content_length().map(|len| len + offset);
+        if let (Some(expected), Some(actual)) = (expected_size, reported_size) {
+            if actual != expected {
+                return Err(SizeMismatch { expected, actual }).with_context(|| {
+                    format!("Server reports wrong size for {}", truncate_url(url))
+                });
+            }
+        }
+
+        let total_size = expected_size.or(reported_size).unwrap_or(0);
 
         let mut file = if append_mode {
             OpenOptions::new()
@@ -262,62 +498,65 @@ pub async fn download_file_with_callback(
                 .with_context(|| format!("Failed to create {}", output_path.display()))?
         };
 
-        let progress = Arc::new(DownloadProgress::new(offset));
-        let progress_clone = progress.clone();
-        let content_length = if total_size > 0 {
-            Some(total_size)
-        } else {
-            None
-        };
-        let (shutdown_tx, mut shutdown_rx) = watch::channel(false);
+        let progress = DownloadProgress::new(offset);
+        let stall_timeout = client.stall_timeout;
+        let mut stall_window = StallWindow::new(offset, stall_timeout, Instant::now());
+        let mut stall_check = tokio::time::interval(PROGRESS_CHECK_INTERVAL.min(stall_timeout));
+        stall_check.tick().await;
+        let mut stalled = false;
 
-        let stall_detector = tokio::spawn(async move {
+        let mut last_callback_time = Instant::now();
+        let mut stream = response.bytes_stream();
+        let download_result: Result<u64> = async {
             loop {
                 tokio::select! {
-                    _ = tokio::time::sleep(PROGRESS_CHECK_INTERVAL) => {
-                        let stall_time = progress_clone.time_since_progress();
-                        if stall_time >= STALL_TIMEOUT {
-                            warn!("Download stalled - no progress for {:?}", stall_time);
-                            return Err(anyhow::anyhow!(
-                                "Stalled: no data for {}s",
-                                STALL_TIMEOUT.as_secs()
-                            ));
+                    chunk = stream.next() => {
+                        let Some(chunk_result) = chunk else { break };
+                        let chunk = chunk_result.context("Failed to read chunk")?;
+                        file.write_all(&chunk)
+                            .await
+                            .context("Failed to write chunk")?;
+                        progress.add_bytes(chunk.len() as u64);
+                        if let Some(limit) = &client.rate_limit {
+                            let wait = limit.reserve(chunk.len() as u64, Instant::now());
+                            if !wait.is_zero() {
+                                tokio::time::sleep(wait).await;
+                                stall_window.add_throttled(wait);
+                            }
                         }
 
-                        let bytes = progress_clone.total_bytes();
-                        if let Some(total) = content_length {
-                            let percent = (bytes as f64 / total as f64) * 100.0;
-                            debug!("Progress: {:.1}% ({} / {} bytes)", percent, bytes, total);
+                        if let Some(callback) = progress_callback {
+                            let now = Instant::now();
+                            if now.duration_since(last_callback_time).as_millis()
+                                >= CALLBACK_INTERVAL_MS
+                            {
+                                let downloaded = progress.total_bytes();
+                                let speed = progress.bytes_per_second();
+                                callback(downloaded, total_size, speed);
+                                last_callback_time = now;
+                            }
+                        }
+                    }
+                    _ = stall_check.tick() => {
+                        let bytes = progress.total_bytes();
+                        if stall_window.is_stalled(bytes, Instant::now()) {
+                            warn!(
+                                "Download stalled (under {} KB/s for {}s) at {} bytes: {}",
+                                MIN_THROUGHPUT / 1024,
+                                stall_timeout.as_secs(),
+                                bytes,
+                                truncate_url(url)
+                            );
+                            stalled = true;
+                            break;
+                        }
+                        if total_size > 0 {
+                            let percent = (bytes as f64 / total_size as f64) * 100.0;
+                            debug!("Progress: {:.1}% ({} / {} bytes)", percent, bytes, total_size);
                         } else {
                             debug!("Downloaded: {} bytes", bytes);
                         }
                     }
-                    _ = shutdown_rx.changed() => {
-                        return Ok(());
-                    }
-                }
-            }
-        });
-
-        let mut last_callback_time = Instant::now();
-        let mut stream = response.bytes_stream();
-        let download_result: Result<u64> = async {
-            while let Some(chunk_result) = stream.next().await {
-                let chunk = chunk_result.context("Failed to read chunk")?;
-                file.write_all(&chunk)
-                    .await
-                    .context("Failed to write chunk")?;
-                let len = chunk.len() as u64;
-                progress.add_bytes(len);
-
-                if let Some(callback) = progress_callback {
-                    let now = Instant::now();
-                    if now.duration_since(last_callback_time).as_millis() >= CALLBACK_INTERVAL_MS {
-                        let downloaded = progress.total_bytes();
-                        let speed = progress.bytes_per_second();
-                        callback(downloaded, total_size, speed);
-                        last_callback_time = now;
-                    }
                 }
             }
 
@@ -332,53 +571,73 @@ pub async fn download_file_with_callback(
         }
         .await;
 
-        let _ = shutdown_tx.send(true);
-        let detector_result = stall_detector.await;
-        let total_bytes = match detector_result {
-            Ok(Ok(())) => download_result?,
-            Ok(Err(e)) => return Err(e),
-            Err(e) => {
-                warn!("Stall detector task failed: {}", e);
-                download_result?
+        let total_bytes = download_result?;
+
+        if stalled {
+            // Drop the stuck connection and pick up where it left off with a
+            // range request on a fresh one.
+            offset = total_bytes;
+            if attempts < client.max_resume_retries {
+                attempts += 1;
+                warn!(
+                    "Reconnecting stalled download (attempt {}/{}), resuming at {} bytes",
+                    attempts, client.max_resume_retries, offset
+                );
+                tokio::time::sleep(RESUME_RETRY_BASE_DELAY).await;
+                continue;
             }
-        };
+            bail!(
+                "Stalled: under {} KB/s for {}s, {} reconnects failed",
+                MIN_THROUGHPUT / 1024,
+                stall_timeout.as_secs(),
+                client.max_resume_retries
+            );
+        }
 
         if let Some(expected) = expected_size {
             if total_bytes == expected {
                 return Ok(total_bytes);
             }
             if total_bytes > expected {
-                bail!(
-                    "Size mismatch: expected {} bytes, got {}",
+                return Err(SizeMismatch {
                     expected,
-                    total_bytes
-                );
+                    actual: total_bytes,
+                }
+                .into());
             }
         } else {
             return Ok(total_bytes);
         }
 
         offset = total_bytes;
-        if attempts < MAX_RESUME_RETRIES {
+        if attempts < client.max_resume_retries {
             attempts += 1;
             let delay = RESUME_RETRY_BASE_DELAY
                 .as_secs()
                 .saturating_mul(1u64 << (attempts - 1).min(4))
                 .min(60);
+            warn!(
+                "Connection closed early for {} at {} bytes (attempt {}/{}), resuming in {}s",
+                truncate_url(url),
+                offset,
+                attempts,
+                client.max_resume_retries,
+                delay
+            );
             tokio::time::sleep(Duration::from_secs(delay)).await;
             continue;
         }
 
         bail!(
             "Download incomplete after {} retries: got {} bytes",
-            MAX_RESUME_RETRIES,
+            client.max_resume_retries,
             total_bytes
         );
     }
 }
 
 /// Truncate URL for error messages
-fn truncate_url(url: &str) -> String {
+pub(super) fn truncate_url(url: &str) -> String {
     if url.len() > 80 {
         format!("{}...", &url[..77])
     } else {
@@ -407,4 +666,110 @@ mod tests {
         let client = HttpClient::new();
         assert!(client.is_ok());
     }
+
+    #[test]
+    fn test_host_headers_match_host_and_subdomains() {
+        let config = HashMap::from([(
+            "Files.Example.com".to_string(),
+            vec![
+                "Cookie: session=abc".to_string(),
+                "Referer:https://files.example.com/".to_string(),
+                "not a header".to_string(),
+            ],
+        )]);
+        let headers = HostHeaders::new(&config);
+
+        let expected = [
+            ("Cookie".to_string(), "session=abc".to_string()),
+            (
+                "Referer".to_string(),
+                "https://files.example.com/".to_string(),
+            ),
+        ];
+        assert_eq!(headers.for_url("https://files.example.com/a.7z"), expected);
+        assert_eq!(
+            headers.for_url("https://cdn.files.example.com/a.7z"),
+            expected
+        );
+        assert!(headers
+            .for_url("https://notfiles.example.com/a.7z")
+            .is_empty());
+        assert!(headers.for_url("not a url").is_empty());
+        assert!(!format!("{:?}", headers).contains("session"));
+    }
+
+    #[test]
+    fn test_size_mismatch_message() {
+        let err = anyhow::Error::from(SizeMismatch {
+            expected: 100,
+            actual: 42,
+        });
+        assert_eq!(err.to_string(), "Size mismatch: expected 100 bytes, got 42");
+    }
+
+    #[test]
+    fn test_stall_window() {
+        let start = Instant::now();
+        let timeout = Duration::from_secs(30);
+        let mut window = StallWindow::new(1000, timeout, start);
+
+        // Nothing decided before a full window has passed.
+        assert!(!window.is_stalled(1000, start + Duration::from_secs(10)));
+
+        // Healthy window (1 MB in 30s) starts the next one.
+        assert!(!window.is_stalled(1_001_000, start + timeout));
+
+        // Next window only trickles 60 KB in 30s (2 KB/s) — stalled.
+        assert!(window.is_stalled(1_061_000, start + timeout * 2));
+    }
+
+    #[test]
+    fn test_rate_limit_spreads_chunks() {
+        let limit = RateLimit::new(1000);
+        let now = Instant::now();
+
+        // First chunk goes right away and books the next half second.
+        assert_eq!(limit.reserve(500, now), Duration::ZERO);
+        // A second download's chunk waits for it.
+        assert_eq!(limit.reserve(1000, now), Duration::from_millis(500));
+        // Idle time isn't banked as burst.
+        let later = now + Duration::from_secs(10);
+        assert_eq!(limit.reserve(1000, later), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_rate_limit_is_not_a_stall() {
+        // 16 downloads sharing 100 KB/s get ~6 KB/s each, under the floor.
+        let cap = 100 * 1024;
+        let downloads = 16;
+        let chunk = 8 * 1024;
+        let timeout = Duration::from_secs(30);
+        let limit = RateLimit::new(cap);
+        let start = Instant::now();
+
+        // (clock, bytes, window) per download.
+        let mut sims: Vec<_> = (0..downloads)
+            .map(|_| (start, 0u64, StallWindow::new(0, timeout, start)))
+            .collect();
+        for _ in 0..100 {
+            for (clock, bytes, window) in &mut sims {
+                let wait = limit.reserve(chunk, *clock);
+                // The wait, plus a little real transfer time.
+                *clock += wait + Duration::from_millis(5);
+                window.add_throttled(wait);
+                *bytes += chunk;
+            }
+        }
+
+        for (clock, bytes, window) in &mut sims {
+            assert!(*clock - start > timeout * 2);
+            assert!(!window.is_stalled(*bytes, *clock));
+            // Counting the waits, the same download would look stalled.
+            let mut unaware = StallWindow::new(0, timeout, start);
+            assert!(unaware.is_stalled(*bytes, *clock));
+        }
+
+        assert_eq!(max_concurrent_for_speed(cap), 10);
+        assert_eq!(max_concurrent_for_speed(1024), 1);
+    }
 }
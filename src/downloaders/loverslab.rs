@@ -5,6 +5,7 @@
 //! downloads the correct attachment.  Downloads are sequential (one at
 //! a time) to avoid rate-limiting.
 
+use crate::error::InstallError;
 use anyhow::{bail, Context, Result};
 use reqwest::cookie::Jar;
 use reqwest::Client;
@@ -38,7 +39,10 @@ impl LoversLabDownloader {
     /// Returns `None` if credentials are empty.
     pub async fn login(email: &str, password: &str) -> Result<Self> {
         if email.is_empty() || password.is_empty() {
-            bail!("LoversLab credentials not configured");
+            bail!(InstallError::msg(
+                InstallError::Unauthorized,
+                "LoversLab credentials not configured"
+            ));
         }
 
         info!("Logging into LoversLab...");
@@ -107,7 +111,10 @@ impl LoversLabDownloader {
             || body.contains("Login method does not exist")
             || body.contains("email address or password is incorrect")
         {
-            bail!("LoversLab login failed: incorrect email or password");
+            bail!(InstallError::msg(
+                InstallError::Unauthorized,
+                "LoversLab login failed: incorrect email or password"
+            ));
         }
 
         // Verify we're actually logged in by checking for a logout link
@@ -218,7 +225,10 @@ impl LoversLabDownloader {
 
         // Check if we got redirected to login (session expired)
         if page_html.contains("id=\"elSignIn_submit\"") || page_html.contains("_processLogin") {
-            bail!("LoversLab session expired — re-login required");
+            bail!(InstallError::msg(
+                InstallError::Unauthorized,
+                "LoversLab session expired — re-login required"
+            ));
         }
 
         // Check if the page contains a Mega link instead of a direct download
@@ -13,27 +13,56 @@ pub mod loverslab;
 mod mediafire;
 pub mod mega_native;
 mod nexus;
+mod split;
 pub mod wabbajack_cdn;
 pub mod yandex;
 
 pub use google_drive::GoogleDriveDownloader;
 pub use http::{
-    download_file, download_file_with_callback, download_file_with_progress, HttpClient,
-    ProgressCallback,
+    download_file, download_file_with_callback, download_file_with_headers,
+    download_file_with_progress, max_concurrent_for_speed, parse_header_lines, AuthRequired,
+    HostHeaders, HttpClient, ProgressCallback, SizeMismatch, MAX_RESUME_RETRIES, STALL_TIMEOUT,
 };
 pub use loverslab::LoversLabDownloader;
 pub use mediafire::MediaFireDownloader;
-pub use nexus::{NexusDownloader, NexusRateLimits};
+pub use nexus::{NexusCdn, NexusDownloader, NexusFileUnavailable, NexusRateLimits};
+pub use split::{download_split_file, split_first_part};
 pub use wabbajack_cdn::WabbajackCdnDownloader;
 pub use yandex::YandexDownloader;
 
 use anyhow::{Context, Result};
 use std::future::Future;
 use std::path::Path;
+use std::sync::OnceLock;
 use std::time::Duration;
 use tokio::time::sleep;
 use tracing::{info, warn};
 
+/// `User-Agent` CLF3 identifies itself with.
+pub const DEFAULT_USER_AGENT: &str = concat!("clf3/", env!("CARGO_PKG_VERSION"));
+
+static USER_AGENT_OVERRIDE: OnceLock<String> = OnceLock::new();
+
+/// Use `user_agent` instead of [`DEFAULT_USER_AGENT`] for every client built
+/// afterwards (the `user_agent` setting). Blank keeps the default; only the
+/// first call takes effect.
+pub fn set_user_agent(user_agent: &str) {
+    let user_agent = user_agent.trim();
+    if !user_agent.is_empty() {
+        let _ = USER_AGENT_OVERRIDE.set(user_agent.to_string());
+    }
+}
+
+/// `User-Agent` for CLF3's own requests. Sources that only serve browsers
+/// (MediaFire, Google Drive, LoversLab, ModDB and the Wabbajack CDN's direct
+/// download fallback) send a browser UA instead.
+pub fn user_agent() -> &'static str {
+    USER_AGENT_OVERRIDE
+        .get()
+        .map(String::as_str)
+        .unwrap_or(DEFAULT_USER_AGENT)
+}
+
 /// Default retry configuration
 pub const MAX_RETRIES: u32 = 3;
 pub const RETRY_DELAY: Duration = Duration::from_secs(5);
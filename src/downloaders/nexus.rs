@@ -1,5 +1,7 @@
 //! Nexus Mods downloader with rate limiting and Premium support
 
+use crate::error::InstallError;
+use crate::game_finder::GameType;
 use anyhow::{bail, Context, Result};
 use chrono::{DateTime, Utc};
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
@@ -7,10 +9,79 @@ use reqwest::{Client, Response};
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::RwLock;
+use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
 const API_BASE_URL: &str = "https://api.nexusmods.com";
 const AUTH_HEADER: &str = "apikey";
+/// Give up on a download server that takes longer than this to connect to.
+const SERVER_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Which Nexus download server to use. Premium accounts are offered several
+/// (the Nexus CDN plus regional servers); others get one and this is moot.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum NexusCdn {
+    /// The first link Nexus returns.
+    #[default]
+    Default,
+    /// Probe the servers once per session and keep using the fastest.
+    Fastest,
+    /// The server with this name or short name, e.g. "Amsterdam".
+    Region(String),
+}
+
+impl NexusCdn {
+    /// Parse the `nexus_cdn` setting / `--nexus-cdn`: empty or `default`,
+    /// `fastest`, or a server name.
+    pub fn parse(value: &str) -> Self {
+        let value = value.trim();
+        if value.is_empty() || value.eq_ignore_ascii_case("default") {
+            NexusCdn::Default
+        } else if value.eq_ignore_ascii_case("fastest") {
+            NexusCdn::Fastest
+        } else {
+            NexusCdn::Region(value.to_string())
+        }
+    }
+}
+
+impl std::fmt::Display for NexusCdn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NexusCdn::Default => f.write_str("default"),
+            NexusCdn::Fastest => f.write_str("fastest"),
+            NexusCdn::Region(name) => f.write_str(name),
+        }
+    }
+}
+
+/// The Nexus API reports the requested file as gone: deleted by the author,
+/// moved to the mod's archived files, or the mod itself removed. Retrying
+/// won't help, and the exact version may no longer be downloadable at all.
+#[derive(Debug, thiserror::Error)]
+#[error("Nexus file {mod_id}/{file_id} is no longer available ({reason})")]
+pub struct NexusFileUnavailable {
+    pub game_domain: String,
+    pub mod_id: u64,
+    pub file_id: u64,
+    pub reason: String,
+}
+
+impl NexusFileUnavailable {
+    /// Recognise a "file not found / archived / removed" API response.
+    fn from_response(status: u16, body: &str) -> Option<String> {
+        let lower = body.to_lowercase();
+        match status {
+            404 => Some("file not found".to_string()),
+            410 => Some("file removed".to_string()),
+            _ if lower.contains("archived") => Some("file archived by the author".to_string()),
+            _ if lower.contains("has been deleted") || lower.contains("has been removed") => {
+                Some("file removed by the author".to_string())
+            }
+            _ => None,
+        }
+    }
+}
 
 /// Nexus API rate limits (tracked from response headers)
 #[derive(Debug, Clone)]
@@ -91,6 +162,11 @@ pub struct NexusDownloader {
     validated: AtomicBool,
     /// Human-readable auth source for validation errors.
     auth_label: &'static str,
+    cdn: NexusCdn,
+    /// Short name of the fastest server once probed (`NexusCdn::Fastest`);
+    /// None inside if no server answered. The lock is held while probing so
+    /// concurrent downloads wait for one probe.
+    fastest_server: tokio::sync::Mutex<Option<Option<String>>>,
 }
 
 impl NexusDownloader {
@@ -135,7 +211,7 @@ impl NexusDownloader {
 
         let client = Client::builder()
             .default_headers(headers)
-            .user_agent(concat!("clf3/", env!("CARGO_PKG_VERSION")))
+            .user_agent(super::user_agent())
             .build()
             .context("Failed to create HTTP client")?;
 
@@ -146,9 +222,17 @@ impl NexusDownloader {
             is_premium: AtomicBool::new(false),
             validated: AtomicBool::new(false),
             auth_label,
+            cdn: NexusCdn::Default,
+            fastest_server: tokio::sync::Mutex::new(None),
         })
     }
 
+    /// Choose the download server (`--nexus-cdn`, `nexus_cdn` setting).
+    pub fn with_cdn(mut self, cdn: NexusCdn) -> Self {
+        self.cdn = cdn;
+        self
+    }
+
     /// Validate the API key and get user info (including Premium status)
     ///
     /// This should be called once at startup to verify credentials and check
@@ -171,12 +255,14 @@ impl NexusDownloader {
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
-            bail!(
+            let message = format!(
                 "Nexus {} validation failed ({}): {}",
-                self.auth_label,
-                status,
-                body
+                self.auth_label, status, body
             );
+            if matches!(status.as_u16(), 401 | 403) {
+                bail!(InstallError::msg(InstallError::Unauthorized, message));
+            }
+            bail!(message);
         }
 
         let user_info: NexusUserInfo =
@@ -277,13 +363,25 @@ impl NexusDownloader {
                 );
             }
 
+            let body = response.text().await.unwrap_or_default();
+            if let Some(reason) = NexusFileUnavailable::from_response(status.as_u16(), &body) {
+                return Err(NexusFileUnavailable {
+                    game_domain: game_domain.to_string(),
+                    mod_id,
+                    file_id,
+                    reason,
+                }
+                .into());
+            }
+
             // A 403 usually means a non-Premium account attempted a direct API download.
             if status.as_u16() == 403 {
                 let is_premium = self.is_premium.load(Ordering::Relaxed);
                 if !is_premium {
-                    bail!(
+                    bail!(InstallError::msg(
+                        InstallError::Unauthorized,
                         "Nexus API forbidden (403). Automated Nexus downloads require a Premium account."
-                    );
+                    ));
                 } else {
                     // Premium user got 403 - might be deleted mod or permissions issue
                     bail!(
@@ -292,7 +390,6 @@ impl NexusDownloader {
                 }
             }
 
-            let body = response.text().await.unwrap_or_default();
             bail!("Nexus API error {}: {}", status, body);
         }
 
@@ -304,13 +401,70 @@ impl NexusDownloader {
         let links: Vec<DownloadLink> =
             serde_json::from_str(&body).context("Failed to parse download links response")?;
 
-        links
-            .into_iter()
-            .next()
-            .map(|link| link.uri)
+        self.pick_link(links)
+            .await
             .context("No download links returned by Nexus API")
     }
 
+    /// Link on the configured server, or the first one if that server isn't
+    /// offered or couldn't be probed.
+    async fn pick_link(&self, mut links: Vec<DownloadLink>) -> Option<String> {
+        let preferred = match &self.cdn {
+            NexusCdn::Default => None,
+            NexusCdn::Region(name) => Some(name.clone()),
+            NexusCdn::Fastest if links.len() > 1 => self.fastest_server(&links).await,
+            NexusCdn::Fastest => None,
+        };
+        if let Some(name) = preferred {
+            match find_server(&links, &name) {
+                Some(i) => return Some(links.swap_remove(i).uri),
+                None => debug!("Nexus server '{}' not offered, using the default", name),
+            }
+        }
+        links.into_iter().next().map(|link| link.uri)
+    }
+
+    /// Short name of the server with the quickest TCP connect, probed on the
+    /// first call and cached for the session.
+    async fn fastest_server(&self, links: &[DownloadLink]) -> Option<String> {
+        let mut cached = self.fastest_server.lock().await;
+        if let Some(fastest) = cached.as_ref() {
+            return fastest.clone();
+        }
+
+        let probes = links.iter().map(|link| async move {
+            let url = reqwest::Url::parse(&link.uri).ok()?;
+            let host = url.host_str()?.to_string();
+            let port = url.port_or_known_default()?;
+            let start = Instant::now();
+            let connect = tokio::net::TcpStream::connect((host.as_str(), port));
+            match tokio::time::timeout(SERVER_PROBE_TIMEOUT, connect).await {
+                Ok(Ok(_)) => Some((link.short_name.clone(), start.elapsed())),
+                _ => {
+                    debug!("Nexus server {} ({}) did not answer", link.name, host);
+                    None
+                }
+            }
+        });
+        let fastest = futures::future::join_all(probes)
+            .await
+            .into_iter()
+            .flatten()
+            .min_by_key(|(_, latency)| *latency);
+
+        match &fastest {
+            Some((name, latency)) => info!(
+                "Using Nexus download server {} ({} ms to connect)",
+                name,
+                latency.as_millis()
+            ),
+            None => warn!("No Nexus download server answered, using the default"),
+        }
+        let fastest = fastest.map(|(name, _)| name);
+        *cached = Some(fastest.clone());
+        fastest
+    }
+
     /// Get the mod page URL for manual fallback
     pub fn get_mod_page_url(game_domain: &str, mod_id: u64, file_id: u64) -> String {
         format!(
@@ -322,26 +476,25 @@ impl NexusDownloader {
     }
 
     /// Map game name to Nexus domain name
+    ///
+    /// Wabbajack game names resolve through `GameType`; the match below only
+    /// covers human-typed aliases and Nexus-only games.
     pub fn game_domain(game_name: &str) -> &str {
+        if let Some(game) = GameType::from_wabbajack_name(game_name) {
+            return game.nexus_domain();
+        }
         match game_name.to_lowercase().as_str() {
-            "falloutnewvegas" | "fallout new vegas" | "fnv" => "newvegas",
-            "fallout3" | "fallout 3" | "fo3" => "fallout3",
-            "fallout4" | "fallout 4" | "fo4" => "fallout4",
-            "skyrim" => "skyrim",
-            "skyrimspecialedition" | "skyrim special edition" | "sse" => "skyrimspecialedition",
-            "skyrimvr" => "skyrimspecialedition", // Uses same domain
-            "oblivion" => "oblivion",
-            "morrowind" => "morrowind",
-            "starfield" => "starfield",
-            "enderal" => "enderal",
-            "enderalspecialedition" => "enderalspecialedition",
-            "cyberpunk2077" | "cyberpunk 2077" => "cyberpunk2077",
-            "baldursgate3" | "baldur's gate 3" | "bg3" => "baldursgate3",
+            "fallout new vegas" | "fnv" => "newvegas",
+            "fallout 3" | "fo3" => "fallout3",
+            "fallout 4" | "fo4" => "fallout4",
+            "skyrim special edition" | "sse" => "skyrimspecialedition",
+            "cyberpunk 2077" => "cyberpunk2077",
+            "baldur's gate 3" | "bg3" => "baldursgate3",
             "vtmb"
             | "vampirethemasqueradebloodlines"
             | "vampire the masquerade bloodlines"
             | "vampire: the masquerade - bloodlines" => "vampirebloodlines",
-            "nierautomata" | "nier automata" => "nierautomata",
+            "nier automata" => "nierautomata",
             "site" | "moddingtools" => "site", // Modding tools
             _ => game_name,                    // Pass through unknown
         }
@@ -362,6 +515,14 @@ struct DownloadLink {
     short_name: String,
 }
 
+/// Index of the link whose server name or short name is `name`.
+fn find_server(links: &[DownloadLink], name: &str) -> Option<usize> {
+    let name = name.trim();
+    links.iter().position(|link| {
+        link.short_name.eq_ignore_ascii_case(name) || link.name.eq_ignore_ascii_case(name)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -407,4 +568,54 @@ mod tests {
         };
         assert!(limits.is_exhausted());
     }
+
+    #[test]
+    fn test_file_unavailable_detection() {
+        assert!(NexusFileUnavailable::from_response(404, "").is_some());
+        assert!(NexusFileUnavailable::from_response(
+            403,
+            r#"{"message":"This file has been archived and is no longer available"}"#
+        )
+        .is_some_and(|r| r.contains("archived")));
+        assert!(NexusFileUnavailable::from_response(403, "Premium only").is_none());
+        assert!(NexusFileUnavailable::from_response(500, "").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cdn_region_selection() {
+        assert_eq!(NexusCdn::parse(" "), NexusCdn::Default);
+        assert_eq!(NexusCdn::parse("Fastest"), NexusCdn::Fastest);
+        assert_eq!(
+            NexusCdn::parse("Amsterdam"),
+            NexusCdn::Region("Amsterdam".into())
+        );
+
+        let links = || {
+            ["Nexus CDN", "Amsterdam", "Chicago"]
+                .iter()
+                .map(|name| DownloadLink {
+                    uri: format!("https://{}.example/file.7z", name),
+                    name: format!("{} (Premium)", name),
+                    short_name: name.to_string(),
+                })
+                .collect::<Vec<_>>()
+        };
+        let nexus = NexusDownloader::new("key").unwrap();
+        assert_eq!(
+            nexus.pick_link(links()).await.unwrap(),
+            "https://Nexus CDN.example/file.7z"
+        );
+        let nexus = nexus.with_cdn(NexusCdn::Region("chicago".into()));
+        assert_eq!(
+            nexus.pick_link(links()).await.unwrap(),
+            "https://Chicago.example/file.7z"
+        );
+        // An unknown region falls back to the default link.
+        let nexus = nexus.with_cdn(NexusCdn::Region("Tokyo".into()));
+        assert!(nexus
+            .pick_link(links())
+            .await
+            .unwrap()
+            .contains("Nexus CDN"));
+    }
 }
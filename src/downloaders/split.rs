@@ -0,0 +1,335 @@
+//! Archives a host serves as numbered parts
+//!
+//! Some hosts cap file sizes, so one logical archive is uploaded as
+//! `Mod.7z.001`, `Mod.7z.002`, ... and the modlist only records the URL of
+//! the first part plus the size and hash of the whole archive. The parts are
+//! downloaded in parallel and joined in order; the caller verifies the
+//! combined hash like any other download.
+
+use super::http::{download_file_with_headers, truncate_url, HttpClient, ProgressCallback};
+use anyhow::{bail, Context, Result};
+use futures::stream::{self, StreamExt};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::fs::File;
+use tracing::{debug, info};
+
+/// Parts downloaded at once
+const PARALLEL_PARTS: usize = 4;
+
+/// How often combined progress is reported while parts download
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Digit width of a `.NNN` part suffix if `name` ends with one.
+fn part_suffix_width(name: &str) -> Option<usize> {
+    let (_, digits) = name.rsplit_once('.')?;
+    (digits.len() >= 3 && digits.bytes().all(|b| b.is_ascii_digit())).then_some(digits.len())
+}
+
+/// Whether `url` is the first part of a split archive stored as `archive_name`.
+///
+/// Returns the digit width of the part suffix. An archive whose own name
+/// ends in `.001` is a single part the modlist lists on its own, not a split.
+pub fn split_first_part(url: &str, archive_name: &str) -> Option<usize> {
+    if part_suffix_width(archive_name).is_some() {
+        return None;
+    }
+    let parsed = reqwest::Url::parse(url).ok()?;
+    let segment = parsed.path_segments()?.next_back()?;
+    let width = part_suffix_width(segment)?;
+    let (_, digits) = segment.rsplit_once('.')?;
+    (digits.parse::<u32>().ok()? == 1).then_some(width)
+}
+
+/// URL of part `index` (1-based), given the URL of the first part.
+pub fn part_url(first_url: &str, index: u32, width: usize) -> Result<String> {
+    let mut parsed = reqwest::Url::parse(first_url)
+        .with_context(|| format!("Invalid URL: {}", truncate_url(first_url)))?;
+    let path = parsed.path();
+    let stem = path
+        .len()
+        .checked_sub(width)
+        .and_then(|end| path.get(..end))
+        .filter(|stem| stem.ends_with('.'))
+        .with_context(|| format!("Not a split archive URL: {}", truncate_url(first_url)))?;
+    let path = format!("{}{:0width$}", stem, index, width = width);
+    parsed.set_path(&path);
+    Ok(parsed.to_string())
+}
+
+/// Where part `index` is kept while it downloads.
+fn part_path(output_path: &Path, index: u32) -> PathBuf {
+    let mut name = output_path.as_os_str().to_os_string();
+    name.push(format!(".part{:03}", index));
+    PathBuf::from(name)
+}
+
+/// Size the host reports for `url` in response to a HEAD request, or None
+/// if it doesn't exist.
+async fn probe_part_size(
+    client: &HttpClient,
+    url: &str,
+    headers: &[(String, String)],
+) -> Result<Option<u64>> {
+    let mut request = client.inner().head(url);
+    for (name, value) in headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    let response = request
+        .send()
+        .await
+        .with_context(|| format!("Connection failed: {}", truncate_url(url)))?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        bail!("HTTP {} for {}", status.as_u16(), truncate_url(url));
+    }
+    // Read the header itself: a HEAD response has no body, so
+    // `content_length()` would report 0.
+    let size = response
+        .headers()
+        .get(reqwest::header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok()?.parse::<u64>().ok())
+        .with_context(|| format!("Host did not report the size of {}", truncate_url(url)))?;
+    Ok(Some(size))
+}
+
+/// Size of each part, found by probing `.001`, `.002`, ... until the parts
+/// add up to `expected_size`.
+///
+/// None if the first part can't be probed (no HEAD support, no size) or is
+/// already the whole archive; the URL is then fetched as a single file.
+async fn probe_parts(
+    client: &HttpClient,
+    first_url: &str,
+    width: usize,
+    headers: &[(String, String)],
+    expected_size: u64,
+) -> Result<Option<Vec<(String, u64)>>> {
+    let max_parts = 10u32.saturating_pow(width as u32).saturating_sub(1);
+    let mut parts = Vec::new();
+    let mut total = 0u64;
+
+    for index in 1..=max_parts {
+        let url = part_url(first_url, index, width)?;
+        let probed = probe_part_size(client, &url, headers).await;
+        if index == 1 && !matches!(probed, Ok(Some(size)) if size < expected_size) {
+            match &probed {
+                Err(e) => debug!("Can't probe {}: {:#}", truncate_url(&url), e),
+                Ok(None) => debug!("HEAD {} found nothing", truncate_url(&url)),
+                Ok(Some(size)) => debug!("{} alone is {} bytes", truncate_url(&url), size),
+            }
+            return Ok(None);
+        }
+        let size = probed
+            .with_context(|| {
+                format!(
+                    "Failed to probe split archive part .{:0width$}",
+                    index,
+                    width = width
+                )
+            })?
+            .with_context(|| {
+                format!(
+                    "Split archive part .{:0width$} is missing ({} of {} bytes found in {} parts): {}",
+                    index,
+                    total,
+                    expected_size,
+                    parts.len(),
+                    truncate_url(&url),
+                    width = width
+                )
+            })?;
+
+        debug!("Split archive part {} is {} bytes", index, size);
+        total += size;
+        parts.push((url, size));
+
+        if total == expected_size {
+            return Ok(Some(parts));
+        }
+        if total > expected_size {
+            bail!(
+                "Split archive parts add up to more than expected ({} > {} bytes after {} parts)",
+                total,
+                expected_size,
+                parts.len()
+            );
+        }
+    }
+
+    bail!(
+        "Split archive has more than {} parts without reaching {} bytes",
+        max_parts,
+        expected_size
+    )
+}
+
+/// Download a split archive whose first part is at `first_url` into
+/// `output_path`, joining the parts in order. Falls back to a plain download
+/// of `first_url` when it turns out not to be split (see [`probe_parts`]).
+///
+/// Parts are kept next to `output_path` until all of them are done, so a
+/// retry resumes them instead of starting over.
+pub async fn download_split_file(
+    client: &HttpClient,
+    first_url: &str,
+    width: usize,
+    headers: &[(String, String)],
+    output_path: &Path,
+    expected_size: u64,
+    progress_callback: Option<&ProgressCallback>,
+) -> Result<u64> {
+    let Some(parts) = probe_parts(client, first_url, width, headers, expected_size).await? else {
+        return download_file_with_headers(
+            client,
+            first_url,
+            headers,
+            output_path,
+            Some(expected_size),
+            progress_callback,
+        )
+        .await;
+    };
+    info!(
+        "Downloading {} parts of split archive {}",
+        parts.len(),
+        output_path.display()
+    );
+
+    // Each part reports into its own counter; the sum is forwarded on a
+    // timer since the per-part callbacks can't borrow the caller's.
+    let part_bytes: Arc<Vec<AtomicU64>> =
+        Arc::new(parts.iter().map(|_| AtomicU64::new(0)).collect());
+    let part_count = parts.len();
+    let report = |part_bytes: &[AtomicU64]| {
+        if let Some(callback) = progress_callback {
+            let done: u64 = part_bytes.iter().map(|b| b.load(Ordering::Relaxed)).sum();
+            callback(done, expected_size, 0.0);
+        }
+    };
+
+    let downloads = stream::iter(parts.iter().enumerate())
+        .map(|(i, (url, size))| {
+            let counters = Arc::clone(&part_bytes);
+            async move {
+                let on_progress: ProgressCallback = Box::new(move |downloaded, _, _| {
+                    counters[i].store(downloaded, Ordering::Relaxed);
+                });
+                let path = part_path(output_path, i as u32 + 1);
+                download_file_with_headers(
+                    client,
+                    url,
+                    headers,
+                    &path,
+                    Some(*size),
+                    Some(&on_progress),
+                )
+                .await
+                .with_context(|| format!("Split archive part {} of {}", i + 1, part_count))
+            }
+        })
+        .buffer_unordered(PARALLEL_PARTS)
+        .collect::<Vec<Result<u64>>>();
+    tokio::pin!(downloads);
+
+    let mut ticker = tokio::time::interval(PROGRESS_INTERVAL);
+    let results = loop {
+        tokio::select! {
+            results = &mut downloads => break results,
+            _ = ticker.tick() => report(&part_bytes),
+        }
+    };
+    report(&part_bytes);
+
+    for result in results {
+        result?;
+    }
+
+    let mut output = File::create(output_path)
+        .await
+        .with_context(|| format!("Failed to create {}", output_path.display()))?;
+    let mut total = 0u64;
+    for index in 1..=parts.len() as u32 {
+        let path = part_path(output_path, index);
+        let mut part = File::open(&path)
+            .await
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        total += tokio::io::copy(&mut part, &mut output)
+            .await
+            .with_context(|| format!("Failed to append {}", path.display()))?;
+    }
+    output.sync_all().await?;
+    drop(output);
+
+    if total != expected_size {
+        bail!(
+            "Joined split archive is {} bytes, expected {}",
+            total,
+            expected_size
+        );
+    }
+    for index in 1..=parts.len() as u32 {
+        let _ = tokio::fs::remove_file(part_path(output_path, index)).await;
+    }
+
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_first_part() {
+        assert_eq!(
+            split_first_part("https://host.example/files/Mod.7z.001", "Mod.7z"),
+            Some(3)
+        );
+        assert_eq!(
+            split_first_part("https://host.example/Mod.zip.0001?dl=1", "Mod.zip"),
+            Some(4)
+        );
+        // Later parts, single files and parts listed on their own.
+        assert_eq!(
+            split_first_part("https://host.example/Mod.7z.002", "Mod.7z"),
+            None
+        );
+        assert_eq!(
+            split_first_part("https://host.example/Mod.7z", "Mod.7z"),
+            None
+        );
+        assert_eq!(
+            split_first_part("https://host.example/Mod.7z.001", "Mod.7z.001"),
+            None
+        );
+        assert_eq!(split_first_part("https://host.example/v.01", "v"), None);
+    }
+
+    #[test]
+    fn test_part_url() {
+        let first = "https://host.example/files/Mod.7z.001?token=abc";
+        assert_eq!(
+            part_url(first, 2, 3).unwrap(),
+            "https://host.example/files/Mod.7z.002?token=abc"
+        );
+        assert_eq!(
+            part_url(first, 12, 3).unwrap(),
+            "https://host.example/files/Mod.7z.012?token=abc"
+        );
+        assert!(part_url("https://host.example/Mod.7z", 2, 3).is_err());
+    }
+
+    #[test]
+    fn test_part_path() {
+        assert_eq!(
+            part_path(Path::new("/dl/Mod.7z"), 2),
+            PathBuf::from("/dl/Mod.7z.part002")
+        );
+    }
+}
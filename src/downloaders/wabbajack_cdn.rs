@@ -2,7 +2,10 @@
 //!
 //! Wabbajack CDN stores large files in multiple parts (chunks).
 //! This module fetches the definition file and downloads parts in parallel.
+//! Each part is checked against its size and xxHash64 from the definition;
+//! a bad part is retried on its own instead of restarting the whole file.
 
+use crate::error::InstallError;
 use anyhow::{bail, Context, Result};
 use flate2::read::GzDecoder;
 use futures::stream::{self, StreamExt};
@@ -57,16 +60,41 @@ pub struct CdnPart {
 /// Wabbajack CDN downloader
 pub struct WabbajackCdnDownloader {
     client: Client,
+    /// Attempts per CDN part before the download fails.
+    part_attempts: u32,
 }
 
 impl WabbajackCdnDownloader {
     pub fn new() -> Result<Self> {
         let client = Client::builder()
-            .user_agent("Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0")
+            .user_agent(super::user_agent())
             .build()
             .context("Failed to create HTTP client")?;
 
-        Ok(Self { client })
+        Ok(Self {
+            client,
+            part_attempts: super::MAX_RETRIES,
+        })
+    }
+
+    /// Retry a failed or corrupt part this many times (0 = no retries).
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.part_attempts = max_retries + 1;
+        self
+    }
+
+    /// Whether `url` points at Wabbajack's multi-part file CDN (as opposed to
+    /// an ordinary web server).
+    pub fn is_cdn_url(url: &str) -> bool {
+        let url = Self::remap_url(url);
+        let host = url
+            .split_once("://")
+            .map_or(url.as_str(), |(_, rest)| rest)
+            .split(['/', '?'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        host == "build.wabbajack.org" || CDN_REMAPS.iter().any(|(_, to)| host == *to)
     }
 
     /// Remap B-CDN URLs to official Wabbajack domains
@@ -193,6 +221,7 @@ impl WabbajackCdnDownloader {
         let parts_with_urls: Vec<_> = definition.parts.iter().zip(part_urls.iter()).collect();
 
         let client = self.client.clone();
+        let part_attempts = self.part_attempts;
         let output_path_owned = output_path.to_path_buf();
 
         let results: Vec<Result<()>> = stream::iter(parts_with_urls)
@@ -202,28 +231,21 @@ impl WabbajackCdnDownloader {
                 let output_path = output_path_owned.clone();
                 let downloaded_bytes = downloaded_bytes.clone();
                 let progress_callback = progress_callback.clone();
-                let part_index = part.index;
                 let part_offset = part.offset as u64;
-                let part_size = part.size;
 
                 async move {
-                    // Download the part with retry
+                    // Download and verify the part; a corrupt part is retried alone
                     let bytes = super::with_retry(
-                        &format!("CDN part {}", part_index),
-                        super::MAX_RETRIES,
-                        || Self::download_part_static(&client, &url),
+                        &format!("CDN part {}", part.index),
+                        part_attempts,
+                        || async {
+                            let bytes = Self::download_part_static(&client, &url).await?;
+                            verify_part(part, &bytes)?;
+                            Ok(bytes)
+                        },
                     )
                     .await?;
 
-                    if bytes.len() != part_size {
-                        bail!(
-                            "Part {} size mismatch: expected {}, got {}",
-                            part_index,
-                            part_size,
-                            bytes.len()
-                        );
-                    }
-
                     // Write at the correct offset using pwrite (atomic, no seek race)
                     let output_path_clone = output_path.clone();
                     let bytes_clone = bytes.clone();
@@ -364,6 +386,29 @@ impl Default for WabbajackCdnDownloader {
     }
 }
 
+/// Check a downloaded part against the size and hash in the definition.
+fn verify_part(part: &CdnPart, bytes: &[u8]) -> Result<()> {
+    if bytes.len() != part.size {
+        bail!(
+            "Part {} size mismatch: expected {}, got {}",
+            part.index,
+            part.size,
+            bytes.len()
+        );
+    }
+    let actual = crate::hash::compute_bytes_hash(bytes);
+    if actual != part.hash {
+        bail!(InstallError::msg(
+            InstallError::HashMismatch,
+            format!(
+                "Part {} hash mismatch: expected {}, got {}",
+                part.index, part.hash, actual
+            )
+        ));
+    }
+    Ok(())
+}
+
 /// Parse CDN definition JSON with fallback for malformed responses
 fn parse_definition(json_str: &str) -> Result<CdnFileDefinition> {
     // Try direct parsing first
@@ -398,6 +443,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_is_cdn_url() {
+        assert!(WabbajackCdnDownloader::is_cdn_url(
+            "https://authored-files.wabbajack.org/Tuxborn.wabbajack_0d2c"
+        ));
+        assert!(WabbajackCdnDownloader::is_cdn_url(
+            "https://wabbajack.b-cdn.net/Tuxborn.wabbajack_0d2c"
+        ));
+        assert!(!WabbajackCdnDownloader::is_cdn_url(
+            "https://github.com/author/list/releases/download/v1/list.wabbajack"
+        ));
+        assert!(!WabbajackCdnDownloader::is_cdn_url(
+            "https://example.com/authored-files.wabbajack.org/x"
+        ));
+    }
+
     #[test]
     fn test_parse_definition() {
         let json = r#"{"Author":"test","OriginalFileName":"test.7z","Size":1000,"Hash":"abc=","Parts":[{"Size":1000,"Offset":0,"Hash":"abc=","Index":0}],"MungedName":"test.7z_123"}"#;
@@ -416,6 +477,24 @@ mod tests {
         assert_eq!(def.author, "test");
     }
 
+    #[test]
+    fn test_verify_part() {
+        let data = b"part payload";
+        let part = CdnPart {
+            hash: crate::hash::compute_bytes_hash(data),
+            index: 3,
+            offset: 0,
+            size: data.len(),
+        };
+        assert!(verify_part(&part, data).is_ok());
+
+        let err = verify_part(&part, b"part pAyload").unwrap_err();
+        assert!(err.to_string().contains("Part 3 hash mismatch"));
+
+        let err = verify_part(&part, b"short").unwrap_err();
+        assert!(err.to_string().contains("size mismatch"));
+    }
+
     #[test]
     fn test_get_part_urls() {
         let def = CdnFileDefinition {
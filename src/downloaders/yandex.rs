@@ -47,8 +47,17 @@ struct ListItem {
 
 impl YandexDownloader {
     pub fn new() -> Result<Self> {
+        // Yandex wants a browser-style prefix on the default UA.
+        let user_agent = if super::user_agent() == super::DEFAULT_USER_AGENT {
+            format!(
+                "Mozilla/5.0 (X11; Linux x86_64) {}",
+                super::DEFAULT_USER_AGENT
+            )
+        } else {
+            super::user_agent().to_string()
+        };
         let client = Client::builder()
-            .user_agent("Mozilla/5.0 (X11; Linux x86_64) clf3/1.0")
+            .user_agent(user_agent)
             .build()
             .context("Failed to create Yandex HTTP client")?;
         Ok(Self { client })
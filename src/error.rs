@@ -0,0 +1,168 @@
+//! Typed install errors
+//!
+//! Internally everything is `anyhow`. Failures a caller may want to react to
+//! are tagged where they happen (`bail!(InstallError::msg(InstallError::Unauthorized, ..))`) or
+//! recognised from their cause (a `reqwest::Error`, ENOSPC, a JSON error).
+//! `InstallError::from` sorts an `anyhow::Error` into a variant; every variant
+//! keeps the full context chain, so display is unchanged.
+//!
+//! Only the `Installer` entry points (`new`, `run_pipelined`,
+//! `run_downloads_only`) return `InstallError`. The downloaders in
+//! `crate::downloaders` still return `anyhow::Result`: the installer's retry
+//! logic walks their cause chain (`NexusFileUnavailable`, `SizeMismatch`,
+//! ENOSPC), which a wrapping enum would hide. Callers of a downloader
+//! directly get the same classification with `InstallError::from(err)`.
+
+use crate::downloaders::{NexusFileUnavailable, SizeMismatch};
+use std::error::Error as StdError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum InstallError {
+    /// Connection, timeout or HTTP failure; usually worth retrying.
+    #[error("{0:#}")]
+    Network(anyhow::Error),
+    /// A file's size or hash doesn't match the modlist.
+    #[error("{0:#}")]
+    HashMismatch(anyhow::Error),
+    /// Credentials were rejected or the account lacks access (e.g. non-Premium
+    /// Nexus). Re-authenticating may help.
+    #[error("{0:#}")]
+    Unauthorized(anyhow::Error),
+    /// The disk (or quota) holding the downloads or install is full.
+    #[error("{0:#}")]
+    DiskFull(anyhow::Error),
+    /// Game files the modlist needs are missing or modified.
+    #[error("{0:#}")]
+    MissingGameFile(anyhow::Error),
+    /// The modlist or a server response couldn't be parsed.
+    #[error("{0:#}")]
+    ParseError(anyhow::Error),
+    /// The download source has been removed or archived.
+    #[error("{0:#}")]
+    SourceUnavailable(anyhow::Error),
+    #[error("{0:#}")]
+    Other(anyhow::Error),
+}
+
+type Variant = fn(anyhow::Error) -> InstallError;
+
+impl InstallError {
+    /// Tag a message with this error's kind, for `bail!`.
+    pub fn msg(variant: Variant, message: impl std::fmt::Display) -> Self {
+        variant(anyhow::anyhow!("{}", message))
+    }
+
+    /// The underlying error with its full context chain.
+    pub fn inner(&self) -> &anyhow::Error {
+        match self {
+            Self::Network(e)
+            | Self::HashMismatch(e)
+            | Self::Unauthorized(e)
+            | Self::DiskFull(e)
+            | Self::MissingGameFile(e)
+            | Self::ParseError(e)
+            | Self::SourceUnavailable(e)
+            | Self::Other(e) => e,
+        }
+    }
+
+    /// Whether running the install again may get past this error: network
+    /// trouble, a corrupt download, or anything unrecognised. Removed
+    /// sources, rejected logins, a full disk and the like fail the same way
+    /// every time.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::Network(_) | Self::HashMismatch(_) | Self::Other(_)
+        )
+    }
+
+    fn variant(&self) -> Variant {
+        match self {
+            Self::Network(_) => Self::Network,
+            Self::HashMismatch(_) => Self::HashMismatch,
+            Self::Unauthorized(_) => Self::Unauthorized,
+            Self::DiskFull(_) => Self::DiskFull,
+            Self::MissingGameFile(_) => Self::MissingGameFile,
+            Self::ParseError(_) => Self::ParseError,
+            Self::SourceUnavailable(_) => Self::SourceUnavailable,
+            Self::Other(_) => Self::Other,
+        }
+    }
+}
+
+/// Kind of a single cause in an error chain, if it's one we recognise.
+fn classify(cause: &(dyn StdError + 'static)) -> Option<Variant> {
+    if let Some(e) = cause.downcast_ref::<InstallError>() {
+        return Some(e.variant());
+    }
+    if cause.is::<NexusFileUnavailable>() {
+        return Some(InstallError::SourceUnavailable);
+    }
+    if cause.is::<SizeMismatch>() {
+        return Some(InstallError::HashMismatch);
+    }
+    if cause.is::<serde_json::Error>() {
+        return Some(InstallError::ParseError);
+    }
+    if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+        let status = e.status().map(|s| s.as_u16());
+        return Some(if matches!(status, Some(401 | 403)) {
+            InstallError::Unauthorized
+        } else if e.is_decode() {
+            InstallError::ParseError
+        } else {
+            InstallError::Network
+        });
+    }
+    if let Some(e) = cause.downcast_ref::<std::io::Error>() {
+        if matches!(e.raw_os_error(), Some(libc::ENOSPC | libc::EDQUOT)) {
+            return Some(InstallError::DiskFull);
+        }
+    }
+    None
+}
+
+impl From<anyhow::Error> for InstallError {
+    fn from(err: anyhow::Error) -> Self {
+        // Outermost recognised cause wins: it's the most specific tag.
+        let variant = err
+            .chain()
+            .find_map(classify)
+            .unwrap_or(InstallError::Other);
+        variant(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn causes_are_classified_through_context() {
+        let disk_full = std::io::Error::from_raw_os_error(libc::ENOSPC);
+        let err = anyhow::Error::new(disk_full).context("Failed to write foo.7z");
+        let err = InstallError::from(err);
+        assert!(matches!(err, InstallError::DiskFull(_)));
+        assert!(err.to_string().starts_with("Failed to write foo.7z: "));
+
+        let tagged: anyhow::Result<()> =
+            Err(InstallError::msg(InstallError::Unauthorized, "LoversLab login failed").into());
+        let err = InstallError::from(tagged.context("Downloading x").unwrap_err());
+        assert!(matches!(err, InstallError::Unauthorized(_)));
+        assert!(!err.is_retryable());
+        assert_eq!(err.to_string(), "Downloading x: LoversLab login failed");
+
+        let json = serde_json::from_str::<u32>("nope").unwrap_err();
+        assert!(matches!(
+            InstallError::from(anyhow::Error::new(json)),
+            InstallError::ParseError(_)
+        ));
+        assert!(matches!(
+            InstallError::from(anyhow::anyhow!("something else")),
+            InstallError::Other(_)
+        ));
+        assert!(InstallError::msg(InstallError::Network, "connection reset").is_retryable());
+    }
+}
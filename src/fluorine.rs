@@ -6,8 +6,12 @@
 //! sidebar. The registration is just a string-list entry in Fluorine's QSettings
 //! INI file — no IPC, no API.
 //!
-//! This module also auto-downloads the latest Fluorine release when the user
-//! wants to integrate but doesn't have it installed yet.
+//! This module also auto-downloads Fluorine when the user wants to integrate
+//! but doesn't have it installed yet: the latest release, or the one pinned
+//! with `fluorine install --version` / `Settings::fluorine_version`. The MO2
+//! build inside a modlist comes from the modlist itself and isn't touched.
+//! Downloads are checked against GitHub's SHA-256 digest and retried, first
+//! from the release download link and then through the API asset endpoint.
 
 #![allow(dead_code)]
 
@@ -20,6 +24,9 @@ use std::path::{Path, PathBuf};
 /// Owner/repo on GitHub.
 const REPO: &str = "SulfurNitride/Fluorine-Manager";
 
+/// Attempts per download source before falling back to the next one.
+const DOWNLOAD_ATTEMPTS: u32 = 3;
+
 /// Where Fluorine stores its global QSettings INI (where `PortableInstances=` lives).
 /// Hard-coded because Fluorine sets `setApplicationName("ModOrganizer")` with no
 /// organization override on Linux, so Qt picks `Mod Organizer Team` by default.
@@ -146,15 +153,50 @@ pub fn register_portable_instance(install_path: &Path, make_current: bool) -> Re
     Ok(())
 }
 
+/// A Fluorine release that was downloaded and extracted.
+#[derive(Debug, Clone)]
+pub struct FluorineDownload {
+    /// Install root containing the binary.
+    pub root: PathBuf,
+    /// Release tag, e.g. `v0.2.0`.
+    pub version: String,
+    /// Where the archive came from, e.g. "GitHub release".
+    pub source: &'static str,
+}
+
 /// Download and extract the latest Fluorine release to the auto-install dir.
-/// Returns the install root containing the binary.
-pub async fn download_latest(dest_root: Option<PathBuf>) -> Result<PathBuf> {
+pub async fn download_latest(dest_root: Option<PathBuf>) -> Result<FluorineDownload> {
+    download_release(dest_root, None).await
+}
+
+/// Download and extract Fluorine release `version` (a GitHub tag such as
+/// `v0.2.0`), or the latest when `None`. If the pinned tag can't be found the
+/// latest release is used instead. The archive is checked against the SHA-256
+/// digest GitHub publishes for it; failed or corrupt downloads are retried
+/// and then fetched from the next source.
+pub async fn download_release(
+    dest_root: Option<PathBuf>,
+    version: Option<&str>,
+) -> Result<FluorineDownload> {
     let dest = match dest_root {
         Some(p) => p,
         None => default_install_root()?,
     };
 
-    let release = fetch_latest_release().await?;
+    let release = match version {
+        Some(tag) => match fetch_release(Some(tag)).await {
+            Ok(release) => release,
+            Err(e) => {
+                tracing::warn!(
+                    "Fluorine release {} unavailable ({:#}); using the latest release",
+                    tag,
+                    e
+                );
+                fetch_release(None).await?
+            }
+        },
+        None => fetch_release(None).await?,
+    };
     let asset = release
         .assets
         .iter()
@@ -170,20 +212,14 @@ pub async fn download_latest(dest_root: Option<PathBuf>) -> Result<PathBuf> {
     );
 
     let client = reqwest::Client::builder()
-        .user_agent("clf3")
+        .user_agent(crate::downloaders::user_agent())
         .build()
         .context("Failed to build reqwest client")?;
 
-    let bytes = client
-        .get(&asset.browser_download_url)
-        .send()
-        .await
-        .context("Fluorine download failed")?
-        .error_for_status()
-        .context("Fluorine download returned non-2xx")?
-        .bytes()
+    let (bytes, source) = download_asset(&client, asset)
         .await
-        .context("Failed to read Fluorine download body")?;
+        .with_context(|| format!("Could not download Fluorine {}", release.tag_name))?;
+    tracing::info!("Downloaded Fluorine {} from {}", release.tag_name, source);
 
     fs::create_dir_all(&dest).with_context(|| format!("Failed to create {:?}", dest))?;
     extract_tar_gz(&bytes, &dest).context("Failed to extract Fluorine release")?;
@@ -208,11 +244,81 @@ pub async fn download_latest(dest_root: Option<PathBuf>) -> Result<PathBuf> {
         fs::set_permissions(&install.binary, perms)?;
     }
 
-    Ok(install
-        .binary
-        .parent()
-        .map(|p| p.to_path_buf())
-        .unwrap_or(dest))
+    Ok(FluorineDownload {
+        root: install
+            .binary
+            .parent()
+            .map(|p| p.to_path_buf())
+            .unwrap_or(dest),
+        version: release.tag_name,
+        source,
+    })
+}
+
+/// Places to fetch `asset` from, in order, as (name, URL, `Accept` header).
+fn asset_sources(asset: &GitHubAsset) -> Vec<(&'static str, &str, Option<&'static str>)> {
+    let mut sources = vec![("GitHub release", asset.browser_download_url.as_str(), None)];
+    if !asset.url.is_empty() {
+        sources.push((
+            "GitHub API",
+            asset.url.as_str(),
+            Some("application/octet-stream"),
+        ));
+    }
+    sources
+}
+
+/// Download `asset` and check its digest, retrying each source a few times
+/// before moving to the next. Returns the bytes and the source that worked.
+async fn download_asset(
+    client: &reqwest::Client,
+    asset: &GitHubAsset,
+) -> Result<(Vec<u8>, &'static str)> {
+    let mut last_error = None;
+    for (source, url, accept) in asset_sources(asset) {
+        for attempt in 1..=DOWNLOAD_ATTEMPTS {
+            match fetch_verified(client, url, accept, asset.digest.as_deref()).await {
+                Ok(bytes) => return Ok((bytes, source)),
+                Err(e) => {
+                    tracing::warn!(
+                        "Fluorine download from {} failed (attempt {}/{}): {:#}",
+                        source,
+                        attempt,
+                        DOWNLOAD_ATTEMPTS,
+                        e
+                    );
+                    last_error = Some(e);
+                }
+            }
+            if attempt < DOWNLOAD_ATTEMPTS {
+                tokio::time::sleep(std::time::Duration::from_secs(1 << attempt)).await;
+            }
+        }
+    }
+    Err(last_error.unwrap_or_else(|| anyhow!("No download source for {}", asset.name)))
+}
+
+async fn fetch_verified(
+    client: &reqwest::Client,
+    url: &str,
+    accept: Option<&str>,
+    digest: Option<&str>,
+) -> Result<Vec<u8>> {
+    let mut request = client.get(url);
+    if let Some(accept) = accept {
+        request = request.header("Accept", accept);
+    }
+    let bytes = request
+        .send()
+        .await
+        .context("request failed")?
+        .error_for_status()
+        .context("server returned non-2xx")?
+        .bytes()
+        .await
+        .context("failed to read body")?;
+    verify_digest(&bytes, digest).context("download is corrupt")?;
+    Ok(bytes.to_vec())
 }
 
 #[derive(Debug, Deserialize)]
@@ -226,12 +332,26 @@ struct GitHubAsset {
     name: String,
     size: u64,
     browser_download_url: String,
+    /// API endpoint for the asset; serves the file with
+    /// `Accept: application/octet-stream`.
+    #[serde(default)]
+    url: String,
+    /// `sha256:<hex>`; missing on assets uploaded before GitHub added digests.
+    #[serde(default)]
+    digest: Option<String>,
 }
 
-async fn fetch_latest_release() -> Result<GitHubRelease> {
-    let url = format!("https://api.github.com/repos/{}/releases/latest", REPO);
+/// Fetch release metadata for `tag`, or the latest release.
+async fn fetch_release(tag: Option<&str>) -> Result<GitHubRelease> {
+    let url = match tag {
+        Some(tag) => format!(
+            "https://api.github.com/repos/{}/releases/tags/{}",
+            REPO, tag
+        ),
+        None => format!("https://api.github.com/repos/{}/releases/latest", REPO),
+    };
     let client = reqwest::Client::builder()
-        .user_agent("clf3")
+        .user_agent(crate::downloaders::user_agent())
         .build()
         .context("Failed to build reqwest client")?;
     let release: GitHubRelease = client
@@ -248,6 +368,22 @@ async fn fetch_latest_release() -> Result<GitHubRelease> {
     Ok(release)
 }
 
+/// Check `bytes` against a GitHub asset digest (`sha256:<hex>`). Assets
+/// without one are accepted with a warning.
+fn verify_digest(bytes: &[u8], digest: Option<&str>) -> Result<()> {
+    use sha2::{Digest, Sha256};
+
+    let Some(expected) = digest.and_then(|d| d.strip_prefix("sha256:")) else {
+        tracing::warn!("Fluorine release has no published SHA-256; skipping checksum");
+        return Ok(());
+    };
+    let actual = format!("{:x}", Sha256::digest(bytes));
+    if !actual.eq_ignore_ascii_case(expected) {
+        anyhow::bail!("SHA-256 mismatch: expected {}, got {}", expected, actual);
+    }
+    Ok(())
+}
+
 fn extract_tar_gz(bytes: &[u8], dest: &Path) -> Result<()> {
     let gz = flate2::read::GzDecoder::new(bytes);
     let mut archive = tar::Archive::new(gz);
@@ -386,6 +522,31 @@ mod tests {
     use super::*;
     use tempfile::tempdir;
 
+    #[test]
+    fn release_digest_is_verified() {
+        // sha256("abc")
+        let digest = "sha256:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert!(verify_digest(b"abc", Some(digest)).is_ok());
+        assert!(verify_digest(b"abd", Some(digest)).is_err());
+        assert!(verify_digest(b"abd", None).is_ok());
+    }
+
+    #[test]
+    fn api_endpoint_is_fallback_source() {
+        let mut asset = GitHubAsset {
+            name: "fluorine-manager.tar.gz".into(),
+            size: 0,
+            browser_download_url: "https://github.com/a/b/releases/download/v1/f.tar.gz".into(),
+            url: "https://api.github.com/repos/a/b/releases/assets/1".into(),
+            digest: None,
+        };
+        let names: Vec<_> = asset_sources(&asset).iter().map(|s| s.0).collect();
+        assert_eq!(names, ["GitHub release", "GitHub API"]);
+
+        asset.url.clear();
+        assert_eq!(asset_sources(&asset).len(), 1);
+    }
+
     #[test]
     fn appends_new_portable_instance() {
         let dir = tempdir().unwrap();
@@ -0,0 +1,100 @@
+//! Cached launcher scans
+//!
+//! Walking every Steam library, Heroic manifest and Proton directory is slow
+//! on large libraries, and the GUI and installer each need the results more
+//! than once. The first call scans; later calls reuse the result until
+//! `refresh_scan_cache` is called or Steam's config/library directories change
+//! on disk (tracked by their modification times, across every Steam install).
+
+use super::proton::{find_steam_protons, SteamProton};
+use super::steam::steam_install_paths;
+use super::{detect_all_games, GameScanResult};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+struct Entry<T> {
+    stamp: Option<SystemTime>,
+    value: Arc<T>,
+}
+
+type Slot<T> = Mutex<Option<Entry<T>>>;
+
+static GAMES: Slot<GameScanResult> = Mutex::new(None);
+static PROTONS: Slot<Vec<SteamProton>> = Mutex::new(None);
+
+/// Steam files and directories whose mtime changes when games or Protons are
+/// installed, removed or moved between libraries.
+const STEAM_STAMP_PATHS: &[&str] = &[
+    "config/config.vdf",
+    "config/libraryfolders.vdf",
+    "steamapps/libraryfolders.vdf",
+    "steamapps",
+    "compatibilitytools.d",
+];
+
+/// Latest modification time of the library/compat tool config across all
+/// Steam installs, or `None` when Steam isn't installed. Doesn't resolve the
+/// active install, so checking the cache never spawns a process.
+fn steam_stamp() -> Option<SystemTime> {
+    steam_install_paths()
+        .iter()
+        .flat_map(|steam| STEAM_STAMP_PATHS.iter().map(move |p| steam.join(p)))
+        .filter_map(|path| std::fs::metadata(path).ok()?.modified().ok())
+        .max()
+}
+
+/// Return the cached value if the stamp still matches, otherwise rescan.
+/// The lock is held while scanning so concurrent callers share one scan.
+fn cached<T>(slot: &Slot<T>, stamp: Option<SystemTime>, scan: impl FnOnce() -> T) -> Arc<T> {
+    let mut slot = slot.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(entry) = slot.as_ref().filter(|e| e.stamp == stamp) {
+        return Arc::clone(&entry.value);
+    }
+    let value = Arc::new(scan());
+    *slot = Some(Entry {
+        stamp,
+        value: Arc::clone(&value),
+    });
+    value
+}
+
+/// Installed games from all launchers, scanned once and reused.
+pub fn cached_games() -> Arc<GameScanResult> {
+    cached(&GAMES, steam_stamp(), detect_all_games)
+}
+
+/// Compatible Protons (see `find_steam_protons`), scanned once and reused.
+pub fn cached_protons() -> Arc<Vec<SteamProton>> {
+    cached(&PROTONS, steam_stamp(), find_steam_protons)
+}
+
+/// Drop cached scans so the next call rescans (e.g. a "Rescan" button).
+pub fn refresh_scan_cache() {
+    *GAMES.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    *PROTONS.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn rescans_only_when_stamp_changes() {
+        static SLOT: Slot<u32> = Mutex::new(None);
+        let mut scans = 0;
+        let mut scan = |stamp| {
+            cached(&SLOT, stamp, || {
+                scans += 1;
+                scans
+            })
+        };
+
+        let t0 = Some(SystemTime::UNIX_EPOCH);
+        let t1 = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1));
+        assert_eq!(*scan(t0), 1);
+        assert_eq!(*scan(t0), 1);
+        assert_eq!(*scan(t1), 2);
+        assert_eq!(*scan(None), 3);
+    }
+}
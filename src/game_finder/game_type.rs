@@ -0,0 +1,415 @@
+//! Supported games as a typed enum
+//!
+//! `GameType` is the single place that knows how a game is named by
+//! Wabbajack, Nexus and humans, which Steam app IDs it ships under and which
+//! script extender loader it uses. Launcher/registry details stay in
+//! [`KNOWN_GAMES`](super::known_games::KNOWN_GAMES); `GameType::known_game`
+//! links the two.
+
+use super::known_games::{KnownGame, KNOWN_GAMES};
+use std::fmt;
+
+/// A game CLF3 can install modlists for (one per Wabbajack `GameType`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameType {
+    Morrowind,
+    Oblivion,
+    Fallout3,
+    FalloutNewVegas,
+    Skyrim,
+    Enderal,
+    SkyrimSpecialEdition,
+    EnderalSpecialEdition,
+    SkyrimVR,
+    Fallout4,
+    Fallout4VR,
+    Starfield,
+    Witcher3,
+    Cyberpunk2077,
+    BaldursGate3,
+    NieRAutomata,
+}
+
+impl GameType {
+    /// Every supported game. Order matters for `from_nexus_domain`: the
+    /// first game using a shared domain wins (SSE before Skyrim VR).
+    pub const ALL: &'static [GameType] = &[
+        GameType::Morrowind,
+        GameType::Oblivion,
+        GameType::Fallout3,
+        GameType::FalloutNewVegas,
+        GameType::Skyrim,
+        GameType::Enderal,
+        GameType::SkyrimSpecialEdition,
+        GameType::EnderalSpecialEdition,
+        GameType::SkyrimVR,
+        GameType::Fallout4,
+        GameType::Fallout4VR,
+        GameType::Starfield,
+        GameType::Witcher3,
+        GameType::Cyberpunk2077,
+        GameType::BaldursGate3,
+        GameType::NieRAutomata,
+    ];
+
+    /// Wabbajack's `GameType` string as written in modlist JSON.
+    pub fn wabbajack_name(self) -> &'static str {
+        match self {
+            GameType::Morrowind => "Morrowind",
+            GameType::Oblivion => "Oblivion",
+            GameType::Fallout3 => "Fallout3",
+            GameType::FalloutNewVegas => "FalloutNewVegas",
+            GameType::Skyrim => "Skyrim",
+            GameType::Enderal => "Enderal",
+            GameType::SkyrimSpecialEdition => "SkyrimSpecialEdition",
+            GameType::EnderalSpecialEdition => "EnderalSpecialEdition",
+            GameType::SkyrimVR => "SkyrimVR",
+            GameType::Fallout4 => "Fallout4",
+            GameType::Fallout4VR => "Fallout4VR",
+            GameType::Starfield => "Starfield",
+            GameType::Witcher3 => "Witcher3",
+            GameType::Cyberpunk2077 => "Cyberpunk2077",
+            GameType::BaldursGate3 => "BaldursGate3",
+            GameType::NieRAutomata => "NieRAutomata",
+        }
+    }
+
+    /// Parse a Wabbajack `GameType` string (case-insensitive). Accepts the
+    /// short aliases older modlists and the gallery use.
+    pub fn from_wabbajack_name(name: &str) -> Option<Self> {
+        let name = name.trim();
+        if let Some(game) = Self::ALL
+            .iter()
+            .find(|g| g.wabbajack_name().eq_ignore_ascii_case(name))
+        {
+            return Some(*game);
+        }
+        match name.to_lowercase().as_str() {
+            "skyrimse" => Some(GameType::SkyrimSpecialEdition),
+            "falloutnv" => Some(GameType::FalloutNewVegas),
+            "enderalse" => Some(GameType::EnderalSpecialEdition),
+            "thewitcher3" => Some(GameType::Witcher3),
+            _ => None,
+        }
+    }
+
+    /// Full human-readable name.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            GameType::Morrowind => "Morrowind",
+            GameType::Oblivion => "Oblivion",
+            GameType::Fallout3 => "Fallout 3",
+            GameType::FalloutNewVegas => "Fallout New Vegas",
+            GameType::Skyrim => "Skyrim",
+            GameType::Enderal => "Enderal",
+            GameType::SkyrimSpecialEdition => "Skyrim Special Edition",
+            GameType::EnderalSpecialEdition => "Enderal Special Edition",
+            GameType::SkyrimVR => "Skyrim VR",
+            GameType::Fallout4 => "Fallout 4",
+            GameType::Fallout4VR => "Fallout 4 VR",
+            GameType::Starfield => "Starfield",
+            GameType::Witcher3 => "The Witcher 3",
+            GameType::Cyberpunk2077 => "Cyberpunk 2077",
+            GameType::BaldursGate3 => "Baldur's Gate 3",
+            GameType::NieRAutomata => "NieR: Automata",
+        }
+    }
+
+    /// Compact name for space-constrained UI (gallery cards, filters).
+    pub fn short_name(self) -> &'static str {
+        match self {
+            GameType::Skyrim => "Skyrim LE",
+            GameType::SkyrimSpecialEdition => "Skyrim SE",
+            GameType::FalloutNewVegas => "Fallout NV",
+            GameType::Enderal | GameType::EnderalSpecialEdition => "Enderal",
+            other => other.display_name(),
+        }
+    }
+
+    /// Nexus Mods game domain (`nexusmods.com/<domain>/mods/...`).
+    pub fn nexus_domain(self) -> &'static str {
+        match self {
+            GameType::Morrowind => "morrowind",
+            GameType::Oblivion => "oblivion",
+            GameType::Fallout3 => "fallout3",
+            GameType::FalloutNewVegas => "newvegas",
+            GameType::Skyrim => "skyrim",
+            GameType::Enderal => "enderal",
+            // VR editions share their flat-screen game's Nexus pages
+            GameType::SkyrimSpecialEdition | GameType::SkyrimVR => "skyrimspecialedition",
+            GameType::EnderalSpecialEdition => "enderalspecialedition",
+            GameType::Fallout4 | GameType::Fallout4VR => "fallout4",
+            GameType::Starfield => "starfield",
+            GameType::Witcher3 => "witcher3",
+            GameType::Cyberpunk2077 => "cyberpunk2077",
+            GameType::BaldursGate3 => "baldursgate3",
+            GameType::NieRAutomata => "nierautomata",
+        }
+    }
+
+    /// Parse a Nexus game domain. Shared domains resolve to the flat-screen game.
+    pub fn from_nexus_domain(domain: &str) -> Option<Self> {
+        let domain = domain.trim();
+        Self::ALL
+            .iter()
+            .find(|g| g.nexus_domain().eq_ignore_ascii_case(domain))
+            .copied()
+    }
+
+    /// Steam app IDs the game ships under, canonical first (store variants
+    /// such as Fallout 3 GOTY follow).
+    pub fn app_ids(self) -> &'static [u32] {
+        match self {
+            GameType::Morrowind => &[22320],
+            GameType::Oblivion => &[22330],
+            GameType::Fallout3 => &[22300, 22370],
+            GameType::FalloutNewVegas => &[22380],
+            GameType::Skyrim => &[72850],
+            GameType::Enderal => &[933480],
+            GameType::SkyrimSpecialEdition => &[489830],
+            GameType::EnderalSpecialEdition => &[976620],
+            GameType::SkyrimVR => &[611670],
+            GameType::Fallout4 => &[377160],
+            GameType::Fallout4VR => &[611660],
+            GameType::Starfield => &[1716740],
+            GameType::Witcher3 => &[292030],
+            GameType::Cyberpunk2077 => &[1091500],
+            GameType::BaldursGate3 => &[1086940],
+            GameType::NieRAutomata => &[524220],
+        }
+    }
+
+    /// Find the game for a Steam app ID (including store variants).
+    pub fn from_app_id(app_id: u32) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|g| g.app_ids().contains(&app_id))
+            .copied()
+    }
+
+    /// Script extender loader in the game directory, for games that have one.
+    pub fn script_extender_exe(self) -> Option<&'static str> {
+        match self {
+            GameType::Oblivion => Some("obse_loader.exe"),
+            GameType::Fallout3 => Some("fose_loader.exe"),
+            GameType::FalloutNewVegas => Some("nvse_loader.exe"),
+            GameType::Skyrim | GameType::Enderal => Some("skse_loader.exe"),
+            GameType::SkyrimSpecialEdition | GameType::EnderalSpecialEdition => {
+                Some("skse64_loader.exe")
+            }
+            GameType::SkyrimVR => Some("sksevr_loader.exe"),
+            GameType::Fallout4 => Some("f4se_loader.exe"),
+            GameType::Fallout4VR => Some("f4sevr_loader.exe"),
+            GameType::Starfield => Some("sfse_loader.exe"),
+            GameType::Morrowind
+            | GameType::Witcher3
+            | GameType::Cyberpunk2077
+            | GameType::BaldursGate3
+            | GameType::NieRAutomata => None,
+        }
+    }
+
+    /// Main game executable, relative to the install directory.
+    pub fn main_exe(self) -> &'static str {
+        match self {
+            GameType::Morrowind => "Morrowind.exe",
+            GameType::Oblivion => "Oblivion.exe",
+            GameType::Fallout3 => "Fallout3.exe",
+            GameType::FalloutNewVegas => "FalloutNV.exe",
+            GameType::Skyrim | GameType::Enderal => "TESV.exe",
+            GameType::SkyrimSpecialEdition | GameType::EnderalSpecialEdition => "SkyrimSE.exe",
+            GameType::SkyrimVR => "SkyrimVR.exe",
+            GameType::Fallout4 => "Fallout4.exe",
+            GameType::Fallout4VR => "Fallout4VR.exe",
+            GameType::Starfield => "Starfield.exe",
+            GameType::Witcher3 => "bin/x64/witcher3.exe",
+            GameType::Cyberpunk2077 => "bin/x64/Cyberpunk2077.exe",
+            GameType::BaldursGate3 => "bin/bg3.exe",
+            GameType::NieRAutomata => "NieRAutomata.exe",
+        }
+    }
+
+    /// Master data files every intact install ships, relative to the install
+    /// directory. Empty for games without a Bethesda-style `Data` folder.
+    pub fn key_data_files(self) -> &'static [&'static str] {
+        match self {
+            GameType::Morrowind => &["Data Files/Morrowind.esm"],
+            GameType::Oblivion => &["Data/Oblivion.esm"],
+            GameType::Fallout3 => &["Data/Fallout3.esm"],
+            GameType::FalloutNewVegas => &["Data/FalloutNV.esm"],
+            GameType::Skyrim
+            | GameType::Enderal
+            | GameType::SkyrimSpecialEdition
+            | GameType::EnderalSpecialEdition => &["Data/Skyrim.esm"],
+            GameType::SkyrimVR => &["Data/Skyrim.esm", "Data/SkyrimVR.esm"],
+            GameType::Fallout4 => &["Data/Fallout4.esm"],
+            GameType::Fallout4VR => &["Data/Fallout4.esm", "Data/Fallout4_VR.esm"],
+            GameType::Starfield => &["Data/Starfield.esm"],
+            GameType::Witcher3
+            | GameType::Cyberpunk2077
+            | GameType::BaldursGate3
+            | GameType::NieRAutomata => &[],
+        }
+    }
+
+    /// Official DLC as `(name, master file)` pairs, relative to the install
+    /// directory. Total conversions bundle what they need, so they have none.
+    pub fn dlc(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            GameType::Morrowind => &[
+                ("Tribunal", "Data Files/Tribunal.esm"),
+                ("Bloodmoon", "Data Files/Bloodmoon.esm"),
+            ],
+            GameType::Oblivion => &[
+                ("Shivering Isles", "Data/DLCShiveringIsles.esp"),
+                ("Knights of the Nine", "Data/Knights.esp"),
+            ],
+            GameType::Fallout3 => &[
+                ("Operation: Anchorage", "Data/Anchorage.esm"),
+                ("The Pitt", "Data/ThePitt.esm"),
+                ("Broken Steel", "Data/BrokenSteel.esm"),
+                ("Point Lookout", "Data/PointLookout.esm"),
+                ("Mothership Zeta", "Data/Zeta.esm"),
+            ],
+            GameType::FalloutNewVegas => &[
+                ("Dead Money", "Data/DeadMoney.esm"),
+                ("Honest Hearts", "Data/HonestHearts.esm"),
+                ("Old World Blues", "Data/OldWorldBlues.esm"),
+                ("Lonesome Road", "Data/LonesomeRoad.esm"),
+                ("Gun Runners' Arsenal", "Data/GunRunnersArsenal.esm"),
+            ],
+            GameType::Skyrim | GameType::SkyrimSpecialEdition | GameType::SkyrimVR => &[
+                ("Dawnguard", "Data/Dawnguard.esm"),
+                ("Hearthfire", "Data/HearthFires.esm"),
+                ("Dragonborn", "Data/Dragonborn.esm"),
+            ],
+            GameType::Fallout4 => &[
+                ("Automatron", "Data/DLCRobot.esm"),
+                ("Wasteland Workshop", "Data/DLCworkshop01.esm"),
+                ("Far Harbor", "Data/DLCCoast.esm"),
+                ("Contraptions Workshop", "Data/DLCworkshop02.esm"),
+                ("Vault-Tec Workshop", "Data/DLCworkshop03.esm"),
+                ("Nuka-World", "Data/DLCNukaWorld.esm"),
+            ],
+            GameType::Starfield => &[("Shattered Space", "Data/ShatteredSpace.esm")],
+            GameType::Enderal
+            | GameType::EnderalSpecialEdition
+            | GameType::Fallout4VR
+            | GameType::Witcher3
+            | GameType::Cyberpunk2077
+            | GameType::BaldursGate3
+            | GameType::NieRAutomata => &[],
+        }
+    }
+
+    /// For total conversions: the engine game they are built on. Both ship
+    /// the same main exe, so `total_conversion_marker` tells them apart.
+    pub fn base_game(self) -> Option<GameType> {
+        match self {
+            GameType::Enderal => Some(GameType::Skyrim),
+            GameType::EnderalSpecialEdition => Some(GameType::SkyrimSpecialEdition),
+            _ => None,
+        }
+    }
+
+    /// File only present in a total conversion's install directory.
+    pub fn total_conversion_marker(self) -> Option<&'static str> {
+        match self {
+            GameType::Enderal | GameType::EnderalSpecialEdition => Some("Enderal Launcher.exe"),
+            _ => None,
+        }
+    }
+
+    /// Total conversions built on this game.
+    pub fn total_conversions(self) -> impl Iterator<Item = GameType> {
+        Self::ALL
+            .iter()
+            .copied()
+            .filter(move |g| g.base_game() == Some(self))
+    }
+
+    /// Launcher/registry metadata for the canonical store entry.
+    pub fn known_game(self) -> Option<&'static KnownGame> {
+        KNOWN_GAMES
+            .iter()
+            .find(|g| g.wabbajack_type == Some(self.wabbajack_name()))
+    }
+}
+
+impl fmt::Display for GameType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.display_name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wabbajack_names_round_trip() {
+        for game in GameType::ALL {
+            assert_eq!(
+                GameType::from_wabbajack_name(game.wabbajack_name()),
+                Some(*game)
+            );
+        }
+        assert_eq!(
+            GameType::from_wabbajack_name("skyrimse"),
+            Some(GameType::SkyrimSpecialEdition)
+        );
+        assert_eq!(GameType::from_wabbajack_name("NotAGame"), None);
+    }
+
+    #[test]
+    fn shared_nexus_domain_resolves_to_flat_game() {
+        assert_eq!(GameType::SkyrimVR.nexus_domain(), "skyrimspecialedition");
+        assert_eq!(
+            GameType::from_nexus_domain("skyrimspecialedition"),
+            Some(GameType::SkyrimSpecialEdition)
+        );
+        assert_eq!(
+            GameType::from_nexus_domain("newvegas"),
+            Some(GameType::FalloutNewVegas)
+        );
+    }
+
+    #[test]
+    fn enderal_is_a_total_conversion_of_skyrim() {
+        assert_eq!(
+            GameType::EnderalSpecialEdition.base_game(),
+            Some(GameType::SkyrimSpecialEdition)
+        );
+        assert_eq!(
+            GameType::SkyrimSpecialEdition
+                .total_conversions()
+                .collect::<Vec<_>>(),
+            vec![GameType::EnderalSpecialEdition]
+        );
+        assert_eq!(
+            GameType::EnderalSpecialEdition.main_exe(),
+            GameType::SkyrimSpecialEdition.main_exe()
+        );
+    }
+
+    /// Guards against `app_ids` drifting from the launcher table.
+    #[test]
+    fn app_ids_match_known_games() {
+        for known in KNOWN_GAMES {
+            let id: u32 = known.steam_app_id.parse().unwrap();
+            let game = GameType::from_app_id(id)
+                .unwrap_or_else(|| panic!("{} ({}) has no GameType", known.name, id));
+            if let Some(wj) = known.wabbajack_type {
+                assert_eq!(game.wabbajack_name(), wj);
+                assert_eq!(game.app_ids()[0], id, "{} must be canonical", known.name);
+            }
+        }
+        for game in GameType::ALL {
+            assert!(
+                game.known_game().is_some(),
+                "{:?} missing from KNOWN_GAMES",
+                game
+            );
+        }
+    }
+}
@@ -25,7 +25,8 @@ pub struct KnownGame {
     pub registry_path: &'static str,
     /// Registry value name for install path
     pub registry_value: &'static str,
-    /// Expected folder name in steamapps/common/
+    /// Folder name in steamapps/common/ on an English Steam client. For
+    /// reference only: detection uses the appmanifest's `installdir`.
     pub steam_folder: &'static str,
     /// Wabbajack `GameType` string from modlist JSON (e.g. "FalloutNewVegas").
     /// None for store variants that don't have their own Wabbajack enum entry.
@@ -265,25 +266,7 @@ pub fn find_by_name(name: &str) -> Option<&'static KnownGame> {
 /// Find a known game by its Wabbajack `GameType` string (e.g. "FalloutNewVegas",
 /// "SkyrimSpecialEdition"). Accepts common aliases Wabbajack has used across versions.
 pub fn find_by_wabbajack_type(wj_type: &str) -> Option<&'static KnownGame> {
-    // Direct match against the canonical wabbajack_type field
-    if let Some(g) = KNOWN_GAMES
-        .iter()
-        .find(|g| g.wabbajack_type == Some(wj_type))
-    {
-        return Some(g);
-    }
-
-    // Alias fallback — older modlists / short-form names
-    let aliased = match wj_type {
-        "SkyrimSE" => "SkyrimSpecialEdition",
-        "FalloutNV" => "FalloutNewVegas",
-        "EnderalSE" => "EnderalSpecialEdition",
-        "TheWitcher3" => "Witcher3",
-        _ => return None,
-    };
-    KNOWN_GAMES
-        .iter()
-        .find(|g| g.wabbajack_type == Some(aliased))
+    super::GameType::from_wabbajack_name(wj_type)?.known_game()
 }
 
 /// Convenience: return `(steam_app_id, gog_app_id)` for a Wabbajack game_type.
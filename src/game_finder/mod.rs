@@ -19,23 +19,40 @@
 #![allow(dead_code)]
 #![allow(unused_imports)]
 
+mod cache;
+mod game_type;
 mod heroic;
 pub mod known_games;
 pub mod proton;
+mod runtime;
 mod steam;
+mod validate;
 mod vdf;
 
 use std::path::PathBuf;
 
+pub use cache::{cached_games, cached_protons, refresh_scan_cache};
+pub use game_type::GameType;
 pub use heroic::detect_heroic_games;
 pub use known_games::{
     find_by_gog_id, find_by_name, find_by_steam_id, find_by_wabbajack_type, ids_for_wabbajack_type,
     variants_for_wabbajack_type, KnownGame, KNOWN_GAMES,
 };
 pub use proton::{find_steam_path, find_steam_protons, SteamProton};
+pub use runtime::{
+    compare_runtime, exe_version, installed_game_version, runtime_edition, RuntimeMatch,
+};
 pub use steam::{
-    detect_steam_games, find_game_install_path, find_game_prefix_path, get_known_game,
+    active_steam_is_flatpak, active_steam_path, detect_steam_games, find_compatdata,
+    find_game_install_path, find_game_prefix_path, find_shortcut, find_shortcut_compatdata,
+    get_known_game, quit_steam, restart_steam, set_shortcut_proton, set_steam_preference,
+    start_steam, steam_is_running, steam_preference, FoundShortcut, SteamPreference,
+};
+pub use validate::{
+    dlc_for_game_file, dlc_report, identify_game_dir, is_dlc_file, resolve_game_file,
+    validate_game_path, DlcStatus,
 };
+pub use vdf::Shortcut;
 
 // ============================================================================
 // Core Types
@@ -103,6 +120,19 @@ pub struct Game {
 }
 
 impl Game {
+    /// The supported game this install is, matched by Steam/GOG ID, then by
+    /// name, then by the files in its install directory (localized titles,
+    /// Epic). `None` for games CLF3 doesn't install modlists for.
+    pub fn game_type(&self) -> Option<GameType> {
+        if let Some(game) = self.app_id.parse().ok().and_then(GameType::from_app_id) {
+            return Some(game);
+        }
+        find_by_gog_id(&self.app_id)
+            .or_else(|| find_by_name(&self.name))
+            .and_then(|known| GameType::from_wabbajack_name(known.wabbajack_type?))
+            .or_else(|| identify_game_dir(&self.install_path))
+    }
+
     /// Check if this game has a Wine prefix
     pub fn has_prefix(&self) -> bool {
         self.prefix_path.is_some()
@@ -212,20 +242,22 @@ impl GameScanResult {
 /// - Steam (native, Flatpak, Snap) via appmanifest_*.acf parsing
 /// - Heroic (GOG, Epic) via installed.json
 ///
-/// Returns a `GameScanResult` containing all found games.
+/// Both launchers are scanned in parallel. Returns a `GameScanResult`
+/// containing all found games; see `cached_games` to avoid rescanning.
 pub fn detect_all_games() -> GameScanResult {
-    let mut result = GameScanResult::default();
+    let (steam_games, heroic_games) = std::thread::scope(|s| {
+        let heroic = s.spawn(detect_heroic_games);
+        let steam = detect_steam_games();
+        (steam, heroic.join().unwrap_or_default())
+    });
 
-    // Detect Steam games
-    let steam_games = detect_steam_games();
-    result.steam_count = steam_games.len();
+    let mut result = GameScanResult {
+        steam_count: steam_games.len(),
+        heroic_count: heroic_games.len(),
+        ..Default::default()
+    };
     result.games.extend(steam_games);
-
-    // Detect Heroic games
-    let heroic_games = detect_heroic_games();
-    result.heroic_count = heroic_games.len();
     result.games.extend(heroic_games);
-
     result
 }
 
@@ -21,23 +21,10 @@ pub struct SteamProton {
     pub is_experimental: bool,
 }
 
-/// Find the primary Steam installation path
+/// Find the Steam installation to use: native or Flatpak, per
+/// `SteamPreference`.
 pub fn find_steam_path() -> Option<PathBuf> {
-    let home = std::env::var("HOME").ok()?;
-    let home_path = PathBuf::from(&home);
-
-    let candidates = [
-        home_path.join(".local/share/Steam"),
-        home_path.join(".steam/debian-installation"),
-        home_path.join(".steam/steam"),
-        home_path.join(".var/app/com.valvesoftware.Steam/data/Steam"),
-        home_path.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"),
-        home_path.join("snap/steam/common/.local/share/Steam"),
-    ];
-
-    candidates
-        .into_iter()
-        .find(|p| p.join("steamapps").exists())
+    super::steam::active_steam_path()
 }
 
 /// Find all compatible Protons (Proton 10+ only)
@@ -52,8 +39,10 @@ pub fn find_steam_protons() -> Vec<SteamProton> {
         .to_string_lossy()
         .contains(".var/app/com.valvesoftware.Steam");
 
-    // 1. Steam's built-in Protons (steamapps/common/Proton*)
-    protons.extend(find_builtin_protons(&steam_path));
+    // 1. Steam's built-in Protons (steamapps/common/Proton*), in any library
+    for library in super::steam::get_library_folders(&steam_path) {
+        protons.extend(find_builtin_protons(&library));
+    }
 
     // 2. Custom Protons in user's compatibilitytools.d
     protons.extend(find_custom_protons(&steam_path));
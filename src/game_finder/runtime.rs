@@ -0,0 +1,169 @@
+//! Game runtime (executable version) checks
+//!
+//! Skyrim SE and Fallout 4 each have two incompatible runtime lines (pre-AE vs
+//! AE, old-gen vs next-gen) and script extender plugins are built against
+//! one of them. A modlist built for one line installs fine on the other and
+//! then crashes on launch, so the installed edition is compared with the one
+//! the modlist was built for before anything is downloaded.
+
+use super::GameType;
+use crate::paths::resolve_case_insensitive;
+use std::path::Path;
+
+/// Read the file version ("1.6.1170.0") from a Windows executable's
+/// `VS_FIXEDFILEINFO` resource.
+pub fn exe_version(path: &Path) -> Option<String> {
+    let data = std::fs::read(path).ok()?;
+    version_from_bytes(&data)
+}
+
+/// Version of `game`'s main executable under `game_dir`.
+pub fn installed_game_version(game: GameType, game_dir: &Path) -> Option<String> {
+    exe_version(&resolve_case_insensitive(game_dir, game.main_exe())?)
+}
+
+fn version_from_bytes(data: &[u8]) -> Option<String> {
+    const SIGNATURE: [u8; 4] = 0xFEEF_04BDu32.to_le_bytes();
+    let at = data.windows(4).position(|w| w == SIGNATURE)?;
+    // dwSignature, dwStrucVersion, dwFileVersionMS, dwFileVersionLS
+    let dword = |offset: usize| {
+        let bytes = data.get(at + offset..at + offset + 4)?;
+        Some(u32::from_le_bytes(bytes.try_into().ok()?))
+    };
+    let (ms, ls) = (dword(8)?, dword(12)?);
+    Some(format!(
+        "{}.{}.{}.{}",
+        ms >> 16,
+        ms & 0xFFFF,
+        ls >> 16,
+        ls & 0xFFFF
+    ))
+}
+
+fn parse_version(version: &str) -> Option<Vec<u32>> {
+    version
+        .trim()
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect()
+}
+
+/// Runtime line a version belongs to, for games that have more than one.
+pub fn runtime_edition(game: GameType, version: &str) -> Option<&'static str> {
+    let v = parse_version(version)?;
+    match game {
+        GameType::SkyrimSpecialEdition => Some(if v.as_slice() < &[1, 6][..] {
+            "pre-AE"
+        } else {
+            "AE"
+        }),
+        GameType::Fallout4 => Some(if v.as_slice() < &[1, 10, 980][..] {
+            "old-gen"
+        } else {
+            "next-gen"
+        }),
+        _ => None,
+    }
+}
+
+/// Installed runtime compared with the one a modlist was built for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuntimeMatch {
+    /// Same version, or a game without separate runtime lines.
+    Compatible,
+    /// Same runtime line, different patch. Game file hashes may not match.
+    DifferentPatch,
+    /// Different runtime line; the modlist's plugins won't load.
+    WrongEdition {
+        required: &'static str,
+        installed: &'static str,
+        /// How to get the required runtime.
+        fix: &'static str,
+    },
+}
+
+/// Compare the installed runtime with the modlist's `required` version.
+pub fn compare_runtime(game: GameType, required: &str, installed: &str) -> RuntimeMatch {
+    let (Some(req), Some(inst)) = (parse_version(required), parse_version(installed)) else {
+        return RuntimeMatch::Compatible;
+    };
+    if req == inst {
+        return RuntimeMatch::Compatible;
+    }
+    match (
+        runtime_edition(game, required),
+        runtime_edition(game, installed),
+    ) {
+        (Some(required), Some(installed)) if required != installed => RuntimeMatch::WrongEdition {
+            required,
+            installed,
+            fix: if req < inst {
+                downgrade_hint(game)
+            } else {
+                "Update the game through your launcher"
+            },
+        },
+        (Some(_), Some(_)) => RuntimeMatch::DifferentPatch,
+        _ => RuntimeMatch::Compatible,
+    }
+}
+
+fn downgrade_hint(game: GameType) -> &'static str {
+    match game {
+        GameType::SkyrimSpecialEdition => {
+            "Downgrade to 1.5.97 with the Skyrim SE downgrade patcher (or Steam's \
+             download_depot), then re-run"
+        }
+        GameType::Fallout4 => {
+            "Downgrade to 1.10.163 with the Fallout 4 downgrader (or Steam's \
+             download_depot), then re-run"
+        }
+        _ => "Roll the game back to the version the modlist was built for",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_is_read_from_fixed_file_info() {
+        let mut data = vec![0u8; 64];
+        data.extend(0xFEEF_04BDu32.to_le_bytes());
+        data.extend(0x0001_0000u32.to_le_bytes());
+        data.extend(((1u32 << 16) | 6).to_le_bytes());
+        data.extend((1170u32 << 16).to_le_bytes());
+        assert_eq!(version_from_bytes(&data).as_deref(), Some("1.6.1170.0"));
+        assert_eq!(version_from_bytes(&data[..70]), None);
+    }
+
+    #[test]
+    fn skyrim_editions_are_compared() {
+        let game = GameType::SkyrimSpecialEdition;
+        assert_eq!(runtime_edition(game, "1.5.97.0"), Some("pre-AE"));
+        assert_eq!(runtime_edition(game, "1.6.1170.0"), Some("AE"));
+        assert_eq!(
+            compare_runtime(game, "1.6.1170.0", "1.6.1170.0"),
+            RuntimeMatch::Compatible
+        );
+        assert_eq!(
+            compare_runtime(game, "1.6.1130.0", "1.6.1170.0"),
+            RuntimeMatch::DifferentPatch
+        );
+        match compare_runtime(game, "1.5.97.0", "1.6.1170.0") {
+            RuntimeMatch::WrongEdition {
+                required,
+                installed,
+                fix,
+            } => {
+                assert_eq!((required, installed), ("pre-AE", "AE"));
+                assert!(fix.contains("downgrade patcher"));
+            }
+            other => panic!("expected WrongEdition, got {:?}", other),
+        }
+        assert_eq!(
+            compare_runtime(GameType::Fallout3, "1.7.0.3", "1.7.0.4"),
+            RuntimeMatch::Compatible
+        );
+    }
+}
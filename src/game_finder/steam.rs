@@ -3,11 +3,16 @@
 //! Detects games installed via Steam by parsing appmanifest_*.acf files.
 //! Supports native, Flatpak, and Snap Steam installations.
 
+use anyhow::{bail, Context, Result};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant, SystemTime};
+use tracing::debug;
 
 use super::known_games::{find_by_steam_id, KnownGame};
-use super::vdf::{parse_library_folders, AppManifest};
+use super::vdf::{parse_library_folders, parse_shortcuts, set_compat_tool, AppManifest, Shortcut};
 use super::{Game, Launcher};
 
 /// All possible Steam installation paths to check
@@ -20,6 +25,51 @@ const STEAM_PATHS: &[&str] = &[
     "snap/steam/common/.local/share/Steam",
 ];
 
+const FLATPAK_APP_ID: &str = "com.valvesoftware.Steam";
+
+/// Which Steam install to target (Protons, shortcuts, restarts) when both a
+/// native and a Flatpak Steam are present.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SteamPreference {
+    /// The running client, else the most recently used one.
+    #[default]
+    Auto,
+    Native,
+    Flatpak,
+}
+
+impl SteamPreference {
+    /// Parse a `Settings::steam_install` value. Unknown values mean `Auto`.
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "native" => SteamPreference::Native,
+            "flatpak" => SteamPreference::Flatpak,
+            _ => SteamPreference::Auto,
+        }
+    }
+}
+
+impl std::fmt::Display for SteamPreference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            SteamPreference::Auto => "auto",
+            SteamPreference::Native => "native",
+            SteamPreference::Flatpak => "flatpak",
+        })
+    }
+}
+
+static STEAM_PREFERENCE: OnceLock<SteamPreference> = OnceLock::new();
+
+/// Set which Steam install to target, from settings. Call once at startup.
+pub fn set_steam_preference(preference: SteamPreference) {
+    let _ = STEAM_PREFERENCE.set(preference);
+}
+
+pub fn steam_preference() -> SteamPreference {
+    STEAM_PREFERENCE.get().copied().unwrap_or_default()
+}
+
 /// Detect all Steam games across all installations
 pub fn detect_steam_games() -> Vec<Game> {
     let mut games = Vec::new();
@@ -86,8 +136,8 @@ fn find_steam_installations(home: &str) -> Vec<SteamInstallation> {
             if !installations.iter().any(|i: &SteamInstallation| {
                 i.path.canonicalize().unwrap_or(i.path.clone()) == canonical
             }) {
-                println!(
-                    "[game_finder] Found Steam installation: {} (flatpak={}, snap={})",
+                debug!(
+                    "Found Steam installation: {} (flatpak={}, snap={})",
                     full_path.display(),
                     is_flatpak,
                     is_snap
@@ -104,8 +154,82 @@ fn find_steam_installations(home: &str) -> Vec<SteamInstallation> {
     installations
 }
 
+/// Steam installs with the one to target first: the preferred variant, then
+/// a running client, then the most recently used (by config.vdf /
+/// loginusers.vdf mtime). Otherwise in `STEAM_PATHS` order.
+///
+/// Resolved once per run: checking for a running Flatpak client spawns
+/// `flatpak ps`, and the target shouldn't switch mid-install anyway.
+fn installations_by_priority() -> &'static [SteamInstallation] {
+    static BY_PRIORITY: OnceLock<Vec<SteamInstallation>> = OnceLock::new();
+    BY_PRIORITY.get_or_init(|| {
+        let Ok(home) = std::env::var("HOME") else {
+            return Vec::new();
+        };
+        let mut installations = find_steam_installations(&home);
+        order_installations(&mut installations, steam_preference(), |i| {
+            installation_running(&home, i)
+        });
+        installations
+    })
+}
+
+fn order_installations(
+    installations: &mut [SteamInstallation],
+    preference: SteamPreference,
+    running: impl Fn(&SteamInstallation) -> bool,
+) {
+    installations.sort_by_cached_key(|i| {
+        let preferred = match preference {
+            SteamPreference::Auto => true,
+            SteamPreference::Native => !i.is_flatpak,
+            SteamPreference::Flatpak => i.is_flatpak,
+        };
+        (
+            std::cmp::Reverse(preferred),
+            std::cmp::Reverse(running(i)),
+            std::cmp::Reverse(last_used(&i.path)),
+        )
+    });
+}
+
+fn last_used(steam_path: &Path) -> Option<SystemTime> {
+    ["config/loginusers.vdf", "config/config.vdf"]
+        .iter()
+        .filter_map(|p| fs::metadata(steam_path.join(p)).ok()?.modified().ok())
+        .max()
+}
+
+/// Root of the Steam install to target (see [`SteamPreference`]), if one
+/// with a library exists.
+pub fn active_steam_path() -> Option<PathBuf> {
+    installations_by_priority()
+        .iter()
+        .map(|i| i.path.clone())
+        .find(|p| p.join("steamapps").exists())
+}
+
+/// Whether the targeted Steam install is the Flatpak.
+pub fn active_steam_is_flatpak() -> bool {
+    installations_by_priority()
+        .first()
+        .is_some_and(|i| i.is_flatpak)
+}
+
+/// Roots of every Steam install found, in no particular order. Only looks at
+/// the filesystem, so it's cheap enough for cache checks.
+pub(super) fn steam_install_paths() -> Vec<PathBuf> {
+    let Ok(home) = std::env::var("HOME") else {
+        return Vec::new();
+    };
+    find_steam_installations(&home)
+        .into_iter()
+        .map(|i| i.path)
+        .collect()
+}
+
 /// Get all library folders for a Steam installation
-fn get_library_folders(steam_path: &Path) -> Vec<PathBuf> {
+pub(super) fn get_library_folders(steam_path: &Path) -> Vec<PathBuf> {
     let mut folders = Vec::new();
 
     // The Steam installation directory itself is always a library
@@ -224,9 +348,14 @@ pub fn find_game_install_path(app_id: &str) -> Option<PathBuf> {
 
 /// Find the Wine prefix for a specific Steam game by App ID
 pub fn find_game_prefix_path(app_id: &str) -> Option<PathBuf> {
-    let home = std::env::var("HOME").ok()?;
+    find_compatdata(app_id)
+}
 
-    for steam_info in find_steam_installations(&home) {
+/// Find the Proton prefix (`steamapps/compatdata/<app_id>/pfx`) for a Steam
+/// App ID across all libraries. Returns `None` until Steam has created the
+/// prefix, which happens on first launch.
+pub fn find_compatdata(app_id: &str) -> Option<PathBuf> {
+    for steam_info in installations_by_priority() {
         let libraries = get_library_folders(&steam_info.path);
 
         for library_path in libraries {
@@ -244,7 +373,264 @@ pub fn find_game_prefix_path(app_id: &str) -> Option<PathBuf> {
     None
 }
 
+/// A non-Steam shortcut found in a user's shortcuts.vdf.
+#[derive(Debug, Clone)]
+pub struct FoundShortcut {
+    pub shortcut: Shortcut,
+    /// The shortcuts.vdf it was read from.
+    pub vdf_path: PathBuf,
+    /// Where Steam keeps the shortcut's Proton prefix. Only exists after the
+    /// first launch.
+    pub compatdata: PathBuf,
+}
+
+/// Find a non-Steam shortcut whose name or executable path contains `needle`
+/// (case-insensitive), across every Steam installation and user.
+pub fn find_shortcut(needle: &str) -> Option<FoundShortcut> {
+    let needle = needle.to_lowercase();
+
+    for steam_info in installations_by_priority() {
+        let Ok(users) = fs::read_dir(steam_info.path.join("userdata")) else {
+            continue;
+        };
+
+        for user in users.flatten() {
+            let vdf_path = user.path().join("config/shortcuts.vdf");
+            let Ok(data) = fs::read(&vdf_path) else {
+                continue;
+            };
+
+            let found = parse_shortcuts(&data).into_iter().find(|s| {
+                s.name.to_lowercase().contains(&needle) || s.exe.to_lowercase().contains(&needle)
+            });
+            if let Some(shortcut) = found {
+                let compatdata = steam_info
+                    .path
+                    .join("steamapps/compatdata")
+                    .join(shortcut.app_id.to_string());
+                return Some(FoundShortcut {
+                    shortcut,
+                    vdf_path,
+                    compatdata,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// Whether a Steam client is running, judged by the pid in `steam.pid`.
+/// Shortcuts written while it runs only show up after a restart.
+pub fn steam_is_running() -> bool {
+    let Ok(home) = std::env::var("HOME") else {
+        return false;
+    };
+    pid_alive(&PathBuf::from(&home).join(".steam/steam.pid"))
+        || find_steam_installations(&home)
+            .iter()
+            .any(|i| installation_running(&home, i))
+}
+
+fn installation_running(home: &str, installation: &SteamInstallation) -> bool {
+    if installation.is_flatpak {
+        flatpak_steam_running()
+    } else {
+        pid_alive(&PathBuf::from(home).join(".steam/steam.pid"))
+            || pid_alive(&installation.path.join("steam.pid"))
+    }
+}
+
+fn pid_alive(pid_file: &Path) -> bool {
+    fs::read_to_string(pid_file)
+        .ok()
+        .and_then(|s| s.trim().parse::<u32>().ok())
+        .is_some_and(|pid| Path::new("/proc").join(pid.to_string()).exists())
+}
+
+/// Flatpak Steam runs in its own pid namespace, so its steam.pid is no use
+/// from outside; ask flatpak instead.
+fn flatpak_steam_running() -> bool {
+    Command::new("flatpak")
+        .args(["ps", "--columns=application"])
+        .stderr(Stdio::null())
+        .output()
+        .map(|out| {
+            String::from_utf8_lossy(&out.stdout)
+                .lines()
+                .any(|l| l.trim() == FLATPAK_APP_ID)
+        })
+        .unwrap_or(false)
+}
+
+/// Quit Steam and start it again so it reloads shortcuts.vdf. Waits up to
+/// `timeout` for the old client to exit.
+pub fn restart_steam(timeout: Duration) -> Result<()> {
+    quit_steam(timeout)?;
+    start_steam()
+}
+
+/// Ask a running Steam to exit and wait up to `timeout` for it. Steam
+/// rewrites its config files on exit, so edits to them have to wait for this.
+pub fn quit_steam(timeout: Duration) -> Result<()> {
+    if !steam_is_running() {
+        return Ok(());
+    }
+    spawn_steam(Some("-shutdown")).context("Failed to run steam -shutdown")?;
+    let started = Instant::now();
+    while steam_is_running() {
+        if started.elapsed() > timeout {
+            bail!("Steam did not exit within {}s", timeout.as_secs());
+        }
+        std::thread::sleep(Duration::from_millis(500));
+    }
+    Ok(())
+}
+
+/// Start the Steam client in the background.
+pub fn start_steam() -> Result<()> {
+    spawn_steam(None).context("Failed to start Steam")?;
+    Ok(())
+}
+
+/// Run `steam [arg]`. Flatpak Steam is driven through `flatpak run` when it
+/// is the targeted install or no `steam` command is available.
+fn spawn_steam(arg: Option<&str>) -> std::io::Result<std::process::Child> {
+    let spawn = |program: &str, prefix: &[&str]| {
+        Command::new(program)
+            .args(prefix)
+            .args(arg)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+    };
+    if active_steam_is_flatpak() {
+        return spawn("flatpak", &["run", FLATPAK_APP_ID]);
+    }
+    match spawn("steam", &[]) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            spawn("flatpak", &["run", FLATPAK_APP_ID])
+        }
+        result => result,
+    }
+}
+
+/// Make a non-Steam shortcut run with the Proton whose config name is
+/// `tool`, by writing its `CompatToolMapping` entry in the active Steam's
+/// config.vdf. Steam must not be running (see `quit_steam`) or it overwrites
+/// the change on exit.
+pub fn set_shortcut_proton(app_id: u32, tool: &str) -> Result<()> {
+    let config_path = active_steam_path()
+        .context("No Steam installation found")?
+        .join("config/config.vdf");
+    let content = fs::read_to_string(&config_path)
+        .with_context(|| format!("Failed to read {}", config_path.display()))?;
+    let updated = set_compat_tool(&content, app_id, tool).with_context(|| {
+        format!(
+            "{} has no CompatToolMapping section; open Steam once and try again",
+            config_path.display()
+        )
+    })?;
+    fs::write(&config_path, updated)
+        .with_context(|| format!("Failed to write {}", config_path.display()))
+}
+
+/// Find the Proton prefix of a non-Steam shortcut (e.g. the MO2 shortcut NaK
+/// creates) whose name or executable path contains `needle`
+/// (case-insensitive). Checks every Steam user's shortcuts.vdf. Returns `None`
+/// if no shortcut matches or it hasn't been launched yet.
+pub fn find_shortcut_compatdata(needle: &str) -> Option<PathBuf> {
+    let needle = needle.to_lowercase();
+
+    for steam_info in installations_by_priority() {
+        let Ok(users) = fs::read_dir(steam_info.path.join("userdata")) else {
+            continue;
+        };
+
+        for user in users.flatten() {
+            let Ok(data) = fs::read(user.path().join("config/shortcuts.vdf")) else {
+                continue;
+            };
+
+            let prefix = parse_shortcuts(&data)
+                .into_iter()
+                .filter(|s| {
+                    s.name.to_lowercase().contains(&needle)
+                        || s.exe.to_lowercase().contains(&needle)
+                })
+                .find_map(|s| find_compatdata(&s.app_id.to_string()));
+            if prefix.is_some() {
+                return prefix;
+            }
+        }
+    }
+
+    None
+}
+
 /// Get the known game configuration for a Steam App ID
 pub fn get_known_game(app_id: &str) -> Option<&'static KnownGame> {
     find_by_steam_id(app_id)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn install(root: &Path, relative: &str, is_flatpak: bool) -> SteamInstallation {
+        let path = root.join(relative);
+        fs::create_dir_all(path.join("steamapps")).unwrap();
+        fs::create_dir_all(path.join("config")).unwrap();
+        SteamInstallation {
+            path,
+            is_flatpak,
+            is_snap: false,
+        }
+    }
+
+    fn touch(path: &Path, age: Duration) {
+        let file = fs::File::create(path).unwrap();
+        file.set_modified(SystemTime::now() - age).unwrap();
+    }
+
+    #[test]
+    fn test_order_installations() {
+        let tmp = tempfile::tempdir().unwrap();
+        let native = install(tmp.path(), ".local/share/Steam", false);
+        let flatpak = install(
+            tmp.path(),
+            ".var/app/com.valvesoftware.Steam/data/Steam",
+            true,
+        );
+        touch(
+            &native.path.join("config/config.vdf"),
+            Duration::from_secs(7200),
+        );
+        touch(
+            &flatpak.path.join("config/loginusers.vdf"),
+            Duration::from_secs(60),
+        );
+        let mut installs = vec![native, flatpak];
+
+        // Auto: most recently used first.
+        order_installations(&mut installs, SteamPreference::Auto, |_| false);
+        assert!(installs[0].is_flatpak);
+
+        // A running client beats recency.
+        order_installations(&mut installs, SteamPreference::Auto, |i| !i.is_flatpak);
+        assert!(!installs[0].is_flatpak);
+
+        // An explicit preference beats both.
+        order_installations(&mut installs, SteamPreference::Flatpak, |i| !i.is_flatpak);
+        assert!(installs[0].is_flatpak);
+        order_installations(&mut installs, SteamPreference::Native, |_| false);
+        assert!(!installs[0].is_flatpak);
+
+        assert_eq!(
+            SteamPreference::parse(" Flatpak "),
+            SteamPreference::Flatpak
+        );
+        assert_eq!(SteamPreference::parse(""), SteamPreference::Auto);
+    }
+}
@@ -0,0 +1,276 @@
+//! Game directory validation
+//!
+//! Checks that a directory is actually an install of the game a modlist
+//! targets before any work starts. Total conversions (Enderal) share their
+//! base game's executable, so both directions are checked: an Enderal list
+//! must not be pointed at plain Skyrim, and a Skyrim list must not be pointed
+//! at Enderal.
+//!
+//! Presence of the main executable and master data files is checked here;
+//! exact game file hashes are the installer's `game_preflight` job. The same
+//! check identifies an install whose folder or store title isn't the English
+//! one (`identify_game_dir`).
+//!
+//! `resolve_game_file` and `dlc_for_game_file` are shared by the preflight
+//! and the `GameFileSource` copy, so both look in the same places and name
+//! the DLC a missing file comes from.
+
+use super::GameType;
+use crate::paths::resolve_case_insensitive;
+use anyhow::{bail, Result};
+use std::path::{Path, PathBuf};
+
+/// Folders Bethesda games keep plugins and archives in: `Data`, and
+/// Morrowind's `Data Files`. Modlists write game files with or without it.
+const DATA_DIRS: &[&str] = &["Data", "Data Files"];
+
+/// Verify `path` looks like an install of `game`: the main executable and
+/// master data files must be present, and total conversions must not be
+/// mixed up with their base game.
+pub fn validate_game_path(game: GameType, path: &Path) -> Result<()> {
+    if !path.is_dir() {
+        bail!("Game directory does not exist: {}", path.display());
+    }
+
+    let has = |relative: &str| resolve_case_insensitive(path, relative).is_some();
+
+    let missing: Vec<&str> = std::iter::once(game.main_exe())
+        .chain(game.key_data_files().iter().copied())
+        .filter(|&f| !has(f))
+        .collect();
+    if !missing.is_empty() {
+        bail!(
+            "{} does not look like a {} install: {} not found",
+            path.display(),
+            game,
+            missing.join(", ")
+        );
+    }
+
+    if let Some(marker) = game.total_conversion_marker() {
+        if !has(marker) {
+            let base = game
+                .base_game()
+                .map(|b| b.display_name())
+                .unwrap_or("another game");
+            bail!(
+                "{} looks like a {} install, but this modlist needs {} ({} not found). \
+                 Install {} separately and point --game at it.",
+                path.display(),
+                base,
+                game,
+                marker,
+                game
+            );
+        }
+    }
+
+    for tc in game.total_conversions() {
+        if tc.total_conversion_marker().is_some_and(has) {
+            bail!(
+                "{} is the {} total conversion, but this modlist targets {}",
+                path.display(),
+                tc,
+                game
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// The game installed at `path`, told by its executable and master files
+/// rather than the folder name, which non-English Steam clients and other
+/// stores localize. `None` if it's no supported game.
+pub fn identify_game_dir(path: &Path) -> Option<GameType> {
+    GameType::ALL
+        .iter()
+        .copied()
+        .find(|&game| validate_game_path(game, path).is_ok())
+}
+
+/// One official DLC and whether its master file is installed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DlcStatus {
+    pub name: &'static str,
+    pub file: &'static str,
+    pub present: bool,
+}
+
+/// Check which of `game`'s official DLC are installed under `path`.
+pub fn dlc_report(game: GameType, path: &Path) -> Vec<DlcStatus> {
+    game.dlc()
+        .iter()
+        .map(|&(name, file)| DlcStatus {
+            name,
+            file,
+            present: resolve_case_insensitive(path, file).is_some(),
+        })
+        .collect()
+}
+
+/// Whether a modlist game file (as written in a `GameFileSource` state, with
+/// or without the leading `Data\`) is this DLC's master file.
+pub fn is_dlc_file(dlc: &DlcStatus, game_file: &str) -> bool {
+    let wanted = game_file.replace('\\', "/").to_lowercase();
+    let file = dlc.file.to_lowercase();
+    wanted == file || file.ends_with(&format!("/{}", wanted))
+}
+
+/// Find a modlist game file (`GameFileSource` path, with or without the
+/// leading data folder) under `game_dir`.
+pub fn resolve_game_file(game_dir: &Path, game_file: &str) -> Option<PathBuf> {
+    resolve_case_insensitive(game_dir, game_file).or_else(|| {
+        DATA_DIRS
+            .iter()
+            .find_map(|dir| resolve_case_insensitive(game_dir, &format!("{}/{}", dir, game_file)))
+    })
+}
+
+/// Official DLC a game file belongs to, by name. DLC archives are named after
+/// their master (`Dawnguard.bsa`, `DLCCoast - Main.ba2`), so the file's stem
+/// is matched against each master's. `game` is the Wabbajack game name from
+/// the `GameFileSource` state.
+pub fn dlc_for_game_file(game: &str, game_file: &str) -> Option<&'static str> {
+    let game = GameType::from_wabbajack_name(game)?;
+    let basename = game_file
+        .rsplit(['\\', '/'])
+        .next()
+        .unwrap_or(game_file)
+        .to_lowercase();
+    game.dlc().iter().find_map(|&(name, master)| {
+        let master = master.rsplit('/').next().unwrap_or(master).to_lowercase();
+        let stem = master.rsplit_once('.').map_or(master.as_str(), |(s, _)| s);
+        let rest = basename.strip_prefix(stem)?;
+        (rest.starts_with('.') || rest.starts_with(" - ")).then_some(name)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn install(files: &[&str]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for f in files {
+            let p = dir.path().join(f);
+            fs::create_dir_all(p.parent().unwrap()).unwrap();
+            fs::write(p, b"").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn enderal_and_skyrim_are_told_apart() {
+        let skyrim = install(&["SkyrimSE.exe", "Data/Skyrim.esm"]);
+        let enderal = install(&["SkyrimSE.exe", "Data/Skyrim.esm", "Enderal Launcher.exe"]);
+
+        assert!(validate_game_path(GameType::SkyrimSpecialEdition, skyrim.path()).is_ok());
+        assert!(validate_game_path(GameType::EnderalSpecialEdition, enderal.path()).is_ok());
+
+        let err = validate_game_path(GameType::EnderalSpecialEdition, skyrim.path()).unwrap_err();
+        assert!(err.to_string().contains("Enderal Launcher.exe not found"));
+        let err = validate_game_path(GameType::SkyrimSpecialEdition, enderal.path()).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("is the Enderal Special Edition total conversion"));
+    }
+
+    #[test]
+    fn missing_exe_is_reported() {
+        let dir = install(&["bin/x64/witcher3.exe"]);
+        assert!(validate_game_path(GameType::Witcher3, dir.path()).is_ok());
+        let err = validate_game_path(GameType::Fallout4, dir.path()).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Fallout4.exe, Data/Fallout4.esm not found"));
+
+        let no_masters = install(&["SkyrimSE.exe", "Data/Update.esm"]);
+        let err =
+            validate_game_path(GameType::SkyrimSpecialEdition, no_masters.path()).unwrap_err();
+        assert!(err.to_string().contains(": Data/Skyrim.esm not found"));
+    }
+
+    #[test]
+    fn installs_are_identified_whatever_the_folder_is_called() {
+        let cases: [(&str, &[&str], Option<GameType>); 5] = [
+            (
+                "Skyrim Edición Especial",
+                &["SkyrimSE.exe", "Data/Skyrim.esm"],
+                Some(GameType::SkyrimSpecialEdition),
+            ),
+            (
+                "Фоллаут 4",
+                &["Fallout4.exe", "Data/Fallout4.esm"],
+                Some(GameType::Fallout4),
+            ),
+            (
+                "Enderal SE (DE)",
+                &["SkyrimSE.exe", "Data/Skyrim.esm", "Enderal Launcher.exe"],
+                Some(GameType::EnderalSpecialEdition),
+            ),
+            (
+                "ウィッチャー3",
+                &["bin/x64/witcher3.exe"],
+                Some(GameType::Witcher3),
+            ),
+            ("Skyrim Special Edition", &["Data/Skyrim.esm"], None),
+        ];
+        for (folder, files, expected) in cases {
+            let root = tempfile::tempdir().unwrap();
+            let dir = root.path().join(folder);
+            for f in files {
+                let p = dir.join(f);
+                fs::create_dir_all(p.parent().unwrap()).unwrap();
+                fs::write(p, b"").unwrap();
+            }
+            assert_eq!(identify_game_dir(&dir), expected, "{}", folder);
+        }
+    }
+
+    #[test]
+    fn dlc_presence_is_reported() {
+        let dir = install(&["SkyrimSE.exe", "Data/Skyrim.esm", "data/dawnguard.esm"]);
+        let report = dlc_report(GameType::SkyrimSpecialEdition, dir.path());
+        let present: Vec<&str> = report
+            .iter()
+            .filter(|d| d.present)
+            .map(|d| d.name)
+            .collect();
+        assert_eq!(present, vec!["Dawnguard"]);
+        assert_eq!(report.len(), 3);
+
+        assert!(is_dlc_file(&report[1], "HearthFires.esm"));
+        assert!(is_dlc_file(&report[1], "Data\\HearthFires.esm"));
+        assert!(!is_dlc_file(&report[1], "Skyrim.esm"));
+        assert!(dlc_report(GameType::EnderalSpecialEdition, dir.path()).is_empty());
+    }
+
+    #[test]
+    fn game_files_resolve_and_map_to_dlc() {
+        let morrowind = install(&["Morrowind.exe", "Data Files/Tribunal.bsa"]);
+        assert!(resolve_game_file(morrowind.path(), "Tribunal.bsa").is_some());
+        assert!(resolve_game_file(morrowind.path(), "Data Files\\tribunal.bsa").is_some());
+        assert!(resolve_game_file(morrowind.path(), "Bloodmoon.bsa").is_none());
+
+        assert_eq!(
+            dlc_for_game_file("SkyrimSpecialEdition", "Data\\Dawnguard.bsa"),
+            Some("Dawnguard")
+        );
+        assert_eq!(
+            dlc_for_game_file("Fallout4", "DLCCoast - Textures.ba2"),
+            Some("Far Harbor")
+        );
+        // DLCworkshop01 must not claim DLCworkshop02's files.
+        assert_eq!(
+            dlc_for_game_file("Fallout4", "DLCworkshop02 - Main.ba2"),
+            Some("Contraptions Workshop")
+        );
+        assert_eq!(
+            dlc_for_game_file("SkyrimSpecialEdition", "Skyrim - Textures0.bsa"),
+            None
+        );
+        assert_eq!(dlc_for_game_file("NotAGame", "Dawnguard.esm"), None);
+    }
+}
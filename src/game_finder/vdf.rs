@@ -206,6 +206,182 @@ pub fn parse_library_folders(content: &str) -> Vec<String> {
     paths
 }
 
+/// Parse binary VDF (as used by `shortcuts.vdf`) into the same tree as text
+/// VDF. Numbers are stored as decimal strings; int32 values are read as
+/// unsigned because shortcut `appid`s are u32s written as i32.
+pub fn parse_binary_vdf(data: &[u8]) -> Option<VdfValue> {
+    let mut pos = 0;
+    parse_binary_object(data, &mut pos)
+}
+
+fn parse_binary_object(data: &[u8], pos: &mut usize) -> Option<VdfValue> {
+    let mut map = HashMap::new();
+
+    // 0x08 closes an object; running out of data closes the root.
+    while let Some(&kind) = data.get(*pos) {
+        *pos += 1;
+        if kind == 0x08 {
+            break;
+        }
+        let key = read_cstr(data, pos)?;
+        let value = match kind {
+            0x00 => parse_binary_object(data, pos)?,
+            0x01 => VdfValue::String(read_cstr(data, pos)?),
+            0x02 => VdfValue::String(u32::from_le_bytes(read_bytes(data, pos)?).to_string()),
+            0x03 => VdfValue::String(f32::from_le_bytes(read_bytes(data, pos)?).to_string()),
+            0x07 => VdfValue::String(u64::from_le_bytes(read_bytes(data, pos)?).to_string()),
+            _ => return None,
+        };
+        map.insert(key, value);
+    }
+
+    Some(VdfValue::Object(map))
+}
+
+fn read_cstr(data: &[u8], pos: &mut usize) -> Option<String> {
+    let rest = data.get(*pos..)?;
+    let len = rest.iter().position(|&b| b == 0)?;
+    *pos += len + 1;
+    Some(String::from_utf8_lossy(&rest[..len]).into_owned())
+}
+
+fn read_bytes<const N: usize>(data: &[u8], pos: &mut usize) -> Option<[u8; N]> {
+    let bytes = data.get(*pos..*pos + N)?.try_into().ok()?;
+    *pos += N;
+    Some(bytes)
+}
+
+/// A non-Steam game from `userdata/<user>/config/shortcuts.vdf`
+#[derive(Debug, Clone)]
+pub struct Shortcut {
+    /// AppID Steam generated for the shortcut (also its compatdata dir name)
+    pub app_id: u32,
+    pub name: String,
+    pub exe: String,
+    pub launch_options: String,
+}
+
+/// Parse shortcuts.vdf. Key case varies between Steam versions
+/// (`AppName` vs `appname`), so keys are matched case-insensitively.
+pub fn parse_shortcuts(data: &[u8]) -> Vec<Shortcut> {
+    let Some(root) = parse_binary_vdf(data) else {
+        return Vec::new();
+    };
+    let Some(shortcuts) = root.get("shortcuts").and_then(|v| v.as_object()) else {
+        return Vec::new();
+    };
+
+    let field = |entry: &HashMap<String, VdfValue>, key: &str| {
+        entry
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .and_then(|(_, v)| v.as_str())
+            .map(str::to_string)
+    };
+
+    shortcuts
+        .values()
+        .filter_map(|v| v.as_object())
+        .filter_map(|entry| {
+            Some(Shortcut {
+                app_id: field(entry, "appid")?.parse().ok()?,
+                name: field(entry, "AppName").unwrap_or_default(),
+                exe: field(entry, "Exe").unwrap_or_default(),
+                launch_options: field(entry, "LaunchOptions").unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Point `app_id` at the compatibility tool `tool` (a Proton's config name)
+/// in the text of Steam's `config/config.vdf`, replacing any existing
+/// mapping for it. Edits the text in place so the rest of the file keeps
+/// its layout. `None` if the file has no `CompatToolMapping` section.
+pub fn set_compat_tool(config_vdf: &str, app_id: u32, tool: &str) -> Option<String> {
+    let bytes = config_vdf.as_bytes();
+    let section = config_vdf
+        .to_ascii_lowercase()
+        .find("\"compattoolmapping\"")?;
+    let open = section + config_vdf[section..].find('{')?;
+    let close = matching_brace(bytes, open)?;
+
+    // Drop the old entry: its quoted key at depth 1 and the block after it.
+    let key = format!("\"{}\"", app_id);
+    let mut body = config_vdf[open + 1..close].to_string();
+    if let Some(start) = top_level_key(body.as_bytes(), &key) {
+        let block_open = start + body[start..].find('{')?;
+        let block_close = matching_brace(body.as_bytes(), block_open)?;
+        body.replace_range(start..=block_close, "");
+    }
+
+    let entry = format!(
+        "\n\t\t\t\t\t{key}\n\t\t\t\t\t{{\n\
+         \t\t\t\t\t\t\"name\"\t\t\"{tool}\"\n\
+         \t\t\t\t\t\t\"config\"\t\t\"\"\n\
+         \t\t\t\t\t\t\"priority\"\t\t\"250\"\n\
+         \t\t\t\t\t}}"
+    );
+    Some(format!(
+        "{}{}{}{}",
+        &config_vdf[..=open],
+        entry,
+        body,
+        &config_vdf[close..]
+    ))
+}
+
+/// Index of the `}` closing the `{` at `open`, skipping quoted strings.
+fn matching_brace(bytes: &[u8], open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, &b) in bytes.iter().enumerate().skip(open) {
+        if in_string {
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match b {
+            b'"' => in_string = true,
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Start of the quoted token `key` outside any nested block of `body`.
+fn top_level_key(body: &[u8], key: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut i = 0;
+    while i < body.len() {
+        match body[i] {
+            b'"' => {
+                let end = i + 1 + body[i + 1..].iter().position(|&b| b == b'"')?;
+                if depth == 0 && &body[i..=end] == key.as_bytes() {
+                    return Some(i);
+                }
+                i = end;
+            }
+            b'{' => depth += 1,
+            b'}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -251,4 +427,77 @@ mod tests {
         assert!(paths.contains(&"/home/user/.local/share/Steam".to_string()));
         assert!(paths.contains(&"/mnt/games/SteamLibrary".to_string()));
     }
+
+    #[test]
+    fn test_parse_shortcuts() {
+        let mut data = b"\x00shortcuts\x00\x000\x00".to_vec();
+        data.extend(b"\x02appid\x00");
+        data.extend(0xC1A2_B3C4u32.to_le_bytes());
+        data.extend(b"\x01AppName\x00Mod Organizer 2 - Tuxborn\x00");
+        data.extend(b"\x01Exe\x00\"/games/Tuxborn/ModOrganizer.exe\"\x00");
+        data.extend(b"\x01launchoptions\x00%command% -ignoredupe\x00");
+        data.extend(b"\x00tags\x00\x08");
+        data.extend(b"\x08\x08\x08");
+
+        let shortcuts = parse_shortcuts(&data);
+        assert_eq!(shortcuts.len(), 1);
+        assert_eq!(shortcuts[0].app_id, 0xC1A2_B3C4);
+        assert_eq!(shortcuts[0].name, "Mod Organizer 2 - Tuxborn");
+        assert!(shortcuts[0].exe.ends_with("ModOrganizer.exe\""));
+        assert_eq!(shortcuts[0].launch_options, "%command% -ignoredupe");
+
+        assert!(parse_shortcuts(b"\x00shortcuts\x00\x02appid").is_empty());
+    }
+
+    #[test]
+    fn test_set_compat_tool() {
+        let config = r#""InstallConfigStore"
+{
+	"Software"
+	{
+		"Valve"
+		{
+			"Steam"
+			{
+				"CompatToolMapping"
+				{
+					"3242345678"
+					{
+						"name"		"proton_9"
+						"config"		""
+						"priority"		"250"
+					}
+					"489830"
+					{
+						"name"		"proton_experimental"
+					}
+				}
+			}
+		}
+	}
+}
+"#;
+        let updated = set_compat_tool(config, 3242345678, "GE-Proton10-20").unwrap();
+        let mapping = parse_vdf(&updated)
+            .and_then(|root| {
+                root.get("InstallConfigStore")?
+                    .get("Software")?
+                    .get("Valve")?
+                    .get("Steam")?
+                    .get("CompatToolMapping")
+                    .cloned()
+            })
+            .unwrap();
+        assert_eq!(
+            mapping.get("3242345678").and_then(|m| m.get_str("name")),
+            Some("GE-Proton10-20")
+        );
+        assert_eq!(
+            mapping.get("489830").and_then(|m| m.get_str("name")),
+            Some("proton_experimental")
+        );
+        assert_eq!(updated.matches("\"3242345678\"").count(), 1);
+
+        assert!(set_compat_tool("\"InstallConfigStore\" { }", 1, "proton_9").is_none());
+    }
 }
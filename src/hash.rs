@@ -6,22 +6,77 @@
 use anyhow::{Context, Result};
 use base64::{engine::general_purpose::STANDARD, Engine};
 use std::fs::File;
-use std::io::{BufReader, Read};
+use std::io::Read;
 use std::path::Path;
+use tokio_util::sync::CancellationToken;
 
-/// Compute xxHash64 of a file and return as base64 string (Wabbajack format).
-///
-/// Uses streaming to handle large files without loading into memory.
-pub fn compute_file_hash(path: &Path) -> Result<String> {
-    let file = File::open(path)
+/// Read size for file hashing. Memory use stays at one buffer regardless
+/// of file size.
+const HASH_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Hash algorithm and the text encoding of its digest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    /// xxHash64 as base64 of the little-endian digest (Wabbajack format).
+    XxHash64,
+}
+
+enum Hasher {
+    XxHash64(xxhash_rust::xxh64::Xxh64),
+}
+
+impl Hasher {
+    fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::XxHash64 => Hasher::XxHash64(xxhash_rust::xxh64::Xxh64::new(0)),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::XxHash64(h) => h.update(data),
+        }
+    }
+
+    fn finish(self) -> String {
+        match self {
+            Hasher::XxHash64(h) => STANDARD.encode(h.digest().to_le_bytes()),
+        }
+    }
+}
+
+/// Hash a file in fixed-size chunks, so multi-GB archives never sit in
+/// memory. `progress` gets `(bytes hashed, file size)` after every chunk.
+pub fn hash_file_streaming(
+    path: &Path,
+    algorithm: HashAlgorithm,
+    progress: Option<&dyn Fn(u64, u64)>,
+) -> Result<String> {
+    let hash = hash_file_cancellable(path, algorithm, progress, &CancellationToken::new())?;
+    Ok(hash.expect("hashing without a cancelled token finishes"))
+}
+
+/// [`hash_file_streaming`] that stops between chunks once `cancel` is
+/// cancelled, returning `None`.
+pub fn hash_file_cancellable(
+    path: &Path,
+    algorithm: HashAlgorithm,
+    progress: Option<&dyn Fn(u64, u64)>,
+    cancel: &CancellationToken,
+) -> Result<Option<String>> {
+    let mut file = File::open(path)
         .with_context(|| format!("Failed to open file for hashing: {}", path.display()))?;
+    let total = file.metadata().map(|m| m.len()).unwrap_or(0);
 
-    let mut reader = BufReader::with_capacity(8 * 1024 * 1024, file); // 8MB buffer
-    let mut buf = vec![0u8; 8 * 1024 * 1024]; // 8MB chunks
-    let mut hasher = xxhash_rust::xxh64::Xxh64::new(0);
+    let mut buf = vec![0u8; HASH_CHUNK_SIZE];
+    let mut hasher = Hasher::new(algorithm);
+    let mut done = 0u64;
 
     loop {
-        let bytes_read = reader
+        if cancel.is_cancelled() {
+            return Ok(None);
+        }
+        let bytes_read = file
             .read(&mut buf)
             .with_context(|| format!("Failed to read file for hashing: {}", path.display()))?;
 
@@ -30,15 +85,18 @@ pub fn compute_file_hash(path: &Path) -> Result<String> {
         }
 
         hasher.update(&buf[..bytes_read]);
+        done += bytes_read as u64;
+        if let Some(progress) = progress {
+            progress(done, total.max(done));
+        }
     }
 
-    let hash = hasher.digest();
-
-    // Convert to base64 (little-endian bytes, like Wabbajack)
-    let hash_bytes = hash.to_le_bytes();
-    let base64_hash = STANDARD.encode(hash_bytes);
+    Ok(Some(hasher.finish()))
+}
 
-    Ok(base64_hash)
+/// Compute xxHash64 of a file and return as base64 string (Wabbajack format).
+pub fn compute_file_hash(path: &Path) -> Result<String> {
+    hash_file_streaming(path, HashAlgorithm::XxHash64, None)
 }
 
 /// Verify a file's hash matches the expected Wabbajack hash.
@@ -60,7 +118,6 @@ pub fn compute_bytes_hash(data: &[u8]) -> String {
 /// Verify a file's hash and return detailed result.
 ///
 /// Returns (matches, actual_hash) for logging/debugging.
-#[allow(dead_code)] // Part of hash API, not yet wired up
 pub fn verify_file_hash_detailed(path: &Path, expected_hash: &str) -> Result<(bool, String)> {
     let actual_hash = compute_file_hash(path)?;
     let matches = actual_hash == expected_hash;
@@ -155,6 +212,28 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_streaming_hash_matches_bytes_hash() -> Result<()> {
+        // Spans several chunks and ends mid-chunk.
+        let data: Vec<u8> = (0..HASH_CHUNK_SIZE * 2 + 123)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        let mut tmp = NamedTempFile::new()?;
+        tmp.write_all(&data)?;
+        tmp.flush()?;
+
+        let calls = std::cell::RefCell::new(Vec::new());
+        let progress = |done: u64, total: u64| calls.borrow_mut().push((done, total));
+        let hash = hash_file_streaming(tmp.path(), HashAlgorithm::XxHash64, Some(&progress))?;
+        assert_eq!(hash, compute_bytes_hash(&data));
+
+        let calls = calls.into_inner();
+        let len = data.len() as u64;
+        assert_eq!(calls.last(), Some(&(len, len)));
+        assert!(calls.windows(2).all(|w| w[0].0 < w[1].0));
+        Ok(())
+    }
+
     #[test]
     fn test_verify_hash() -> Result<()> {
         let mut tmp = NamedTempFile::new()?;
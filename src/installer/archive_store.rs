@@ -0,0 +1,178 @@
+//! Shared archive store (`--archive-store <dir>`)
+//!
+//! A directory of archives named by their Wabbajack hash, e.g. a NAS a group
+//! of users fills once and installs from. The name before the first `.` is
+//! the hash, either as 16 hex digits (`6F1A2B3C4D5E6F70.7z`) or as the
+//! modlist's base64 (`bxorPE1ebxA=`, or the URL-safe alphabet since `/` can't
+//! appear in a file name). Subdirectories are searched too.
+//!
+//! Every archive is looked up here before its download source, whatever the
+//! source type. Matches are hardlinked into the downloads dir (copied across
+//! filesystems) and hash-verified; misses and bad copies download as usual.
+
+use crate::hash::verify_file_hash;
+use anyhow::{Context, Result};
+use base64::Engine;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+use walkdir::WalkDir;
+
+/// Index of a store directory: hash (upper-case hex) → file.
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveStore {
+    root: PathBuf,
+    by_hash: HashMap<String, PathBuf>,
+}
+
+/// Upper-case hex form of a hash given as hex or base64; None for anything
+/// that isn't an 8-byte hash.
+fn hash_key(text: &str) -> Option<String> {
+    if text.len() == 16 && text.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Some(text.to_ascii_uppercase());
+    }
+    let engines = [
+        base64::engine::general_purpose::STANDARD,
+        base64::engine::general_purpose::URL_SAFE,
+    ];
+    let bytes = engines.iter().find_map(|e| e.decode(text).ok())?;
+    (bytes.len() == 8).then(|| bytes.iter().map(|b| format!("{:02X}", b)).collect())
+}
+
+impl ArchiveStore {
+    /// Scan `root` for archives named by hash.
+    pub fn open(root: &Path) -> Result<Self> {
+        if !root.is_dir() {
+            anyhow::bail!("Archive store {} is not a directory", root.display());
+        }
+        let mut by_hash = HashMap::new();
+        for entry in WalkDir::new(root).follow_links(true) {
+            let entry = entry
+                .with_context(|| format!("Failed to scan archive store {}", root.display()))?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy();
+            let stem = name.split('.').next().unwrap_or_default();
+            if let Some(key) = hash_key(stem) {
+                by_hash.entry(key).or_insert_with(|| entry.into_path());
+            }
+        }
+        Ok(Self {
+            root: root.to_path_buf(),
+            by_hash,
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Number of archives in the store.
+    pub fn len(&self) -> usize {
+        self.by_hash.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_hash.is_empty()
+    }
+
+    /// Store file for a modlist archive hash.
+    pub fn find(&self, hash: &str) -> Option<&Path> {
+        self.by_hash.get(&hash_key(hash)?).map(PathBuf::as_path)
+    }
+
+    /// Place the stored copy of `hash` at `dest` if there is one of the right
+    /// size and it verifies. Returns false on a miss; a bad copy is logged,
+    /// removed from `dest` and also reported as a miss.
+    pub fn place(&self, hash: &str, size: u64, dest: &Path) -> bool {
+        let Some(source) = self.find(hash) else {
+            return false;
+        };
+        match fs::metadata(source) {
+            Ok(meta) if meta.len() == size => {}
+            Ok(meta) => {
+                warn!(
+                    "Archive store copy {} is {} bytes, expected {}; downloading instead",
+                    source.display(),
+                    meta.len(),
+                    size
+                );
+                return false;
+            }
+            Err(e) => {
+                warn!("Cannot read archive store copy {}: {}", source.display(), e);
+                return false;
+            }
+        }
+
+        let _ = fs::remove_file(dest);
+        let placed = fs::hard_link(source, dest).or_else(|_| fs::copy(source, dest).map(|_| ()));
+        if let Err(e) = placed {
+            warn!(
+                "Failed to copy {} from the archive store: {}",
+                source.display(),
+                e
+            );
+            let _ = fs::remove_file(dest);
+            return false;
+        }
+
+        match verify_file_hash(dest, hash) {
+            Ok(true) => {
+                debug!("Placed {} from the archive store", source.display());
+                true
+            }
+            Ok(false) => {
+                warn!(
+                    "Archive store copy {} fails hash verification; downloading instead",
+                    source.display()
+                );
+                let _ = fs::remove_file(dest);
+                false
+            }
+            Err(e) => {
+                warn!("Failed to verify {}: {}", dest.display(), e);
+                let _ = fs::remove_file(dest);
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::compute_bytes_hash;
+
+    #[test]
+    fn store_matches_hex_and_base64_names() {
+        let store_dir = tempfile::tempdir().unwrap();
+        let downloads = tempfile::tempdir().unwrap();
+
+        let nested = store_dir.path().join("skyrim");
+        fs::create_dir(&nested).unwrap();
+        let hash = compute_bytes_hash(b"archive contents");
+        let hex = hash_key(&hash).unwrap();
+        fs::write(
+            nested.join(format!("{}.7z", hex.to_lowercase())),
+            b"archive contents",
+        )
+        .unwrap();
+        fs::write(store_dir.path().join("readme.txt"), b"not an archive").unwrap();
+
+        let store = ArchiveStore::open(store_dir.path()).unwrap();
+        assert_eq!(store.len(), 1);
+        assert!(store.find(&hash).is_some());
+
+        let dest = downloads.path().join("Mod-1-0.7z");
+        assert!(!store.place(&hash, 3, &dest), "wrong size is a miss");
+        assert!(store.place(&hash, 16, &dest));
+        assert_eq!(fs::read(&dest).unwrap(), b"archive contents");
+
+        let url_safe = hash.replace('/', "_").replace('+', "-");
+        assert_eq!(hash_key(&url_safe), Some(hex));
+        assert_eq!(hash_key("readme"), None);
+    }
+}
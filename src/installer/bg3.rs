@@ -0,0 +1,465 @@
+//! Baldur's Gate 3 mod deployment
+//!
+//! BG3 doesn't load mods from a Bethesda-style `Data` folder. `.pak` mods
+//! live in `AppData/Local/Larian Studios/Baldur's Gate 3/Mods` and are only
+//! loaded when listed in `PlayerProfiles/Public/modsettings.lsx`. MO2's BG3
+//! plugin keeps a mod's paks at its root or in `PAK_FILES/` and maps them
+//! there at launch; under Wine that doesn't happen, so after install the paks
+//! of enabled mods are copied into the prefix and added to modsettings.lsx.
+//!
+//! The entries need each mod's UUID and version, which are read from the
+//! `info.json` most BG3 mods ship next to their paks (the BG3 Mod Manager
+//! format). Paks without one are still copied, but have to be activated in
+//! a mod manager.
+
+use super::conflicts::{active_profile, enabled_mods};
+use crate::game_finder::find_by_wabbajack_type;
+use anyhow::{bail, Context, Result};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// MO2 BG3 plugin folder for paks inside a mod.
+const PAK_DIR: &str = "PAK_FILES";
+
+/// Copy of the game's own modsettings.lsx, made before it is first changed.
+pub const MODSETTINGS_BACKUP: &str = "modsettings.lsx.clf3-backup";
+
+/// A mod module as listed in modsettings.lsx.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModInfo {
+    pub uuid: String,
+    pub folder: String,
+    pub name: String,
+    pub version64: String,
+    pub md5: String,
+}
+
+/// A `.pak` in an enabled mod.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PakFile {
+    pub mod_name: String,
+    pub source: PathBuf,
+    pub file_name: String,
+}
+
+/// Paks and modules of an install's enabled mods, lowest priority first
+/// (the load order modsettings.lsx uses).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Bg3Mods {
+    pub paks: Vec<PakFile>,
+    pub modules: Vec<ModInfo>,
+    /// Mods with paks but no usable info.json.
+    pub without_info: Vec<String>,
+}
+
+fn json_string(value: &serde_json::Value, key: &str) -> Option<String> {
+    match value.get(key)? {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Modules described by an `info.json`. Entries without a UUID or folder are
+/// skipped.
+pub fn parse_info_json(text: &str) -> Vec<ModInfo> {
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(text) else {
+        return Vec::new();
+    };
+    let md5 = json_string(&json, "MD5").unwrap_or_default();
+    let Some(mods) = json.get("Mods").and_then(|m| m.as_array()) else {
+        return Vec::new();
+    };
+    mods.iter()
+        .filter_map(|m| {
+            let uuid = json_string(m, "UUID")?;
+            let folder = json_string(m, "Folder")?;
+            Some(ModInfo {
+                name: json_string(m, "Name").unwrap_or_else(|| folder.clone()),
+                version64: json_string(m, "Version64")
+                    .or_else(|| json_string(m, "Version"))
+                    .unwrap_or_else(|| "36028797018963968".into()),
+                md5: md5.clone(),
+                uuid,
+                folder,
+            })
+        })
+        .collect()
+}
+
+fn files_with_extension(dir: &Path, ext: &str) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .filter(|p| {
+            p.extension()
+                .is_some_and(|e| e.to_string_lossy().eq_ignore_ascii_case(ext))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Paks and modules of `enabled` mods (highest priority first, as in
+/// modlist.txt). When mods ship a pak of the same name, the higher one wins.
+pub fn find_bg3_mods(install_dir: &Path, enabled: &[&str]) -> Bg3Mods {
+    let mut result = Bg3Mods::default();
+    let mut pak_index: HashMap<String, usize> = HashMap::new();
+    let mut seen_modules = HashSet::new();
+    for mod_name in enabled.iter().rev() {
+        let mod_dir = install_dir.join("mods").join(mod_name);
+        let dirs = [mod_dir.clone(), mod_dir.join(PAK_DIR)];
+        let paks: Vec<PathBuf> = dirs
+            .iter()
+            .flat_map(|d| files_with_extension(d, "pak"))
+            .collect();
+        if paks.is_empty() {
+            continue;
+        }
+        for source in paks {
+            let file_name = source
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+            let pak = PakFile {
+                mod_name: mod_name.to_string(),
+                source,
+                file_name: file_name.clone(),
+            };
+            match pak_index.get(&file_name.to_lowercase()) {
+                Some(&i) => result.paks[i] = pak,
+                None => {
+                    pak_index.insert(file_name.to_lowercase(), result.paks.len());
+                    result.paks.push(pak);
+                }
+            }
+        }
+
+        let modules: Vec<ModInfo> = dirs
+            .iter()
+            .flat_map(|d| files_with_extension(d, "json"))
+            .filter(|p| {
+                p.file_name()
+                    .is_some_and(|n| n.eq_ignore_ascii_case("info.json"))
+            })
+            .filter_map(|p| fs::read_to_string(p).ok())
+            .flat_map(|text| parse_info_json(&text))
+            .collect();
+        if modules.is_empty() {
+            result.without_info.push(mod_name.to_string());
+        }
+        for module in modules {
+            if seen_modules.insert(module.uuid.to_lowercase()) {
+                result.modules.push(module);
+            }
+        }
+    }
+    result
+}
+
+/// BG3 mods of the active profile, for a finished install.
+pub fn bg3_mods_for_install(install_dir: &Path) -> Result<Bg3Mods> {
+    let profile = active_profile(install_dir)?;
+    let modlist_path = install_dir
+        .join("profiles")
+        .join(&profile)
+        .join("modlist.txt");
+    let modlist_txt = fs::read_to_string(&modlist_path)
+        .with_context(|| format!("Failed to read {}", modlist_path.display()))?;
+    Ok(find_bg3_mods(install_dir, &enabled_mods(&modlist_txt)))
+}
+
+/// The game's folder in a prefix's `AppData/Local`, which holds `Mods/` and
+/// `PlayerProfiles/`.
+pub fn larian_dir(prefix: &Path) -> PathBuf {
+    let users = prefix.join("drive_c/users");
+    let user = fs::read_dir(&users)
+        .ok()
+        .and_then(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.file_name().to_string_lossy().into_owned())
+                .find(|name| name != "Public" && name != "root")
+        })
+        .unwrap_or_else(|| "steamuser".into());
+    let folder = find_by_wabbajack_type("BaldursGate3")
+        .and_then(|g| g.appdata_local_folder)
+        .unwrap_or("Larian Studios/Baldur's Gate 3");
+    users.join(user).join("AppData/Local").join(folder)
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// UUIDs already listed in a modsettings.lsx, lower-cased.
+fn listed_uuids(lsx: &str) -> HashSet<String> {
+    lsx.split(r#"id="UUID""#)
+        .skip(1)
+        .filter_map(|rest| {
+            let value = rest.split_once(r#"value=""#)?.1;
+            Some(value.split('"').next()?.to_lowercase())
+        })
+        .collect()
+}
+
+/// Byte offset of the `</children>` closing `<node id="{node}">`'s children.
+fn children_end(lsx: &str, node: &str) -> Option<usize> {
+    let start = lsx.find(&format!(r#"<node id="{}">"#, node))?;
+    let open = start + lsx[start..].find("<children>")? + "<children>".len();
+    let mut depth = 1;
+    let mut pos = open;
+    loop {
+        let next_open = lsx[pos..].find("<children>").map(|i| pos + i);
+        let next_close = pos + lsx[pos..].find("</children>")?;
+        match next_open {
+            Some(o) if o < next_close => {
+                depth += 1;
+                pos = o + "<children>".len();
+            }
+            _ => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(next_close);
+                }
+                pos = next_close + "</children>".len();
+            }
+        }
+    }
+}
+
+/// Indentation of the line `offset` is on.
+fn indent_at(lsx: &str, offset: usize) -> &str {
+    let line_start = lsx[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let line = &lsx[line_start..offset];
+    &line[..line.len() - line.trim_start().len()]
+}
+
+fn insert_nodes(lsx: &mut String, node: &str, nodes: &[String]) -> Result<()> {
+    let Some(end) = children_end(lsx, node) else {
+        bail!("modsettings.lsx has no {} list", node);
+    };
+    let indent = format!("{}    ", indent_at(lsx, end));
+    let mut block = String::new();
+    for n in nodes {
+        for line in n.lines() {
+            block.push_str(&indent);
+            block.push_str(line);
+            block.push('\n');
+        }
+    }
+    // Keep the closing tag on its own line at its indentation.
+    let line_start = lsx[..end].rfind('\n').map_or(0, |i| i + 1);
+    if lsx[line_start..end].trim().is_empty() {
+        lsx.insert_str(line_start, &block);
+    } else {
+        let closing = indent_at(lsx, end).to_string();
+        lsx.insert_str(end, &format!("\n{}{}", block, closing));
+    }
+    Ok(())
+}
+
+fn short_desc(module: &ModInfo) -> String {
+    format!(
+        concat!(
+            "<node id=\"ModuleShortDesc\">\n",
+            "    <attribute id=\"Folder\" type=\"LSString\" value=\"{}\"/>\n",
+            "    <attribute id=\"MD5\" type=\"LSString\" value=\"{}\"/>\n",
+            "    <attribute id=\"Name\" type=\"LSString\" value=\"{}\"/>\n",
+            "    <attribute id=\"PublishHandle\" type=\"uint64\" value=\"0\"/>\n",
+            "    <attribute id=\"UUID\" type=\"guid\" value=\"{}\"/>\n",
+            "    <attribute id=\"Version64\" type=\"int64\" value=\"{}\"/>\n",
+            "</node>"
+        ),
+        xml_escape(&module.folder),
+        xml_escape(&module.md5),
+        xml_escape(&module.name),
+        xml_escape(&module.uuid),
+        xml_escape(&module.version64),
+    )
+}
+
+/// Empty modsettings.lsx, for a game that hasn't been started yet. The game
+/// adds its own base module on first launch.
+const EMPTY_MODSETTINGS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<save>
+    <version major="4" minor="7" revision="1" build="3"/>
+    <region id="ModuleSettings">
+        <node id="root">
+            <children>
+                <node id="Mods">
+                    <children>
+                    </children>
+                </node>
+            </children>
+        </node>
+    </region>
+</save>
+"#;
+
+/// `existing` (or an empty modsettings.lsx) with `modules` appended to the
+/// load order. Modules already listed keep their place; older files with a
+/// separate `ModOrder` list get the new UUIDs there too.
+pub fn merge_modsettings(existing: Option<&str>, modules: &[ModInfo]) -> Result<String> {
+    let mut lsx = existing.unwrap_or(EMPTY_MODSETTINGS).to_string();
+    let listed = listed_uuids(&lsx);
+    let new: Vec<&ModInfo> = modules
+        .iter()
+        .filter(|m| !listed.contains(&m.uuid.to_lowercase()))
+        .collect();
+    if new.is_empty() {
+        return Ok(lsx);
+    }
+    if lsx.contains(r#"<node id="ModOrder">"#) {
+        let order: Vec<String> = new
+            .iter()
+            .map(|m| {
+                format!(
+                    "<node id=\"Module\">\n    <attribute id=\"UUID\" type=\"FixedString\" value=\"{}\"/>\n</node>",
+                    xml_escape(&m.uuid)
+                )
+            })
+            .collect();
+        insert_nodes(&mut lsx, "ModOrder", &order)?;
+    }
+    let descs: Vec<String> = new.iter().map(|m| short_desc(m)).collect();
+    insert_nodes(&mut lsx, "Mods", &descs)?;
+    Ok(lsx)
+}
+
+/// What [`deploy`] did.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeployedMods {
+    /// Paks copied (or already up to date) in the Mods folder.
+    pub paks: usize,
+    /// Modules newly added to modsettings.lsx.
+    pub activated: usize,
+}
+
+/// Copy the paks into `larian_dir/Mods` and list the modules in its
+/// modsettings.lsx, backing up the game's copy the first time.
+pub fn deploy(mods: &Bg3Mods, larian_dir: &Path) -> Result<DeployedMods> {
+    let mods_dir = larian_dir.join("Mods");
+    fs::create_dir_all(&mods_dir)
+        .with_context(|| format!("Failed to create {}", mods_dir.display()))?;
+    for pak in &mods.paks {
+        let dest = mods_dir.join(&pak.file_name);
+        let up_to_date = match (fs::metadata(&dest), fs::metadata(&pak.source)) {
+            (Ok(d), Ok(s)) => d.len() == s.len() && fs::read(&dest)? == fs::read(&pak.source)?,
+            _ => false,
+        };
+        if !up_to_date {
+            fs::copy(&pak.source, &dest).with_context(|| {
+                format!(
+                    "Failed to copy {} to {}",
+                    pak.source.display(),
+                    dest.display()
+                )
+            })?;
+        }
+    }
+
+    let profile_dir = larian_dir.join("PlayerProfiles/Public");
+    let settings_path = profile_dir.join("modsettings.lsx");
+    let existing = fs::read_to_string(&settings_path).ok();
+    let merged = merge_modsettings(existing.as_deref(), &mods.modules)
+        .with_context(|| format!("Failed to update {}", settings_path.display()))?;
+    let activated =
+        listed_uuids(&merged).len() - existing.as_deref().map_or(0, |e| listed_uuids(e).len());
+    if existing.as_deref() != Some(merged.as_str()) {
+        fs::create_dir_all(&profile_dir)?;
+        let backup = profile_dir.join(MODSETTINGS_BACKUP);
+        if existing.is_some() && !backup.exists() {
+            fs::copy(&settings_path, &backup)
+                .with_context(|| format!("Failed to back up {}", settings_path.display()))?;
+        }
+        fs::write(&settings_path, merged)
+            .with_context(|| format!("Failed to write {}", settings_path.display()))?;
+    }
+    Ok(DeployedMods {
+        paks: mods.paks.len(),
+        activated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paks_are_deployed_and_listed_in_modsettings() {
+        let tmp = tempfile::tempdir().unwrap();
+        let install = tmp.path().join("install");
+        let write = |path: PathBuf, data: &str| {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, data).unwrap();
+        };
+        write(install.join("mods/Camp Clothes/CampClothes.pak"), "old");
+        write(
+            install.join("mods/Camp Clothes/info.json"),
+            r#"{"Mods":[{"Name":"Camp Clothes","Folder":"CampClothes","UUID":"aaaa-1","Version":"36028797018963970"}],"MD5":"abc"}"#,
+        );
+        write(
+            install.join("mods/Camp Clothes Fix/PAK_FILES/CampClothes.pak"),
+            "fixed",
+        );
+        write(install.join("mods/Loose Pak/Loose.pak"), "loose");
+        write(install.join("mods/Disabled/Disabled.pak"), "off");
+
+        let enabled = ["Loose Pak", "Camp Clothes Fix", "Camp Clothes"];
+        let mods = find_bg3_mods(&install, &enabled);
+        let names: Vec<_> = mods.paks.iter().map(|p| p.file_name.as_str()).collect();
+        assert_eq!(names, ["CampClothes.pak", "Loose.pak"]);
+        assert_eq!(mods.paks[0].mod_name, "Camp Clothes Fix");
+        assert_eq!(mods.modules.len(), 1);
+        assert_eq!(mods.without_info, ["Camp Clothes Fix", "Loose Pak"]);
+
+        // Existing settings keep the game's base module and gain the mod once.
+        let larian = tmp.path().join("larian");
+        let base = merge_modsettings(
+            None,
+            &[ModInfo {
+                uuid: "base-0".into(),
+                folder: "GustavX".into(),
+                name: "GustavX".into(),
+                version64: "1".into(),
+                md5: String::new(),
+            }],
+        )
+        .unwrap();
+        write(larian.join("PlayerProfiles/Public/modsettings.lsx"), &base);
+
+        let deployed = deploy(&mods, &larian).unwrap();
+        assert_eq!(
+            deployed,
+            DeployedMods {
+                paks: 2,
+                activated: 1
+            }
+        );
+        assert_eq!(
+            fs::read_to_string(larian.join("Mods/CampClothes.pak")).unwrap(),
+            "fixed"
+        );
+        let lsx = fs::read_to_string(larian.join("PlayerProfiles/Public/modsettings.lsx")).unwrap();
+        assert!(lsx.find("GustavX").unwrap() < lsx.find("CampClothes").unwrap());
+        assert!(lsx.contains(r#"<attribute id="UUID" type="guid" value="aaaa-1"/>"#));
+        assert!(lsx.trim_end().ends_with("</save>"));
+        assert!(larian
+            .join("PlayerProfiles/Public")
+            .join(MODSETTINGS_BACKUP)
+            .is_file());
+
+        assert_eq!(deploy(&mods, &larian).unwrap().activated, 0);
+    }
+}
@@ -0,0 +1,248 @@
+//! Open pending downloads in the browser a batch at a time
+//! (`clf3 open-downloads`).
+//!
+//! Without Nexus Premium, or for manual sources, every archive has to be
+//! fetched by clicking download on its page. Opening pages one by one is
+//! slow and opening hundreds at once floods the browser, so the queue hands
+//! out fixed-size batches of the archives still missing from the downloads
+//! dir and tracks which of them have arrived. By default only Nexus and
+//! manual archives are queued; the installer fetches the other sources
+//! itself.
+
+use super::status::archive_present;
+use crate::downloaders::NexusDownloader;
+use crate::modlist::{Archive, DownloadState};
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// An archive the user has to download in a browser.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingDownload {
+    /// File name the archive must be saved under.
+    pub name: String,
+    /// Page to open.
+    pub url: String,
+    pub expected_size: u64,
+}
+
+/// Browser page for an archive, or None for sources without one (game
+/// files, which the installer copies itself).
+fn page_url(state: &DownloadState) -> Option<String> {
+    match state {
+        DownloadState::Nexus(s) => Some(NexusDownloader::get_mod_page_url(
+            NexusDownloader::game_domain(&s.game_name),
+            s.mod_id,
+            s.file_id,
+        )),
+        DownloadState::Http(s) => Some(s.url.clone()),
+        DownloadState::WabbajackCDN(s) => Some(s.url.clone()),
+        DownloadState::GoogleDrive(s) => {
+            Some(format!("https://drive.google.com/file/d/{}/view", s.id))
+        }
+        DownloadState::MediaFire(s) => Some(s.url.clone()),
+        DownloadState::Manual(s) => Some(s.url.clone()),
+        DownloadState::Mega(s) => Some(s.url.clone()),
+        DownloadState::GameFileSource(_) => None,
+    }
+}
+
+/// Which archives [`BrowserQueue`] opens pages for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QueueSources {
+    /// Nexus mod pages only.
+    Nexus,
+    /// Nexus and manual sources, the ones the installer can't always fetch.
+    #[default]
+    Browser,
+    /// Every source with a page, including direct links the installer
+    /// downloads itself.
+    All,
+}
+
+impl QueueSources {
+    fn includes(self, state: &DownloadState) -> bool {
+        match self {
+            QueueSources::Nexus => matches!(state, DownloadState::Nexus(_)),
+            QueueSources::Browser => {
+                matches!(state, DownloadState::Nexus(_) | DownloadState::Manual(_))
+            }
+            QueueSources::All => true,
+        }
+    }
+}
+
+/// Pending browser downloads, in modlist order, batch by batch.
+pub struct BrowserQueue {
+    downloads_dir: PathBuf,
+    pending: Vec<PendingDownload>,
+    batch_size: usize,
+    /// Index into `pending` of the next batch.
+    next: usize,
+}
+
+impl BrowserQueue {
+    /// Queue every archive from `sources` not yet in `downloads_dir`.
+    pub fn new(
+        archives: &[Archive],
+        downloads_dir: &Path,
+        batch_size: usize,
+        sources: QueueSources,
+    ) -> Self {
+        let pending = archives
+            .iter()
+            .filter(|a| sources.includes(&a.state))
+            .filter(|a| !archive_present(downloads_dir, &a.name, a.size))
+            .filter_map(|a| {
+                Some(PendingDownload {
+                    name: a.name.clone(),
+                    url: page_url(&a.state)?,
+                    expected_size: a.size,
+                })
+            })
+            .collect();
+        Self {
+            downloads_dir: downloads_dir.to_path_buf(),
+            pending,
+            batch_size: batch_size.max(1),
+            next: 0,
+        }
+    }
+
+    /// Archives that were missing when the queue was built.
+    pub fn total(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Number of batches for the whole queue.
+    pub fn batch_count(&self) -> usize {
+        self.pending.len().div_ceil(self.batch_size)
+    }
+
+    /// Next batch to open, skipping archives that arrived in the meantime.
+    /// Empty once the queue is exhausted.
+    pub fn next_batch(&mut self) -> Vec<PendingDownload> {
+        let mut batch = Vec::with_capacity(self.batch_size);
+        while batch.len() < self.batch_size && self.next < self.pending.len() {
+            let item = &self.pending[self.next];
+            self.next += 1;
+            if !self.is_downloaded(item) {
+                batch.push(item.clone());
+            }
+        }
+        batch
+    }
+
+    pub fn is_downloaded(&self, item: &PendingDownload) -> bool {
+        archive_present(&self.downloads_dir, &item.name, item.expected_size)
+    }
+
+    /// Archives from batches already handed out that still aren't there.
+    pub fn still_pending(&self) -> Vec<&PendingDownload> {
+        self.pending[..self.next]
+            .iter()
+            .filter(|p| !self.is_downloaded(p))
+            .collect()
+    }
+
+    /// Archives not downloaded yet, in handed-out batches or not.
+    pub fn remaining(&self) -> usize {
+        self.pending
+            .iter()
+            .filter(|p| !self.is_downloaded(p))
+            .count()
+    }
+}
+
+/// Open `url` in the default browser.
+pub fn open_in_browser(url: &str) -> Result<()> {
+    Command::new("xdg-open")
+        .arg(url)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run xdg-open for {}", url))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modlist::{HttpState, ManualState, NexusState};
+
+    fn nexus(name: &str, size: u64) -> Archive {
+        Archive {
+            hash: String::new(),
+            meta: String::new(),
+            name: name.to_string(),
+            size,
+            state: DownloadState::Nexus(NexusState {
+                game_name: "SkyrimSpecialEdition".to_string(),
+                mod_id: 1,
+                file_id: 2,
+                name: None,
+                author: None,
+                description: None,
+                version: None,
+                image_url: None,
+                is_nsfw: false,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_batches_skip_downloaded_archives() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("have.7z"), b"1234").unwrap();
+        let mut archives: Vec<Archive> = ["have.7z", "a.7z", "b.7z", "c.7z"]
+            .iter()
+            .map(|n| nexus(n, 4))
+            .collect();
+        archives.push(Archive {
+            hash: String::new(),
+            meta: String::new(),
+            name: "web.zip".to_string(),
+            size: 4,
+            state: DownloadState::Http(HttpState {
+                url: "https://example.com/web.zip".to_string(),
+                headers: Vec::new(),
+                mirrors: Vec::new(),
+            }),
+        });
+
+        archives.push(Archive {
+            hash: String::new(),
+            meta: String::new(),
+            name: "manual.zip".to_string(),
+            size: 4,
+            state: DownloadState::Manual(ManualState {
+                url: "https://example.com/manual".to_string(),
+                prompt: String::new(),
+            }),
+        });
+
+        let queued = |sources| BrowserQueue::new(&archives, tmp.path(), 2, sources).total();
+        assert_eq!(queued(QueueSources::Nexus), 3);
+        assert_eq!(queued(QueueSources::Browser), 4);
+        assert_eq!(queued(QueueSources::All), 5);
+
+        let mut queue = BrowserQueue::new(&archives, tmp.path(), 2, QueueSources::Browser);
+        assert_eq!(queue.total(), 4);
+        assert_eq!(queue.batch_count(), 2);
+
+        let first = queue.next_batch();
+        assert_eq!(first.len(), 2);
+        assert!(first[0].url.contains("skyrimspecialedition/mods/1"));
+
+        // a.7z arrives; c.7z arrives before its batch is handed out.
+        std::fs::write(tmp.path().join("a.7z"), b"1234").unwrap();
+        std::fs::write(tmp.path().join("c.7z"), b"1234").unwrap();
+        assert_eq!(queue.still_pending().len(), 1);
+        assert_eq!(queue.remaining(), 2);
+
+        let second = queue.next_batch();
+        assert_eq!(second.len(), 1);
+        assert_eq!(second[0].name, "manual.zip");
+        assert!(queue.next_batch().is_empty());
+    }
+}
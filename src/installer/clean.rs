@@ -0,0 +1,280 @@
+//! Cleanup of leftovers from interrupted runs (`clf3 clean`).
+//!
+//! Downloads are written in place, so an interrupted one stays behind as an
+//! archive of the wrong size; browser downloads for manual sources leave
+//! `.part`/`.crdownload` files; a killed extraction leaves its `.tmp*` dirs.
+//! None of it is ever reused, and across many installs it adds up. Archives
+//! are judged against the "known" modlists: the ones passed in plus those of
+//! every install recorded in settings. Only top-level entries are touched.
+
+use super::purge::companion_of;
+use crate::modlist::{parse_wabbajack_file, Archive, InstallManifest};
+use crate::settings::Settings;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Suffixes browsers give downloads still in progress.
+const PARTIAL_SUFFIXES: &[&str] = &[".part", ".crdownload"];
+
+/// Why an entry is up for deletion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StaleKind {
+    /// Unfinished browser download.
+    Partial,
+    /// Extraction temp dir left by an interrupted run.
+    TempDir,
+    /// Archive a known modlist uses, but with a size none of them expect:
+    /// an interrupted or corrupt download.
+    WrongSize,
+    /// Archive no known modlist uses (only with `unreferenced`).
+    Unreferenced,
+}
+
+impl std::fmt::Display for StaleKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StaleKind::Partial => write!(f, "partial download"),
+            StaleKind::TempDir => write!(f, "temp dir"),
+            StaleKind::WrongSize => write!(f, "wrong size"),
+            StaleKind::Unreferenced => write!(f, "unreferenced"),
+        }
+    }
+}
+
+/// One file or dir to delete.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaleEntry {
+    pub path: PathBuf,
+    pub kind: StaleKind,
+    pub size: u64,
+}
+
+/// Archive names and sizes used by the known modlists.
+#[derive(Debug, Default)]
+pub struct KnownArchives {
+    sizes: HashMap<String, HashSet<u64>>,
+    /// Names of the modlists that were loaded.
+    pub modlists: Vec<String>,
+}
+
+impl KnownArchives {
+    pub fn add(&mut self, modlist_name: &str, archives: &[Archive]) {
+        for archive in archives {
+            self.sizes
+                .entry(archive.name.clone())
+                .or_default()
+                .insert(archive.size);
+        }
+        self.modlists.push(modlist_name.to_string());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sizes.is_empty()
+    }
+
+    /// Sizes known modlists expect for the archive `name`, if any uses it.
+    fn sizes(&self, name: &str) -> Option<&HashSet<u64>> {
+        self.sizes.get(name)
+    }
+}
+
+/// Load the archive lists of `wabbajack_files` and of every install
+/// recorded in `settings` whose .wabbajack is still on disk. Lists that
+/// can't be read are skipped with a warning.
+pub fn known_archives(wabbajack_files: &[PathBuf], settings: &Settings) -> KnownArchives {
+    let recorded = settings.installed_modlists.values().filter_map(|record| {
+        InstallManifest::load_from(Path::new(&record.install_dir))
+            .ok()
+            .flatten()?
+            .wabbajack_path
+    });
+    let mut seen = HashSet::new();
+    let mut known = KnownArchives::default();
+    for path in wabbajack_files.iter().cloned().chain(recorded) {
+        if !seen.insert(path.clone()) || !path.is_file() {
+            continue;
+        }
+        match parse_wabbajack_file(&path) {
+            Ok(modlist) => known.add(&modlist.name, &modlist.archives),
+            Err(e) => tracing::warn!("Skipping {}: {:#}", path.display(), e),
+        }
+    }
+    known
+}
+
+/// Leftovers in `downloads_dir` and extraction temp dirs in `temp_dirs`
+/// (install and temp directories). With `unreferenced`, archives no known
+/// modlist uses are included too. Missing directories are skipped.
+pub fn find_stale(
+    downloads_dir: &Path,
+    temp_dirs: &[&Path],
+    known: &KnownArchives,
+    unreferenced: bool,
+) -> std::io::Result<Vec<StaleEntry>> {
+    let mut stale = Vec::new();
+    // Archives being deleted, so their sidecars go along.
+    let mut deleted_archives = HashMap::new();
+    let mut sidecars = Vec::new();
+
+    for dir in std::iter::once(downloads_dir).chain(temp_dirs.iter().copied()) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            // tempfile creates dirs like .tmpXXXXXX
+            if name.starts_with(".tmp") && entry.file_type()?.is_dir() {
+                stale.push(StaleEntry {
+                    size: dir_size(&entry.path()),
+                    path: entry.path(),
+                    kind: StaleKind::TempDir,
+                });
+            }
+        }
+    }
+
+    for entry in fs::read_dir(downloads_dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with('.') || !entry.file_type()?.is_file() {
+            continue;
+        }
+        let size = entry.metadata()?.len();
+        if companion_of(&name).is_some() {
+            sidecars.push((entry.path(), name, size));
+            continue;
+        }
+        let kind = match known.sizes(&name) {
+            Some(sizes) if sizes.contains(&size) => continue,
+            Some(_) => StaleKind::WrongSize,
+            None if PARTIAL_SUFFIXES.iter().any(|s| name.ends_with(s)) => StaleKind::Partial,
+            None if unreferenced => StaleKind::Unreferenced,
+            None => continue,
+        };
+        deleted_archives.insert(name, kind);
+        stale.push(StaleEntry {
+            path: entry.path(),
+            kind,
+            size,
+        });
+    }
+
+    for (path, name, size) in sidecars {
+        let owner = companion_of(&name).unwrap_or(&name);
+        let kind = match deleted_archives.get(owner) {
+            Some(&kind) => kind,
+            // Sidecar of an unreferenced archive that's already gone.
+            None if unreferenced && known.sizes(owner).is_none() => StaleKind::Unreferenced,
+            None => continue,
+        };
+        stale.push(StaleEntry { path, kind, size });
+    }
+
+    stale.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(stale)
+}
+
+/// Delete `entries`, returning how many were removed and the bytes freed.
+/// Failures are logged and skipped.
+pub fn remove_stale(entries: &[StaleEntry]) -> (usize, u64) {
+    let mut removed = 0;
+    let mut freed = 0;
+    for entry in entries {
+        let result = if entry.kind == StaleKind::TempDir {
+            fs::remove_dir_all(&entry.path)
+        } else {
+            fs::remove_file(&entry.path)
+        };
+        match result {
+            Ok(()) => {
+                removed += 1;
+                freed += entry.size;
+            }
+            Err(e) => tracing::warn!("Failed to delete {}: {}", entry.path.display(), e),
+        }
+    }
+    (removed, freed)
+}
+
+/// Total size of the files under `dir`.
+fn dir_size(dir: &Path) -> u64 {
+    walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_partial_wrong_size_and_unreferenced_leftovers() {
+        let tmp = tempfile::tempdir().unwrap();
+        let downloads = tmp.path().join("downloads");
+        let install = tmp.path().join("install");
+        fs::create_dir_all(downloads.join(".tmpabc")).unwrap();
+        fs::create_dir_all(install.join(".tmpdef")).unwrap();
+        fs::write(install.join(".tmpdef/file"), b"12345").unwrap();
+        for (name, data) in [
+            ("good.7z", &b"1234"[..]),
+            ("good.7z.clf3hash", b"h"),
+            ("cut.7z", b"12"),
+            ("cut.7z.clf3hash", b"h"),
+            ("other.zip", b"123"),
+            ("other.zip.meta", b"m"),
+            ("manual.zip.part", b"1"),
+        ] {
+            fs::write(downloads.join(name), data).unwrap();
+        }
+        let archives: Vec<Archive> = serde_json::from_str(
+            r#"[
+                {"Hash": "a", "Meta": "", "Name": "good.7z", "Size": 4,
+                 "State": {"$type": "HttpDownloader, Wabbajack.Lib", "Url": "https://x/a"}},
+                {"Hash": "b", "Meta": "", "Name": "cut.7z", "Size": 4,
+                 "State": {"$type": "HttpDownloader, Wabbajack.Lib", "Url": "https://x/b"}}
+            ]"#,
+        )
+        .unwrap();
+        let mut known = KnownArchives::default();
+        known.add("Test", &archives);
+
+        let names = |stale: &[StaleEntry]| -> Vec<(String, StaleKind)> {
+            stale
+                .iter()
+                .map(|e| {
+                    let name = e.path.file_name().unwrap().to_string_lossy().into_owned();
+                    (name, e.kind)
+                })
+                .collect()
+        };
+
+        let stale = find_stale(&downloads, &[&install], &known, false).unwrap();
+        assert_eq!(
+            names(&stale),
+            vec![
+                (".tmpabc".to_string(), StaleKind::TempDir),
+                ("cut.7z".to_string(), StaleKind::WrongSize),
+                ("cut.7z.clf3hash".to_string(), StaleKind::WrongSize),
+                ("manual.zip.part".to_string(), StaleKind::Partial),
+                (".tmpdef".to_string(), StaleKind::TempDir),
+            ]
+        );
+        assert_eq!(stale.last().unwrap().size, 5);
+
+        let stale = find_stale(&downloads, &[&install], &known, true).unwrap();
+        assert!(names(&stale).contains(&("other.zip.meta".to_string(), StaleKind::Unreferenced)));
+        assert!(!names(&stale).iter().any(|(n, _)| n.starts_with("good")));
+
+        let (removed, freed) = remove_stale(&stale);
+        assert_eq!(removed, stale.len());
+        assert_eq!(freed, stale.iter().map(|e| e.size).sum::<u64>());
+        assert!(downloads.join("good.7z").exists());
+        assert!(!install.join(".tmpdef").exists());
+    }
+}
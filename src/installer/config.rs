@@ -2,10 +2,16 @@
 //!
 //! Defines the configuration structure for modlist installation.
 
+use super::archive_store::ArchiveStore;
+use super::dedupe::LinkMode;
+use super::mirrors::MirrorMap;
+use super::mod_filter::ModFilter;
 use super::progress::ProgressReporter;
+use super::purge::PurgeDownloads;
 use serde::Serialize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 /// How the install pipeline schedules download vs. extraction work.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -26,6 +32,101 @@ impl Default for ExtractStrategy {
     }
 }
 
+/// Order in which missing archives are downloaded (`--download-order`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DownloadOrder {
+    /// Modlist order (archives that feed BSAs first when streaming).
+    /// Reproducible between runs.
+    #[default]
+    Manifest,
+    /// Smallest first: many archives finish early, so extraction starts
+    /// sooner.
+    Smallest,
+    /// Largest first: the long downloads aren't left for the end.
+    Largest,
+}
+
+/// What happens to a downloaded archive whose hash doesn't match the
+/// modlist (`--on-hash-mismatch`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HashMismatchAction {
+    /// Delete it and download again (unless hash retries are off).
+    #[default]
+    Delete,
+    /// Move it to `.bad/` in the downloads dir next to a note with the
+    /// expected and actual hash, then download again.
+    Quarantine,
+    /// Leave it in place and fail the archive, for inspection. The next run
+    /// re-checks it and downloads it again.
+    Keep,
+}
+
+/// Last install phase to run (`--phase`). Earlier phases always run first,
+/// reusing whatever a previous run already finished.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum InstallPhase {
+    /// Download and verify archives only.
+    Downloads,
+    /// Also extract and patch archive contents into the install.
+    Extract,
+    /// Also write inline files, transform textures and build BSAs.
+    Directives,
+    /// Everything: cleanup, MO2 meta.ini files and the install manifest.
+    #[default]
+    Mo2,
+}
+
+/// When and how often failed downloads are retried (`--max-retries`,
+/// `--no-hash-retry`). Applies to every download source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt for network errors, truncated files
+    /// and (unless disabled) hash mismatches. 0 fails fast on everything,
+    /// including rate limits.
+    pub max_retries: u32,
+    /// Re-download an archive whose hash doesn't match. Off means a
+    /// mismatch fails the archive straight away.
+    pub retry_hash_mismatch: bool,
+}
+
+/// Retries after a 429 before giving up. Rate limits clear on their own,
+/// so these don't count against `max_retries`.
+const MAX_RATE_LIMIT_RETRIES: u32 = 10;
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            retry_hash_mismatch: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Total download attempts per archive.
+    pub fn attempts(&self) -> u32 {
+        self.max_retries + 1
+    }
+
+    pub fn rate_limit_retries(&self) -> u32 {
+        if self.max_retries == 0 {
+            0
+        } else {
+            MAX_RATE_LIMIT_RETRIES
+        }
+    }
+
+    /// Reconnects within one HTTP download. A resume keeps the bytes
+    /// already fetched, so at least the HTTP client's default is allowed.
+    pub fn resume_retries(&self) -> u32 {
+        if self.max_retries == 0 {
+            0
+        } else {
+            self.max_retries.max(crate::downloaders::MAX_RESUME_RETRIES)
+        }
+    }
+}
+
 /// Progress callback type for reporting download/installation progress
 pub type ProgressCallback = Arc<dyn Fn(ProgressEvent) + Send + Sync>;
 
@@ -39,8 +140,10 @@ pub enum ProgressEvent {
         name: String,
         downloaded: u64,
         total: u64,
-        /// Bytes per second
+        /// Bytes per second, averaged over the last few seconds
         speed: f64,
+        /// Seconds left at `speed`, if it's known
+        eta_secs: Option<u64>,
     },
     /// A download has completed
     DownloadComplete { name: String },
@@ -64,6 +167,17 @@ pub enum ProgressEvent {
     DirectiveComplete { index: usize, total: usize },
     /// Status message update
     Status { message: String },
+    /// Persistent log line (what the CLI prints above its progress bars)
+    Log { message: String },
+    /// Entries extracted so far from one archive, so a huge archive shows
+    /// movement before its files are installed
+    FileProgress {
+        name: String,
+        entries_done: usize,
+        entries_total: usize,
+        bytes_done: u64,
+        bytes_total: u64,
+    },
     /// Directive processing phase started (e.g., FromArchive, PatchedFromArchive)
     DirectivePhaseStarted {
         /// Type of directive being processed
@@ -71,6 +185,19 @@ pub enum ProgressEvent {
         /// Total directives of this type to process
         total: usize,
     },
+    /// The install run finished. `succeeded` is false when any archive
+    /// failed or needs a manual download, or any directive failed.
+    InstallComplete {
+        succeeded: bool,
+        archives_downloaded: usize,
+        archives_skipped: usize,
+        archives_manual: usize,
+        archives_failed: usize,
+        directives_completed: usize,
+        directives_failed: usize,
+    },
+    /// The install stopped on an error
+    InstallError { message: String },
 }
 
 /// Configuration for a modlist installation
@@ -88,6 +215,10 @@ pub struct InstallConfig {
     /// Game installation directory (for GameFileSource)
     pub game_dir: PathBuf,
 
+    /// Wine/Proton prefix MO2 will run in (`--wine-prefix`). Paths inside
+    /// its `drive_c` are written to configs as `C:` paths instead of `Z:`.
+    pub wine_prefix: Option<PathBuf>,
+
     /// Nexus API key (required for Nexus download links unless an OAuth token is provided)
     pub nexus_api_key: String,
 
@@ -136,6 +267,84 @@ pub struct InstallConfig {
     /// Recorded in the post-install manifest so `clf3 modlist update` can
     /// fall back to it when the gallery entry has moved.
     pub wabbajack_url: Option<String>,
+
+    /// Include/exclude patterns for a partial install. Empty = install
+    /// every mod (the only mode modlist authors support).
+    pub mod_filter: ModFilter,
+
+    /// Ignore `.clf3hash` sidecars and fully re-hash every existing archive.
+    /// Fresh sidecars are written for archives that still verify.
+    pub force_rehash: bool,
+
+    /// Hash existing outputs whose size already matches instead of trusting
+    /// the size alone. Slower, but catches silent corruption on re-runs.
+    pub verify_outputs: bool,
+
+    /// User-supplied fallback URLs by archive hash (`--mirrors`), tried after
+    /// an archive's primary source fails.
+    pub mirrors: MirrorMap,
+
+    /// Shared directory of archives named by hash (`--archive-store`),
+    /// checked before any archive is downloaded.
+    pub archive_store: Option<ArchiveStore>,
+
+    /// GPU adapter index for BC7 encoding (None = auto-select). The CLI
+    /// fills this from `--gpu` or the saved `select-gpu` choice.
+    pub gpu_index: Option<usize>,
+
+    /// Device for BC7 encoding (`--texture-encoder`, `select-gpu cpu|gpu`).
+    pub texture_encoder: crate::textures::EncoderMode,
+
+    /// Drop and resume a download whose connection averages under 10 KB/s
+    /// for this long (`--stall-timeout`).
+    pub stall_timeout: Duration,
+
+    /// Combined download speed cap in bytes/sec (`--max-download-speed`,
+    /// metered connections). `None` = unlimited.
+    pub max_download_speed: Option<u64>,
+
+    /// Download retry behaviour (`--max-retries`, `--no-hash-retry`).
+    pub retry: RetryPolicy,
+
+    /// Handling of downloads that fail their hash (`--on-hash-mismatch`).
+    pub on_hash_mismatch: HashMismatchAction,
+
+    /// Download scheduling (`--download-order`).
+    pub download_order: DownloadOrder,
+
+    /// Nexus download server (`--nexus-cdn`, `nexus_cdn` setting).
+    pub nexus_cdn: crate::downloaders::NexusCdn,
+
+    /// Downloads to delete after a clean install (`--purge-downloads`).
+    /// Default keeps everything so re-installs and updates reuse them.
+    pub purge_downloads: PurgeDownloads,
+
+    /// Install even if the output dir's `.clf3-install.json` names a
+    /// different modlist (`--force`).
+    pub force: bool,
+
+    /// Share storage between identical asset outputs after install
+    /// (`--link-mode`, experimental). See `dedupe` for the tradeoffs.
+    pub link_mode: LinkMode,
+
+    /// Stop after this phase (`--phase`). Default runs the whole install.
+    pub last_phase: InstallPhase,
+
+    /// MO2 profile to select after installing (`--profile`). Resolved
+    /// during init: None there keeps the author's `selected_profile`.
+    pub profile: Option<String>,
+
+    /// After a clean install, write which mods overwrite which loose files
+    /// (`--report-conflicts`).
+    pub report_conflicts: bool,
+
+    /// Never download (`--offline`): every needed archive must already be
+    /// in the downloads dir, and no Nexus credentials are required.
+    pub offline: bool,
+
+    /// Extra headers (login cookies, referer) for HTTP sources by host, from
+    /// the `http_headers` setting.
+    pub http_headers: crate::downloaders::HostHeaders,
 }
 
 impl std::fmt::Debug for InstallConfig {
@@ -145,6 +354,7 @@ impl std::fmt::Debug for InstallConfig {
             .field("output_dir", &self.output_dir)
             .field("downloads_dir", &self.downloads_dir)
             .field("game_dir", &self.game_dir)
+            .field("wine_prefix", &self.wine_prefix)
             .field("nexus_api_key", &"[REDACTED]")
             .field(
                 "nexus_oauth_token",
@@ -162,30 +372,75 @@ impl std::fmt::Debug for InstallConfig {
             .field("reporter", &"<reporter>")
             .field("loverslab_email", &self.loverslab_email)
             .field("loverslab_password", &"[REDACTED]")
+            .field("mod_filter", &self.mod_filter)
+            .field("force_rehash", &self.force_rehash)
+            .field("verify_outputs", &self.verify_outputs)
+            .field("mirrors", &self.mirrors.len())
+            .field(
+                "archive_store",
+                &self.archive_store.as_ref().map(ArchiveStore::root),
+            )
+            .field("gpu_index", &self.gpu_index)
+            .field("texture_encoder", &self.texture_encoder)
+            .field("stall_timeout", &self.stall_timeout)
+            .field("max_download_speed", &self.max_download_speed)
+            .field("retry", &self.retry)
+            .field("on_hash_mismatch", &self.on_hash_mismatch)
+            .field("download_order", &self.download_order)
+            .field("profile", &self.profile)
+            .field("report_conflicts", &self.report_conflicts)
+            .field("offline", &self.offline)
+            .field("http_headers", &self.http_headers)
+            .field("nexus_cdn", &self.nexus_cdn)
+            .field("purge_downloads", &self.purge_downloads)
+            .field("force", &self.force)
+            .field("link_mode", &self.link_mode)
+            .field("last_phase", &self.last_phase)
             .finish()
     }
 }
 
+/// State database of installs from `wabbajack_path`, named after the file.
+pub fn state_db_path(wabbajack_path: &Path) -> PathBuf {
+    let modlist_name = wabbajack_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown");
+
+    // Store in local cache directory to avoid CIFS/NFS locking issues
+    crate::paths::modlist_db(modlist_name)
+        .unwrap_or_else(|| PathBuf::from("/tmp/clf3").join(format!("{}.db", modlist_name)))
+}
+
 impl InstallConfig {
     /// Get the path to the state database
     /// Uses local cache directory (~/.cache/clf3/) to avoid network filesystem issues
     pub fn db_path(&self) -> PathBuf {
-        // Use modlist name for the database filename
-        let modlist_name = self
-            .wabbajack_path
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("unknown");
-
-        // Store in local cache directory to avoid CIFS/NFS locking issues
-        let cache_dir = dirs::cache_dir()
-            .unwrap_or_else(|| PathBuf::from("/tmp"))
-            .join("clf3");
+        let db_path = state_db_path(&self.wabbajack_path);
 
         // Create cache directory if it doesn't exist
-        let _ = std::fs::create_dir_all(&cache_dir);
+        if let Some(cache_dir) = db_path.parent() {
+            let _ = std::fs::create_dir_all(cache_dir);
+        }
+
+        db_path
+    }
 
-        cache_dir.join(format!("{}.db", modlist_name))
+    /// Delete the state database (`--fresh`), so the next run re-imports the
+    /// modlist instead of picking up where the last one stopped. Returns
+    /// whether there was one.
+    pub fn discard_saved_state(&self) -> std::io::Result<bool> {
+        let db_path = state_db_path(&self.wabbajack_path);
+        let existed = db_path.exists();
+        for suffix in ["", "-wal", "-shm"] {
+            let mut path = db_path.clone().into_os_string();
+            path.push(suffix);
+            match std::fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+        Ok(existed)
     }
 
     /// Validate the configuration
@@ -198,13 +453,23 @@ impl InstallConfig {
             return Err(ConfigError::GameDirNotFound(self.game_dir.clone()));
         }
 
+        if let Some(prefix) = &self.wine_prefix {
+            if !prefix.is_dir() {
+                return Err(ConfigError::WinePrefixNotFound(prefix.clone()));
+            }
+            // wineboot creates both; a dir without them was never booted.
+            if !prefix.join("drive_c").is_dir() || !prefix.join("system.reg").is_file() {
+                return Err(ConfigError::WinePrefixNotInitialized(prefix.clone()));
+            }
+        }
+
         let has_api_key = !self.nexus_api_key.trim().is_empty();
         let has_oauth_token = self
             .nexus_oauth_token
             .as_deref()
             .map(|token| !token.trim().is_empty())
             .unwrap_or(false);
-        if !has_api_key && !has_oauth_token {
+        if !has_api_key && !has_oauth_token && !self.offline {
             return Err(ConfigError::MissingNexusKey);
         }
         if self.max_concurrent_downloads == 0 {
@@ -241,9 +506,29 @@ pub enum ConfigError {
     #[error("Game directory not found: {0}")]
     GameDirNotFound(PathBuf),
 
+    #[error("Wine prefix not found: {0}")]
+    WinePrefixNotFound(PathBuf),
+
+    #[error(
+        "{0} is not an initialized Wine prefix (no drive_c or system.reg). \
+         Run the game or `wineboot` in it once first"
+    )]
+    WinePrefixNotInitialized(PathBuf),
+
     #[error("Nexus API key or OAuth token is required (premium account needed)")]
     MissingNexusKey,
 
     #[error("Invalid concurrency setting: {0}")]
     InvalidConcurrency(&'static str),
+
+    #[error(
+        "{} already contains a different modlist ({name} {version}); \
+         installing over it would mix the two. Choose another directory or pass --force",
+        .dir.display()
+    )]
+    InstallDirInUse {
+        dir: PathBuf,
+        name: String,
+        version: String,
+    },
 }
@@ -71,10 +71,7 @@ impl ConfigCache {
 
     /// Get the cache database path
     fn cache_path() -> Result<PathBuf> {
-        let config_dir = dirs::config_dir()
-            .context("Could not determine config directory")?
-            .join("clf3");
-        Ok(config_dir.join("modlist_configs.db"))
+        crate::paths::modlist_configs_db().context("Could not determine config directory")
     }
 
     /// Initialize database schema
@@ -160,6 +157,24 @@ impl ConfigCache {
         }
     }
 
+    /// Most recently saved config for a modlist, any version
+    pub fn get_latest_config(&self, name: &str) -> Result<Option<ModlistConfig>> {
+        let config_json: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT config_json FROM modlist_configs WHERE lower(modlist_name) = lower(?1)
+                 ORDER BY updated_at DESC LIMIT 1",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        match config_json {
+            Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+            None => Ok(None),
+        }
+    }
+
     /// Save config for a modlist
     pub fn save_config(
         &self,
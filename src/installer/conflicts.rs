@@ -0,0 +1,203 @@
+//! Loose-file conflicts between mods (`install --report-conflicts`).
+//!
+//! MO2 layers every enabled mod folder into one virtual Data directory. When
+//! several mods ship the same relative path, the one nearest the top of the
+//! profile's modlist.txt wins. This works that out from the directive output
+//! paths, so it only covers loose files: BSA contents are resolved by plugin
+//! load order instead.
+
+use super::mo2_meta::split_mod_path;
+use super::profiles::selected_profile;
+use crate::modlist::ModlistDb;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Report file written into the install dir.
+pub const REPORT_FILENAME: &str = "clf3-conflicts.txt";
+
+/// One file shipped by more than one enabled mod.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileConflict {
+    /// Path inside the mod folder, `/`-separated.
+    pub path: String,
+    /// Mod whose copy MO2 uses.
+    pub winner: String,
+    /// Mods whose copy is hidden, highest priority first.
+    pub overridden: Vec<String>,
+}
+
+/// Conflicts for one MO2 profile.
+#[derive(Debug, Clone, Default)]
+pub struct ConflictReport {
+    pub profile: String,
+    /// Sorted by path.
+    pub conflicts: Vec<FileConflict>,
+}
+
+impl ConflictReport {
+    /// Number of distinct mods involved in any conflict.
+    pub fn mods_involved(&self) -> usize {
+        let mut mods: Vec<&str> = self
+            .conflicts
+            .iter()
+            .flat_map(|c| std::iter::once(&c.winner).chain(&c.overridden))
+            .map(String::as_str)
+            .collect();
+        mods.sort_unstable();
+        mods.dedup();
+        mods.len()
+    }
+
+    /// Text report grouped by file.
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "Loose-file conflicts in profile '{}': {} files\n",
+            self.profile,
+            self.conflicts.len()
+        );
+        for conflict in &self.conflicts {
+            let _ = writeln!(out, "\n{}", conflict.path);
+            let _ = writeln!(out, "  wins:       {}", conflict.winner);
+            for loser in &conflict.overridden {
+                let _ = writeln!(out, "  overridden: {}", loser);
+            }
+        }
+        out
+    }
+}
+
+/// Enabled mods of an MO2 modlist.txt, highest priority first. Separators
+/// and disabled (`-`) or unmanaged (`*`) entries are left out.
+pub fn enabled_mods(modlist_txt: &str) -> Vec<&str> {
+    modlist_txt
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix('+'))
+        .filter(|name| !name.ends_with("_separator"))
+        .collect()
+}
+
+/// Files written by more than one mod in `priority` (highest first), from
+/// directive output paths. Mods not in `priority` are not active in MO2 and
+/// are ignored, as is each mod's own meta.ini.
+pub fn find_conflicts<'a>(
+    to_paths: impl IntoIterator<Item = &'a str>,
+    priority: &[&str],
+) -> Vec<FileConflict> {
+    let rank: HashMap<&str, usize> = priority.iter().enumerate().map(|(i, m)| (*m, i)).collect();
+
+    // lowercase path -> (display path, ranks of the mods shipping it)
+    let mut files: HashMap<String, (String, Vec<usize>)> = HashMap::new();
+    for to_path in to_paths {
+        let Some((mod_name, inner)) = split_mod_path(to_path) else {
+            continue;
+        };
+        let Some(&mod_rank) = rank.get(mod_name) else {
+            continue;
+        };
+        let inner = inner.replace('\\', "/");
+        if inner.eq_ignore_ascii_case("meta.ini") {
+            continue;
+        }
+        let entry = files
+            .entry(inner.to_lowercase())
+            .or_insert_with(|| (inner, Vec::new()));
+        if !entry.1.contains(&mod_rank) {
+            entry.1.push(mod_rank);
+        }
+    }
+
+    let mut conflicts: Vec<FileConflict> = files
+        .into_values()
+        .filter(|(_, ranks)| ranks.len() > 1)
+        .map(|(path, mut ranks)| {
+            ranks.sort_unstable();
+            let mut mods = ranks.into_iter().map(|r| priority[r].to_string());
+            FileConflict {
+                path,
+                winner: mods.next().expect("at least two mods"),
+                overridden: mods.collect(),
+            }
+        })
+        .collect();
+    conflicts.sort_by(|a, b| a.path.to_lowercase().cmp(&b.path.to_lowercase()));
+    conflicts
+}
+
+/// The profile MO2 opens with (`selected_profile` in ModOrganizer.ini), or
+/// the first one on disk.
+pub(crate) fn active_profile(output_dir: &Path) -> Result<String> {
+    let ini = std::fs::read_to_string(output_dir.join("ModOrganizer.ini")).unwrap_or_default();
+    if let Some(profile) = selected_profile(&ini) {
+        return Ok(profile);
+    }
+    let mut names: Vec<String> = std::fs::read_dir(output_dir.join("profiles"))
+        .context("No profiles directory in the install")?
+        .flatten()
+        .filter(|e| e.path().join("modlist.txt").is_file())
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+        .into_iter()
+        .next()
+        .context("No MO2 profile with a modlist.txt in the install")
+}
+
+/// Conflicts of a finished install, for the profile MO2 opens with.
+pub fn conflict_report(db: &ModlistDb, output_dir: &Path) -> Result<ConflictReport> {
+    let profile = active_profile(output_dir)?;
+    let modlist_path = output_dir
+        .join("profiles")
+        .join(&profile)
+        .join("modlist.txt");
+    let modlist_txt = std::fs::read_to_string(&modlist_path)
+        .with_context(|| format!("Failed to read {}", modlist_path.display()))?;
+
+    let directives = db.get_all_directives_summary()?;
+    let conflicts = find_conflicts(
+        directives.iter().map(|d| d.to_path.as_str()),
+        &enabled_mods(&modlist_txt),
+    );
+    Ok(ConflictReport { profile, conflicts })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_conflicts_uses_modlist_priority() {
+        let modlist = "# This file was automatically generated by Mod Organizer.\n\
+                       +Patch\n\
+                       +Textures_separator\n\
+                       +HD Textures\n\
+                       -Disabled\n\
+                       +Base\n";
+        let priority = enabled_mods(modlist);
+        assert_eq!(priority, ["Patch", "HD Textures", "Base"]);
+
+        let conflicts = find_conflicts(
+            [
+                "mods\\Base\\textures\\a.dds",
+                "mods\\HD Textures\\Textures\\A.dds",
+                "mods\\Patch\\textures\\a.dds",
+                "mods\\Disabled\\textures\\a.dds",
+                "mods\\Base\\meta.ini",
+                "mods\\Patch\\meta.ini",
+                "mods\\Base\\only.esp",
+                "profiles\\Default\\modlist.txt",
+            ],
+            &priority,
+        );
+        assert_eq!(
+            conflicts,
+            [FileConflict {
+                path: "textures/a.dds".to_string(),
+                winner: "Patch".to_string(),
+                overridden: vec!["HD Textures".to_string(), "Base".to_string()],
+            }]
+        );
+    }
+}
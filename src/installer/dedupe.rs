@@ -0,0 +1,127 @@
+//! Experimental output deduplication (`--link-mode`)
+//!
+//! Large modlists install the same texture or mesh into several mods. After
+//! the install, outputs with identical hashes can share storage:
+//!
+//! - `reflink`: copy-on-write clones (btrfs, XFS, bcachefs). Each file stays
+//!   independent, so editing one never touches the others. Unsupported
+//!   filesystems are detected on the first file and the pass stops.
+//!
+//! There is no hardlink mode: the installer rewrites outputs in place on
+//! updates and re-runs, which would change every mod's copy of a shared
+//! inode. Plugins, INIs and other small files tools rewrite aren't linked.
+
+use crate::modlist::ModlistDb;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+/// How identical install outputs are stored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LinkMode {
+    /// Every output is its own file (default).
+    #[default]
+    Copy,
+    /// Share data between identical outputs with copy-on-write clones.
+    Reflink,
+}
+
+/// Large asset types worth sharing. Textures are the bulk of the savings.
+const LINKABLE_EXTS: &[&str] = &[
+    "dds", "nif", "tri", "hkx", "bsa", "ba2", "wav", "xwm", "fuz", "lip", "png",
+];
+
+#[derive(Debug, Default)]
+pub struct LinkStats {
+    /// Outputs now sharing storage with another output.
+    pub linked: usize,
+    /// Bytes no longer stored twice.
+    pub bytes_saved: u64,
+}
+
+fn is_linkable(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| LINKABLE_EXTS.iter().any(|l| l.eq_ignore_ascii_case(ext)))
+}
+
+/// Replace `dup` with a link to `canonical`. The link is made beside `dup`
+/// and renamed over it, so a failure leaves `dup` untouched.
+fn link_one(mode: LinkMode, canonical: &Path, dup: &Path) -> std::io::Result<()> {
+    let mut tmp = dup.as_os_str().to_owned();
+    tmp.push(".clf3link");
+    let tmp = PathBuf::from(tmp);
+    let _ = fs::remove_file(&tmp);
+    match mode {
+        LinkMode::Reflink => reflink_copy::reflink(canonical, &tmp)?,
+        LinkMode::Copy => return Ok(()),
+    }
+    fs::rename(&tmp, dup).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })
+}
+
+/// Group installed outputs by hash and link duplicates to the first copy.
+pub fn link_duplicate_outputs(
+    db: &ModlistDb,
+    output_dir: &Path,
+    mode: LinkMode,
+) -> Result<LinkStats> {
+    let mut stats = LinkStats::default();
+    if mode == LinkMode::Copy {
+        return Ok(stats);
+    }
+
+    let mut groups: HashMap<(String, u64), Vec<PathBuf>> = HashMap::new();
+    for d in db.get_all_directives_summary()? {
+        if d.size > 0 && !d.hash.is_empty() && is_linkable(&d.to_path) {
+            groups
+                .entry((d.hash, d.size))
+                .or_default()
+                .push(crate::paths::join_windows_path(output_dir, &d.to_path));
+        }
+    }
+
+    for ((_, size), paths) in groups.into_iter().filter(|(_, p)| p.len() > 1) {
+        let mut existing = paths
+            .into_iter()
+            .filter(|p| fs::metadata(p).is_ok_and(|m| m.len() == size));
+        let Some(canonical) = existing.next() else {
+            continue;
+        };
+        for dup in existing {
+            match link_one(mode, &canonical, &dup) {
+                Ok(()) => {
+                    stats.linked += 1;
+                    stats.bytes_saved += size;
+                }
+                Err(e) if stats.linked == 0 => {
+                    warn!(
+                        "Reflinks not supported here ({}); skipping deduplication",
+                        e
+                    );
+                    return Ok(stats);
+                }
+                Err(e) => debug!("Could not link {}: {}", dup.display(), e),
+            }
+        }
+    }
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_read_only_assets_are_linkable() {
+        assert!(is_linkable("mods\\A\\textures\\rock.DDS"));
+        assert!(is_linkable("mods/A/meshes/rock.nif"));
+        assert!(!is_linkable("mods/A/Plugin.esp"));
+        assert!(!is_linkable("profiles/Default/Skyrim.ini"));
+    }
+}
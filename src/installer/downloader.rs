@@ -6,21 +6,27 @@
 //! are reported to the user with their download instructions.
 
 use crate::downloaders::{
-    download_file_with_callback, GoogleDriveDownloader, HttpClient, LoversLabDownloader,
-    MediaFireDownloader, NexusDownloader, ProgressCallback as HttpProgressCallback,
-    WabbajackCdnDownloader, YandexDownloader,
+    download_file_with_callback, download_file_with_headers, download_split_file,
+    parse_header_lines, split_first_part, AuthRequired, GoogleDriveDownloader, HttpClient,
+    LoversLabDownloader, MediaFireDownloader, NexusDownloader, NexusFileUnavailable,
+    ProgressCallback as HttpProgressCallback, SizeMismatch, WabbajackCdnDownloader,
+    YandexDownloader,
 };
-use crate::hash::{verify_file_hash, verify_file_hash_detailed};
-use crate::modlist::{ArchiveInfo, DownloadState, ModlistDb};
+use crate::error::InstallError;
+use crate::hash::{
+    hash_file_streaming, verify_file_hash, verify_file_hash_detailed, HashAlgorithm,
+};
+use crate::modlist::{ArchiveAlias, ArchiveInfo, DownloadState, ModlistDb};
 
-use super::config::{InstallConfig, ProgressEvent};
-use super::progress::{ProgressHandle, ProgressReporter};
+use super::config::{DownloadOrder, HashMismatchAction, InstallConfig, ProgressEvent};
+use super::progress::{eta_secs, ProgressHandle, ProgressReporter, SpeedWindow};
+use super::space_guard;
 
 use anyhow::{bail, Context, Result};
 use futures::stream::{self, StreamExt};
 use rayon::prelude::*;
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -84,19 +90,11 @@ fn resolve_game_file_source_archive(
         return None;
     };
 
-    let game_file = &state.game_file;
-    crate::paths::resolve_case_insensitive(&config.game_dir, game_file).or_else(|| {
-        let data_path = format!("Data/{}", game_file);
-        crate::paths::resolve_case_insensitive(&config.game_dir, &data_path)
-    })
+    crate::game_finder::resolve_game_file(&config.game_dir, &state.game_file)
 }
 
-/// Max retries for network operations
-const MAX_RETRIES: u32 = 3;
 /// Delay between retries
 const RETRY_DELAY: Duration = Duration::from_secs(2);
-/// Max retries for rate limit errors (429)
-const MAX_RATE_LIMIT_RETRIES: u32 = 10;
 /// Initial delay for rate limit retry (increases exponentially)
 const RATE_LIMIT_BASE_DELAY: Duration = Duration::from_secs(30);
 
@@ -157,6 +155,10 @@ pub struct ManualDownloadInfo {
     pub url: String,
     pub prompt: Option<String>,
     pub expected_size: u64,
+    /// The source reports the file as deleted or archived (dead Nexus
+    /// link), so the exact version may not be obtainable at all.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub source_removed: bool,
 }
 
 /// Information about a failed download
@@ -166,6 +168,9 @@ pub struct FailedDownloadInfo {
     pub url: String,
     pub error: String,
     pub expected_size: u64,
+    /// Running the install again may fetch it (network error, corrupt
+    /// download), as opposed to a removed source or rejected login.
+    pub retryable: bool,
 }
 
 /// Shared state for download coordination
@@ -182,6 +187,9 @@ struct DownloadContext {
     ll_semaphore: tokio::sync::Semaphore,
     config: InstallConfig,
     reporter: Arc<dyn ProgressReporter>,
+    /// Further modlist entries per archive hash (same file, other name or
+    /// source).
+    aliases: HashMap<String, Vec<ArchiveAlias>>,
     // Counters
     downloaded: AtomicUsize,
     skipped: AtomicUsize,
@@ -200,35 +208,19 @@ impl DownloadContext {
     }
 }
 
-/// Helper: build a DownloadContext from config and pending count.
-async fn build_context(config: &InstallConfig, total_archives: usize) -> Result<DownloadContext> {
-    let loverslab = init_loverslab(config).await;
-    Ok(DownloadContext {
-        nexus: NexusDownloader::from_config(
-            &config.nexus_api_key,
-            config.nexus_oauth_token.as_deref(),
-        )?,
-        http: HttpClient::new()?,
-        cdn: WabbajackCdnDownloader::new()?,
-        gdrive: GoogleDriveDownloader::new()?,
-        mediafire: MediaFireDownloader::new()?,
-        yandex: YandexDownloader::new()?,
-        loverslab,
-        ll_semaphore: tokio::sync::Semaphore::new(1),
-        config: config.clone(),
-        reporter: config.reporter.clone(),
-        downloaded: AtomicUsize::new(0),
-        skipped: AtomicUsize::new(0),
-        failed: AtomicUsize::new(0),
-        manual_downloads: Mutex::new(Vec::new()),
-        failed_downloads: Mutex::new(Vec::new()),
-        completed_archives: AtomicUsize::new(0),
-        total_archives,
-    })
+/// Archives the install still needs, from one scan of the output dir.
+#[derive(Debug, Default)]
+pub struct NeededArchives {
+    /// Hashes of the archives feeding outputs that are missing or the wrong
+    /// size.
+    pub hashes: HashSet<String>,
+    /// Number of such outputs.
+    pub missing_outputs: usize,
 }
 
-/// Download all pending archives (smart mode: only downloads archives needed for missing outputs)
-pub async fn download_archives(db: &ModlistDb, config: &InstallConfig) -> Result<DownloadStats> {
+/// Scan the output dir for outputs that are missing or the wrong size and
+/// collect the archives they come from.
+pub fn needed_archive_hashes(db: &ModlistDb, config: &InstallConfig) -> Result<NeededArchives> {
     let reporter = &config.reporter;
 
     // Smart check: scan output directory to find what's missing
@@ -245,7 +237,7 @@ pub async fn download_archives(db: &ModlistDb, config: &InstallConfig) -> Result
 
     // Get all directives that need archives and check which outputs are missing
     let directive_outputs = db.get_directive_outputs_with_archives()?;
-    let mut needed_archives: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut needed_archives = HashSet::new();
     let mut missing_count = 0;
 
     for (to_path, size, archive_hash) in &directive_outputs {
@@ -265,6 +257,150 @@ pub async fn download_archives(db: &ModlistDb, config: &InstallConfig) -> Result
             missing_count += 1;
         }
     }
+    Ok(NeededArchives {
+        hashes: needed_archives,
+        missing_outputs: missing_count,
+    })
+}
+
+/// Names of `needed` archives that are neither game files nor in the
+/// downloads dir at the expected size (`--offline` pre-check). Hashes are
+/// verified later as usual.
+pub fn missing_local_archives(
+    db: &ModlistDb,
+    config: &InstallConfig,
+    needed: &NeededArchives,
+) -> Result<Vec<String>> {
+    if needed.hashes.is_empty() {
+        return Ok(Vec::new());
+    }
+    let needed: Vec<String> = needed.hashes.iter().cloned().collect();
+    let mut missing: Vec<String> = db
+        .get_archives_by_hashes(&needed)?
+        .into_iter()
+        .filter(|a| resolve_game_file_source_archive(config, a).is_none())
+        .filter(|a| {
+            !super::status::archive_present(&config.downloads_dir, &a.name, a.size.max(0) as u64)
+        })
+        .map(|a| a.name)
+        .collect();
+    missing.sort();
+    Ok(missing)
+}
+
+/// `--offline`: stop instead of downloading. Only reached when a local
+/// archive failed verification after the pre-check passed.
+fn refuse_offline_downloads(config: &InstallConfig, need_download: &[ArchiveInfo]) -> Result<()> {
+    if !config.offline || need_download.is_empty() {
+        return Ok(());
+    }
+    for archive in need_download {
+        config
+            .reporter
+            .log(&format!("  Not available offline: {}", archive.name));
+    }
+    bail!(
+        "Offline mode: {} archives would have to be downloaded again",
+        need_download.len()
+    );
+}
+
+/// Archives at least this big get their own progress bar while verifying.
+const VERIFY_PROGRESS_MIN_SIZE: u64 = 512 * 1024 * 1024;
+
+/// Hash an archive already in the downloads dir. Returns (matches, actual
+/// hash).
+fn verify_existing_archive(
+    reporter: &dyn ProgressReporter,
+    path: &Path,
+    archive: &ArchiveInfo,
+) -> Result<(bool, String)> {
+    let size = archive.size.max(0) as u64;
+    let handle = (size >= VERIFY_PROGRESS_MIN_SIZE).then(|| {
+        reporter.begin_item(
+            &format!("Verify {}", truncate_name(&archive.name, 40)),
+            Some(size),
+        )
+    });
+    let speed = std::sync::Mutex::new(SpeedWindow::default());
+    let progress = |done: u64, total: u64| {
+        if let Some(handle) = &handle {
+            let speed = speed
+                .lock()
+                .expect("speed lock")
+                .record(done, std::time::Instant::now());
+            handle.set_bytes(done, total, speed);
+        }
+    };
+    let actual = hash_file_streaming(path, HashAlgorithm::XxHash64, Some(&progress));
+    if let Some(handle) = &handle {
+        handle.finish();
+    }
+    let actual = actual?;
+    Ok((actual == archive.hash, actual))
+}
+
+/// Reorder pending downloads for `--download-order`. Stable, so equal sizes
+/// keep modlist order.
+fn sort_by_download_order(pending: &mut [ArchiveInfo], order: DownloadOrder) {
+    match order {
+        DownloadOrder::Manifest => {}
+        DownloadOrder::Smallest => pending.sort_by_key(|a| a.size),
+        DownloadOrder::Largest => pending.sort_by_key(|a| std::cmp::Reverse(a.size)),
+    }
+}
+
+/// Helper: build a DownloadContext from config and pending count.
+async fn build_context(
+    db: &ModlistDb,
+    config: &InstallConfig,
+    total_archives: usize,
+) -> Result<DownloadContext> {
+    let loverslab = init_loverslab(config).await;
+    Ok(DownloadContext {
+        nexus: NexusDownloader::from_config(
+            &config.nexus_api_key,
+            config.nexus_oauth_token.as_deref(),
+        )?
+        .with_cdn(config.nexus_cdn.clone()),
+        http: HttpClient::new()?
+            .with_stall_timeout(config.stall_timeout)
+            .with_max_retries(config.retry.resume_retries())
+            .with_rate_limit(config.max_download_speed),
+        cdn: WabbajackCdnDownloader::new()?.with_max_retries(config.retry.max_retries),
+        gdrive: GoogleDriveDownloader::new()?,
+        mediafire: MediaFireDownloader::new()?,
+        yandex: YandexDownloader::new()?,
+        loverslab,
+        ll_semaphore: tokio::sync::Semaphore::new(1),
+        config: config.clone(),
+        reporter: config.reporter.clone(),
+        aliases: db.get_archive_aliases()?,
+        downloaded: AtomicUsize::new(0),
+        skipped: AtomicUsize::new(0),
+        failed: AtomicUsize::new(0),
+        manual_downloads: Mutex::new(Vec::new()),
+        failed_downloads: Mutex::new(Vec::new()),
+        completed_archives: AtomicUsize::new(0),
+        total_archives,
+    })
+}
+
+/// Download all pending archives (smart mode: only downloads archives needed for missing outputs)
+///
+/// `needed` reuses an earlier scan of the output dir; None scans it here.
+pub async fn download_archives(
+    db: &ModlistDb,
+    config: &InstallConfig,
+    needed: Option<NeededArchives>,
+) -> Result<DownloadStats> {
+    let reporter = &config.reporter;
+
+    let needed = match needed {
+        Some(needed) => needed,
+        None => needed_archive_hashes(db, config)?,
+    };
+    let (needed_archives, missing_count) = (needed.hashes, needed.missing_outputs);
 
     if needed_archives.is_empty() {
         reporter.log("All output files exist - no downloads needed!");
@@ -312,7 +448,9 @@ pub async fn download_archives(db: &ModlistDb, config: &InstallConfig) -> Result
                     );
                 }
                 // Fast path: sidecar cache says hash+size+mtime match — skip re-hashing
-                if super::sidecar::archive_hash_valid(&output_path, &archive.hash) {
+                if !config.force_rehash
+                    && super::sidecar::archive_hash_valid(&output_path, &archive.hash)
+                {
                     db.mark_archive_downloaded(
                         &archive.hash,
                         output_path.to_string_lossy().as_ref(),
@@ -385,7 +523,7 @@ Copied files with different names will not be reused. Examples: {}",
                     .unwrap_or_default()
                     .to_string_lossy();
                 reporter.overall_set_message(&format!("Verifying {}...", truncate_name(&name, 40)));
-                let result = verify_file_hash_detailed(output_path, &archive.hash);
+                let result = verify_existing_archive(reporter.as_ref(), output_path, archive);
                 reporter.overall_inc();
                 let done = verify_counter.fetch_add(1, Ordering::Relaxed) + 1;
                 verify_status.set_count(done, verify_total);
@@ -509,6 +647,10 @@ Copied files with different names will not be reused. Examples: {}",
         });
     }
 
+    refuse_offline_downloads(config, &need_download)?;
+
+    sort_by_download_order(&mut need_download, config.download_order);
+
     reporter.log(&format!(
         "Need to download {} archives",
         need_download.len()
@@ -523,7 +665,7 @@ Copied files with different names will not be reused. Examples: {}",
 
     // Create shared context
     let total_archives = pending.len();
-    let ctx = Arc::new(build_context(config, total_archives).await?);
+    let ctx = Arc::new(build_context(db, config, total_archives).await?);
 
     // Collect hashes and paths for DB updates (can't borrow db across await)
     let results: Vec<DownloadResultTuple> = stream::iter(pending)
@@ -710,41 +852,15 @@ pub async fn download_archives_streaming(
     config: &InstallConfig,
     tx: &std::sync::mpsc::SyncSender<ArchiveEvent>,
     priority: Option<&HashMap<String, u32>>,
+    needed: Option<NeededArchives>,
 ) -> Result<DownloadStats> {
     let reporter = &config.reporter;
 
-    // Smart check: scan output directory to find what's missing
-    reporter.log("Scanning output directory for existing files...");
-    let existing_outputs = scan_existing_outputs(&config.output_dir)?;
-    reporter.log(&format!(
-        "Found {} existing output files",
-        existing_outputs.len()
-    ));
-
-    // Build set of BSA temp_ids whose output is already valid (sidecar matches)
-    let valid_bsa_temp_ids = build_valid_bsa_set(db, config);
-
-    // Get all directives that need archives and check which outputs are missing
-    let directive_outputs = db.get_directive_outputs_with_archives()?;
-    let mut needed_archives: std::collections::HashSet<String> = std::collections::HashSet::new();
-    let mut missing_count = 0;
-
-    for (to_path, size, archive_hash) in &directive_outputs {
-        if is_bsa_staging_path(to_path, &valid_bsa_temp_ids) {
-            continue;
-        }
-
-        let normalized = crate::paths::normalize_for_lookup(to_path);
-        let output_exists = existing_outputs
-            .get(&normalized)
-            .map(|&existing_size| existing_size == *size as u64)
-            .unwrap_or(false);
-
-        if !output_exists {
-            needed_archives.insert(archive_hash.clone());
-            missing_count += 1;
-        }
-    }
+    let needed = match needed {
+        Some(needed) => needed,
+        None => needed_archive_hashes(db, config)?,
+    };
+    let (needed_archives, missing_count) = (needed.hashes, needed.missing_outputs);
 
     if needed_archives.is_empty() {
         reporter.log("All output files exist - no downloads needed!");
@@ -784,7 +900,9 @@ pub async fn download_archives_streaming(
         let output_path = config.downloads_dir.join(&archive.name);
         if output_path.exists() && fs::metadata(&output_path).is_ok() {
             // Fast path: sidecar cache says hash+size+mtime match
-            if super::sidecar::archive_hash_valid(&output_path, &archive.hash) {
+            if !config.force_rehash
+                && super::sidecar::archive_hash_valid(&output_path, &archive.hash)
+            {
                 db.mark_archive_downloaded(&archive.hash, output_path.to_string_lossy().as_ref())?;
                 already_downloaded += 1;
                 already_downloaded_size += archive.size as u64;
@@ -848,7 +966,7 @@ pub async fn download_archives_streaming(
                     .unwrap_or_default()
                     .to_string_lossy();
                 reporter.overall_set_message(&format!("Verifying {}...", truncate_name(&name, 40)));
-                let result = verify_file_hash_detailed(output_path, &archive.hash);
+                let result = verify_existing_archive(reporter.as_ref(), output_path, archive);
                 reporter.overall_inc();
                 let done = verify_counter.fetch_add(1, Ordering::Relaxed) + 1;
                 verify_status.set_count(done, verify_total);
@@ -946,13 +1064,19 @@ pub async fn download_archives_streaming(
         });
     }
 
-    // Sort downloads by priority (highest first) — BSA-feeding archives first
-    if let Some(prio) = priority {
-        need_download.sort_by(|a, b| {
-            let pa = prio.get(&a.hash).copied().unwrap_or(0);
-            let pb = prio.get(&b.hash).copied().unwrap_or(0);
-            pb.cmp(&pa)
-        });
+    refuse_offline_downloads(config, &need_download)?;
+
+    // Sort downloads by priority (highest first) — BSA-feeding archives first,
+    // unless the user picked a size order.
+    match (config.download_order, priority) {
+        (DownloadOrder::Manifest, Some(prio)) => {
+            need_download.sort_by(|a, b| {
+                let pa = prio.get(&a.hash).copied().unwrap_or(0);
+                let pb = prio.get(&b.hash).copied().unwrap_or(0);
+                pb.cmp(&pa)
+            });
+        }
+        (order, _) => sort_by_download_order(&mut need_download, order),
     }
 
     reporter.log(&format!(
@@ -968,7 +1092,7 @@ pub async fn download_archives_streaming(
     reporter.overall_set_message("Starting downloads...");
 
     let total_archives = pending.len();
-    let ctx = Arc::new(build_context(config, total_archives).await?);
+    let ctx = Arc::new(build_context(db, config, total_archives).await?);
 
     // Stream downloads, emitting events as each completes
     stream::iter(pending)
@@ -1105,11 +1229,28 @@ async fn process_archive(
                 ctx.reporter.overall_inc();
                 update_overall_message(ctx);
                 report_archive_complete(ctx, &archive.name);
+                link_alias_names(ctx, archive, output_path);
                 return (DownloadResult::Skipped, None);
             }
         }
     }
 
+    // A copy in the shared archive store beats any download source
+    if let Some(store) = &ctx.config.archive_store {
+        if store.place(&archive.hash, archive.size as u64, output_path) {
+            ctx.reporter.log(&format!(
+                "Using {} from the archive store",
+                truncate_name(&archive.name, 40)
+            ));
+            ctx.downloaded.fetch_add(1, Ordering::Relaxed);
+            ctx.reporter.overall_inc();
+            update_overall_message(ctx);
+            report_archive_complete(ctx, &archive.name);
+            link_alias_names(ctx, archive, output_path);
+            return (DownloadResult::Success, None);
+        }
+    }
+
     // Parse the download state
     let state: DownloadState = match serde_json::from_str(&archive.state_json) {
         Ok(s) => s,
@@ -1136,10 +1277,23 @@ async fn process_archive(
 
     // Download based on source type
     let source = source_type_name(&state);
-    let result = download_archive(&state, archive, output_path, ctx, &handle).await;
+    let mut result = download_archive(&state, archive, output_path, ctx, &handle).await;
+    // A mismatching file kept for inspection must not be replaced by an alias
+    let kept = ctx.config.on_hash_mismatch == HashMismatchAction::Keep
+        && matches!(&result, Err(e) if matches!(
+            e.downcast_ref::<InstallError>(),
+            Some(InstallError::HashMismatch(_))
+        ));
+    if result.is_err() && !kept {
+        if let Some(alias_result) = download_from_aliases(archive, output_path, ctx, &handle).await
+        {
+            result = Ok(alias_result);
+        }
+    }
 
     match result {
         Ok(url_to_cache) => {
+            link_alias_names(ctx, archive, output_path);
             handle.finish();
             ctx.downloaded.fetch_add(1, Ordering::Relaxed);
             ctx.reporter.overall_inc();
@@ -1147,6 +1301,71 @@ async fn process_archive(
             report_archive_complete(ctx, &archive.name);
             (DownloadResult::Success, url_to_cache)
         }
+        Err(e) if nexus_file_unavailable(&e).is_some() => {
+            // Not a failure on our side: the author removed or archived the
+            // file. Hand it to the user with the mod page instead.
+            let gone = nexus_file_unavailable(&e).expect("checked by guard");
+            handle.finish_with_error(&format!(
+                "GONE [Nexus] {} - {}",
+                truncate_name(&archive.name, 30),
+                gone.reason
+            ));
+            ctx.reporter.log(&format!(
+                "GONE [Nexus] {} - {}; needs manual download",
+                truncate_name(&archive.name, 30),
+                gone.reason
+            ));
+            ctx.manual_downloads.lock().await.push(ManualDownloadInfo {
+                name: archive.name.clone(),
+                url: NexusDownloader::get_mod_page_url(
+                    &gone.game_domain,
+                    gone.mod_id,
+                    gone.file_id,
+                ),
+                prompt: Some(format!(
+                    "Removed from Nexus ({}). This exact file version may no longer be \
+                     available; check the mod's archived files or ask the modlist author \
+                     for an alternative.",
+                    gone.reason
+                )),
+                expected_size: archive.size as u64,
+                source_removed: true,
+            });
+            ctx.reporter.overall_inc();
+            update_overall_message(ctx);
+            report_archive_complete(ctx, &archive.name);
+            (DownloadResult::Manual, None)
+        }
+        Err(e) if http_login_required(&e, &state, &ctx.config).is_some() => {
+            let auth = http_login_required(&e, &state, &ctx.config).expect("checked by guard");
+            handle.finish_with_error(&format!(
+                "LOGIN [HTTP] {} - {} needs a login",
+                truncate_name(&archive.name, 30),
+                auth.host
+            ));
+            ctx.reporter.log(&format!(
+                "LOGIN [HTTP] {} - HTTP {} from {}; needs manual download",
+                truncate_name(&archive.name, 30),
+                auth.status,
+                auth.host
+            ));
+            ctx.manual_downloads.lock().await.push(ManualDownloadInfo {
+                name: archive.name.clone(),
+                url: get_manual_url(&state),
+                prompt: Some(format!(
+                    "{} requires a login (HTTP {}). Download it in a browser where you're \
+                     logged in, or add your session cookie for {} under \"http_headers\" \
+                     in settings.json and run the install again.",
+                    auth.host, auth.status, auth.host
+                )),
+                expected_size: archive.size as u64,
+                source_removed: false,
+            });
+            ctx.reporter.overall_inc();
+            update_overall_message(ctx);
+            report_archive_complete(ctx, &archive.name);
+            (DownloadResult::Manual, None)
+        }
         Err(e) => {
             ctx.failed.fetch_add(1, Ordering::Relaxed);
             ctx.reporter.overall_inc();
@@ -1170,6 +1389,7 @@ async fn process_archive(
                 url: get_manual_url(&state),
                 error: error_msg,
                 expected_size: archive.size as u64,
+                retryable: InstallError::from(e).is_retryable(),
             });
             report_archive_complete(ctx, &archive.name);
             (DownloadResult::Failed, None)
@@ -1177,6 +1397,73 @@ async fn process_archive(
     }
 }
 
+/// Try the sources of the archive's aliases after its own source failed.
+/// The cached URL belongs to the alias source, so none is returned.
+async fn download_from_aliases(
+    archive: &ArchiveInfo,
+    output_path: &Path,
+    ctx: &DownloadContext,
+    handle: &Arc<dyn ProgressHandle>,
+) -> Option<Option<(String, i64)>> {
+    let aliases = ctx.aliases.get(&archive.hash)?;
+    for alias in aliases {
+        if alias.state_json == archive.state_json {
+            continue;
+        }
+        let Ok(state) = serde_json::from_str::<DownloadState>(&alias.state_json) else {
+            continue;
+        };
+        if check_manual(&state, archive, ctx.loverslab.is_some()).is_some() {
+            continue;
+        }
+        info!(
+            "Trying the source of duplicate entry {} for {}",
+            alias.name, archive.name
+        );
+        let _ = std::fs::remove_file(output_path);
+        match download_archive(&state, archive, output_path, ctx, handle).await {
+            Ok(_) => {
+                ctx.reporter.log(&format!(
+                    "Downloaded {} via the source of {}",
+                    truncate_name(&archive.name, 35),
+                    truncate_name(&alias.name, 35)
+                ));
+                return Some(None);
+            }
+            Err(e) => warn!(
+                "Source of {} failed for {}: {:#}",
+                alias.name, archive.name, e
+            ),
+        }
+    }
+    None
+}
+
+/// Make the downloaded archive also available under the names of its
+/// aliases: hardlinked where possible, copied otherwise.
+fn link_alias_names(ctx: &DownloadContext, archive: &ArchiveInfo, output_path: &Path) {
+    let Some(aliases) = ctx.aliases.get(&archive.hash) else {
+        return;
+    };
+    for alias in aliases {
+        let alias_path = ctx.config.downloads_dir.join(&alias.name);
+        if alias_path == output_path || alias_path.exists() {
+            continue;
+        }
+        let linked = fs::hard_link(output_path, &alias_path)
+            .or_else(|_| fs::copy(output_path, &alias_path).map(|_| ()));
+        match linked {
+            Ok(()) => debug!("Linked {} as {}", archive.name, alias.name),
+            Err(e) => warn!(
+                "Failed to place {} as {}: {}",
+                archive.name,
+                alias_path.display(),
+                e
+            ),
+        }
+    }
+}
+
 /// Update the overall progress bar message with current stats
 fn update_overall_message(ctx: &DownloadContext) {
     let downloaded = ctx.downloaded.load(Ordering::Relaxed);
@@ -1239,6 +1526,7 @@ fn check_manual(
                 url: manual_state.url.clone(),
                 prompt: Some(manual_state.prompt.clone()),
                 expected_size: archive.size as u64,
+                source_removed: false,
             })
         }
         // Mega is now handled via Wabbajack proxy, not manual
@@ -1268,7 +1556,7 @@ fn is_loverslab_url(url: &str) -> bool {
     crate::downloaders::loverslab::is_loverslab_url(url)
 }
 
-fn is_mediafire_url(url: &str) -> bool {
+pub(crate) fn is_mediafire_url(url: &str) -> bool {
     let lower = url.to_lowercase();
     lower.contains("mediafire.com/")
 }
@@ -1278,11 +1566,11 @@ fn is_mega_url(url: &str) -> bool {
     lower.contains("mega.nz/") || lower.contains("mega.co.nz/")
 }
 
-fn is_yandex_url(url: &str) -> bool {
+pub(crate) fn is_yandex_url(url: &str) -> bool {
     crate::downloaders::yandex::is_yandex_url(url)
 }
 
-fn is_moddb_url(url: &str) -> bool {
+pub(crate) fn is_moddb_url(url: &str) -> bool {
     url.contains("moddb.com")
 }
 
@@ -1358,7 +1646,10 @@ fn root_cause_text(text: &str) -> String {
     }
 }
 
-async fn resolve_moddb_download_url(client: &HttpClient, source_url: &str) -> Result<String> {
+pub(crate) async fn resolve_moddb_download_url(
+    client: &HttpClient,
+    source_url: &str,
+) -> Result<String> {
     let source_url = moddb_abs_url(source_url);
     let start_url = if is_moddb_start_url(&source_url) {
         source_url.clone()
@@ -1424,13 +1715,11 @@ fn source_type_name(state: &DownloadState) -> &'static str {
 /// Get a user-friendly URL for manual download from the state
 fn get_manual_url(state: &DownloadState) -> String {
     match state {
-        DownloadState::Nexus(s) => {
-            let domain = crate::downloaders::NexusDownloader::game_domain(&s.game_name);
-            format!(
-                "https://www.nexusmods.com/{}/mods/{}?tab=files",
-                domain, s.mod_id
-            )
-        }
+        DownloadState::Nexus(s) => NexusDownloader::get_mod_page_url(
+            NexusDownloader::game_domain(&s.game_name),
+            s.mod_id,
+            s.file_id,
+        ),
         DownloadState::Http(s) => s.url.clone(),
         DownloadState::WabbajackCDN(s) => s.url.clone(),
         DownloadState::GoogleDrive(s) => format!("https://drive.google.com/file/d/{}/view", s.id),
@@ -1441,6 +1730,34 @@ fn get_manual_url(state: &DownloadState) -> String {
     }
 }
 
+/// The dead-Nexus-file error anywhere in `e`'s context chain, if any.
+fn nexus_file_unavailable(e: &anyhow::Error) -> Option<&NexusFileUnavailable> {
+    e.chain()
+        .find_map(|cause| cause.downcast_ref::<NexusFileUnavailable>())
+}
+
+/// A plain HTTP source answered 401/403 and the user has no headers set up
+/// for its host, so only a logged-in browser can fetch it.
+fn http_login_required<'a>(
+    e: &'a anyhow::Error,
+    state: &DownloadState,
+    config: &InstallConfig,
+) -> Option<&'a AuthRequired> {
+    let DownloadState::Http(http_state) = state else {
+        return None;
+    };
+    if !config.http_headers.for_url(&http_state.url).is_empty() {
+        return None;
+    }
+    e.chain()
+        .find_map(|cause| cause.downcast_ref::<AuthRequired>())
+}
+
+/// Whether the server sent (or announced) a different size than expected.
+fn is_size_mismatch(e: &anyhow::Error) -> bool {
+    e.chain().any(|cause| cause.is::<SizeMismatch>())
+}
+
 /// Extract the root cause error message (skip context chain)
 fn root_cause(e: &anyhow::Error) -> String {
     // Get the deepest error in the chain
@@ -1528,6 +1845,102 @@ async fn download_from_mirror(
     Ok(())
 }
 
+/// Try each alternate URL for an archive (modlist `Mirrors`, then the
+/// `--mirrors` file) until one downloads with the expected hash.
+async fn download_from_alternate_urls(
+    state: &DownloadState,
+    archive: &ArchiveInfo,
+    output_path: &Path,
+    ctx: &DownloadContext,
+    handle: &Arc<dyn ProgressHandle>,
+) -> bool {
+    let urls = ctx.config.mirrors.urls_for(state, &archive.hash);
+    let expected_size = archive.size as u64;
+
+    for (index, url) in urls.iter().enumerate() {
+        handle.set_bytes(0, expected_size, 0.0);
+        handle.set_message(&format!(
+            "{} (mirror {}/{})",
+            truncate_name(&archive.name, 30),
+            index + 1,
+            urls.len()
+        ));
+        let _ = std::fs::remove_file(output_path);
+        info!("Trying mirror for {}: {}", archive.name, url);
+
+        let progress_callback =
+            make_progress_callback(archive.name.clone(), &ctx.config.progress_callback, handle);
+        if let Err(e) = download_file_with_callback(
+            &ctx.http,
+            url,
+            output_path,
+            Some(expected_size),
+            progress_callback.as_ref(),
+        )
+        .await
+        {
+            warn!("Mirror {} failed for {}: {:#}", url, archive.name, e);
+            continue;
+        }
+
+        match verify_file_hash(output_path, &archive.hash) {
+            Ok(true) => {
+                ctx.reporter.log(&format!(
+                    "Downloaded {} from mirror {}",
+                    truncate_name(&archive.name, 35),
+                    url
+                ));
+                return true;
+            }
+            Ok(false) => warn!("Mirror {} served wrong data for {}", url, archive.name),
+            Err(e) => warn!(
+                "Failed to verify mirror download of {}: {}",
+                archive.name, e
+            ),
+        }
+    }
+
+    if !urls.is_empty() {
+        let _ = std::fs::remove_file(output_path);
+    }
+    false
+}
+
+/// Folder in the downloads dir for archives that failed their hash
+/// (`--on-hash-mismatch quarantine`).
+pub const QUARANTINE_DIR: &str = ".bad";
+
+/// Move an archive that failed its hash into [`QUARANTINE_DIR`], next to a
+/// `<name>.txt` note of where it came from and what was expected. A later
+/// failure of the same archive replaces both.
+fn quarantine_archive(
+    downloads_dir: &Path,
+    archive: &ArchiveInfo,
+    path: &Path,
+    actual_hash: &str,
+    source_url: &str,
+) -> Result<PathBuf> {
+    let dir = downloads_dir.join(QUARANTINE_DIR);
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let dest = dir.join(&archive.name);
+    fs::rename(path, &dest).with_context(|| format!("Failed to quarantine {}", path.display()))?;
+    let actual_size = fs::metadata(&dest).map(|m| m.len()).unwrap_or(0);
+    let note = format!(
+        "Archive: {}\nSource: {}\nExpected hash: {}\nActual hash: {}\nExpected size: {}\nActual size: {}\nQuarantined: {}\n",
+        archive.name,
+        source_url,
+        archive.hash,
+        actual_hash,
+        archive.size,
+        actual_size,
+        chrono::Local::now().to_rfc3339()
+    );
+    let note_path = dir.join(format!("{}.txt", archive.name));
+    fs::write(&note_path, note)
+        .with_context(|| format!("Failed to write {}", note_path.display()))?;
+    Ok(dest)
+}
+
 /// Download a single archive based on its source type (with retry)
 /// Returns Ok with optional (url, expires) to cache on success
 async fn download_archive(
@@ -1537,8 +1950,11 @@ async fn download_archive(
     ctx: &DownloadContext,
     handle: &Arc<dyn ProgressHandle>,
 ) -> Result<Option<(String, i64)>> {
+    let policy = ctx.config.retry;
+    let max_attempts = policy.attempts();
     let mut attempt = 0u32;
     let mut rate_limit_retries = 0u32;
+    let mut disk_full_retries = 0u32;
     let display_name = truncate_name(&archive.name, 40);
     let expected_size = archive.size as u64;
     let is_alt_variant = crate::installer::game_preflight::has_known_alt_variant(&archive.name);
@@ -1554,6 +1970,16 @@ async fn download_archive(
             let _ = std::fs::remove_file(output_path);
         }
 
+        if space_guard::wait_for_space(
+            &ctx.config.downloads_dir,
+            space_guard::MIN_FREE_BYTES,
+            &ctx.reporter,
+        )
+        .await
+        {
+            handle.set_message(&display_name);
+        }
+
         let result = download_archive_inner(state, archive, output_path, ctx, handle).await;
 
         match result {
@@ -1565,19 +1991,21 @@ async fn download_archive(
                         if actual_size != expected_size && !is_alt_variant {
                             // Size mismatch - delete and retry
                             let _ = std::fs::remove_file(output_path);
-                            if attempt < MAX_RETRIES {
+                            if attempt < max_attempts {
                                 ctx.reporter.log(&format!(
-                                    "Size mismatch for {} (got {} expected {}), retrying...",
+                                    "Size mismatch for {} (got {} expected {}), retrying ({}/{})...",
                                     truncate_name(&archive.name, 25),
                                     actual_size,
-                                    expected_size
+                                    expected_size,
+                                    attempt,
+                                    policy.max_retries
                                 ));
                                 tokio::time::sleep(RETRY_DELAY).await;
                                 continue;
                             } else {
                                 bail!(
                                     "Size mismatch after {} attempts: expected {} bytes, got {}",
-                                    MAX_RETRIES,
+                                    attempt,
                                     expected_size,
                                     actual_size
                                 );
@@ -1590,11 +2018,13 @@ async fn download_archive(
                         }
                     }
                     Err(e) => {
-                        if attempt < MAX_RETRIES {
+                        if attempt < max_attempts {
                             ctx.reporter.log(&format!(
-                                "Cannot verify {} ({}), retrying...",
+                                "Cannot verify {} ({}), retrying ({}/{})...",
                                 truncate_name(&archive.name, 25),
-                                e
+                                e,
+                                attempt,
+                                policy.max_retries
                             ));
                             tokio::time::sleep(RETRY_DELAY).await;
                             continue;
@@ -1610,42 +2040,83 @@ async fn download_archive(
                     truncate_name(&archive.name, 30)
                 ));
 
-                match verify_file_hash(output_path, &archive.hash) {
-                    Ok(true) => {
+                match verify_file_hash_detailed(output_path, &archive.hash) {
+                    Ok((true, _)) => {
                         // Hash matches - success!
                     }
-                    Ok(false) if is_alt_variant => {
+                    Ok((false, _)) if is_alt_variant => {
                         // CC alt-variant (Steam vs Bethesda / patch drift) — accept it
                         warn!(
                             "{} has different hash (known CC alt-variant) — accepting",
                             archive.name
                         );
                     }
-                    Ok(false) => {
-                        // Hash mismatch - corrupted download, delete and retry
-                        let _ = std::fs::remove_file(output_path);
-                        if attempt < MAX_RETRIES {
+                    Ok((false, actual_hash)) => {
+                        // Hash mismatch - corrupted download, set it aside and retry
+                        match ctx.config.on_hash_mismatch {
+                            HashMismatchAction::Delete => {
+                                let _ = std::fs::remove_file(output_path);
+                            }
+                            HashMismatchAction::Quarantine => {
+                                match quarantine_archive(
+                                    &ctx.config.downloads_dir,
+                                    archive,
+                                    output_path,
+                                    &actual_hash,
+                                    &get_manual_url(state),
+                                ) {
+                                    Ok(dest) => ctx.reporter.log(&format!(
+                                        "Hash mismatch for {}, moved to {}",
+                                        truncate_name(&archive.name, 35),
+                                        dest.display()
+                                    )),
+                                    Err(e) => {
+                                        warn!("{:#}", e);
+                                        let _ = std::fs::remove_file(output_path);
+                                    }
+                                }
+                            }
+                            HashMismatchAction::Keep => {
+                                bail!(InstallError::msg(
+                                    InstallError::HashMismatch,
+                                    format!(
+                                        "Hash mismatch for {} (expected {}, got {}); kept at {}",
+                                        archive.name,
+                                        archive.hash,
+                                        actual_hash,
+                                        output_path.display()
+                                    )
+                                ));
+                            }
+                        }
+                        if policy.retry_hash_mismatch && attempt < max_attempts {
                             ctx.reporter.log(&format!(
-                                "Hash mismatch for {}, re-downloading...",
-                                truncate_name(&archive.name, 35)
+                                "Hash mismatch for {}, re-downloading ({}/{})...",
+                                truncate_name(&archive.name, 35),
+                                attempt,
+                                policy.max_retries
                             ));
                             tokio::time::sleep(RETRY_DELAY).await;
                             continue;
                         } else {
-                            bail!(
-                                "Hash verification failed after {} attempts for {}",
-                                MAX_RETRIES,
-                                archive.name
-                            );
+                            bail!(InstallError::msg(
+                                InstallError::HashMismatch,
+                                format!(
+                                    "Hash verification failed after {} attempts for {}",
+                                    attempt, archive.name
+                                )
+                            ));
                         }
                     }
                     Err(e) => {
                         // Hash computation failed - treat as retry
-                        if attempt < MAX_RETRIES {
+                        if attempt < max_attempts {
                             ctx.reporter.log(&format!(
-                                "Hash verify error for {} ({}), retrying...",
+                                "Hash verify error for {} ({}), retrying ({}/{})...",
                                 truncate_name(&archive.name, 25),
-                                e
+                                e,
+                                attempt,
+                                policy.max_retries
                             ));
                             tokio::time::sleep(RETRY_DELAY).await;
                             continue;
@@ -1657,14 +2128,51 @@ async fn download_archive(
 
                 return Ok(url_to_cache);
             }
+            Err(e) if space_guard::is_disk_full(&e) => {
+                // Not the download's fault: drop the partial file, wait for
+                // room for the whole archive and try again without using up
+                // an attempt, a few times at most.
+                let _ = std::fs::remove_file(output_path);
+                disk_full_retries += 1;
+                if disk_full_retries > space_guard::MAX_DISK_FULL_RETRIES {
+                    return Err(e.context(format!(
+                        "Disk full: {} still failed to write after {} waits for free space. \
+                         Free up space in {} (or raise your disk quota) and run again",
+                        archive.name,
+                        space_guard::MAX_DISK_FULL_RETRIES,
+                        ctx.config.downloads_dir.display()
+                    )));
+                }
+                attempt -= 1;
+                handle.set_message(&format!(
+                    "{} (paused, disk full)",
+                    truncate_name(&archive.name, 30)
+                ));
+                let waited = space_guard::wait_for_space(
+                    &ctx.config.downloads_dir,
+                    expected_size.saturating_add(space_guard::MIN_FREE_BYTES),
+                    &ctx.reporter,
+                )
+                .await;
+                if !waited {
+                    // The filesystem reports room (e.g. a quota it doesn't
+                    // show); don't retry straight away.
+                    tokio::time::sleep(RETRY_DELAY).await;
+                }
+                continue;
+            }
             Err(e) => {
                 let error_str = format!("{:#}", e);
-                let is_rate_limit =
-                    error_str.contains("429") || error_str.to_lowercase().contains("rate limit");
+                // Dead links and wrong-size files won't fix themselves on
+                // retry; go straight to mirrors.
+                let source_gone = nexus_file_unavailable(&e).is_some() || is_size_mismatch(&e);
+                let is_rate_limit = !source_gone
+                    && (error_str.contains("429")
+                        || error_str.to_lowercase().contains("rate limit"));
 
                 if is_rate_limit {
                     rate_limit_retries += 1;
-                    if rate_limit_retries <= MAX_RATE_LIMIT_RETRIES {
+                    if rate_limit_retries <= policy.rate_limit_retries() {
                         // Exponential backoff: 30s, 60s, 120s, 240s, 300s (capped)
                         let delay_secs = (RATE_LIMIT_BASE_DELAY.as_secs()
                             << (rate_limit_retries - 1).min(3))
@@ -1675,19 +2183,31 @@ async fn download_archive(
                             truncate_name(&archive.name, 25),
                             delay_secs,
                             rate_limit_retries,
-                            MAX_RATE_LIMIT_RETRIES
+                            policy.rate_limit_retries()
                         ));
                         tokio::time::sleep(Duration::from_secs(delay_secs)).await;
                         continue;
                     }
-                } else if attempt < MAX_RETRIES {
+                } else if attempt < max_attempts && !source_gone {
                     // Regular retry for network errors
+                    ctx.reporter.log(&format!(
+                        "Download failed for {} ({}), retrying ({}/{})...",
+                        truncate_name(&archive.name, 25),
+                        error_str,
+                        attempt,
+                        policy.max_retries
+                    ));
                     tokio::time::sleep(RETRY_DELAY).await;
                     continue;
                 }
 
-                // Primary download exhausted retries - try proxy/mirror fallback
-                // for proxyable sources (Google Drive, Mega, MediaFire)
+                // Primary download exhausted retries - try modlist/user mirrors
+                if download_from_alternate_urls(state, archive, output_path, ctx, handle).await {
+                    return Ok(None);
+                }
+
+                // Then proxy/mirror fallback for proxyable sources
+                // (Google Drive, Mega, MediaFire)
                 if let Some(source_url) = get_proxy_source_url(state) {
                     let progress_callback = make_progress_callback(
                         archive.name.clone(),
@@ -1759,10 +2279,16 @@ fn make_progress_callback(
 ) -> Option<HttpProgressCallback> {
     // Always create a callback that updates the ProgressHandle (CLI bars).
     // Optionally also forward to the legacy GUI ProgressEvent callback.
+    // The downloader's since-start speed is replaced by a moving average.
     let gui_cb = callback.clone();
     let name = archive_name;
     let h = Arc::clone(handle);
-    Some(Box::new(move |downloaded: u64, total: u64, speed: f64| {
+    let window = std::sync::Mutex::new(SpeedWindow::default());
+    Some(Box::new(move |downloaded: u64, total: u64, _speed: f64| {
+        let speed = window
+            .lock()
+            .expect("speed lock")
+            .record(downloaded, std::time::Instant::now());
         h.set_bytes(downloaded, total, speed);
         if let Some(ref cb) = gui_cb {
             cb(ProgressEvent::DownloadProgress {
@@ -1770,6 +2296,7 @@ fn make_progress_callback(
                 downloaded,
                 total,
                 speed,
+                eta_secs: eta_secs(downloaded, total, speed),
             });
         }
     }) as HttpProgressCallback)
@@ -1851,9 +2378,26 @@ async fn download_archive_inner(
         }
 
         DownloadState::Http(http_state) => {
-            download_file_with_callback(
+            // The modlist's own headers, then the user's for this host.
+            let mut headers = parse_header_lines(&http_state.headers);
+            headers.extend_from_slice(ctx.config.http_headers.for_url(&http_state.url));
+            if let Some(width) = split_first_part(&http_state.url, &archive.name) {
+                download_split_file(
+                    &ctx.http,
+                    &http_state.url,
+                    width,
+                    &headers,
+                    output_path,
+                    archive.size as u64,
+                    callback_ref,
+                )
+                .await?;
+                return Ok(((), None));
+            }
+            download_file_with_headers(
                 &ctx.http,
                 &http_state.url,
+                &headers,
                 output_path,
                 Some(archive.size as u64),
                 callback_ref,
@@ -1875,6 +2419,7 @@ async fn download_archive_inner(
                     downloaded: archive.size as u64,
                     total: archive.size as u64,
                     speed: 0.0, // CDN doesn't provide speed info
+                    eta_secs: None,
                 });
             }
             Ok(((), None))
@@ -1895,6 +2440,7 @@ async fn download_archive_inner(
                             downloaded: archive.size as u64,
                             total: archive.size as u64,
                             speed: 0.0,
+                            eta_secs: None,
                         });
                     }
                 }
@@ -1933,6 +2479,7 @@ async fn download_archive_inner(
                     downloaded: archive.size as u64,
                     total: archive.size as u64,
                     speed: 0.0,
+                    eta_secs: None,
                 });
             }
             Ok(((), None))
@@ -2165,35 +2712,22 @@ fn copy_game_file(
     let game_file_path = &state.game_file;
 
     // Check game directory and downloads directory
-    let mut source_path: Option<PathBuf> = None;
-    for (base, relative) in [
-        (&config.game_dir, game_file_path.as_str()),
-        (
-            &config.game_dir,
-            &format!("Data/{}", game_file_path) as &str,
-        ),
-    ] {
-        if let Some(resolved) = crate::paths::resolve_case_insensitive(base, relative) {
-            if resolved.exists() {
-                source_path = Some(resolved);
-                break;
-            }
-        }
-    }
-    if source_path.is_none() {
-        let dl_path = config.downloads_dir.join(&archive.name);
-        if dl_path.exists() {
-            source_path = Some(dl_path);
-        }
-    }
-
-    let source = source_path.with_context(|| {
-        format!(
-            "Game file not found: {} in {} or downloads dir",
-            game_file_path,
-            config.game_dir.display()
-        )
-    })?;
+    let source = crate::game_finder::resolve_game_file(&config.game_dir, game_file_path)
+        .or_else(|| {
+            let dl_path = config.downloads_dir.join(&archive.name);
+            dl_path.exists().then_some(dl_path)
+        })
+        .with_context(|| {
+            let dlc = crate::game_finder::dlc_for_game_file(&state.game, game_file_path)
+                .map(|dlc| format!(" (part of the {} DLC; is it installed?)", dlc))
+                .unwrap_or_default();
+            format!(
+                "Game file not found: {} in {} or downloads dir{}",
+                game_file_path,
+                config.game_dir.display(),
+                dlc
+            )
+        })?;
 
     // Skip copy if source and output are the same file
     if source == output_path {
@@ -2252,7 +2786,7 @@ async fn download_pending_archives_streaming(
     reporter.overall_set_message("Starting automatic downloads...");
 
     let total_archives = pending.len();
-    let ctx = Arc::new(build_context(config, total_archives).await?);
+    let ctx = Arc::new(build_context(db, config, total_archives).await?);
 
     stream::iter(pending)
         .map(|archive| {
@@ -2338,7 +2872,7 @@ async fn download_non_nexus_files(
     reporter.overall_set_message("Starting downloads...");
 
     let total_archives = pending.len();
-    let ctx = Arc::new(build_context(config, total_archives).await?);
+    let ctx = Arc::new(build_context(db, config, total_archives).await?);
 
     let results: Vec<DownloadResultTuple> = stream::iter(pending)
         .map(|archive| {
@@ -2412,4 +2946,70 @@ mod tests {
             "https://www.moddb.com/downloads/mirror/88982/130/abc123"
         );
     }
+
+    #[test]
+    fn download_order_sorts_by_size_stably() {
+        let archive = |name: &str, size: i64| ArchiveInfo {
+            hash: String::new(),
+            name: name.into(),
+            size,
+            meta: String::new(),
+            state_json: String::new(),
+            download_status: "pending".into(),
+            extraction_status: "pending".into(),
+            local_path: None,
+            cached_url: None,
+            url_expires: None,
+        };
+        let names = |v: &[ArchiveInfo]| v.iter().map(|a| a.name.clone()).collect::<Vec<_>>();
+        let manifest = vec![archive("a", 30), archive("b", 10), archive("c", 30)];
+
+        let mut pending = manifest.clone();
+        sort_by_download_order(&mut pending, DownloadOrder::Manifest);
+        assert_eq!(names(&pending), ["a", "b", "c"]);
+        sort_by_download_order(&mut pending, DownloadOrder::Smallest);
+        assert_eq!(names(&pending), ["b", "a", "c"]);
+        let mut pending = manifest;
+        sort_by_download_order(&mut pending, DownloadOrder::Largest);
+        assert_eq!(names(&pending), ["a", "c", "b"]);
+    }
+
+    #[test]
+    fn quarantined_archive_is_moved_with_a_note() {
+        let downloads = tempfile::tempdir().unwrap();
+        let path = downloads.path().join("Mod-1-0.7z");
+        fs::write(&path, b"wrong bytes").unwrap();
+        let archive = ArchiveInfo {
+            hash: "AAAAAAAAAAA=".into(),
+            name: "Mod-1-0.7z".into(),
+            size: 11,
+            meta: String::new(),
+            state_json: String::new(),
+            download_status: "pending".into(),
+            extraction_status: "pending".into(),
+            local_path: None,
+            cached_url: None,
+            url_expires: None,
+        };
+
+        let dest = quarantine_archive(
+            downloads.path(),
+            &archive,
+            &path,
+            "BBBBBBBBBBB=",
+            "https://example.com/Mod-1-0.7z",
+        )
+        .unwrap();
+        assert_eq!(
+            dest,
+            downloads.path().join(QUARANTINE_DIR).join("Mod-1-0.7z")
+        );
+        assert!(!path.exists());
+        assert_eq!(fs::read(&dest).unwrap(), b"wrong bytes");
+        let note = fs::read_to_string(downloads.path().join(QUARANTINE_DIR).join("Mod-1-0.7z.txt"))
+            .unwrap();
+        assert!(note.contains("Expected hash: AAAAAAAAAAA="));
+        assert!(note.contains("Actual hash: BBBBBBBBBBB="));
+        assert!(note.contains("Source: https://example.com/Mod-1-0.7z"));
+    }
 }
@@ -0,0 +1,105 @@
+//! Directive failures grouped by directive type.
+//!
+//! A single failure count hides what went wrong: a failed
+//! `TransformedTexture` points at the GPU/encoder, a failed
+//! `PatchedFromArchive` at a source archive that doesn't match. Every
+//! failure site records its type here, and the install summary prints one
+//! line per type with the first error seen.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// Failures of one directive type.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct TypeFailures {
+    pub directive_type: String,
+    pub count: usize,
+    /// First error recorded for this type.
+    pub first_error: String,
+}
+
+impl TypeFailures {
+    /// Short pointer at the usual cause for this directive type.
+    pub fn hint(&self) -> Option<&'static str> {
+        // Running out of file descriptors fails any type the same way.
+        if self.first_error.contains("Too many open files") {
+            return Some(super::limits::OPEN_FILES_FIX);
+        }
+        match self.directive_type.as_str() {
+            "FromArchive" => Some("source archive is corrupt or incomplete; re-download it"),
+            "PatchedFromArchive" => {
+                Some("source file doesn't match the patch; re-download the archive")
+            }
+            "TransformedTexture" => {
+                Some("texture encoding failed; check the GPU with `clf3 list-gpu`")
+            }
+            "CreateBSA" => Some("BSA build failed; check free disk space"),
+            "InlineFile" | "RemappedInlineFile" => {
+                Some(".wabbajack file may be damaged; re-download it")
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Thread-safe per-type failure tracker, shared through `ProcessContext`.
+#[derive(Debug, Default)]
+pub struct DirectiveFailures {
+    by_type: Mutex<BTreeMap<String, (usize, String)>>,
+}
+
+impl DirectiveFailures {
+    /// Record one failed directive.
+    pub fn record(&self, directive_type: &str, error: impl std::fmt::Display) {
+        self.record_many(directive_type, 1, error);
+    }
+
+    /// Record `count` directives that failed for the same reason (e.g. the
+    /// whole archive couldn't be opened).
+    pub fn record_many(&self, directive_type: &str, count: usize, error: impl std::fmt::Display) {
+        if count == 0 {
+            return;
+        }
+        let mut map = self.by_type.lock().expect("failures lock poisoned");
+        let entry = map
+            .entry(directive_type.to_string())
+            .or_insert_with(|| (0, error.to_string()));
+        entry.0 += count;
+    }
+
+    /// Per-type failures, most frequent first.
+    pub fn summary(&self) -> Vec<TypeFailures> {
+        let map = self.by_type.lock().expect("failures lock poisoned");
+        let mut out: Vec<_> = map
+            .iter()
+            .map(|(t, (count, first_error))| TypeFailures {
+                directive_type: t.clone(),
+                count: *count,
+                first_error: first_error.clone(),
+            })
+            .collect();
+        out.sort_by(|a, b| b.count.cmp(&a.count));
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_first_error_per_type() {
+        let failures = DirectiveFailures::default();
+        failures.record("TransformedTexture", "encoder init failed");
+        failures.record("TransformedTexture", "later error");
+        failures.record_many("FromArchive", 3, "archive truncated");
+        failures.record_many("CreateBSA", 0, "never recorded");
+
+        let summary = failures.summary();
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary[0].directive_type, "FromArchive");
+        assert_eq!(summary[0].count, 3);
+        assert_eq!(summary[1].count, 2);
+        assert_eq!(summary[1].first_error, "encoder init failed");
+    }
+}
@@ -11,9 +11,9 @@
 //! - [`check_game_files_from_db`] — reads archives from the installer DB (used
 //!   inside the install pipeline, after import_wabbajack_to_db).
 
+use crate::game_finder::{dlc_for_game_file, resolve_game_file};
 use crate::hash::compute_file_hash;
 use crate::modlist::{Archive, DownloadState, GameFileSourceState, Modlist, ModlistDb};
-use crate::paths::resolve_case_insensitive;
 use anyhow::Result;
 use rayon::prelude::*;
 use std::path::{Path, PathBuf};
@@ -26,6 +26,8 @@ pub struct GameFileCheck {
     pub file: String,
     /// Expected hash from the modlist.
     pub expected_hash: String,
+    /// Official DLC the file belongs to, if any.
+    pub dlc: Option<&'static str>,
     /// What we saw on disk.
     pub status: CheckStatus,
 }
@@ -58,6 +60,8 @@ pub struct PreflightReport {
     /// One entry per required file. Empty `checks` means the modlist has no
     /// GameFileSource archives (trivially OK).
     pub checks: Vec<GameFileCheck>,
+    /// Game version the modlist was built against, if it records one.
+    pub game_version: Option<String>,
 }
 
 impl PreflightReport {
@@ -104,7 +108,10 @@ impl PreflightReport {
             self.total
         );
         for c in missing {
-            s.push_str(&format!("  MISSING:  {}\n", c.file));
+            match c.dlc {
+                Some(dlc) => s.push_str(&format!("  MISSING:  {} ({} DLC)\n", c.file, dlc)),
+                None => s.push_str(&format!("  MISSING:  {}\n", c.file)),
+            }
         }
         for c in mismatched {
             match &c.status {
@@ -186,6 +193,23 @@ fn collect_game_files_from_db(db: &ModlistDb) -> Result<Vec<GameFileSourceState>
     Ok(out)
 }
 
+/// Game version recorded in the GameFileSource entries. The executable's
+/// entry is preferred; the rest normally carry the same version anyway.
+fn required_version(game_files: &[GameFileSourceState]) -> Option<String> {
+    let recorded = |gf: &&GameFileSourceState| !gf.game_version.trim().is_empty();
+    game_files
+        .iter()
+        .filter(recorded)
+        .find(|gf| gf.game_file.to_lowercase().ends_with(".exe"))
+        .or_else(|| game_files.iter().find(recorded))
+        .map(|gf| gf.game_version.trim().to_string())
+}
+
+/// Game version a parsed modlist was built against, if it records one.
+pub fn required_game_version(modlist: &Modlist) -> Option<String> {
+    required_version(&collect_game_files_from_archives(&modlist.archives))
+}
+
 /// Core verification: given a list of required game files and a target game
 /// directory, hash everything in parallel and return a report.
 fn verify(game_files: &[GameFileSourceState], game_dir: &Path) -> PreflightReport {
@@ -199,11 +223,11 @@ fn verify(game_files: &[GameFileSourceState], game_dir: &Path) -> PreflightRepor
             let file = gfs.game_file.clone();
             let expected_hash = gfs.hash.clone();
 
-            // Try the path as written, then under Data/ which is where most
-            // Bethesda GameFileSource entries actually live (they encode `.esm`
-            // without a leading `Data\\` sometimes, sometimes with).
-            let resolved = resolve_case_insensitive(game_dir, &file)
-                .or_else(|| resolve_case_insensitive(game_dir, &format!("Data/{}", file)));
+            // Try the path as written, then under Data/ (Data Files/ for
+            // Morrowind), which is where most Bethesda GameFileSource entries
+            // actually live (they encode `.esm` without a leading `Data\\`
+            // sometimes, sometimes with).
+            let resolved = resolve_game_file(game_dir, &file);
 
             let status = match resolved {
                 None => CheckStatus::Missing,
@@ -228,6 +252,7 @@ fn verify(game_files: &[GameFileSourceState], game_dir: &Path) -> PreflightRepor
             };
 
             GameFileCheck {
+                dlc: dlc_for_game_file(&gfs.game, &file),
                 file,
                 expected_hash,
                 status,
@@ -239,6 +264,7 @@ fn verify(game_files: &[GameFileSourceState], game_dir: &Path) -> PreflightRepor
         game_dir: game_dir.to_path_buf(),
         total,
         checks,
+        game_version: required_version(game_files),
     }
 }
 
@@ -0,0 +1,243 @@
+//! ENB and ReShade files in the game root
+//!
+//! ENB and ReShade hook the game through a proxy DLL (`d3d11.dll`,
+//! `dxgi.dll`) and read their presets next to the executable, so they don't
+//! work from MO2's virtual Data folder. Lists with a Stock Game folder ship
+//! them straight into it; lists built around the Root Builder plugin keep
+//! them in `mods/<mod>/root/` and rely on the plugin to copy them out at
+//! launch, which it can't do under Wine. For those, the files of enabled
+//! mods are copied into the game root after install. Game files they
+//! replace are backed up first.
+
+use super::conflicts::{active_profile, enabled_mods};
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Game files replaced by root files are copied here, inside the install
+/// dir, before they are overwritten.
+pub const BACKUP_DIR: &str = "clf3-game-root-backup";
+
+/// Root Builder's folder inside a mod.
+const ROOT_BUILDER_DIR: &str = "root";
+
+/// Proxy DLLs and config files ENB and ReShade read from the game root.
+const ROOT_FILES: &[&str] = &[
+    "d3d9.dll",
+    "d3d11.dll",
+    "dxgi.dll",
+    "d3dcompiler_46e.dll",
+    "enblocal.ini",
+    "enbseries.ini",
+    "reshade.ini",
+    "reshadepreset.ini",
+];
+
+/// Folders of shaders and presets that go with them.
+const ROOT_DIRS: &[&str] = &["enbseries", "reshade-shaders", "reshade-presets"];
+
+/// Whether a top-level file of a Root Builder folder belongs to ENB or
+/// ReShade: a known name, an `enb*` shader/config/texture, or a ReShade
+/// preset.
+pub fn is_graphics_root_file(name: &str) -> bool {
+    let name = name.to_lowercase();
+    let ext = name.rsplit_once('.').map_or("", |(_, ext)| ext);
+    ROOT_FILES.contains(&name.as_str())
+        || (name.starts_with("enb") && matches!(ext, "fx" | "fxh" | "ini" | "bmp" | "dds"))
+        || (name.contains("reshade") && ext == "ini")
+}
+
+fn is_graphics_root_dir(name: &str) -> bool {
+    ROOT_DIRS.contains(&name.to_lowercase().as_str())
+}
+
+/// An ENB/ReShade file in a mod's Root Builder folder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RootFile {
+    pub mod_name: String,
+    pub source: PathBuf,
+    /// Path relative to the game root.
+    pub relative: PathBuf,
+}
+
+/// ENB/ReShade files in the Root Builder folders of `enabled` mods (highest
+/// priority first). When mods ship the same file, the higher one wins, as
+/// with Root Builder.
+pub fn find_root_files(install_dir: &Path, enabled: &[&str]) -> Vec<RootFile> {
+    let mut by_path: HashMap<String, RootFile> = HashMap::new();
+    for mod_name in enabled.iter().rev() {
+        let root = install_dir
+            .join("mods")
+            .join(mod_name)
+            .join(ROOT_BUILDER_DIR);
+        let Ok(entries) = fs::read_dir(&root) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let path = entry.path();
+            let files: Vec<PathBuf> = if path.is_dir() && is_graphics_root_dir(&name) {
+                WalkDir::new(&path)
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.file_type().is_file())
+                    .map(|e| e.into_path())
+                    .collect()
+            } else if path.is_file() && is_graphics_root_file(&name) {
+                vec![path]
+            } else {
+                continue;
+            };
+            for source in files {
+                let Ok(relative) = source.strip_prefix(&root).map(Path::to_path_buf) else {
+                    continue;
+                };
+                let key = relative.to_string_lossy().to_lowercase();
+                by_path.insert(
+                    key,
+                    RootFile {
+                        mod_name: mod_name.to_string(),
+                        source,
+                        relative,
+                    },
+                );
+            }
+        }
+    }
+    let mut files: Vec<RootFile> = by_path.into_values().collect();
+    files.sort_by(|a, b| a.relative.cmp(&b.relative));
+    files
+}
+
+/// Game root MO2 launches from: the list's Stock Game folder if it has one,
+/// else the game directory.
+pub fn game_root(install_dir: &Path, game_dir: &Path) -> PathBuf {
+    ["Stock Game", "Game Root"]
+        .iter()
+        .map(|name| install_dir.join(name))
+        .find(|dir| dir.is_dir())
+        .unwrap_or_else(|| game_dir.to_path_buf())
+}
+
+/// What [`place_root_files`] did.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlacedRootFiles {
+    /// Files copied into the game root, relative to it.
+    pub placed: Vec<PathBuf>,
+    /// Game files backed up before being replaced, relative to the game root.
+    pub backed_up: Vec<PathBuf>,
+}
+
+/// Copy `files` into `game_root`. A different file already there is copied
+/// to `backup_dir` first, unless an earlier run already backed it up (the
+/// backup keeps the game's own copy).
+pub fn place_root_files(
+    files: &[RootFile],
+    game_root: &Path,
+    backup_dir: &Path,
+) -> Result<PlacedRootFiles> {
+    let mut result = PlacedRootFiles::default();
+    for file in files {
+        let dest = game_root.join(&file.relative);
+        if dest.is_file() {
+            if fs::read(&dest)? == fs::read(&file.source)? {
+                continue;
+            }
+            let backup = backup_dir.join(&file.relative);
+            if !backup.exists() {
+                if let Some(parent) = backup.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::copy(&dest, &backup)
+                    .with_context(|| format!("Failed to back up {}", dest.display()))?;
+                result.backed_up.push(file.relative.clone());
+            }
+        }
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&file.source, &dest).with_context(|| {
+            format!(
+                "Failed to copy {} to {}",
+                file.source.display(),
+                dest.display()
+            )
+        })?;
+        result.placed.push(file.relative.clone());
+    }
+    Ok(result)
+}
+
+/// ENB/ReShade files of the active profile's enabled mods, for a finished
+/// install.
+pub fn root_files_for_install(install_dir: &Path) -> Result<Vec<RootFile>> {
+    let profile = active_profile(install_dir)?;
+    let modlist_path = install_dir
+        .join("profiles")
+        .join(&profile)
+        .join("modlist.txt");
+    let modlist_txt = fs::read_to_string(&modlist_path)
+        .with_context(|| format!("Failed to read {}", modlist_path.display()))?;
+    Ok(find_root_files(install_dir, &enabled_mods(&modlist_txt)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn root_files_are_found_and_placed_with_backup() {
+        let tmp = tempfile::tempdir().unwrap();
+        let install = tmp.path().join("install");
+        let game = tmp.path().join("game");
+        let write = |path: PathBuf, data: &[u8]| {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, data).unwrap();
+        };
+        let enb = install.join("mods/ENB Binaries/root");
+        write(enb.join("d3d11.dll"), b"enb");
+        write(enb.join("enblocal.ini"), b"old");
+        write(enb.join("readme.txt"), b"skip");
+        write(install.join("mods/ENB Preset/root/enblocal.ini"), b"new");
+        write(
+            install.join("mods/ENB Preset/root/enbseries/effect.fx"),
+            b"fx",
+        );
+        write(
+            install.join("mods/ReShade/root/MyPreset-ReShade.ini"),
+            b"rs",
+        );
+        write(install.join("mods/Textures/textures/a.dds"), b"dds");
+        write(game.join("d3d11.dll"), b"game's own");
+
+        assert!(!is_graphics_root_file("SkyrimSE.exe"));
+        let files = find_root_files(&install, &["ENB Preset", "ENB Binaries", "Textures"]);
+        let found: Vec<_> = files
+            .iter()
+            .map(|f| (f.relative.to_str().unwrap(), f.mod_name.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("d3d11.dll", "ENB Binaries"),
+                ("enblocal.ini", "ENB Preset"),
+                ("enbseries/effect.fx", "ENB Preset"),
+            ]
+        );
+
+        let backups = install.join(BACKUP_DIR);
+        let placed = place_root_files(&files, &game, &backups).unwrap();
+        assert_eq!(placed.placed.len(), 3);
+        assert_eq!(placed.backed_up, [PathBuf::from("d3d11.dll")]);
+        assert_eq!(fs::read(game.join("d3d11.dll")).unwrap(), b"enb");
+        assert_eq!(fs::read(game.join("enblocal.ini")).unwrap(), b"new");
+        assert_eq!(fs::read(backups.join("d3d11.dll")).unwrap(), b"game's own");
+
+        // A second run changes nothing and keeps the original backup.
+        let again = place_root_files(&files, &game, &backups).unwrap();
+        assert_eq!(again, PlacedRootFiles::default());
+        assert_eq!(game_root(&install, &game), game);
+    }
+}
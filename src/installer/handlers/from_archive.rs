@@ -225,20 +225,31 @@ fn extract_with_7z(archive_path: &Path, file_path: &str, temp_base_dir: &Path) -
     match sevenzip::extract_file_case_insensitive(archive_path, file_path) {
         Ok(data) => return Ok(data),
         Err(_) => {
-            // Fall back to full extraction with case-insensitive search
             tracing::debug!(
-                "Direct extraction failed for '{}', trying full extraction",
+                "Direct extraction failed for '{}', trying on-disk extraction",
                 file_path
             );
         }
     }
 
-    // Full extraction fallback - extract to temp and find the file
+    // Extract just this entry to disk, then the whole archive if that fails
+    // too, and find the file case-insensitively.
     let temp_dir =
         tempfile::tempdir_in(temp_base_dir).context("Failed to create temp directory")?;
 
-    sevenzip::extract_all(archive_path, temp_dir.path())
-        .with_context(|| format!("Failed to extract archive: {}", archive_path.display()))?;
+    if let Err(e) = sevenzip::extract_files_case_insensitive(
+        archive_path,
+        &[file_path.to_string()],
+        temp_dir.path(),
+    ) {
+        tracing::debug!(
+            "Selective extraction failed for '{}' ({:#}), extracting the whole archive",
+            file_path,
+            e
+        );
+        sevenzip::extract_all(archive_path, temp_dir.path())
+            .with_context(|| format!("Failed to extract archive: {}", archive_path.display()))?;
+    }
 
     // Find the file case-insensitively
     let target_normalized = paths::normalize_for_lookup(file_path);
@@ -4,6 +4,7 @@
 
 use crate::installer::processor::ProcessContext;
 use crate::modlist::{InlineFileDirective, RemappedInlineFileDirective};
+use crate::paths::PathPlaceholders;
 
 use anyhow::{Context, Result};
 use std::fs::File;
@@ -52,10 +53,17 @@ pub fn handle_remapped_inline_file(
         .read_wabbajack_file(&entry_name)
         .with_context(|| format!("Failed to read remapped inline file: {}", entry_name))?;
 
-    // Remap path placeholders
-    // Common placeholders: GAME_PATH, MO2_PATH, etc.
+    // Remap path placeholders (GAME_PATH, MO2_PATH, DOWNLOAD_PATH)
     let content = String::from_utf8_lossy(&data);
-    let remapped = remap_paths(&content, ctx);
+    let mut placeholders = PathPlaceholders::new(
+        &ctx.config.output_dir,
+        &ctx.config.downloads_dir,
+        &ctx.config.game_dir,
+    );
+    if let Some(prefix) = &ctx.config.wine_prefix {
+        placeholders = placeholders.with_wine_prefix(prefix);
+    }
+    let remapped = placeholders.expand(&content);
 
     // Note: Size check is tricky here since remapping may change the size
     // The directive.size is the expected output size after remapping
@@ -73,105 +81,6 @@ pub fn handle_remapped_inline_file(
     Ok(())
 }
 
-/// Remap path placeholders in content
-fn remap_paths(content: &str, ctx: &ProcessContext) -> String {
-    // Early-out: if content has no magic tokens or legacy placeholders, skip all replacements
-    if !content.contains("{--||")
-        && !content.contains("[Game Folder Files]")
-        && !content.contains("[MO2_PATH]")
-        && !content.contains("[DOWNLOADS_PATH]")
-        && !content.contains("download_directory=")
-    {
-        return content.to_string();
-    }
-
-    let mut result = content.to_string();
-
-    // Get base paths and normalize once (remove trailing slashes, ensure forward slashes)
-    let mo2_base = ctx
-        .config
-        .output_dir
-        .to_string_lossy()
-        .trim_end_matches('/')
-        .trim_end_matches('\\')
-        .replace('\\', "/");
-    let game_base = ctx
-        .config
-        .game_dir
-        .to_string_lossy()
-        .trim_end_matches('/')
-        .trim_end_matches('\\')
-        .replace('\\', "/");
-    let downloads_base = ctx
-        .config
-        .downloads_dir
-        .to_string_lossy()
-        .trim_end_matches('/')
-        .trim_end_matches('\\')
-        .replace('\\', "/");
-
-    // Derive all variants from the base forward-slash path
-    let mo2_forward = format!("Z:{}", mo2_base);
-    let game_forward = format!("Z:{}", game_base);
-    let downloads_forward = format!("Z:{}", downloads_base);
-
-    let mo2_double_back = format!("Z:{}", mo2_base.replace('/', "\\\\"));
-    let game_double_back = format!("Z:{}", game_base.replace('/', "\\\\"));
-    let downloads_double_back = format!("Z:{}", downloads_base.replace('/', "\\\\"));
-
-    let mo2_back = format!("Z:{}", mo2_base.replace('/', "\\"));
-    let game_back = format!("Z:{}", game_base.replace('/', "\\"));
-    let downloads_back = format!("Z:{}", downloads_base.replace('/', "\\"));
-
-    // Replace Wabbajack magic tokens
-    // Format: {--||PATH_TYPE_MAGIC_STYLE||--}
-
-    // MO2 path tokens
-    result = result.replace("{--||MO2_PATH_MAGIC_FORWARD||--}", &mo2_forward);
-    result = result.replace("{--||MO2_PATH_MAGIC_DOUBLE_BACK||--}", &mo2_double_back);
-    result = result.replace("{--||MO2_PATH_MAGIC_BACK||--}", &mo2_back);
-
-    // Game path tokens
-    result = result.replace("{--||GAME_PATH_MAGIC_FORWARD||--}", &game_forward);
-    result = result.replace("{--||GAME_PATH_MAGIC_DOUBLE_BACK||--}", &game_double_back);
-    result = result.replace("{--||GAME_PATH_MAGIC_BACK||--}", &game_back);
-
-    // Downloads path tokens
-    result = result.replace("{--||DOWNLOADS_PATH_MAGIC_FORWARD||--}", &downloads_forward);
-    result = result.replace(
-        "{--||DOWNLOADS_PATH_MAGIC_DOUBLE_BACK||--}",
-        &downloads_double_back,
-    );
-    result = result.replace("{--||DOWNLOADS_PATH_MAGIC_BACK||--}", &downloads_back);
-
-    // Legacy placeholders (older Wabbajack format)
-    result = result.replace("[Game Folder Files]", &game_back);
-    result = result.replace("[MO2_PATH]", &mo2_back);
-    result = result.replace("[DOWNLOADS_PATH]", &downloads_back);
-
-    // Handle download_directory specially - replace hardcoded Windows paths
-    // Pattern: download_directory=X:/... or download_directory=X:\...
-    let lines: Vec<&str> = result.lines().collect();
-    let mut new_lines: Vec<String> = Vec::new();
-
-    for line in lines {
-        if let Some(value) = line.strip_prefix("download_directory=") {
-            // Check if it's a Windows drive path (like E:/ or C:\)
-            // MO2 ini format expects double-backslash escaping for paths.
-            if value.len() >= 2 && value.chars().nth(1) == Some(':') {
-                new_lines.push(format!("download_directory={}", downloads_double_back));
-            } else {
-                new_lines.push(line.to_string());
-            }
-        } else {
-            new_lines.push(line.to_string());
-        }
-    }
-    result = new_lines.join("\n");
-
-    result
-}
-
 #[cfg(test)]
 mod tests {
     #[test]
@@ -0,0 +1,420 @@
+//! Integrity check of a finished install (`clf3 check`).
+//!
+//! Re-hashes every installed output against the hash the modlist expects,
+//! using the .wabbajack recorded in `.clf3-install.json`. Mismatching files
+//! are split by modification time: changed after the install finished means
+//! someone edited them (MO2, a tool, the user); older means the bytes
+//! changed underneath, i.e. corruption.
+//!
+//! Read-only: unlike `--verify-hashes` nothing is deleted. Hashing a big
+//! install takes a while, so progress is reported in bytes and the check
+//! can be cancelled, returning what it found so far.
+
+use crate::installer::pipeline::extract_bsa_temp_id;
+use crate::installer::sidecar;
+use crate::modlist::{self, DirectiveSummary, ModlistDb};
+use crate::paths;
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::cell::Cell;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::SystemTime;
+use tokio_util::sync::CancellationToken;
+
+/// What is wrong with an installed file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    Missing,
+    /// Different from the modlist and changed after the install finished.
+    Modified,
+    /// Different from the modlist but not touched since the install.
+    Corrupted,
+}
+
+/// One output that doesn't match the modlist.
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityIssue {
+    /// Path relative to the install dir, as written in the modlist.
+    pub path: String,
+    pub directive_type: String,
+    pub kind: IssueKind,
+}
+
+/// Result of [`check_integrity`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IntegrityReport {
+    /// Outputs checked (BSA staging files excluded).
+    pub checked: usize,
+    /// Outputs left unchecked because the check was cancelled.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub unchecked: usize,
+    /// Sorted by path.
+    pub issues: Vec<IntegrityIssue>,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+/// Progress of [`check_integrity_with_progress`]. Bytes count only files
+/// that are hashed; missing ones count as done at their expected size.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CheckProgress {
+    pub files_done: usize,
+    pub files_total: usize,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+}
+
+/// The check was cancelled before this output was verified.
+#[derive(Debug, PartialEq, Eq)]
+struct Cancelled;
+
+impl IntegrityReport {
+    pub fn count(&self, kind: IssueKind) -> usize {
+        self.issues.iter().filter(|i| i.kind == kind).count()
+    }
+
+    /// Human-readable report, as printed by `clf3 check` and exported by the GUI.
+    pub fn render(&self, name: &str) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "=== {} ===", name);
+        let _ = writeln!(out, "Checked:     {} files", self.checked);
+        if self.unchecked > 0 {
+            let _ = writeln!(
+                out,
+                "Cancelled:   {} files not checked; results are partial",
+                self.unchecked
+            );
+        }
+        if self.issues.is_empty() {
+            if self.unchecked > 0 {
+                let _ = writeln!(out, "\nAll checked files match the modlist.");
+            } else {
+                let _ = writeln!(out, "\nAll files match the modlist.");
+            }
+            return out;
+        }
+        let _ = writeln!(
+            out,
+            "Problems:    {} missing, {} modified since install, {} corrupted",
+            self.count(IssueKind::Missing),
+            self.count(IssueKind::Modified),
+            self.count(IssueKind::Corrupted)
+        );
+        for (kind, title) in [
+            (IssueKind::Missing, "Missing"),
+            (IssueKind::Corrupted, "Corrupted (unchanged since install)"),
+            (IssueKind::Modified, "Edited after install"),
+        ] {
+            let paths: Vec<&str> = self
+                .issues
+                .iter()
+                .filter(|i| i.kind == kind)
+                .map(|i| i.path.as_str())
+                .collect();
+            if paths.is_empty() {
+                continue;
+            }
+            let _ = writeln!(out, "\n{}:", title);
+            for path in &paths {
+                let _ = writeln!(out, "  {}", path);
+            }
+        }
+        let _ = writeln!(
+            out,
+            "\nRe-run the install to restore missing and corrupted files."
+        );
+        out
+    }
+}
+
+/// How an output can be verified.
+enum Verify {
+    /// Hash the file against the directive hash.
+    Hash,
+    /// Trust the `.clf3hash` sidecar (the directive hash is of the source,
+    /// not of the file CLF3 wrote).
+    Sidecar,
+    /// Only check that the file exists. Remapped inline files have install
+    /// paths substituted in, so they never match the modlist hash.
+    Exists,
+}
+
+fn verify_mode(directive_type: &str) -> Option<Verify> {
+    match directive_type {
+        "FromArchive" | "PatchedFromArchive" | "InlineFile" => Some(Verify::Hash),
+        "TransformedTexture" | "CreateBSA" => Some(Verify::Sidecar),
+        "RemappedInlineFile" => Some(Verify::Exists),
+        _ => None,
+    }
+}
+
+/// A mismatching file is `Modified` if it changed after `installed_at`.
+/// Without an install time every mismatch is reported as `Corrupted`.
+fn classify(path: &Path, installed_at: Option<SystemTime>) -> IssueKind {
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    match (modified, installed_at) {
+        (Some(modified), Some(installed_at)) if modified > installed_at => IssueKind::Modified,
+        _ => IssueKind::Corrupted,
+    }
+}
+
+/// Verify one output, calling `hashed(bytes)` as chunks of it are hashed.
+fn check_one(
+    d: &DirectiveSummary,
+    output_dir: &Path,
+    installed_at: Option<SystemTime>,
+    cancel: &CancellationToken,
+    hashed: &dyn Fn(u64),
+) -> Result<Option<IssueKind>, Cancelled> {
+    let path = paths::join_windows_path(output_dir, &d.to_path);
+    if !path.is_file() {
+        return Ok(Some(IssueKind::Missing));
+    }
+    let Some(mode) = verify_mode(&d.directive_type) else {
+        return Ok(None);
+    };
+    let ok = match mode {
+        Verify::Hash => {
+            let last = Cell::new(0);
+            let progress = |done: u64, _total: u64| {
+                hashed(done - last.replace(done));
+            };
+            match crate::hash::hash_file_cancellable(
+                &path,
+                crate::hash::HashAlgorithm::XxHash64,
+                Some(&progress),
+                cancel,
+            ) {
+                Ok(Some(hash)) => hash == d.hash,
+                Ok(None) => return Err(Cancelled),
+                Err(e) => {
+                    tracing::warn!("Failed to hash {}: {}", path.display(), e);
+                    false
+                }
+            }
+        }
+        Verify::Sidecar => sidecar::sidecar_valid(&path, &d.hash),
+        Verify::Exists => true,
+    };
+    Ok((!ok).then(|| classify(&path, installed_at)))
+}
+
+/// Check every output of `db` under `output_dir`. `installed_at` is when the
+/// install finished (from `.clf3-install.json`).
+pub fn check_integrity(
+    db: &ModlistDb,
+    output_dir: &Path,
+    installed_at: Option<SystemTime>,
+) -> Result<IntegrityReport> {
+    check_integrity_with_progress(
+        db,
+        output_dir,
+        installed_at,
+        &|_| {},
+        &CancellationToken::new(),
+    )
+}
+
+/// [`check_integrity`], calling `progress` as files are hashed. Once
+/// `cancel` is cancelled no further files are started and files being
+/// hashed are abandoned; the report covers the files finished by then.
+pub fn check_integrity_with_progress(
+    db: &ModlistDb,
+    output_dir: &Path,
+    installed_at: Option<SystemTime>,
+    progress: &(dyn Fn(CheckProgress) + Sync),
+    cancel: &CancellationToken,
+) -> Result<IntegrityReport> {
+    let directives: Vec<DirectiveSummary> = db
+        .get_all_directives_summary()?
+        .into_iter()
+        .filter(|d| verify_mode(&d.directive_type).is_some())
+        .filter(|d| extract_bsa_temp_id(&d.to_path).is_none())
+        .collect();
+
+    let total = directives.len();
+    let bytes_total: u64 = directives
+        .iter()
+        .filter(|d| matches!(verify_mode(&d.directive_type), Some(Verify::Hash)))
+        .map(|d| d.size)
+        .sum();
+    let done = AtomicUsize::new(0);
+    let bytes_done = AtomicU64::new(0);
+    let report = |files_done: usize| {
+        progress(CheckProgress {
+            files_done,
+            files_total: total,
+            bytes_done: bytes_done.load(Ordering::Relaxed).min(bytes_total),
+            bytes_total,
+        })
+    };
+    report(0);
+    let mut issues: Vec<IntegrityIssue> = directives
+        .par_iter()
+        .filter_map(|d| {
+            if cancel.is_cancelled() {
+                return None;
+            }
+            let file_bytes = Cell::new(0);
+            let hashed = |bytes: u64| {
+                file_bytes.set(file_bytes.get() + bytes);
+                bytes_done.fetch_add(bytes, Ordering::Relaxed);
+                report(done.load(Ordering::Relaxed));
+            };
+            let issue = check_one(d, output_dir, installed_at, cancel, &hashed).ok()?;
+            // Missing or short files still move the bar their full size.
+            if matches!(verify_mode(&d.directive_type), Some(Verify::Hash)) {
+                bytes_done.fetch_add(d.size.saturating_sub(file_bytes.get()), Ordering::Relaxed);
+            }
+            report(done.fetch_add(1, Ordering::Relaxed) + 1);
+            issue.map(|kind| IntegrityIssue {
+                path: d.to_path.replace('\\', "/"),
+                directive_type: d.directive_type.clone(),
+                kind,
+            })
+        })
+        .collect();
+    issues.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let checked = done.into_inner();
+    Ok(IntegrityReport {
+        checked,
+        unchecked: total - checked,
+        issues,
+    })
+}
+
+/// Check an install CLF3 finished, using the .wabbajack and install time in
+/// its `.clf3-install.json` (`wabbajack_file` overrides the recorded one).
+/// Returns the modlist name and the report.
+pub fn check_install(
+    install_dir: &Path,
+    wabbajack_file: Option<PathBuf>,
+    progress: &(dyn Fn(CheckProgress) + Sync),
+    cancel: &CancellationToken,
+) -> Result<(String, IntegrityReport)> {
+    let manifest = modlist::InstallManifest::load_from(install_dir)?.with_context(|| {
+        format!(
+            "No {} in {}. Only installs finished by CLF3 can be checked",
+            modlist::MANIFEST_FILENAME,
+            install_dir.display()
+        )
+    })?;
+    let wabbajack_file = wabbajack_file
+        .or_else(|| manifest.wabbajack_path.clone())
+        .context("No .wabbajack file recorded for this install. Pass --wabbajack-file")?;
+    let installed_at = chrono::DateTime::parse_from_rfc3339(&manifest.installed_at)
+        .ok()
+        .map(SystemTime::from);
+
+    let parsed = modlist::parse_wabbajack_file(&wabbajack_file)?;
+    let mut db = ModlistDb::in_memory()?;
+    db.import_modlist(&parsed)?;
+    let report = check_integrity_with_progress(&db, install_dir, installed_at, progress, cancel)?;
+    Ok((parsed.name, report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::Duration;
+
+    fn directive(to_path: &str, data: &[u8]) -> DirectiveSummary {
+        DirectiveSummary {
+            id: 0,
+            directive_type: "FromArchive".to_string(),
+            to_path: to_path.to_string(),
+            hash: crate::hash::compute_bytes_hash(data),
+            size: data.len() as u64,
+            archive_hash: None,
+        }
+    }
+
+    #[test]
+    fn test_mismatches_split_by_install_time() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::create_dir_all(tmp.path().join("mods/A")).unwrap();
+        fs::write(tmp.path().join("mods/A/good.esp"), b"good").unwrap();
+        fs::write(tmp.path().join("mods/A/bad.esp"), b"oops").unwrap();
+
+        let good = directive("mods\\A\\good.esp", b"good");
+        let bad = directive("mods\\A\\bad.esp", b"orig");
+        let gone = directive("mods\\A\\gone.esp", b"gone");
+
+        let before = SystemTime::now() - Duration::from_secs(3600);
+        let after = SystemTime::now() + Duration::from_secs(3600);
+
+        let cancel = CancellationToken::new();
+        let check = |d: &DirectiveSummary, at: Option<SystemTime>| {
+            check_one(d, tmp.path(), at, &cancel, &|_| {})
+        };
+        assert_eq!(check(&good, Some(before)), Ok(None));
+        assert_eq!(check(&gone, Some(before)), Ok(Some(IssueKind::Missing)));
+        assert_eq!(check(&bad, Some(before)), Ok(Some(IssueKind::Modified)));
+        assert_eq!(check(&bad, Some(after)), Ok(Some(IssueKind::Corrupted)));
+        assert_eq!(check(&bad, None), Ok(Some(IssueKind::Corrupted)));
+
+        cancel.cancel();
+        assert_eq!(check(&good, Some(before)), Err(Cancelled));
+    }
+
+    #[test]
+    fn test_progress_counts_bytes_and_cancel_keeps_partial_results() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::write(tmp.path().join("a.esp"), b"aaaa").unwrap();
+        let mut db = ModlistDb::in_memory().unwrap();
+        let modlist: modlist::Modlist = serde_json::from_str(&format!(
+            r#"{{
+                "Name": "Test", "Version": "1.0", "WabbajackVersion": "3.0",
+                "GameType": "SkyrimSpecialEdition", "IsNSFW": false, "Archives": [],
+                "Directives": [
+                    {{"$type": "InlineFile", "To": "a.esp", "Hash": "{}", "Size": 4,
+                     "SourceDataID": "6f1c5a52-3c1e-4a7b-9a5c-0d2c4b1e8f00"}},
+                    {{"$type": "InlineFile", "To": "b.esp", "Hash": "x", "Size": 6,
+                     "SourceDataID": "6f1c5a52-3c1e-4a7b-9a5c-0d2c4b1e8f01"}}
+                ]
+            }}"#,
+            crate::hash::compute_bytes_hash(b"aaaa")
+        ))
+        .unwrap();
+        db.import_modlist(&modlist).unwrap();
+
+        let last = std::sync::Mutex::new(CheckProgress::default());
+        let progress = |p: CheckProgress| *last.lock().unwrap() = p;
+        let report = check_integrity_with_progress(
+            &db,
+            tmp.path(),
+            None,
+            &progress,
+            &CancellationToken::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            (report.checked, report.unchecked, report.issues.len()),
+            (2, 0, 1)
+        );
+        assert_eq!(
+            *last.lock().unwrap(),
+            CheckProgress {
+                files_done: 2,
+                files_total: 2,
+                bytes_done: 10,
+                bytes_total: 10,
+            }
+        );
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let report =
+            check_integrity_with_progress(&db, tmp.path(), None, &|_| {}, &cancel).unwrap();
+        assert_eq!((report.checked, report.unchecked), (0, 2));
+        assert!(report.render("Test").contains("2 files not checked"));
+    }
+}
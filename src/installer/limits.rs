@@ -0,0 +1,137 @@
+//! Linux resource limits that big installs run into.
+//!
+//! Extraction keeps many archives and output files open at once, and MO2's
+//! virtual filesystem under Proton watches the install with inotify. Distro
+//! defaults for both can be too low for a list with hundreds of thousands of
+//! files, and what fails ("Too many open files", MO2 not noticing changes)
+//! doesn't point at the limit. CLF3 raises its own open-file limit as far as
+//! it may and warns, with the commands to fix it, about anything still low.
+
+use std::path::Path;
+
+/// Open files CLF3's own extraction workers can need at once.
+const MIN_OPEN_FILES: u64 = 4096;
+
+/// Value suggested when raising either limit; what most current distros
+/// ship as the hard open-file limit, and plenty for any modlist.
+const RECOMMENDED_LIMIT: u64 = 524_288;
+
+/// How to raise the open-file limit.
+pub const OPEN_FILES_FIX: &str =
+    "Raise the open-file limit: add `DefaultLimitNOFILE=524288` under \
+     [Manager] in /etc/systemd/system.conf and /etc/systemd/user.conf, then log out and back in";
+
+/// A limit that's too low for the install, and how to raise it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimitWarning {
+    pub problem: String,
+    pub fix: String,
+}
+
+/// Raise the soft open-file limit to the hard limit, as most programs that
+/// open many files do. Returns the soft limit now in effect.
+pub fn raise_open_files_limit() -> Option<u64> {
+    let mut limit: libc::rlimit = unsafe { std::mem::zeroed() };
+    if unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit) } != 0 {
+        return None;
+    }
+    if limit.rlim_cur < limit.rlim_max {
+        let raised = libc::rlimit {
+            rlim_cur: limit.rlim_max,
+            rlim_max: limit.rlim_max,
+        };
+        // Fails for an unlimited hard limit above fs.nr_open; keep the old one.
+        if unsafe { libc::setrlimit(libc::RLIMIT_NOFILE, &raised) } == 0 {
+            return Some(limit.rlim_max);
+        }
+    }
+    Some(limit.rlim_cur)
+}
+
+/// Current soft open-file limit.
+fn open_files_limit() -> Option<u64> {
+    let mut limit: libc::rlimit = unsafe { std::mem::zeroed() };
+    (unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit) } == 0).then_some(limit.rlim_cur)
+}
+
+/// `fs.inotify.max_user_watches`.
+fn inotify_watches() -> Option<u64> {
+    std::fs::read_to_string(Path::new("/proc/sys/fs/inotify/max_user_watches"))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Limits too low for installing (and running) a list of `file_count`
+/// files. Limits that can't be read aren't reported.
+pub fn check_limits(file_count: u64) -> Vec<LimitWarning> {
+    evaluate(open_files_limit(), inotify_watches(), file_count)
+}
+
+fn evaluate(open_files: Option<u64>, watches: Option<u64>, file_count: u64) -> Vec<LimitWarning> {
+    let mut warnings = Vec::new();
+    if let Some(open_files) = open_files.filter(|&n| n < MIN_OPEN_FILES) {
+        warnings.push(LimitWarning {
+            problem: format!(
+                "Open-file limit is {}; extraction can fail with \"Too many open files\"",
+                open_files
+            ),
+            fix: OPEN_FILES_FIX.to_string(),
+        });
+    }
+    // Worst case MO2's VFS watches every file of the list.
+    if let Some(watches) = watches.filter(|&n| n < file_count) {
+        let suggested = RECOMMENDED_LIMIT.max(file_count.next_power_of_two());
+        warnings.push(LimitWarning {
+            problem: format!(
+                "inotify watch limit is {}, below this list's {} files; MO2 may miss \
+                 file changes",
+                watches, file_count
+            ),
+            fix: format!(
+                "echo fs.inotify.max_user_watches={} | sudo tee \
+                 /etc/sysctl.d/90-clf3-inotify.conf && sudo sysctl --system",
+                suggested
+            ),
+        });
+    }
+    warnings
+}
+
+/// Whether `err` comes from running out of file descriptors (EMFILE/ENFILE),
+/// directly or as reported by an external tool such as 7z.
+pub fn is_too_many_open_files(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        cause
+            .downcast_ref::<std::io::Error>()
+            .and_then(|e| e.raw_os_error())
+            .is_some_and(|code| code == libc::EMFILE || code == libc::ENFILE)
+            || cause.to_string().contains("Too many open files")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn low_limits_are_reported_with_a_fix() {
+        assert!(evaluate(Some(524_288), Some(1_000_000), 300_000).is_empty());
+        assert!(evaluate(None, None, 300_000).is_empty());
+
+        let warnings = evaluate(Some(1024), Some(65_536), 300_000);
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].fix, OPEN_FILES_FIX);
+        assert!(warnings[1].fix.contains("max_user_watches=524288"));
+
+        let huge = evaluate(None, Some(65_536), 700_000);
+        assert!(huge[0].fix.contains("max_user_watches=1048576"));
+
+        let emfile = anyhow::Error::new(std::io::Error::from_raw_os_error(libc::EMFILE))
+            .context("Failed to open foo.esp");
+        assert!(is_too_many_open_files(&emfile));
+        assert!(!is_too_many_open_files(&anyhow::anyhow!("disk full")));
+    }
+}
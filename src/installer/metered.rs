@@ -0,0 +1,84 @@
+//! Metered-connection handling.
+//!
+//! On a metered connection (mobile hotspot, capped plan) an install asks
+//! before downloading anything and keeps downloads conservative: a couple
+//! at a time and a capped total speed. "Metered" is the `--metered` flag or
+//! the `metered_connection` setting, or NetworkManager's own flag when
+//! `detect_metered_connection` is on.
+
+use crate::installer::status::archive_present;
+use crate::modlist::{DownloadState, Modlist};
+use crate::settings::Settings;
+use std::path::Path;
+use std::process::Command;
+
+/// Concurrent downloads on a metered connection, unless `--concurrent` says
+/// otherwise.
+pub const METERED_CONCURRENT_DOWNLOADS: usize = 2;
+
+/// Total download speed cap on a metered connection (bytes/sec), unless
+/// `--max-download-speed` says otherwise.
+pub const METERED_SPEED_LIMIT: u64 = 2 * 1024 * 1024;
+
+/// Whether to treat the connection as metered: `forced` (`--metered`), the
+/// `metered_connection` setting, or NetworkManager when detection is on.
+pub fn connection_is_metered(forced: bool, settings: &Settings) -> bool {
+    forced
+        || settings.metered_connection
+        || (settings.detect_metered_connection && network_manager_metered() == Some(true))
+}
+
+/// Whether NetworkManager reports the primary connection as metered.
+/// `None` when NetworkManager isn't running or can't tell.
+pub fn network_manager_metered() -> Option<bool> {
+    let output = Command::new("busctl")
+        .args([
+            "get-property",
+            "org.freedesktop.NetworkManager",
+            "/org/freedesktop/NetworkManager",
+            "org.freedesktop.NetworkManager",
+            "Metered",
+        ])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_metered_property(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse busctl's `u <NMMetered>` output. 1/3 are "yes"/"guess yes", 2/4
+/// "no"/"guess no" and 0 unknown.
+fn parse_metered_property(output: &str) -> Option<bool> {
+    let value: u32 = output.trim().strip_prefix("u ")?.trim().parse().ok()?;
+    match value {
+        1 | 3 => Some(true),
+        2 | 4 => Some(false),
+        _ => None,
+    }
+}
+
+/// Archives still to download for `modlist` and their total size. Game-file
+/// sources are copied from the game dir, not downloaded, and don't count.
+pub fn pending_downloads(modlist: &Modlist, downloads_dir: &Path) -> (usize, u64) {
+    modlist
+        .archives
+        .iter()
+        .filter(|a| !matches!(a.state, DownloadState::GameFileSource(_)))
+        .filter(|a| !archive_present(downloads_dir, &a.name, a.size))
+        .fold((0, 0), |(count, bytes), a| (count + 1, bytes + a.size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_network_manager_metered_states() {
+        assert_eq!(parse_metered_property("u 1\n"), Some(true));
+        assert_eq!(parse_metered_property("u 3"), Some(true));
+        assert_eq!(parse_metered_property("u 4\n"), Some(false));
+        assert_eq!(parse_metered_property("u 0\n"), None);
+        assert_eq!(parse_metered_property(""), None);
+    }
+}
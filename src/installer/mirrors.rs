@@ -0,0 +1,118 @@
+//! Alternate download URLs for archives whose primary source is dead.
+//!
+//! Mirrors come from two places:
+//! - `Mirrors` on an HTTP download state in the modlist itself
+//! - A user-supplied mirror file (`--mirrors <file>`) keyed by archive hash
+//!
+//! The mirror file is JSON mapping Wabbajack archive hashes to URL lists:
+//!
+//! ```json
+//! { "Xa1b2c3d4e5f6g7h8=": ["https://mirror.example/Mod-1-0.7z"] }
+//! ```
+//!
+//! Mirrors are only tried after the primary source has exhausted its retries,
+//! and every mirror download is hash-verified like any other.
+
+use crate::modlist::DownloadState;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Archive hash → alternate URLs, in the order they should be tried.
+#[derive(Debug, Clone, Default)]
+pub struct MirrorMap {
+    by_hash: HashMap<String, Vec<String>>,
+}
+
+impl MirrorMap {
+    /// Load a JSON mirror file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read mirror file {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Invalid mirror file {}", path.display()))
+    }
+
+    /// Parse the JSON mirror map.
+    pub fn parse(json: &str) -> Result<Self> {
+        let by_hash: HashMap<String, Vec<String>> =
+            serde_json::from_str(json).context("Expected an object of hash -> [urls]")?;
+        Ok(Self { by_hash })
+    }
+
+    /// Number of archives with at least one mirror.
+    pub fn len(&self) -> usize {
+        self.by_hash
+            .values()
+            .filter(|urls| !urls.is_empty())
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// All fallback URLs for an archive: modlist-provided mirrors first, then
+    /// the user's mirror file. The primary URL and duplicates are dropped.
+    pub fn urls_for(&self, state: &DownloadState, archive_hash: &str) -> Vec<String> {
+        let (primary, modlist_mirrors) = match state {
+            DownloadState::Http(http) => (Some(http.url.as_str()), http.mirrors.as_slice()),
+            _ => (None, &[][..]),
+        };
+        let user_mirrors = self
+            .by_hash
+            .get(archive_hash)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        let mut urls: Vec<String> = Vec::new();
+        for url in modlist_mirrors.iter().chain(user_mirrors) {
+            let url = url.trim();
+            if url.is_empty() || Some(url) == primary || urls.iter().any(|u| u == url) {
+                continue;
+            }
+            urls.push(url.to_string());
+        }
+        urls
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modlist::HttpState;
+
+    #[test]
+    fn modlist_mirrors_come_before_user_mirrors() {
+        let map = MirrorMap::parse(
+            r#"{"abc=": ["https://user.example/a.7z", "https://modlist.example/a.7z"]}"#,
+        )
+        .unwrap();
+        let state = DownloadState::Http(HttpState {
+            url: "https://dead.example/a.7z".into(),
+            headers: Vec::new(),
+            mirrors: vec![
+                "https://modlist.example/a.7z".into(),
+                "https://dead.example/a.7z".into(),
+            ],
+        });
+        assert_eq!(
+            map.urls_for(&state, "abc="),
+            vec!["https://modlist.example/a.7z", "https://user.example/a.7z"]
+        );
+        assert!(map.urls_for(&state, "other=").len() == 1);
+    }
+
+    #[test]
+    fn user_mirrors_apply_to_any_source() {
+        let map = MirrorMap::parse(r#"{"abc=": ["https://user.example/a.7z"], "x=": []}"#).unwrap();
+        assert_eq!(map.len(), 1);
+        let state = DownloadState::Mega(crate::modlist::MegaState {
+            url: "https://mega.nz/file/dead".into(),
+        });
+        assert_eq!(
+            map.urls_for(&state, "abc="),
+            vec!["https://user.example/a.7z"]
+        );
+        assert!(MirrorMap::parse("[1, 2]").is_err());
+    }
+}
@@ -0,0 +1,227 @@
+//! MO2 custom executables (`[customExecutables]` in `ModOrganizer.ini`)
+//!
+//! Lists ship the author's executables list: SKSE, ENB and BodySlide
+//! launchers, xEdit, LOOT. Paths Wabbajack tokenized come out right after
+//! placeholder expansion, but entries the author added by hand often keep
+//! their own absolute paths (`D:/Modlists/Tuxborn/tools/SSEEdit/SSEEdit.exe`).
+//! Those are matched against the install and game directories by the
+//! longest trailing part of the path that exists there, and rewritten so MO2
+//! launches the copy that was installed.
+
+use crate::paths::{resolve_case_insensitive, PathPlaceholders};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const SECTION: &str = "[customExecutables]";
+/// Keys holding a path; `arguments` is left alone.
+const PATH_KEYS: &[&str] = &["binary", "workingDirectory"];
+
+/// One configured executable.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CustomExecutable {
+    pub title: String,
+    pub binary: String,
+    pub arguments: String,
+    pub working_directory: String,
+}
+
+/// Result of [`remap_custom_executables`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RemappedExecutables {
+    /// The INI with remapped paths.
+    pub ini: String,
+    /// Paths that were rewritten.
+    pub remapped: usize,
+    /// `(title, path)` of paths found in neither directory, left as shipped.
+    pub unresolved: Vec<(String, String)>,
+}
+
+/// `N\key=value` in the executables section, split up.
+fn entry(line: &str) -> Option<(u32, &str, &str)> {
+    let (key, value) = line.split_once('=')?;
+    let (index, key) = key.trim().split_once('\\')?;
+    Some((index.parse().ok()?, key, value))
+}
+
+/// Lines of `ini`'s `[customExecutables]` section with their end-of-line
+/// kept, each flagged whether it is inside the section.
+fn lines_in_section(ini: &str) -> impl Iterator<Item = (bool, &str)> {
+    let mut inside = false;
+    ini.split_inclusive('\n').map(move |line| {
+        let body = line.trim();
+        if body.starts_with('[') {
+            inside = body.eq_ignore_ascii_case(SECTION);
+        }
+        (inside, line)
+    })
+}
+
+/// Executables in `ini`, in MO2's order.
+pub fn parse_custom_executables(ini: &str) -> Vec<CustomExecutable> {
+    let mut by_index: BTreeMap<u32, CustomExecutable> = BTreeMap::new();
+    for (inside, line) in lines_in_section(ini) {
+        let Some((index, key, value)) = inside.then(|| entry(line.trim_end())).flatten() else {
+            continue;
+        };
+        let exe = by_index.entry(index).or_default();
+        let value = unquote(value).to_string();
+        match key {
+            "title" => exe.title = value,
+            "binary" => exe.binary = value,
+            "arguments" => exe.arguments = value,
+            "workingDirectory" => exe.working_directory = value,
+            _ => {}
+        }
+    }
+    by_index.into_values().collect()
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// Where `path` (as written in the INI) is on this machine: itself if it
+/// already points at an existing file, else the longest trailing part of it
+/// found under one of `roots`.
+fn resolve(path: &str, placeholders: &PathPlaceholders, roots: &[&Path]) -> Option<PathBuf> {
+    if let Some(linux) = placeholders.linux_path(path).filter(|p| p.exists()) {
+        return Some(linux);
+    }
+    let without_drive = match path.as_bytes() {
+        [letter, b':', ..] if letter.is_ascii_alphabetic() => &path[2..],
+        _ => path,
+    };
+    let parts: Vec<&str> = without_drive
+        .split(['\\', '/'])
+        .filter(|s| !s.is_empty())
+        .collect();
+    (0..parts.len()).find_map(|start| {
+        let relative = parts[start..].join("/");
+        roots
+            .iter()
+            .find_map(|root| resolve_case_insensitive(root, &relative))
+    })
+}
+
+/// `ini` with every executable's binary and working directory pointed at
+/// the matching file under `roots` (install dir first, then the game dir).
+pub fn remap_custom_executables(
+    ini: &str,
+    placeholders: &PathPlaceholders,
+    roots: &[&Path],
+) -> RemappedExecutables {
+    let titles: BTreeMap<u32, String> = lines_in_section(ini)
+        .filter(|(inside, _)| *inside)
+        .filter_map(|(_, line)| entry(line.trim_end()))
+        .filter(|(_, key, _)| *key == "title")
+        .map(|(index, _, value)| (index, unquote(value).to_string()))
+        .collect();
+
+    let mut out = RemappedExecutables {
+        ini: String::with_capacity(ini.len()),
+        ..Default::default()
+    };
+    for (inside, line) in lines_in_section(ini) {
+        let body = line.trim_end_matches(['\r', '\n']);
+        let path_entry = inside
+            .then(|| entry(body))
+            .flatten()
+            .filter(|(_, key, value)| PATH_KEYS.contains(key) && !unquote(value).is_empty());
+        let Some((index, key, value)) = path_entry else {
+            out.ini.push_str(line);
+            continue;
+        };
+
+        let path = unquote(value);
+        match resolve(path, placeholders, roots) {
+            Some(found) => {
+                let windows = placeholders.windows_path(&found);
+                if windows != path {
+                    out.remapped += 1;
+                }
+                out.ini.push_str(&format!("{}\\{}={}", index, key, windows));
+            }
+            None => {
+                let title = titles.get(&index).cloned().unwrap_or_default();
+                out.unresolved.push((title, path.to_string()));
+                out.ini.push_str(body);
+            }
+        }
+        out.ini.push_str(&line[body.len()..]);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn custom_executables_are_parsed_and_remapped() {
+        let tmp = tempfile::tempdir().unwrap();
+        let install = tmp.path().join("Tuxborn");
+        let game = tmp.path().join("Skyrim Special Edition");
+        fs::create_dir_all(install.join("tools/SSEEdit")).unwrap();
+        fs::write(install.join("tools/SSEEdit/SSEEdit.exe"), b"").unwrap();
+        fs::create_dir_all(&game).unwrap();
+        fs::write(game.join("skse64_loader.exe"), b"").unwrap();
+        let placeholders = PathPlaceholders::new(&install, &tmp.path().join("dl"), &game);
+        let z = |path: &Path| placeholders.windows_path(path);
+
+        let ini = format!(
+            "[General]\r\n\
+             gameName=Skyrim Special Edition\r\n\
+             [customExecutables]\r\n\
+             size=4\r\n\
+             1\\title=SKSE\r\n\
+             1\\binary=C:/Program Files (x86)/Steam/steamapps/common/Skyrim Special Edition/skse64_loader.exe\r\n\
+             1\\workingDirectory=\r\n\
+             2\\title=SSEEdit\r\n\
+             2\\binary=D:\\Modlists\\Tuxborn\\tools\\SSEEdit\\sseedit.exe\r\n\
+             2\\arguments=\"-D:\\\"D:/Games/Skyrim/Data\\\"\"\r\n\
+             3\\title=Already right\r\n\
+             3\\binary={}\r\n\
+             4\\title=LOOT\r\n\
+             4\\binary=E:/Tools/LOOT/LOOT.exe\r\n\
+             [Settings]\r\n\
+             1\\binary=D:/not/an/executable.exe\r\n",
+            z(&install.join("tools/SSEEdit/SSEEdit.exe"))
+        );
+
+        let parsed = parse_custom_executables(&ini);
+        let titles: Vec<_> = parsed.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, ["SKSE", "SSEEdit", "Already right", "LOOT"]);
+        assert_eq!(parsed[1].arguments, "-D:\\\"D:/Games/Skyrim/Data\\\"");
+
+        let remapped = remap_custom_executables(&ini, &placeholders, &[&install, &game]);
+        assert_eq!(remapped.remapped, 2);
+        assert_eq!(
+            remapped.unresolved,
+            [("LOOT".to_string(), "E:/Tools/LOOT/LOOT.exe".to_string())]
+        );
+        let binaries: Vec<_> = parse_custom_executables(&remapped.ini)
+            .into_iter()
+            .map(|e| e.binary)
+            .collect();
+        assert_eq!(
+            binaries,
+            [
+                z(&game.join("skse64_loader.exe")),
+                z(&install.join("tools/SSEEdit/SSEEdit.exe")),
+                z(&install.join("tools/SSEEdit/SSEEdit.exe")),
+                "E:/Tools/LOOT/LOOT.exe".to_string(),
+            ]
+        );
+        assert!(remapped
+            .ini
+            .contains("\r\n[Settings]\r\n1\\binary=D:/not/an/executable.exe\r\n"));
+        assert_eq!(
+            remapped.ini.matches("\r\n").count(),
+            ini.matches("\r\n").count()
+        );
+    }
+}
@@ -0,0 +1,258 @@
+//! MO2 `meta.ini` generation for installed mods.
+//!
+//! Mod Organizer 2 reads `mods/<mod>/meta.ini` to show the mod's source,
+//! version and Nexus IDs, and uses those IDs to check for updates. Most
+//! Wabbajack modlists ship their own `meta.ini` as an inline directive; for
+//! the mods that don't, this module writes one after install using the source
+//! metadata of the archive that contributed the most files to the mod.
+//!
+//! Existing `meta.ini` files are never overwritten — the modlist author's copy
+//! always wins.
+
+use crate::downloaders::NexusDownloader;
+use crate::modlist::{ArchiveInfo, DownloadState, ModlistDb};
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tracing::{debug, warn};
+
+const META_INI: &str = "meta.ini";
+
+/// Counters reported after the meta.ini pass.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MetaIniStats {
+    /// meta.ini files written for mods that had none.
+    pub written: usize,
+    /// Mods that already had a meta.ini (from the modlist or a previous run).
+    pub existing: usize,
+    /// Mods whose source archive is not a Nexus download (minimal meta written).
+    pub without_id: usize,
+}
+
+/// Extract the mod folder name from a directive's `to` path.
+///
+/// Returns `Some("ModName")` for `mods\ModName\...` (either slash direction),
+/// `None` for anything outside `mods/` or for files directly in `mods/`.
+pub fn mod_dir_of(to_path: &str) -> Option<&str> {
+    split_mod_path(to_path).map(|(name, _)| name)
+}
+
+/// Split a directive's `to` path into the mod folder name and the path
+/// inside it: `mods\ModName\textures\a.dds` gives
+/// `("ModName", "textures\a.dds")`. Same rules as [`mod_dir_of`].
+pub fn split_mod_path(to_path: &str) -> Option<(&str, &str)> {
+    const SEPARATORS: [char; 2] = ['\\', '/'];
+    let (root, rest) = to_path
+        .trim_start_matches(SEPARATORS)
+        .split_once(SEPARATORS)?;
+    if !root.eq_ignore_ascii_case("mods") {
+        return None;
+    }
+    let (name, inner) = rest.trim_start_matches(SEPARATORS).split_once(SEPARATORS)?;
+    let inner = inner.trim_start_matches(SEPARATORS);
+    // Must have at least one more component — `mods/foo` alone is a file.
+    (!name.is_empty() && !inner.is_empty()).then_some((name, inner))
+}
+
+/// Render the MO2 `meta.ini` body for a mod installed from `archive`.
+///
+/// Nexus archives get the full `[General]` + `[installedFiles]` sections so
+/// MO2 can check for updates. Every other source gets a minimal meta with the
+/// installation file and (where it is a plain URL) the download URL.
+pub fn render_meta_ini(archive: &ArchiveInfo, state: Option<&DownloadState>) -> String {
+    let mut out = String::from("[General]\n");
+
+    match state {
+        Some(DownloadState::Nexus(nexus)) => {
+            out.push_str(&format!("gameName={}\n", nexus.game_name));
+            out.push_str(&format!("modid={}\n", nexus.mod_id));
+            out.push_str(&format!(
+                "version={}\n",
+                nexus.version.as_deref().unwrap_or_default()
+            ));
+            out.push_str("newestVersion=\n");
+            out.push_str(&format!("installationFile={}\n", archive.name));
+            out.push_str("repository=Nexus\n");
+            out.push_str(&format!(
+                "url=https://www.nexusmods.com/{}/mods/{}\n",
+                NexusDownloader::game_domain(&nexus.game_name),
+                nexus.mod_id
+            ));
+            out.push_str("\n[installedFiles]\n");
+            out.push_str(&format!("1\\modid={}\n", nexus.mod_id));
+            out.push_str(&format!("1\\fileid={}\n", nexus.file_id));
+            out.push_str("size=1\n");
+        }
+        other => {
+            out.push_str(&format!("installationFile={}\n", archive.name));
+            let url = match other {
+                Some(DownloadState::Http(s)) => Some(s.url.as_str()),
+                Some(DownloadState::Mega(s)) => Some(s.url.as_str()),
+                Some(DownloadState::MediaFire(s)) => Some(s.url.as_str()),
+                Some(DownloadState::Manual(s)) => Some(s.url.as_str()),
+                _ => None,
+            };
+            if let Some(url) = url {
+                out.push_str(&format!("url={}\n", url));
+            }
+        }
+    }
+
+    out
+}
+
+/// Write a `meta.ini` for every installed mod that doesn't already have one.
+///
+/// Each mod is attributed to the archive that sourced the most of its files;
+/// mods built purely from inline files have no archive and are skipped.
+pub fn write_mod_meta_files(db: &ModlistDb, output_dir: &Path) -> Result<MetaIniStats> {
+    let mut stats = MetaIniStats::default();
+
+    // mod name -> archive hash -> file count
+    let mut per_mod: HashMap<String, HashMap<String, usize>> = HashMap::new();
+    for (to_path, _size, archive_hash) in db.get_directive_outputs_with_archives()? {
+        if let Some(mod_name) = mod_dir_of(&to_path) {
+            *per_mod
+                .entry(mod_name.to_string())
+                .or_default()
+                .entry(archive_hash)
+                .or_default() += 1;
+        }
+    }
+
+    if per_mod.is_empty() {
+        return Ok(stats);
+    }
+
+    let archives: HashMap<String, ArchiveInfo> = db
+        .get_all_archives()?
+        .into_iter()
+        .map(|a| (a.hash.clone(), a))
+        .collect();
+
+    let mods_dir = output_dir.join("mods");
+    for (mod_name, sources) in per_mod {
+        let mod_dir = mods_dir.join(&mod_name);
+        if !mod_dir.is_dir() {
+            continue;
+        }
+        let meta_path = mod_dir.join(META_INI);
+        if meta_path.exists() {
+            stats.existing += 1;
+            continue;
+        }
+
+        // Ties break on hash so the choice is stable across runs.
+        let Some((primary_hash, _)) = sources
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+        else {
+            continue;
+        };
+        let Some(archive) = archives.get(primary_hash) else {
+            continue;
+        };
+
+        let state = match serde_json::from_str::<DownloadState>(&archive.state_json) {
+            Ok(state) => Some(state),
+            Err(e) => {
+                warn!(
+                    "Could not parse download state for {}: {} — writing minimal meta.ini",
+                    archive.name, e
+                );
+                None
+            }
+        };
+        if !matches!(state, Some(DownloadState::Nexus(_))) {
+            stats.without_id += 1;
+        }
+
+        let body = render_meta_ini(archive, state.as_ref());
+        fs::write(&meta_path, body)
+            .with_context(|| format!("Failed to write {}", meta_path.display()))?;
+        debug!("Wrote {} (source: {})", meta_path.display(), archive.name);
+        stats.written += 1;
+    }
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modlist::{HttpState, NexusState};
+
+    fn archive(name: &str) -> ArchiveInfo {
+        ArchiveInfo {
+            hash: "abc=".into(),
+            name: name.into(),
+            size: 1,
+            meta: String::new(),
+            state_json: String::new(),
+            download_status: "completed".into(),
+            extraction_status: "pending".into(),
+            local_path: None,
+            cached_url: None,
+            url_expires: None,
+        }
+    }
+
+    #[test]
+    fn mod_dir_of_handles_both_separators() {
+        assert_eq!(mod_dir_of("mods\\SkyUI\\SkyUI_SE.esp"), Some("SkyUI"));
+        assert_eq!(mod_dir_of("MODS/SkyUI/interface/a.swf"), Some("SkyUI"));
+        assert_eq!(mod_dir_of("profiles\\Default\\modlist.txt"), None);
+        assert_eq!(mod_dir_of("mods\\loose.txt"), None);
+    }
+
+    #[test]
+    fn nexus_meta_has_ids() {
+        let state = DownloadState::Nexus(NexusState {
+            game_name: "SkyrimSpecialEdition".into(),
+            mod_id: 12604,
+            file_id: 35407,
+            name: None,
+            author: None,
+            description: None,
+            version: Some("5.2SE".into()),
+            image_url: None,
+            is_nsfw: false,
+        });
+        let ini = render_meta_ini(&archive("SkyUI_5_2_SE.7z"), Some(&state));
+        assert!(ini.contains("modid=12604\n"));
+        assert!(ini.contains("version=5.2SE\n"));
+        assert!(ini.contains("1\\fileid=35407\n"));
+        assert!(ini.contains("repository=Nexus\n"));
+        assert!(ini.contains("url=https://www.nexusmods.com/skyrimspecialedition/mods/12604\n"));
+
+        // The Wabbajack game name isn't always the Nexus domain.
+        let state = DownloadState::Nexus(NexusState {
+            game_name: "FalloutNewVegas".into(),
+            mod_id: 66347,
+            file_id: 1,
+            name: None,
+            author: None,
+            description: None,
+            version: None,
+            image_url: None,
+            is_nsfw: false,
+        });
+        let ini = render_meta_ini(&archive("JIP LN NVSE.7z"), Some(&state));
+        assert!(ini.contains("gameName=FalloutNewVegas\n"));
+        assert!(ini.contains("url=https://www.nexusmods.com/newvegas/mods/66347\n"));
+    }
+
+    #[test]
+    fn non_nexus_meta_is_minimal() {
+        let state = DownloadState::Http(HttpState {
+            url: "https://example.test/file.zip".into(),
+            headers: Vec::new(),
+            mirrors: Vec::new(),
+        });
+        let ini = render_meta_ini(&archive("file.zip"), Some(&state));
+        assert!(!ini.contains("modid="));
+        assert!(ini.contains("installationFile=file.zip\n"));
+        assert!(ini.contains("url=https://example.test/file.zip\n"));
+    }
+}
@@ -10,33 +10,77 @@
 //! 6. BSA Build     — CreateBSA directives
 //! 7. Cleanup       — extra files + BSA temp dirs
 
+pub mod archive_store;
+pub mod bg3;
+pub mod browser_queue;
 pub mod bsa_reuse;
+pub mod clean;
 pub mod config;
 pub mod config_cache;
+pub mod conflicts;
+pub mod dedupe;
 pub mod downloader;
+pub mod failures;
 pub mod game_preflight;
+pub mod game_root;
 pub mod handlers;
+pub mod integrity;
+pub mod limits;
+pub mod metered;
+pub mod mirrors;
+pub mod mo2_executables;
+pub mod mo2_meta;
+pub mod mod_filter;
 pub mod pipeline;
 pub mod prevalidation;
 pub mod processor;
+pub mod profiles;
 pub mod progress;
+pub mod progress_channel;
 pub mod progress_cli;
 pub mod progress_json;
+pub mod purge;
+pub mod requirements;
+pub mod setup_check;
 pub mod sidecar;
+pub mod sources;
+pub mod space_guard;
+pub mod status;
+pub mod storage;
 pub mod streaming;
 
+pub use crate::error::InstallError;
+pub use archive_store::ArchiveStore;
 #[allow(unused_imports)] // ProgressCallback/ProgressEvent used by lib crate (GUI)
-pub use config::{ExtractStrategy, InstallConfig, ProgressCallback, ProgressEvent};
+pub use config::{
+    state_db_path, ConfigError, DownloadOrder, ExtractStrategy, HashMismatchAction, InstallConfig,
+    InstallPhase, ProgressCallback, ProgressEvent, RetryPolicy,
+};
 #[allow(unused_imports)] // Used by lib crate (GUI)
 pub use config_cache::{ConfigCache, ModlistConfig};
+pub use dedupe::LinkMode;
+pub use mirrors::MirrorMap;
+pub use mod_filter::ModFilter;
 #[allow(unused_imports)] // NullReporter used by lib crate (GUI)
 pub use progress::{NullReporter, Phase, ProgressHandle, ProgressMode, ProgressReporter};
+#[allow(unused_imports)] // Used by lib crate (embedders)
+pub use progress_channel::ChannelReporter;
 pub use progress_cli::CliReporter;
 pub use progress_json::{JsonEventWriter, JsonReporter};
-
+pub use purge::PurgeDownloads;
+#[allow(unused_imports)] // Used by lib crate (embedders)
+pub use sources::{resolve_archive_sources, ResolvedArchive, ResolvedSource};
+pub use status::InstallStatus;
+
+use crate::game_finder::{
+    compare_runtime, dlc_report, find_compatdata, installed_game_version, is_dlc_file,
+    runtime_edition, validate_game_path, DlcStatus, GameType, RuntimeMatch,
+};
 use crate::modlist::{import_wabbajack_to_db, ModlistDb};
 use anyhow::{bail, Context, Result};
+use std::collections::HashSet;
 use std::fs;
+use std::path::Path;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
@@ -247,6 +291,41 @@ pub struct InstallStats {
     pub manual_downloads: Vec<downloader::ManualDownloadInfo>,
     /// Phase durations in seconds
     pub phase_durations: Vec<(String, f64)>,
+    /// Directive failures by type, most frequent first
+    pub failures_by_type: Vec<failures::TypeFailures>,
+}
+
+impl InstallStats {
+    /// `failures_by_type`, plus an "Other" entry for failures no site
+    /// attributed to a type, so the counts add up to `directives_failed`.
+    pub fn failure_breakdown(&self) -> Vec<failures::TypeFailures> {
+        let mut out = self.failures_by_type.clone();
+        let attributed: usize = out.iter().map(|f| f.count).sum();
+        if attributed < self.directives_failed {
+            out.push(failures::TypeFailures {
+                directive_type: "Other".to_string(),
+                count: self.directives_failed - attributed,
+                first_error: "see the log file".to_string(),
+            });
+        }
+        out
+    }
+
+    /// Manual downloads whose source file was deleted or archived upstream.
+    pub fn removed_source_count(&self) -> usize {
+        self.manual_downloads
+            .iter()
+            .filter(|md| md.source_removed)
+            .count()
+    }
+
+    /// Failed downloads another run may fetch (see `--auto-retry`).
+    pub fn retryable_failures(&self) -> usize {
+        self.failed_downloads
+            .iter()
+            .filter(|fd| fd.retryable)
+            .count()
+    }
 }
 
 /// Main installer orchestrator
@@ -255,11 +334,45 @@ pub struct Installer {
     db: ModlistDb,
 }
 
+/// Modlist name from the .wabbajack metadata, or the file name.
+fn modlist_name(db: &ModlistDb, wabbajack_path: &Path) -> String {
+    db.get_metadata("name")
+        .ok()
+        .flatten()
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| {
+            wabbajack_path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "Modlist".to_string())
+        })
+}
+
 impl Installer {
     /// Create a new installer with the given configuration
-    pub fn new(config: InstallConfig) -> Result<Self> {
+    pub fn new(config: InstallConfig) -> std::result::Result<Self, InstallError> {
+        Self::init(config).map_err(InstallError::from)
+    }
+
+    /// Modlist name from the .wabbajack metadata, or the file name.
+    pub fn modlist_name(&self) -> String {
+        modlist_name(&self.db, &self.config.wabbajack_path)
+    }
+
+    fn init(mut config: InstallConfig) -> Result<Self> {
         // Validate config
         config.validate()?;
+        crate::textures::set_gpu_index(config.gpu_index);
+        crate::textures::set_encoder_mode(config.texture_encoder);
+        if config.texture_encoder == crate::textures::EncoderMode::Gpu {
+            crate::textures::init_gpu()?;
+            if !crate::textures::gpu_encoder_active() {
+                bail!(
+                    "GPU texture encoding is forced but no usable GPU was found. \
+                     Run `clf3 select-gpu auto` (or cpu) to allow CPU encoding."
+                );
+            }
+        }
 
         // Create output and downloads directories if needed
         fs::create_dir_all(&config.output_dir).with_context(|| {
@@ -279,7 +392,24 @@ impl Installer {
         config
             .reporter
             .log(&format!("Parsing: {}", config.wabbajack_path.display()));
-        let db = import_wabbajack_to_db(&config.wabbajack_path, &config.db_path())?;
+        let mut db = import_wabbajack_to_db(&config.wabbajack_path, &config.db_path())?;
+
+        if !config.mod_filter.is_empty() {
+            let filtered =
+                mod_filter::apply_mod_filter(&mut db, &config.wabbajack_path, &config.mod_filter)?;
+            config.reporter.log(
+                "WARNING: PARTIAL INSTALL. Skipping mods breaks load-order and patch \
+                 assumptions; modlist authors do not support partial installs. Do not \
+                 report issues from this install to them.",
+            );
+            config.reporter.log(&format!(
+                "Mod filter: installing {} mods, skipping {} ({} directives dropped)",
+                filtered.mods_kept, filtered.mods_skipped, filtered.directives_removed
+            ));
+            for name in &filtered.skipped_mods {
+                info!("Skipped by mod filter: {}", name);
+            }
+        }
 
         // Show modlist info
         if let (Some(name), Some(version)) = (db.get_metadata("name")?, db.get_metadata("version")?)
@@ -289,6 +419,29 @@ impl Installer {
                 .log(&format!("Modlist: {} v{}", name, version));
         }
 
+        // Refuse to install on top of a different modlist's finished install.
+        if !config.force {
+            if let Some(existing) = crate::modlist::InstallManifest::load_from(&config.output_dir)?
+            {
+                let name = db.get_metadata("name")?.unwrap_or_default();
+                let machine_name = config.machine_name.as_deref().unwrap_or_default();
+                if !existing.is_same_modlist(machine_name, &name) {
+                    return Err(ConfigError::InstallDirInUse {
+                        dir: config.output_dir.clone(),
+                        name: existing.name,
+                        version: existing.installed_version,
+                    }
+                    .into());
+                }
+            }
+        }
+
+        let modlist_name = modlist_name(&db, &config.wabbajack_path);
+        config.profile = profiles::resolve_profile(&db, &modlist_name, config.profile.as_deref())?;
+        if let Some(profile) = &config.profile {
+            config.reporter.log(&format!("MO2 profile: {}", profile));
+        }
+
         let stats = db.get_directive_stats()?;
         config.reporter.log(&format!(
             "Directives: {} total ({} pending)\n",
@@ -298,13 +451,109 @@ impl Installer {
         Ok(Self { config, db })
     }
 
+    /// Log which official DLC are installed, flagging any the modlist pulls
+    /// game files from. The preflight failure that follows names the exact
+    /// files; this tells the user which DLC to buy or re-download.
+    fn log_dlc_report(&self, game: GameType, preflight: &game_preflight::PreflightReport) {
+        let report: Vec<DlcStatus> = dlc_report(game, &self.config.game_dir);
+        if report.is_empty() {
+            return;
+        }
+        let needed = |dlc: &DlcStatus| preflight.checks.iter().any(|c| is_dlc_file(dlc, &c.file));
+        let line = report
+            .iter()
+            .map(|dlc| {
+                let state = match (dlc.present, needed(dlc)) {
+                    (true, _) => "installed",
+                    (false, true) => "MISSING (required by modlist)",
+                    (false, false) => "not installed",
+                };
+                format!("{}: {}", dlc.name, state)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.reporter().log(&format!("DLC: {}", line));
+    }
+
+    /// Refuse to continue when the installed runtime line (e.g. Skyrim AE)
+    /// differs from the one the modlist was built for (e.g. pre-AE): the
+    /// install would succeed but the game would crash on launch.
+    fn check_runtime(&self, game: GameType, required: &str) -> Result<()> {
+        let Some(installed) = installed_game_version(game, &self.config.game_dir) else {
+            return Ok(());
+        };
+        let edition = |v: &str| {
+            runtime_edition(game, v)
+                .map(|e| format!("{} ({})", v, e))
+                .unwrap_or_else(|| v.to_string())
+        };
+        self.reporter().log(&format!(
+            "Game version: {}, modlist built for {}",
+            edition(&installed),
+            edition(required)
+        ));
+        match compare_runtime(game, required, &installed) {
+            RuntimeMatch::Compatible => Ok(()),
+            RuntimeMatch::DifferentPatch => {
+                warn!(
+                    "{} {} differs from the modlist's {}; game file checks may fail",
+                    game, installed, required
+                );
+                Ok(())
+            }
+            RuntimeMatch::WrongEdition {
+                required: wanted,
+                installed: have,
+                fix,
+            } => bail!(InstallError::msg(
+                InstallError::MissingGameFile,
+                format!(
+                    "This modlist needs the {} {} runtime ({}), but {} is installed. \
+                     It would crash on launch. {}.",
+                    wanted, game, required, have, fix
+                )
+            )),
+        }
+    }
+
     fn reporter(&self) -> &Arc<dyn ProgressReporter> {
         &self.config.reporter
     }
 
+    /// `--offline`: refuse to start unless every archive the install still
+    /// needs is already in the downloads dir.
+    ///
+    /// Returns the scan of needed archives in offline mode so the download
+    /// phase doesn't scan the output dir again; None when online.
+    fn check_offline_archives(&self) -> Result<Option<downloader::NeededArchives>> {
+        if !self.config.offline {
+            return Ok(None);
+        }
+        let needed = downloader::needed_archive_hashes(&self.db, &self.config)?;
+        let missing = downloader::missing_local_archives(&self.db, &self.config, &needed)?;
+        if missing.is_empty() {
+            self.reporter()
+                .log("Offline mode: all needed archives are available locally");
+            return Ok(Some(needed));
+        }
+        self.reporter()
+            .log("Archives missing from the downloads dir:");
+        for name in &missing {
+            self.reporter().log(&format!("  {}", name));
+        }
+        bail!(
+            "Offline mode: {} needed archives are not in {}. Copy them there or run without --offline",
+            missing.len(),
+            self.config.downloads_dir.display()
+        );
+    }
+
     /// Download all required archives
-    async fn download_phase(&mut self) -> Result<downloader::DownloadStats> {
-        downloader::download_archives(&self.db, &self.config).await
+    async fn download_phase(
+        &mut self,
+        needed: Option<downloader::NeededArchives>,
+    ) -> Result<downloader::DownloadStats> {
+        downloader::download_archives(&self.db, &self.config, needed).await
     }
 
     /// Validate downloaded archives that haven't been verified yet.
@@ -417,12 +666,53 @@ impl Installer {
         Ok(errors)
     }
 
+    /// Download (and hash-verify) every archive the install needs, then stop.
+    ///
+    /// No extraction or directive work happens. Verified archives get a hash
+    /// sidecar, so a later full install skips straight past them.
+    pub async fn run_downloads_only(&mut self) -> std::result::Result<InstallStats, InstallError> {
+        self.downloads_only().await.map_err(InstallError::from)
+    }
+
+    async fn downloads_only(&mut self) -> Result<InstallStats> {
+        let needed = self.check_offline_archives()?;
+        let mut stats = InstallStats::default();
+        let total_start = Instant::now();
+
+        let download_start = Instant::now();
+        self.reporter().phase_start(Phase::Downloading);
+        let download_stats = self.download_phase(needed).await?;
+        log_phase_metrics("Download Only", download_start);
+
+        stats.archives_downloaded = download_stats.downloaded;
+        stats.archives_skipped = download_stats.skipped;
+        stats.archives_failed = download_stats.failed;
+        stats.archives_manual = download_stats.manual;
+        stats.failed_downloads = download_stats.failed_downloads;
+        stats.manual_downloads = download_stats.manual_downloads;
+        stats
+            .phase_durations
+            .push(("Download".into(), download_start.elapsed().as_secs_f64()));
+
+        log_install_summary(&stats, total_start, &self.config.reporter);
+
+        Ok(stats)
+    }
+
     /// Run pipelined installation: download and extract in parallel.
     ///
     /// Instead of downloading all archives first, this processes each archive
     /// as soon as it finishes downloading. Texture and BSA phases still run
     /// sequentially after all extraction completes (Phase 1 MVP).
-    pub async fn run_pipelined(&mut self) -> Result<InstallStats> {
+    pub async fn run_pipelined(&mut self) -> std::result::Result<InstallStats, InstallError> {
+        self.pipelined().await.map_err(InstallError::from)
+    }
+
+    async fn pipelined(&mut self) -> Result<InstallStats> {
+        if self.config.last_phase == InstallPhase::Downloads {
+            return self.downloads_only().await;
+        }
+        let needed = self.check_offline_archives()?;
         let mut stats = InstallStats::default();
         let total_start = Instant::now();
 
@@ -434,12 +724,25 @@ impl Installer {
             self.config.game_dir.display()
         ));
         if !self.config.game_dir.exists() {
-            bail!(
-                "Game directory does not exist: {}",
-                self.config.game_dir.display()
-            );
+            bail!(InstallError::msg(
+                InstallError::MissingGameFile,
+                format!(
+                    "Game directory does not exist: {}",
+                    self.config.game_dir.display()
+                )
+            ));
+        }
+        let game_type = self
+            .db
+            .get_metadata("game_type")?
+            .and_then(|name| GameType::from_wabbajack_name(&name));
+        if let Some(game) = game_type {
+            validate_game_path(game, &self.config.game_dir)?;
+            self.reporter()
+                .log(&format!("Game directory validated ({})", game));
+        } else {
+            self.reporter().log("Game directory validated");
         }
-        self.reporter().log("Game directory validated");
 
         // Hash every GameFileSource archive against the chosen game directory
         // BEFORE spending bandwidth. Catches: game updated since modlist
@@ -447,6 +750,12 @@ impl Installer {
         //
         // Runs parallel via rayon; typical cost <2s for Bethesda modlists.
         let preflight = game_preflight::check_game_files_from_db(&self.db, &self.config.game_dir)?;
+        if let Some(game) = game_type {
+            self.log_dlc_report(game, &preflight);
+            if let Some(required) = &preflight.game_version {
+                self.check_runtime(game, required)?;
+            }
+        }
         if preflight.total == 0 {
             self.reporter()
                 .log("No game files required by this modlist — skipping hash preflight");
@@ -461,16 +770,28 @@ impl Installer {
                 for line in preflight.format_summary().lines() {
                     self.reporter().log(line);
                 }
-                bail!(
-                    "Game file preflight failed: {} missing, {} hash mismatch. \
-                     Game likely updated or wrong store version — no downloads started. \
-                     Fix game files and re-run.",
-                    preflight.missing().len(),
-                    preflight.mismatched().len()
-                );
+                bail!(InstallError::msg(
+                    InstallError::MissingGameFile,
+                    format!(
+                        "Game file preflight failed: {} missing, {} hash mismatch. \
+                         Game likely updated or wrong store version — no downloads started. \
+                         Fix game files and re-run.",
+                        preflight.missing().len(),
+                        preflight.mismatched().len()
+                    )
+                ));
             }
         }
 
+        let file_count = self.db.get_directive_stats()?.total as u64;
+        for warning in limits::check_limits(file_count) {
+            warn!("{}", warning.problem);
+            self.reporter().log(&format!(
+                "Warning: {}. To fix: {}",
+                warning.problem, warning.fix
+            ));
+        }
+
         log_phase_metrics("Game Check", game_check_start);
 
         // === Phase 2: Pipelined Download + Extract ===
@@ -485,9 +806,13 @@ impl Installer {
             &self.db,
             &dp.ctx.existing_files,
             &self.config.output_dir,
+            self.config.verify_outputs,
             &self.config.reporter,
         )?;
         prevalidation_result.log_summary(&self.config.reporter);
+        for path in &prevalidation_result.corrupt_outputs {
+            dp.ctx.existing_files.remove(path);
+        }
         dp.ctx.prevalidation_stats = prevalidation_result.stats_as_tuples();
         dp.ctx.extra_files_for_cleanup = prevalidation_result.extra_files;
         dp.ctx.skip_set = prevalidation_result.skip_set;
@@ -555,6 +880,7 @@ impl Installer {
                     &config_clone,
                     &tx,
                     Some(&priority_map),
+                    needed,
                 )
                 .await
             })
@@ -619,6 +945,7 @@ impl Installer {
         stats.directives_completed = streaming_stats.extracted + streaming_stats.written;
         stats.directives_skipped = streaming_stats.skipped;
         stats.directives_failed = streaming_stats.failed;
+        stats.failures_by_type = dp.ctx.failures.summary();
 
         if stats.archives_manual > 0 || stats.archives_failed > 0 {
             log_phase_metrics("Pipelined Download+Extract", pipeline_start);
@@ -636,6 +963,9 @@ impl Installer {
         stats
             .phase_durations
             .push(("Download+Extract".into(), pipeline_secs));
+        if self.config.last_phase == InstallPhase::Extract {
+            return Ok(self.stop_after_phase(stats, total_start));
+        }
 
         // === Phase 3: InlineFile + RemappedInlineFile ===
         let inline_start = Instant::now();
@@ -682,6 +1012,14 @@ impl Installer {
                 .phase_durations
                 .push(("BSA Build".into(), bsa_start.elapsed().as_secs_f64()));
         }
+        if self.config.last_phase == InstallPhase::Directives {
+            stats.failures_by_type = dp.ctx.failures.summary();
+            let process_stats = dp.finish();
+            stats.directives_completed += process_stats.completed;
+            stats.directives_skipped += process_stats.skipped;
+            stats.directives_failed += process_stats.failed;
+            return Ok(self.stop_after_phase(stats, total_start));
+        }
 
         // === Phase 6: Cleanup ===
         let cleanup_start = Instant::now();
@@ -693,6 +1031,33 @@ impl Installer {
             .phase_durations
             .push(("Cleanup".into(), cleanup_start.elapsed().as_secs_f64()));
 
+        // Cleanup removes anything not produced by a directive, so generated
+        // meta.ini files must be written after it.
+        match mo2_meta::write_mod_meta_files(&self.db, &self.config.output_dir) {
+            Ok(meta) if meta.written > 0 => self.reporter().log(&format!(
+                "Wrote {} MO2 meta.ini files ({} already present, {} without Nexus IDs)",
+                meta.written, meta.existing, meta.without_id
+            )),
+            Ok(_) => {}
+            Err(e) => warn!("Failed to write MO2 meta.ini files: {:#}", e),
+        }
+
+        match dedupe::link_duplicate_outputs(
+            &self.db,
+            &self.config.output_dir,
+            self.config.link_mode,
+        ) {
+            Ok(linked) if linked.linked > 0 => self.reporter().log(&format!(
+                "Linked {} duplicate files ({:?}), saving {}",
+                linked.linked,
+                self.config.link_mode,
+                progress_cli::format_bytes(linked.bytes_saved)
+            )),
+            Ok(_) => {}
+            Err(e) => warn!("Failed to link duplicate outputs: {:#}", e),
+        }
+
+        stats.failures_by_type = dp.ctx.failures.summary();
         let process_stats = dp.finish();
         stats.directives_completed += process_stats.completed;
         stats.directives_skipped += process_stats.skipped;
@@ -715,9 +1080,22 @@ impl Installer {
             && stats.archives_failed == 0
             && stats.directives_failed == 0;
         if install_succeeded {
+            self.fix_custom_executables();
+            self.deploy_game_mods();
+            if let Some(profile) = &self.config.profile {
+                self.select_profile(profile);
+            }
+            if self.config.report_conflicts {
+                self.report_conflicts();
+            }
             if let Err(e) = self.write_post_install_manifest() {
                 warn!("Failed to write install manifest: {:#}", e);
             }
+            if self.config.purge_downloads != PurgeDownloads::Keep {
+                if let Err(e) = self.purge_downloads() {
+                    warn!("Failed to purge downloads: {:#}", e);
+                }
+            }
         }
 
         log_install_summary(&stats, total_start, &self.config.reporter);
@@ -725,6 +1103,255 @@ impl Installer {
         Ok(stats)
     }
 
+    /// Finish a run cut short by `--phase`. Cleanup, meta.ini files and the
+    /// install manifest are skipped; a later full run picks up from here.
+    fn stop_after_phase(&self, stats: InstallStats, total_start: Instant) -> InstallStats {
+        self.reporter().log(&format!(
+            "Stopping after the {:?} phase (--phase).",
+            self.config.last_phase
+        ));
+        log_install_summary(&stats, total_start, &self.config.reporter);
+        stats
+    }
+
+    /// Point the shipped MO2 executables at the installed tools and game.
+    fn fix_custom_executables(&self) {
+        let ini_path = self.config.output_dir.join("ModOrganizer.ini");
+        let Ok(ini) = fs::read_to_string(&ini_path) else {
+            return;
+        };
+        let mut placeholders = crate::paths::PathPlaceholders::new(
+            &self.config.output_dir,
+            &self.config.downloads_dir,
+            &self.config.game_dir,
+        );
+        if let Some(prefix) = &self.config.wine_prefix {
+            placeholders = placeholders.with_wine_prefix(prefix);
+        }
+        let roots = [
+            self.config.output_dir.as_path(),
+            self.config.game_dir.as_path(),
+        ];
+        let fixed = mo2_executables::remap_custom_executables(&ini, &placeholders, &roots);
+        for (title, path) in &fixed.unresolved {
+            warn!(
+                "MO2 executable '{}' points outside the install and game folders: {}",
+                title, path
+            );
+        }
+        if fixed.remapped == 0 {
+            return;
+        }
+        if let Err(e) = fs::write(&ini_path, &fixed.ini) {
+            warn!("Failed to update MO2 executables: {}", e);
+            return;
+        }
+        self.reporter().log(&format!(
+            "Pointed {} MO2 executable path(s) at this install",
+            fixed.remapped
+        ));
+    }
+
+    /// Put mods MO2 can't serve under Wine where the game loads them, by
+    /// the game's layout.
+    fn deploy_game_mods(&self) {
+        let game_type = self
+            .db
+            .get_metadata("game_type")
+            .ok()
+            .flatten()
+            .and_then(|name| GameType::from_wabbajack_name(&name));
+        match game_type {
+            Some(GameType::BaldursGate3) => self.deploy_bg3_mods(),
+            _ => self.place_game_root_files(),
+        }
+    }
+
+    /// Copy BG3 paks into the prefix's Mods folder and list them in
+    /// modsettings.lsx.
+    fn deploy_bg3_mods(&self) {
+        let output_dir = &self.config.output_dir;
+        let mods = match bg3::bg3_mods_for_install(output_dir) {
+            Ok(mods) if !mods.paks.is_empty() => mods,
+            Ok(_) => return,
+            Err(e) => {
+                info!("Skipped BG3 mod deployment: {:#}", e);
+                return;
+            }
+        };
+        let prefix = self.config.wine_prefix.clone().or_else(|| {
+            GameType::BaldursGate3
+                .app_ids()
+                .iter()
+                .find_map(|id| find_compatdata(&id.to_string()))
+        });
+        let Some(prefix) = prefix else {
+            warn!(
+                "{} BG3 pak(s) need the game's Wine prefix; pass --wine-prefix or launch the game once and install again",
+                mods.paks.len()
+            );
+            return;
+        };
+        let larian_dir = bg3::larian_dir(&prefix);
+        match bg3::deploy(&mods, &larian_dir) {
+            Ok(deployed) => {
+                self.reporter().log(&format!(
+                    "Deployed {} BG3 pak(s) to {} ({} newly enabled in modsettings.lsx)",
+                    deployed.paks,
+                    larian_dir.join("Mods").display(),
+                    deployed.activated
+                ));
+                if !mods.without_info.is_empty() {
+                    self.reporter().log(&format!(
+                        "No info.json for {}; enable these in a mod manager",
+                        mods.without_info.join(", ")
+                    ));
+                }
+            }
+            Err(e) => warn!("Failed to deploy BG3 mods: {:#}", e),
+        }
+    }
+
+    /// Copy ENB/ReShade files from Root Builder folders into the game root,
+    /// backing up the game files they replace.
+    fn place_game_root_files(&self) {
+        let output_dir = &self.config.output_dir;
+        let files = match game_root::root_files_for_install(output_dir) {
+            Ok(files) if !files.is_empty() => files,
+            Ok(_) => return,
+            Err(e) => {
+                info!("Skipped ENB/ReShade placement: {:#}", e);
+                return;
+            }
+        };
+        let root = game_root::game_root(output_dir, &self.config.game_dir);
+        if root.as_os_str().is_empty() || !root.is_dir() {
+            warn!(
+                "{} ENB/ReShade file(s) need the game root, but no game directory is set",
+                files.len()
+            );
+            return;
+        }
+        let backups = output_dir.join(game_root::BACKUP_DIR);
+        let placed = match game_root::place_root_files(&files, &root, &backups) {
+            Ok(placed) => placed,
+            Err(e) => {
+                warn!(
+                    "Failed to place ENB/ReShade files in the game root: {:#}",
+                    e
+                );
+                return;
+            }
+        };
+        for file in &files {
+            if placed.placed.contains(&file.relative) {
+                self.reporter().log(&format!(
+                    "Placed in game root: {} (from {})",
+                    file.relative.display(),
+                    file.mod_name
+                ));
+            }
+        }
+        if !placed.backed_up.is_empty() {
+            self.reporter().log(&format!(
+                "Backed up {} replaced game file(s) to {}",
+                placed.backed_up.len(),
+                backups.display()
+            ));
+        }
+    }
+
+    /// Point ModOrganizer.ini at `profile` and remember the choice.
+    fn select_profile(&self, profile: &str) {
+        if let Err(e) = profiles::apply_profile(&self.config.output_dir, profile) {
+            warn!("Failed to select MO2 profile {}: {:#}", profile, e);
+            return;
+        }
+        self.reporter()
+            .log(&format!("Selected MO2 profile: {}", profile));
+        let name = self.modlist_name();
+        let version = self
+            .db
+            .get_metadata("version")
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        if let Err(e) = profiles::remember_profile(&name, &version, profile) {
+            info!("Failed to cache profile choice: {:#}", e);
+        }
+    }
+
+    /// Log a conflict summary and write the full report into the install
+    /// dir (`--report-conflicts`).
+    fn report_conflicts(&self) {
+        let report = match conflicts::conflict_report(&self.db, &self.config.output_dir) {
+            Ok(report) => report,
+            Err(e) => {
+                warn!("Failed to work out mod conflicts: {:#}", e);
+                return;
+            }
+        };
+        if report.conflicts.is_empty() {
+            self.reporter().log(&format!(
+                "No loose-file conflicts between mods in profile '{}'",
+                report.profile
+            ));
+            return;
+        }
+        let path = self.config.output_dir.join(conflicts::REPORT_FILENAME);
+        if let Err(e) = fs::write(&path, report.render()) {
+            warn!("Failed to write {}: {}", path.display(), e);
+            return;
+        }
+        self.reporter().log(&format!(
+            "{} files are shipped by more than one of {} mods in profile '{}'. \
+             Winners per file: {}",
+            report.conflicts.len(),
+            report.mods_involved(),
+            report.profile,
+            path.display()
+        ));
+    }
+
+    /// Delete downloads per `--purge-downloads`. Only called after a clean
+    /// install, so nothing still needed for this modlist is lost.
+    fn purge_downloads(&self) -> Result<()> {
+        let referenced: HashSet<String> = self
+            .db
+            .get_all_archives()?
+            .into_iter()
+            .map(|a| a.name)
+            .collect();
+        let (files, total) = purge::purge_candidates(
+            &self.config.downloads_dir,
+            &referenced,
+            self.config.purge_downloads,
+        )?;
+        if files.is_empty() {
+            self.reporter().log("Nothing to purge from downloads.");
+            return Ok(());
+        }
+        self.reporter().log(&format!(
+            "Purging {} files from {} ({} will be freed)",
+            files.len(),
+            self.config.downloads_dir.display(),
+            progress_cli::format_bytes(total)
+        ));
+        let mut freed = 0;
+        for file in &files {
+            let size = fs::metadata(file).map(|m| m.len()).unwrap_or(0);
+            match fs::remove_file(file) {
+                Ok(()) => freed += size,
+                Err(e) => warn!("Failed to delete {}: {}", file.display(), e),
+            }
+        }
+        self.reporter().log(&format!(
+            "Freed {} from downloads.",
+            progress_cli::format_bytes(freed)
+        ));
+        Ok(())
+    }
+
     /// Persist `.clf3-install.json` next to the install + mirror it into
     /// settings. Pulls the modlist `name` and `installed_version` from the
     /// state DB metadata populated during import.
@@ -733,7 +1360,7 @@ impl Installer {
         let version = self.db.get_metadata("version")?.unwrap_or_default();
         let machine_name = self.config.machine_name.clone().unwrap_or_default();
 
-        let manifest = crate::modlist::InstallManifest::new(
+        let mut manifest = crate::modlist::InstallManifest::new(
             machine_name.clone(),
             name.clone(),
             version.clone(),
@@ -741,6 +1368,11 @@ impl Installer {
             self.config.downloads_dir.clone(),
             self.config.output_dir.clone(),
         );
+        manifest.wabbajack_path = Some(
+            fs::canonicalize(&self.config.wabbajack_path)
+                .unwrap_or_else(|_| self.config.wabbajack_path.clone()),
+        );
+        manifest.wine_prefix = self.config.wine_prefix.clone();
         manifest.save_to(&self.config.output_dir)?;
         info!(
             "Wrote install manifest: {}",
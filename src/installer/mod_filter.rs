@@ -0,0 +1,309 @@
+//! Partial installs: include/exclude mods by name or category.
+//!
+//! A filter is applied to the state DB right after import. Directives whose
+//! output lands in an excluded `mods/<mod>/` folder are removed from the DB,
+//! so the download phase never sees their archives and the extraction phases
+//! never see their outputs. Everything outside `mods/` (profiles, root game
+//! files, ModOrganizer.ini) is always installed.
+//!
+//! "Category" is the MO2 separator a mod sits under in the modlist's
+//! `profiles/<profile>/modlist.txt`, which is how modlist authors group their
+//! lists (e.g. `Textures_separator`).
+//!
+//! Partial installs are NOT supported by modlist authors — load order and
+//! patches assume every mod is present.
+
+use super::mo2_meta::mod_dir_of;
+use crate::modlist::{Directive, ModlistDb};
+use anyhow::{Context, Result};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use tracing::{info, warn};
+use zip::ZipArchive;
+
+/// MO2 marks separators as mods with this suffix.
+const SEPARATOR_SUFFIX: &str = "_separator";
+
+/// Case-insensitive substring filter over mod name, category and source
+/// archive names.
+#[derive(Debug, Clone, Default)]
+pub struct ModFilter {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl ModFilter {
+    pub fn new(include: Vec<String>, exclude: Vec<String>) -> Self {
+        let normalize = |v: Vec<String>| -> Vec<String> {
+            v.into_iter()
+                .map(|p| p.trim().to_lowercase())
+                .filter(|p| !p.is_empty())
+                .collect()
+        };
+        Self {
+            include: normalize(include),
+            exclude: normalize(exclude),
+        }
+    }
+
+    /// True when no patterns were given (full install).
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Decide whether a mod is installed.
+    ///
+    /// With `include` patterns, a mod must match at least one of them. Any
+    /// `exclude` match then removes it. Separators are always kept so MO2's
+    /// left pane stays readable.
+    pub fn keeps(&self, mod_name: &str, category: Option<&str>, archive_names: &[&str]) -> bool {
+        let name = mod_name.to_lowercase();
+        if name.ends_with(SEPARATOR_SUFFIX) {
+            return true;
+        }
+        let category = category.map(|c| c.to_lowercase());
+        let archives: Vec<String> = archive_names.iter().map(|a| a.to_lowercase()).collect();
+
+        let hit = |patterns: &[String]| {
+            patterns.iter().any(|p| {
+                name.contains(p.as_str())
+                    || category.as_deref().is_some_and(|c| c.contains(p.as_str()))
+                    || archives.iter().any(|a| a.contains(p.as_str()))
+            })
+        };
+
+        if !self.include.is_empty() && !hit(&self.include) {
+            return false;
+        }
+        !hit(&self.exclude)
+    }
+}
+
+/// Result of applying a filter to the state DB.
+#[derive(Debug, Default, Clone)]
+pub struct FilterStats {
+    pub mods_kept: usize,
+    pub mods_skipped: usize,
+    pub directives_removed: usize,
+    /// Names of skipped mods, sorted, for the install log.
+    pub skipped_mods: Vec<String>,
+}
+
+/// Map each mod to the separator it sits under in an MO2 `modlist.txt`.
+///
+/// modlist.txt is written highest-priority first, so a separator's mods are
+/// the lines *above* it (up to the previous separator).
+pub fn separator_categories(modlist_txt: &str) -> HashMap<String, String> {
+    let mut categories = HashMap::new();
+    let mut pending: Vec<&str> = Vec::new();
+
+    for line in modlist_txt.lines() {
+        let line = line.trim();
+        // `+`/`-` = enabled/disabled managed mod; `*` = unmanaged (DLC etc.)
+        let Some(name) = line.strip_prefix('+').or_else(|| line.strip_prefix('-')) else {
+            continue;
+        };
+        if let Some(category) = name.strip_suffix(SEPARATOR_SUFFIX) {
+            for m in pending.drain(..) {
+                categories.insert(m.to_string(), category.to_string());
+            }
+        } else {
+            pending.push(name);
+        }
+    }
+
+    categories
+}
+
+/// Read every `profiles/*/modlist.txt` shipped inline in the .wabbajack and
+/// merge their separator categories. The first profile to mention a mod wins.
+fn load_categories(db: &ModlistDb, wabbajack_path: &Path) -> Result<HashMap<String, String>> {
+    let mut categories = HashMap::new();
+
+    let modlist_txts: Vec<i64> = db
+        .get_all_directives_summary()?
+        .into_iter()
+        .filter(|d| d.directive_type == "InlineFile" || d.directive_type == "RemappedInlineFile")
+        .filter(|d| {
+            let lower = crate::paths::normalize_for_lookup(&d.to_path);
+            lower.starts_with("profiles/") && lower.ends_with("/modlist.txt")
+        })
+        .map(|d| d.id)
+        .collect();
+
+    if modlist_txts.is_empty() {
+        return Ok(categories);
+    }
+
+    let file = File::open(wabbajack_path)
+        .with_context(|| format!("Failed to open {}", wabbajack_path.display()))?;
+    let mut archive =
+        ZipArchive::new(BufReader::new(file)).context("Failed to read wabbajack as ZIP")?;
+
+    for id in modlist_txts {
+        let Some(json) = db.get_directive_data(id)? else {
+            continue;
+        };
+        let source_id = match serde_json::from_str::<Directive>(&json) {
+            Ok(Directive::InlineFile(d)) => d.source_data_id,
+            Ok(Directive::RemappedInlineFile(d)) => d.source_data_id,
+            _ => continue,
+        };
+        let mut text = String::new();
+        match archive.by_name(&source_id.to_string()) {
+            Ok(mut entry) => {
+                if let Err(e) = entry.read_to_string(&mut text) {
+                    warn!("Failed to read inline modlist.txt {}: {}", source_id, e);
+                    continue;
+                }
+            }
+            Err(e) => {
+                warn!(
+                    "Inline modlist.txt {} missing from wabbajack: {}",
+                    source_id, e
+                );
+                continue;
+            }
+        }
+        for (m, c) in separator_categories(&text) {
+            categories.entry(m).or_insert(c);
+        }
+    }
+
+    Ok(categories)
+}
+
+/// Remove every directive belonging to a mod the filter rejects.
+///
+/// BSA staging directives (`TEMP_BSA_FILES/<temp_id>/...`) follow the mod that
+/// owns the `CreateBSA`, so a kept BSA always has all of its inputs.
+pub fn apply_mod_filter(
+    db: &mut ModlistDb,
+    wabbajack_path: &Path,
+    filter: &ModFilter,
+) -> Result<FilterStats> {
+    let mut stats = FilterStats::default();
+    if filter.is_empty() {
+        return Ok(stats);
+    }
+
+    let categories = load_categories(db, wabbajack_path)?;
+    let archive_names: HashMap<String, String> = db
+        .get_all_archives()?
+        .into_iter()
+        .map(|a| (a.hash, a.name))
+        .collect();
+    let directives = db.get_all_directives_summary()?;
+
+    // mod name -> source archive names
+    let mut mod_archives: HashMap<&str, HashSet<&str>> = HashMap::new();
+    for d in &directives {
+        if let Some(mod_name) = mod_dir_of(&d.to_path) {
+            let entry = mod_archives.entry(mod_name).or_default();
+            if let Some(name) = d.archive_hash.as_ref().and_then(|h| archive_names.get(h)) {
+                entry.insert(name.as_str());
+            }
+        }
+    }
+
+    let mut skipped_mods: HashSet<&str> = HashSet::new();
+    for (mod_name, archives) in &mod_archives {
+        let archives: Vec<&str> = archives.iter().copied().collect();
+        let category = categories.get(*mod_name).map(|c| c.as_str());
+        if filter.keeps(mod_name, category, &archives) {
+            stats.mods_kept += 1;
+        } else {
+            skipped_mods.insert(*mod_name);
+        }
+    }
+
+    // Staging dirs of BSAs that live in skipped mods.
+    let mut skipped_temp_ids: HashSet<String> = HashSet::new();
+    for d in directives
+        .iter()
+        .filter(|d| d.directive_type == "CreateBSA")
+    {
+        if !mod_dir_of(&d.to_path).is_some_and(|m| skipped_mods.contains(m)) {
+            continue;
+        }
+        if let Some(Ok(Directive::CreateBSA(bsa))) = db
+            .get_directive_data(d.id)?
+            .map(|json| serde_json::from_str::<Directive>(&json))
+        {
+            skipped_temp_ids.insert(bsa.temp_id.to_string());
+        }
+    }
+
+    let to_remove: Vec<i64> = directives
+        .iter()
+        .filter(|d| {
+            if let Some(m) = mod_dir_of(&d.to_path) {
+                return skipped_mods.contains(m);
+            }
+            let mut parts = d.to_path.split(['\\', '/']);
+            parts.next() == Some("TEMP_BSA_FILES")
+                && parts.next().is_some_and(|id| skipped_temp_ids.contains(id))
+        })
+        .map(|d| d.id)
+        .collect();
+
+    stats.mods_skipped = skipped_mods.len();
+    stats.skipped_mods = skipped_mods.iter().map(|m| m.to_string()).collect();
+    stats.skipped_mods.sort();
+    stats.directives_removed = db.delete_directives(&to_remove)?;
+
+    // The DB no longer matches the .wabbajack — force a fresh import next run
+    // so dropping the filter later gives a full install again.
+    db.set_metadata("wabbajack_fingerprint", "filtered")?;
+
+    info!(
+        "Mod filter: kept {} mods, skipped {} ({} directives removed)",
+        stats.mods_kept, stats.mods_skipped, stats.directives_removed
+    );
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn separators_own_the_mods_above_them() {
+        let txt = "# header\n+Ultra Trees 4K\n-Noble Textures\n+Textures_separator\n+Better Combat\n+Gameplay_separator\n*DLC: Dawnguard\n+Unsorted Mod\n";
+        let cats = separator_categories(txt);
+        assert_eq!(
+            cats.get("Ultra Trees 4K").map(String::as_str),
+            Some("Textures")
+        );
+        assert_eq!(
+            cats.get("Noble Textures").map(String::as_str),
+            Some("Textures")
+        );
+        assert_eq!(
+            cats.get("Better Combat").map(String::as_str),
+            Some("Gameplay")
+        );
+        assert!(!cats.contains_key("Unsorted Mod"));
+        assert!(!cats.contains_key("DLC: Dawnguard"));
+    }
+
+    #[test]
+    fn exclude_matches_category_name_and_archive() {
+        let f = ModFilter::new(Vec::new(), vec!["Textures".into(), "4k".into()]);
+        assert!(!f.keeps("Noble Skyrim", Some("Textures"), &[]));
+        assert!(!f.keeps("Trees", None, &["Trees 4K-123.7z"]));
+        assert!(f.keeps("Better Combat", Some("Gameplay"), &["bc.zip"]));
+        assert!(f.keeps("Textures_separator", None, &[]));
+    }
+
+    #[test]
+    fn include_restricts_then_exclude_removes() {
+        let f = ModFilter::new(vec!["gameplay".into()], vec!["combat".into()]);
+        assert!(f.keeps("Ordinator", Some("Gameplay"), &[]));
+        assert!(!f.keeps("Better Combat", Some("Gameplay"), &[]));
+        assert!(!f.keeps("Noble Skyrim", Some("Textures"), &[]));
+        assert!(ModFilter::new(vec![" ".into()], Vec::new()).is_empty());
+    }
+}
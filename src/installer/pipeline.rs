@@ -17,6 +17,7 @@ use super::processor::{
     build_patch_basis_key, build_patch_basis_key_from_archive_hash_path, index_single_archive,
     ProcessContext,
 };
+use super::space_guard;
 use super::streaming::{
     cleanup_temp_dirs, finalize_archive, process_bsa_archive, process_bsa_patched_directives,
     process_single_archive_fused, process_textures_from_bsa_streaming,
@@ -24,6 +25,7 @@ use super::streaming::{
     process_whole_file_directives, ArchiveDirective, NestedTextureLookupInner, StreamingConfig,
     StreamingStats, TextureLookupInner,
 };
+use crate::archive::sevenzip::ExtractProgress;
 use crate::installer::handlers::create_bsa::{handle_create_bsa, output_bsa_valid};
 use crate::installer::handlers::from_archive::{
     detect_archive_type, ArchiveType as NestedArchiveType,
@@ -1008,19 +1010,31 @@ fn extract_prepared_archive(
             reporter.begin_item(&format!("extracting {}", prepared.archive_name), None);
         let directive_total = prepared.resolved.len().max(1);
         let live_progress = Arc::new(AtomicUsize::new(0));
+        // Entries unpacked so far; shown until directives start completing so
+        // a single huge archive doesn't sit at 0/1 while it extracts.
+        let entry_progress = Arc::new(Mutex::new(ExtractProgress::default()));
         let progress_stop = Arc::new(AtomicBool::new(false));
         let poll_handle = {
             let counter = Arc::clone(&live_progress);
+            let entries = Arc::clone(&entry_progress);
             let stop = Arc::clone(&progress_stop);
             let bar = Arc::clone(&archive_progress);
             std::thread::spawn(move || {
                 while !stop.load(Ordering::Relaxed) {
                     let done = counter.load(Ordering::Relaxed).min(directive_total);
-                    bar.set_count(done, directive_total);
+                    let unpacked = *entries.lock().expect("entry progress lock");
+                    if done == 0 && unpacked.entries_total > 0 {
+                        bar.set_entry_progress(unpacked);
+                    } else {
+                        bar.set_count(done, directive_total);
+                    }
                     std::thread::sleep(std::time::Duration::from_millis(250));
                 }
             })
         };
+        let on_entry = |progress: ExtractProgress| {
+            *entry_progress.lock().expect("entry progress lock") = progress;
+        };
 
         let phase_extract_start = std::time::Instant::now();
         let result = process_single_archive_fused(
@@ -1030,6 +1044,7 @@ fn extract_prepared_archive(
             ctx,
             &prepared.extra_paths,
             Some(live_progress.as_ref()),
+            Some(&on_entry),
         );
         progress_stop.store(true, Ordering::Relaxed);
         let _ = poll_handle.join();
@@ -1113,6 +1128,7 @@ fn extract_prepared_archive(
                     logged_failures,
                     reporter,
                     &ctx.dir_cache,
+                    &ctx.failures,
                 );
                 written.fetch_add(fin_stats.written, Ordering::Relaxed);
                 skipped.fetch_add(fin_stats.skipped, Ordering::Relaxed);
@@ -1170,6 +1186,14 @@ fn extract_prepared_archive(
                 if count < MAX_LOGGED_FAILURES {
                     error!("FAIL: Archive {}: {:#}", prepared.archive_name, e);
                 }
+                let reason = format!("archive {}: {:#}", prepared.archive_name, e);
+                for d in &prepared.resolved {
+                    let dtype = match d {
+                        super::streaming::ArchiveDirective::Patched { .. } => "PatchedFromArchive",
+                        _ => "FromArchive",
+                    };
+                    ctx.failures.record(dtype, &reason);
+                }
                 failed.fetch_add(prepared.resolved.len(), Ordering::Relaxed);
             }
         }
@@ -1229,8 +1253,7 @@ pub(crate) fn run_processing_loop(
 
     // Initialize GPU once for inline DDS processing (BC7 textures)
     if texture_count > 0 {
-        use crate::textures::init_gpu;
-        let _ = init_gpu();
+        super::processor::init_texture_encoder(reporter.as_ref());
     }
 
     // Pre-register GameFileSource archive paths so whole-file directives
@@ -1267,13 +1290,15 @@ pub(crate) fn run_processing_loop(
         let arc_extracted = Arc::new(AtomicUsize::new(0));
         let arc_skipped = Arc::new(AtomicUsize::new(0));
         let arc_failed = Arc::new(AtomicUsize::new(0));
-        let _ = process_whole_file_directives(
+        for (_, err) in process_whole_file_directives(
             &grouped.whole_file,
             ctx,
             &arc_extracted,
             &arc_skipped,
             &arc_failed,
-        );
+        ) {
+            ctx.failures.record("FromArchive", err);
+        }
         extracted.fetch_add(arc_extracted.load(Ordering::Relaxed), Ordering::Relaxed);
         skipped.fetch_add(arc_skipped.load(Ordering::Relaxed), Ordering::Relaxed);
         failed.fetch_add(arc_failed.load(Ordering::Relaxed), Ordering::Relaxed);
@@ -1402,6 +1427,8 @@ pub(crate) fn run_processing_loop(
                                 }
                                 Err(e) => {
                                     error!("Failed to build BSA {}: {:#}", bsa_name, e);
+                                    ctx.failures
+                                        .record("CreateBSA", format!("{}: {:#}", bsa_name, e));
                                     failed.fetch_add(1, Ordering::Relaxed);
                                 }
                             }
@@ -1429,6 +1456,12 @@ pub(crate) fn run_processing_loop(
                     if let Some(prepared) = prepared {
                         // Block if system memory is too low (< 1GB free)
                         memory_pressure_gate(1024);
+                        // Pause while the install disk is nearly full
+                        space_guard::wait_for_space_blocking(
+                            &ctx.config.output_dir,
+                            space_guard::MIN_FREE_BYTES,
+                            &ctx.config.reporter,
+                        );
 
                         // Wait for a concurrency slot
                         {
@@ -1502,6 +1535,10 @@ pub(crate) fn run_processing_loop(
                         .map(|v| v.len())
                         .unwrap_or(0);
                     let patched_count = grouped.patched.get(&hash).map(|v| v.len()).unwrap_or(0);
+                    let reason = format!("archive {} failed to download", name);
+                    ctx.failures.record_many("FromArchive", from_count, &reason);
+                    ctx.failures
+                        .record_many("PatchedFromArchive", patched_count, &reason);
                     failed.fetch_add(from_count + patched_count, Ordering::Relaxed);
                 }
                 ArchiveEvent::Manual { hash, name } => {
@@ -1538,6 +1575,8 @@ pub(crate) fn run_processing_loop(
                         }
                         Err(e) => {
                             error!("Failed to build BSA {}: {:#}", bsa_name, e);
+                            ctx.failures
+                                .record("CreateBSA", format!("{}: {:#}", bsa_name, e));
                             failed.fetch_add(1, Ordering::Relaxed);
                         }
                     }
@@ -1674,7 +1713,7 @@ pub(crate) fn run_processing_loop_phased(
             }
             let retry_directives: Vec<(i64, FromArchiveDirective)> = wf_failed
                 .iter()
-                .map(|&idx| grouped.whole_file[idx].clone())
+                .map(|(idx, _)| grouped.whole_file[*idx].clone())
                 .collect();
             reporter.log(&format!(
                 "Retrying {} failed whole-file directives (attempt {}/3)...",
@@ -1691,6 +1730,9 @@ pub(crate) fn run_processing_loop_phased(
                 &failed,
             );
         }
+        for (_, err) in wf_failed {
+            ctx.failures.record("FromArchive", err);
+        }
     }
 
     // === Prepare all archives (DB work on main thread) ===
@@ -1817,6 +1859,12 @@ pub(crate) fn run_processing_loop_phased(
 
                         // Block if system memory is too low (< 512MB free)
                         memory_pressure_gate(512);
+                        // Pause while the install disk is nearly full
+                        space_guard::wait_for_space_blocking(
+                            &ctx.config.output_dir,
+                            space_guard::MIN_FREE_BYTES,
+                            &ctx.config.reporter,
+                        );
 
                         let prepared = &complex[idx];
                         let owned = PreparedArchive {
@@ -1966,6 +2014,8 @@ pub(crate) fn run_processing_loop_phased(
                             }
                             Err(e) => {
                                 error!("Failed to build BSA {}: {:#}", bsa_name, e);
+                                ctx.failures
+                                    .record("CreateBSA", format!("{}: {:#}", bsa_name, e));
                                 failed.fetch_add(1, Ordering::Relaxed);
                                 bsa_built.fetch_add(1, Ordering::Relaxed);
                             }
@@ -2015,6 +2065,12 @@ pub(crate) fn run_processing_loop_phased(
 
                         // Block if system memory is too low (< 1GB free)
                         memory_pressure_gate(1024);
+                        // Pause while the install disk is nearly full
+                        space_guard::wait_for_space_blocking(
+                            &ctx.config.output_dir,
+                            space_guard::MIN_FREE_BYTES,
+                            &ctx.config.reporter,
+                        );
 
                         let prepared = &simple[idx];
                         let owned = PreparedArchive {
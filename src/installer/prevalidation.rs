@@ -5,6 +5,10 @@
 //! - Per-type statistics for accurate progress bars
 //! - Set of archive hashes actually needed
 //! - List of extra files in the output directory
+//!
+//! Simple file outputs are normally trusted when their size matches. With
+//! `verify_hashes` the size-matching ones are also hashed; mismatches are
+//! deleted so every later phase treats them as missing.
 
 use crate::installer::pipeline::extract_bsa_temp_id;
 use crate::installer::sidecar;
@@ -12,6 +16,7 @@ use crate::modlist::ModlistDb;
 use crate::paths;
 
 use anyhow::Result;
+use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::Arc;
@@ -36,6 +41,10 @@ pub struct PreValidationResult {
     pub needed_archive_hashes: HashSet<String>,
     /// Normalized paths of extra files in output dir (for cleanup)
     pub extra_files: Vec<String>,
+    /// Normalized paths of outputs that had the right size but the wrong
+    /// hash. They have been deleted and must be dropped from any cached
+    /// directory listing.
+    pub corrupt_outputs: Vec<String>,
 }
 
 impl PreValidationResult {
@@ -81,6 +90,13 @@ impl PreValidationResult {
         if !self.extra_files.is_empty() {
             reporter.log(&format!("Extra files to clean: {}", self.extra_files.len()));
         }
+
+        if !self.corrupt_outputs.is_empty() {
+            reporter.log(&format!(
+                "Hash mismatches: {} existing outputs deleted and will be re-extracted",
+                self.corrupt_outputs.len()
+            ));
+        }
     }
 }
 
@@ -91,10 +107,13 @@ impl PreValidationResult {
 /// - TransformedTexture: sidecar hash check
 /// - CreateBSA: sidecar hash check
 /// - BSA staging paths: valid if parent BSA is valid
+///
+/// With `verify_hashes`, size-valid simple outputs are additionally hashed.
 pub fn run_prevalidation(
     db: &ModlistDb,
     existing_files: &HashMap<String, u64>,
     output_dir: &Path,
+    verify_hashes: bool,
     reporter: &Arc<dyn ProgressReporter>,
 ) -> Result<PreValidationResult> {
     reporter.status("Pre-validating installed files...");
@@ -107,6 +126,7 @@ pub fn run_prevalidation(
             type_stats: HashMap::new(),
             needed_archive_hashes: HashSet::new(),
             extra_files: Vec::new(),
+            corrupt_outputs: Vec::new(),
         });
     }
 
@@ -181,6 +201,19 @@ pub fn run_prevalidation(
         }
     }
 
+    let corrupt_outputs = if verify_hashes {
+        verify_skipped_hashes(
+            &directives,
+            output_dir,
+            &mut skip_set,
+            &mut type_stats,
+            &mut needed_archive_hashes,
+            reporter,
+        )
+    } else {
+        Vec::new()
+    };
+
     // Compute extra files: files in output dir not in any directive
     let expected_paths: HashSet<String> = directives
         .iter()
@@ -224,9 +257,78 @@ pub fn run_prevalidation(
         type_stats,
         needed_archive_hashes,
         extra_files,
+        corrupt_outputs,
     })
 }
 
+/// Directive types whose output is a plain file with the directive's hash.
+fn is_simple_output(directive_type: &str) -> bool {
+    matches!(
+        directive_type,
+        "FromArchive" | "PatchedFromArchive" | "InlineFile" | "RemappedInlineFile"
+    )
+}
+
+/// Hash every size-valid simple output and demote mismatches to needs-work.
+///
+/// Mismatching files are deleted so the downloader's output scan and the
+/// extraction phases both see them as missing. Returns their normalized paths.
+fn verify_skipped_hashes(
+    directives: &[crate::modlist::DirectiveSummary],
+    output_dir: &Path,
+    skip_set: &mut HashSet<i64>,
+    type_stats: &mut HashMap<String, TypeStats>,
+    needed_archive_hashes: &mut HashSet<String>,
+    reporter: &Arc<dyn ProgressReporter>,
+) -> Vec<String> {
+    let candidates: Vec<&crate::modlist::DirectiveSummary> = directives
+        .iter()
+        .filter(|d| skip_set.contains(&d.id) && is_simple_output(&d.directive_type))
+        .filter(|d| extract_bsa_temp_id(&d.to_path).is_none())
+        .collect();
+    if candidates.is_empty() {
+        return Vec::new();
+    }
+
+    reporter.status(&format!(
+        "Verifying hashes of {} existing outputs...",
+        candidates.len()
+    ));
+
+    let mismatched: Vec<&crate::modlist::DirectiveSummary> = candidates
+        .into_par_iter()
+        .filter(|d| {
+            let path = paths::join_windows_path(output_dir, &d.to_path);
+            match crate::hash::verify_file_hash(&path, &d.hash) {
+                Ok(matches) => !matches,
+                Err(e) => {
+                    tracing::warn!("Failed to hash {}: {}", path.display(), e);
+                    true
+                }
+            }
+        })
+        .collect();
+
+    let mut corrupt = Vec::with_capacity(mismatched.len());
+    for d in mismatched {
+        let path = paths::join_windows_path(output_dir, &d.to_path);
+        tracing::warn!("Hash mismatch, re-extracting: {}", d.to_path);
+        if let Err(e) = std::fs::remove_file(&path) {
+            tracing::warn!("Failed to delete {}: {}", path.display(), e);
+        }
+        skip_set.remove(&d.id);
+        if let Some(stats) = type_stats.get_mut(&d.directive_type) {
+            stats.already_valid -= 1;
+            stats.needs_work += 1;
+        }
+        if let Some(hash) = d.archive_hash.as_ref().filter(|h| !h.is_empty()) {
+            needed_archive_hashes.insert(hash.clone());
+        }
+        corrupt.push(paths::normalize_for_lookup(&d.to_path));
+    }
+    corrupt
+}
+
 /// Check if a single directive's output is already valid.
 fn check_directive_valid(
     d: &crate::modlist::DirectiveSummary,
@@ -275,6 +377,14 @@ mod tests {
         assert_eq!(stats.needs_work, 0);
     }
 
+    #[test]
+    fn test_is_simple_output() {
+        assert!(is_simple_output("FromArchive"));
+        assert!(is_simple_output("RemappedInlineFile"));
+        assert!(!is_simple_output("TransformedTexture"));
+        assert!(!is_simple_output("CreateBSA"));
+    }
+
     #[test]
     fn test_stats_as_tuples() {
         let mut result = PreValidationResult {
@@ -282,6 +392,7 @@ mod tests {
             type_stats: HashMap::new(),
             needed_archive_hashes: HashSet::new(),
             extra_files: Vec::new(),
+            corrupt_outputs: Vec::new(),
         };
         result.type_stats.insert(
             "FromArchive".to_string(),
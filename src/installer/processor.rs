@@ -14,6 +14,7 @@ use crate::modlist::{ArchiveFileEntry, Directive, ModlistDb};
 use crate::paths;
 
 use super::config::InstallConfig;
+use super::failures::DirectiveFailures;
 use super::handlers;
 use super::handlers::from_archive::{detect_archive_type, ArchiveType};
 
@@ -660,6 +661,8 @@ pub struct ProcessContext<'a> {
     pub extra_files_for_cleanup: Vec<String>,
     /// Directory creation cache — eliminates redundant stat/mkdir syscalls
     pub dir_cache: crate::paths::DirCache,
+    /// Failures by directive type, for the install summary
+    pub failures: DirectiveFailures,
 }
 
 impl<'a> ProcessContext<'a> {
@@ -720,31 +723,16 @@ impl<'a> ProcessContext<'a> {
 
         // SQLite cache for 7z/RAR extractions (persists across runs)
         // Use local cache directory to avoid CIFS/NFS locking issues
-        let cache_dir = dirs::cache_dir()
-            .unwrap_or_else(|| PathBuf::from("/tmp"))
-            .join("clf3");
-        let _ = std::fs::create_dir_all(&cache_dir);
-        let cache_path = cache_dir.join("extraction_cache.db");
+        let cache_path = crate::paths::extraction_cache_db()
+            .unwrap_or_else(|| PathBuf::from("/tmp/clf3/extraction_cache.db"));
+        if let Some(cache_dir) = cache_path.parent() {
+            let _ = std::fs::create_dir_all(cache_dir);
+        }
         let extraction_cache =
             BsaCache::at_path(&cache_path).context("Failed to create extraction cache")?;
 
         // Build cache of existing output files (walk once instead of stat per file)
-        let mut existing_files = HashMap::new();
-        if config.output_dir.exists() {
-            for entry in walkdir::WalkDir::new(&config.output_dir)
-                .into_iter()
-                .filter_map(|e| e.ok())
-            {
-                if entry.file_type().is_file() {
-                    if let Ok(rel_path) = entry.path().strip_prefix(&config.output_dir) {
-                        let normalized = paths::normalize_for_lookup(&rel_path.to_string_lossy());
-                        if let Ok(meta) = entry.metadata() {
-                            existing_files.insert(normalized, meta.len());
-                        }
-                    }
-                }
-            }
-        }
+        let existing_files = super::status::scan_output_files(&config.output_dir);
 
         // Open persistent patch basis store and load verified entries
         let modlist_name = config
@@ -790,6 +778,7 @@ impl<'a> ProcessContext<'a> {
             prevalidation_stats: HashMap::new(),
             extra_files_for_cleanup: Vec::new(),
             dir_cache,
+            failures: DirectiveFailures::default(),
         })
     }
 
@@ -1084,6 +1073,21 @@ pub(crate) fn output_exists(ctx: &ProcessContext, to_path: &str, expected_size:
     }
 }
 
+/// Initialize the BC7 encoder, telling the user once per run when textures
+/// will be encoded on the CPU instead of a GPU.
+pub(crate) fn init_texture_encoder(reporter: &dyn super::progress::ProgressReporter) {
+    static CPU_NOTE: std::sync::Once = std::sync::Once::new();
+    let _ = crate::textures::init_gpu();
+    if !crate::textures::gpu_encoder_active() {
+        CPU_NOTE.call_once(|| {
+            reporter.log(
+                "No GPU in use for texture encoding; BC7 textures will be encoded on the CPU \
+                 (slower).",
+            );
+        });
+    }
+}
+
 /// Check if output DDS file exists and has valid header
 /// For textures where compression size isn't deterministic
 fn output_dds_valid(ctx: &ProcessContext, to_path: &str, directive_hash: &str) -> bool {
@@ -1489,7 +1493,14 @@ impl<'a> DirectiveProcessor<'a> {
     /// Cleanup phase: extra files + BSA temp dirs
     pub fn cleanup_phase(&self) -> Result<()> {
         self.reporter.phase_start(super::progress::Phase::Cleanup);
-        cleanup_extra_files(self.db, &self.ctx)?;
+        if self.ctx.config.mod_filter.is_empty() {
+            cleanup_extra_files(self.db, &self.ctx)?;
+        } else {
+            // Files of filtered-out mods from an earlier full install are
+            // "extra" from this run's point of view — keep them.
+            self.reporter
+                .log("Partial install: skipping extra-file cleanup");
+        }
         cleanup_bsa_temp_dirs(self.ctx.config)?;
         self.reporter.overall_finish();
         Ok(())
@@ -1728,20 +1739,47 @@ pub(crate) fn apply_patch_to_temp(
     })?;
     let mut writer = BufWriter::with_capacity(65536, output_file);
 
-    let written = std::io::copy(&mut reader, &mut writer).with_context(|| {
-        format!(
-            "Failed to write patched file: {}",
-            temp_output_path.display()
+    // A basis shorter than the delta expects, or a result of the wrong size,
+    // means the source isn't the file this patch was built against.
+    let basis_mismatch = |detail: String| {
+        crate::error::InstallError::msg(
+            crate::error::InstallError::HashMismatch,
+            format!(
+                "{} doesn't match the file patch {} was built for ({}); \
+                 delete its archive and re-download it",
+                source_path.display(),
+                patch_name,
+                detail
+            ),
         )
-    })?;
+    };
+
+    let written = match std::io::copy(&mut reader, &mut writer) {
+        Ok(written) => written,
+        Err(e)
+            if e.get_ref()
+                .is_some_and(|inner| inner.is::<crate::octodiff::BasisTooShort>()) =>
+        {
+            let _ = fs::remove_file(temp_output_path);
+            return Err(basis_mismatch(e.to_string()).into());
+        }
+        Err(e) => {
+            return Err(e).with_context(|| {
+                format!(
+                    "Failed to write patched file: {}",
+                    temp_output_path.display()
+                )
+            })
+        }
+    };
 
     if written != expected_size {
         let _ = fs::remove_file(temp_output_path);
-        bail!(
-            "Patch size mismatch: expected {}, got {}",
-            expected_size,
-            written
-        );
+        return Err(basis_mismatch(format!(
+            "patched size {}, expected {}",
+            written, expected_size
+        ))
+        .into());
     }
 
     Ok(())
@@ -1922,6 +1960,8 @@ fn process_transformed_texture(
             _ => {
                 parse_failures += 1;
                 failed.fetch_add(1, Ordering::Relaxed);
+                ctx.failures
+                    .record("TransformedTexture", "directive failed to parse");
                 reporter.overall_inc();
             }
         }
@@ -2007,6 +2047,10 @@ fn process_transformed_texture(
                                 "FAIL: texture archive not found (hash={}): {}",
                                 archive_hash, directive.to
                             );
+                            ctx.failures.record(
+                                "TransformedTexture",
+                                format!("source archive not found: {}", directive.to),
+                            );
                             stage_failed.fetch_add(1, Ordering::Relaxed);
                         }
                     }
@@ -2139,6 +2183,10 @@ fn process_transformed_texture(
                             "FAIL [{}]: failed to write staged texture: {}",
                             id, directive.to
                         );
+                        ctx.failures.record(
+                            "TransformedTexture",
+                            format!("failed to write staged texture: {}", directive.to),
+                        );
                         stage_failed.fetch_add(1, Ordering::Relaxed);
                     }
                 } else {
@@ -2148,6 +2196,10 @@ fn process_transformed_texture(
                         directive.archive_hash_path.len(),
                         directive.to
                     );
+                    ctx.failures.record(
+                        "TransformedTexture",
+                        format!("failed to read texture source: {}", directive.to),
+                    );
                     stage_failed.fetch_add(1, Ordering::Relaxed);
                 }
             }
@@ -2204,7 +2256,7 @@ fn process_transformed_texture(
     reporter.log(&format!("Processing textures by format [{}]", summary_str));
 
     // Initialize GPU for BC7
-    let _ = crate::textures::init_gpu();
+    init_texture_encoder(reporter.as_ref());
 
     // Process each format group
     for (fmt_str, jobs) in &by_format {
@@ -2243,6 +2295,8 @@ fn process_transformed_texture(
                             st.id, st.directive.to, e
                         );
                         failure_tracker.record_failure("texture", &e.to_string());
+                        ctx.failures
+                            .record("TransformedTexture", format!("{}: {}", st.directive.to, e));
                         reporter.overall_inc();
                     }
                 }
@@ -2287,6 +2341,10 @@ fn process_transformed_texture(
                                     st.id, st.directive.to, e
                                 );
                                 failure_tracker.record_failure("texture", &e.to_string());
+                                ctx.failures.record(
+                                    "TransformedTexture",
+                                    format!("{}: {}", st.directive.to, e),
+                                );
                             } else {
                                 completed.fetch_add(1, Ordering::Relaxed);
                             }
@@ -2301,6 +2359,10 @@ fn process_transformed_texture(
                             st.id, st.directive.to, e
                         );
                         failure_tracker.record_failure("texture", &format!("{:#}", e));
+                        ctx.failures.record(
+                            "TransformedTexture",
+                            format!("{}: {:#}", st.directive.to, e),
+                        );
                     }
                 }
                 reporter.overall_inc();
@@ -2366,6 +2428,10 @@ fn process_transformed_texture(
                         );
                         failure_tracker
                             .record_failure("texture", &format!("{}: {}", st.directive.to, e));
+                        ctx.failures.record(
+                            "TransformedTexture",
+                            format!("{}: {:#}", st.directive.to, e),
+                        );
                     }
                 }
                 reporter.overall_inc();
@@ -2409,6 +2475,8 @@ fn process_create_bsa(
                 Ok(Directive::CreateBSA(d)) => Some((id, d)),
                 _ => {
                     failed.fetch_add(1, Ordering::Relaxed);
+                    ctx.failures
+                        .record("CreateBSA", "directive failed to parse");
                     reporter.overall_inc();
                     None
                 }
@@ -2468,6 +2536,8 @@ fn process_create_bsa(
                         }
                         Err(e) => {
                             failed.fetch_add(1, Ordering::Relaxed);
+                            ctx.failures
+                                .record("CreateBSA", format!("{}: {:#}", bsa_name, e));
                             reporter.log(&format!(
                                 "FAIL [{}] create {} {}: {:#}",
                                 id, archive_type, bsa_name, e
@@ -2560,8 +2630,9 @@ fn process_simple_directives(
                 Ok(false) => {
                     skipped.fetch_add(1, Ordering::Relaxed);
                 }
-                Err(_) => {
+                Err(e) => {
                     failed.fetch_add(1, Ordering::Relaxed);
+                    ctx.failures.record(dtype, e);
                 }
             }
             reporter.overall_inc();
@@ -2619,6 +2690,10 @@ fn process_single_directive(ctx: &ProcessContext, directive: &Directive) -> Resu
         Directive::CreateBSA(_) => {
             bail!("CreateBSA not yet implemented")
         }
+        Directive::NoMatch(_) => Ok(false),
+        Directive::Unsupported(d) => {
+            bail!("Unsupported directive type: {}", d.directive_type)
+        }
     }
 }
 
@@ -0,0 +1,210 @@
+//! MO2 profile (preset) selection
+//!
+//! Modlists that offer presets, e.g. "Performance" and "Quality", ship one
+//! MO2 profile per preset: each `profiles/<name>/` has its own modlist.txt,
+//! plugin list and game INIs, and `ModOrganizer.ini` names the one the
+//! author recommends (`selected_profile`). Applying a preset is therefore
+//! just pointing `selected_profile` at another profile once the install is
+//! done. The choice is remembered per modlist in the config cache.
+
+use super::config_cache::ConfigCache;
+use crate::modlist::{Directive, Modlist, ModlistDb};
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+const MO2_INI: &str = "ModOrganizer.ini";
+
+/// Profiles a modlist ships and the one its author selected.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModlistProfiles {
+    /// Sorted profile names.
+    pub names: Vec<String>,
+    /// `selected_profile` from the shipped ModOrganizer.ini.
+    pub recommended: Option<String>,
+}
+
+impl ModlistProfiles {
+    /// Whether there is anything to choose.
+    pub fn has_choice(&self) -> bool {
+        self.names.len() > 1
+    }
+}
+
+/// Profile names from directive output paths (`profiles\<name>\modlist.txt`).
+pub fn profile_names<'a>(to_paths: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let names: BTreeSet<String> = to_paths
+        .into_iter()
+        .filter_map(|path| {
+            let mut parts = path.split(['\\', '/']);
+            let (root, name, file) = (parts.next()?, parts.next()?, parts.next()?);
+            (parts.next().is_none()
+                && root.eq_ignore_ascii_case("profiles")
+                && file.eq_ignore_ascii_case("modlist.txt")
+                && !name.is_empty())
+            .then(|| name.to_string())
+        })
+        .collect();
+    names.into_iter().collect()
+}
+
+/// Profiles of a parsed .wabbajack. Reads the shipped ModOrganizer.ini out
+/// of the archive for the author's pick.
+pub fn read_profiles(wabbajack_path: &Path, modlist: &Modlist) -> Result<ModlistProfiles> {
+    let names = profile_names(modlist.directives.iter().map(|d| d.to_path()));
+    let ini_id = modlist.directives.iter().find_map(|d| match d {
+        Directive::InlineFile(d) if d.to.eq_ignore_ascii_case(MO2_INI) => Some(d.source_data_id),
+        Directive::RemappedInlineFile(d) if d.to.eq_ignore_ascii_case(MO2_INI) => {
+            Some(d.source_data_id)
+        }
+        _ => None,
+    });
+    let recommended = match ini_id {
+        Some(id) => {
+            let file = File::open(wabbajack_path)
+                .with_context(|| format!("Failed to open {}", wabbajack_path.display()))?;
+            let mut archive = zip::ZipArchive::new(BufReader::new(file))?;
+            let mut ini = Vec::new();
+            archive
+                .by_name(&id.to_string())
+                .context("ModOrganizer.ini missing from the .wabbajack")?
+                .read_to_end(&mut ini)?;
+            selected_profile(&String::from_utf8_lossy(&ini))
+        }
+        None => None,
+    };
+    Ok(ModlistProfiles { names, recommended })
+}
+
+/// `selected_profile` from ModOrganizer.ini text. MO2 writes it as
+/// `@ByteArray(<name>)`.
+pub fn selected_profile(ini: &str) -> Option<String> {
+    let value = ini
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("selected_profile="))?
+        .trim();
+    let value = value
+        .strip_prefix("@ByteArray(")
+        .and_then(|v| v.strip_suffix(')'))
+        .unwrap_or(value);
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// ModOrganizer.ini text with `selected_profile` set to `name`, added to
+/// `[General]` if missing. Keeps CRLF line endings.
+pub fn set_selected_profile(ini: &str, name: &str) -> String {
+    let newline = if ini.contains("\r\n") { "\r\n" } else { "\n" };
+    let entry = format!("selected_profile=@ByteArray({})", name);
+    let mut lines: Vec<String> = ini.lines().map(str::to_string).collect();
+
+    if let Some(line) = lines
+        .iter_mut()
+        .find(|l| l.trim().starts_with("selected_profile="))
+    {
+        *line = entry;
+    } else if let Some(i) = lines
+        .iter()
+        .position(|l| l.trim().eq_ignore_ascii_case("[General]"))
+    {
+        lines.insert(i + 1, entry);
+    } else {
+        lines.insert(0, "[General]".to_string());
+        lines.insert(1, entry);
+    }
+
+    let mut out = lines.join(newline);
+    out.push_str(newline);
+    out
+}
+
+/// Make `name` the profile MO2 opens with.
+pub fn apply_profile(output_dir: &Path, name: &str) -> Result<()> {
+    if !output_dir.join("profiles").join(name).is_dir() {
+        bail!("Profile '{}' was not installed", name);
+    }
+    let ini_path = output_dir.join(MO2_INI);
+    let ini = std::fs::read_to_string(&ini_path)
+        .with_context(|| format!("Failed to read {}", ini_path.display()))?;
+    std::fs::write(&ini_path, set_selected_profile(&ini, name))
+        .with_context(|| format!("Failed to write {}", ini_path.display()))
+}
+
+/// The profile to switch to after installing: `requested` (matched without
+/// case against the modlist's profiles), else the one picked for this
+/// modlist last time. None keeps the author's choice.
+pub fn resolve_profile(
+    db: &ModlistDb,
+    modlist_name: &str,
+    requested: Option<&str>,
+) -> Result<Option<String>> {
+    let directives = db.get_all_directives_summary()?;
+    let names = profile_names(directives.iter().map(|d| d.to_path.as_str()));
+    let find = |wanted: &str| {
+        names
+            .iter()
+            .find(|n| n.eq_ignore_ascii_case(wanted))
+            .cloned()
+    };
+
+    if let Some(requested) = requested {
+        return match find(requested) {
+            Some(name) => Ok(Some(name)),
+            None if names.is_empty() => bail!("This modlist has no MO2 profiles to choose from"),
+            None => bail!(
+                "No profile '{}' in this modlist. Available: {}",
+                requested,
+                names.join(", ")
+            ),
+        };
+    }
+
+    let cached = ConfigCache::open()
+        .and_then(|cache| cache.get_latest_config(modlist_name))
+        .ok()
+        .flatten()
+        .and_then(|c| c.profile_name);
+    Ok(cached.as_deref().and_then(find))
+}
+
+/// Remember the profile picked for this modlist.
+pub fn remember_profile(modlist_name: &str, version: &str, profile: &str) -> Result<()> {
+    ConfigCache::open()?.update_config(modlist_name, version, |c| {
+        c.profile_name = Some(profile.to_string());
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_names_from_paths() {
+        let names = profile_names([
+            "profiles\\Quality\\modlist.txt",
+            "profiles\\Quality\\plugins.txt",
+            "Profiles/Performance/ModList.txt",
+            "mods\\profiles\\Other\\modlist.txt",
+            "profiles\\Deep\\nested\\modlist.txt",
+        ]);
+        assert_eq!(names, ["Performance", "Quality"]);
+    }
+
+    #[test]
+    fn test_selected_profile_roundtrip() {
+        let ini = "[General]\r\ngamePath=@ByteArray(Z:\\\\game)\r\nselected_profile=@ByteArray(Quality)\r\n";
+        assert_eq!(selected_profile(ini).as_deref(), Some("Quality"));
+
+        let updated = set_selected_profile(ini, "Performance");
+        assert_eq!(selected_profile(&updated).as_deref(), Some("Performance"));
+        assert!(updated.contains("gamePath=@ByteArray(Z:\\\\game)\r\n"));
+        assert!(updated.ends_with("\r\n"));
+
+        let added = set_selected_profile("[General]\nfoo=1\n", "Quality");
+        assert_eq!(
+            added,
+            "[General]\nselected_profile=@ByteArray(Quality)\nfoo=1\n"
+        );
+    }
+}
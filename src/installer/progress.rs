@@ -1,10 +1,14 @@
 //! Unified progress reporting trait
 //!
 //! All engine code reports progress through `ProgressReporter`.
-//! Implementations: `CliReporter` (indicatif + console), `GuiReporter` (mpsc → Slint).
+//! Implementations: `CliReporter` (indicatif + console), `JsonReporter` (NDJSON on
+//! stdout), `ChannelReporter` (mpsc stream for embedders and GUIs).
 
+use crate::archive::sevenzip::ExtractProgress;
+use std::collections::VecDeque;
 use std::fmt;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// CLI progress rendering mode.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -28,6 +32,9 @@ pub enum Phase {
     DdsTransform,
     BsaBuild,
     Cleanup,
+    /// Optional after a finished install: Proton for the Steam shortcut and
+    /// a Steam restart (`install --setup-steam`).
+    SteamSetup,
 }
 
 impl fmt::Display for Phase {
@@ -41,6 +48,7 @@ impl fmt::Display for Phase {
             Phase::DdsTransform => write!(f, "DDS Transform"),
             Phase::BsaBuild => write!(f, "BSA Build"),
             Phase::Cleanup => write!(f, "Cleanup"),
+            Phase::SteamSetup => write!(f, "Steam Setup"),
         }
     }
 }
@@ -54,6 +62,11 @@ pub trait ProgressHandle: Send + Sync {
     fn set_message(&self, msg: &str);
     /// Update item-level count (e.g. files extracted within an archive).
     fn set_count(&self, done: usize, total: usize);
+    /// Update entries and bytes extracted so far from one archive, while its
+    /// extraction is still running. Falls back to the entry count.
+    fn set_entry_progress(&self, progress: ExtractProgress) {
+        self.set_count(progress.entries_done, progress.entries_total);
+    }
     /// Mark as finished (success).
     fn finish(&self);
     /// Mark as finished with an error message.
@@ -106,3 +119,96 @@ impl ProgressHandle for NullHandle {
 pub struct NullReporter;
 
 impl ProgressReporter for NullReporter {}
+
+/// Transfer rate averaged over the last few seconds.
+///
+/// A rate per chunk swings wildly, and an average since the start spikes on
+/// the first chunks and lags behind later; a sliding window is steady and
+/// still current. Fed the cumulative byte count of one transfer.
+#[derive(Debug)]
+pub struct SpeedWindow {
+    window: Duration,
+    /// `(time, bytes so far)`, oldest first, at least `SAMPLE_INTERVAL` apart.
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl Default for SpeedWindow {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(5))
+    }
+}
+
+impl SpeedWindow {
+    /// Minimum time between kept samples, so fast transfers with small
+    /// chunks don't grow the window.
+    const SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+    /// Shortest span a rate is reported for; less is too noisy to show.
+    const MIN_SPAN: Duration = Duration::from_millis(500);
+
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Record `bytes` transferred so far at `now` and return the rate over
+    /// the window in bytes/sec (0 until `MIN_SPAN` has passed). A count
+    /// going backwards (a retry restarting the file) starts over.
+    pub fn record(&mut self, bytes: u64, now: Instant) -> f64 {
+        if self.samples.back().is_some_and(|&(_, last)| bytes < last) {
+            self.samples.clear();
+        }
+        match self.samples.back() {
+            Some(&(last, _)) if now.saturating_duration_since(last) < Self::SAMPLE_INTERVAL => {}
+            _ => self.samples.push_back((now, bytes)),
+        }
+        // Keep one sample at or before the window start as the baseline.
+        while self.samples.len() > 2
+            && now.saturating_duration_since(self.samples[1].0) >= self.window
+        {
+            self.samples.pop_front();
+        }
+
+        let (start, start_bytes) = self.samples[0];
+        let span = now.saturating_duration_since(start);
+        if span < Self::MIN_SPAN {
+            return 0.0;
+        }
+        bytes.saturating_sub(start_bytes) as f64 / span.as_secs_f64()
+    }
+}
+
+/// Seconds left to transfer the rest of `total` at `speed` bytes/sec.
+pub fn eta_secs(done: u64, total: u64, speed: f64) -> Option<u64> {
+    (speed > 0.0 && done < total).then(|| ((total - done) as f64 / speed).ceil() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn speed_is_averaged_over_the_window() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let mut speed = SpeedWindow::new(Duration::from_secs(2));
+
+        // A big first chunk doesn't show as a spike.
+        assert_eq!(speed.record(0, at(0)), 0.0);
+        assert_eq!(speed.record(5_000_000, at(10)), 0.0);
+        // 1 MB/s from then on; the first burst ages out of the window.
+        for s in 1..=4 {
+            speed.record(5_000_000 + s * 1_000_000, at(10 + s * 1000));
+        }
+        let rate = speed.record(9_500_000, at(4_510));
+        assert!((rate - 1_000_000.0).abs() < 1.0, "{}", rate);
+
+        // A retry restarting at 0 starts over.
+        assert_eq!(speed.record(0, at(5_000)), 0.0);
+
+        assert_eq!(eta_secs(500, 1_500, 100.0), Some(10));
+        assert_eq!(eta_secs(1_500, 1_500, 100.0), None);
+        assert_eq!(eta_secs(0, 1_500, 0.0), None);
+    }
+}
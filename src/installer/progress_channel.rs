@@ -0,0 +1,252 @@
+//! Channel-backed progress reporter for embedding the installer.
+//!
+//! Every reporter call is turned into a `ProgressEvent` and sent over a
+//! `std::sync::mpsc` channel owned by the caller. Each `Installer` gets its
+//! own reporter through `InstallConfig::reporter`, so several installs can run
+//! in one process without sharing progress state.
+//!
+//! ```ignore
+//! let (reporter, events) = ChannelReporter::new();
+//! config.reporter = reporter;
+//! std::thread::spawn(move || for event in events { /* update UI */ });
+//! ```
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+use super::config::ProgressEvent;
+use super::progress::{eta_secs, Phase, ProgressHandle, ProgressReporter};
+use crate::archive::sevenzip::ExtractProgress;
+
+struct State {
+    total: usize,
+    count: usize,
+    current_phase: Option<Phase>,
+}
+
+/// Reporter that forwards progress as `ProgressEvent`s to a channel.
+///
+/// Send errors (receiver dropped) are ignored — the install keeps running.
+pub struct ChannelReporter {
+    tx: Mutex<Sender<ProgressEvent>>,
+    state: Mutex<State>,
+}
+
+impl ChannelReporter {
+    /// Create a reporter and the receiving end of its event stream.
+    pub fn new() -> (Arc<Self>, Receiver<ProgressEvent>) {
+        let (tx, rx) = mpsc::channel();
+        (Self::with_sender(tx), rx)
+    }
+
+    /// Create a reporter that sends into an existing channel.
+    pub fn with_sender(tx: Sender<ProgressEvent>) -> Arc<Self> {
+        Arc::new(Self {
+            tx: Mutex::new(tx),
+            state: Mutex::new(State {
+                total: 0,
+                count: 0,
+                current_phase: None,
+            }),
+        })
+    }
+
+    fn emit(&self, event: ProgressEvent) {
+        let _ = self.tx.lock().expect("channel reporter lock").send(event);
+    }
+
+    fn sender(&self) -> Sender<ProgressEvent> {
+        self.tx.lock().expect("channel reporter lock").clone()
+    }
+}
+
+impl ProgressReporter for ChannelReporter {
+    fn phase_start(&self, phase: Phase) {
+        {
+            let mut state = self.state.lock().expect("channel reporter state lock");
+            state.current_phase = Some(phase);
+            state.count = 0;
+            state.total = 0;
+        }
+        self.emit(ProgressEvent::PhaseChange {
+            phase: phase.to_string(),
+        });
+    }
+
+    fn overall_set_total(&self, total: u64) {
+        let mut state = self.state.lock().expect("channel reporter state lock");
+        state.total = total as usize;
+        state.count = 0;
+    }
+
+    fn overall_inc(&self) {
+        let (index, total, phase) = {
+            let mut state = self.state.lock().expect("channel reporter state lock");
+            state.count += 1;
+            (state.count, state.total, state.current_phase)
+        };
+
+        match phase {
+            Some(Phase::Downloading) | Some(Phase::Validating) | Some(Phase::Extracting) => {
+                self.emit(ProgressEvent::ArchiveComplete { index, total });
+            }
+            _ => {
+                self.emit(ProgressEvent::DirectiveComplete { index, total });
+            }
+        }
+    }
+
+    fn overall_set_message(&self, msg: &str) {
+        self.status(msg);
+    }
+
+    fn begin_item(&self, name: &str, total_bytes: Option<u64>) -> Arc<dyn ProgressHandle> {
+        Arc::new(ChannelHandle {
+            tx: Mutex::new(self.sender()),
+            name: name.to_string(),
+            total_bytes,
+            finished: AtomicBool::new(false),
+        })
+    }
+
+    fn begin_status(&self, label: &str) -> Arc<dyn ProgressHandle> {
+        Arc::new(ChannelHandle {
+            tx: Mutex::new(self.sender()),
+            name: label.to_string(),
+            total_bytes: None,
+            finished: AtomicBool::new(false),
+        })
+    }
+
+    fn log(&self, msg: &str) {
+        self.emit(ProgressEvent::Log {
+            message: msg.to_string(),
+        });
+    }
+
+    fn status(&self, msg: &str) {
+        self.emit(ProgressEvent::Status {
+            message: msg.to_string(),
+        });
+    }
+}
+
+struct ChannelHandle {
+    tx: Mutex<Sender<ProgressEvent>>,
+    name: String,
+    /// `Some` for byte-tracked items (downloads), which get `DownloadComplete`.
+    total_bytes: Option<u64>,
+    finished: AtomicBool,
+}
+
+impl ChannelHandle {
+    fn emit(&self, event: ProgressEvent) {
+        let _ = self.tx.lock().expect("channel handle lock").send(event);
+    }
+}
+
+impl ProgressHandle for ChannelHandle {
+    fn set_bytes(&self, downloaded: u64, total: u64, speed: f64) {
+        let total = if total > 0 {
+            total
+        } else {
+            self.total_bytes.unwrap_or(0)
+        };
+        self.emit(ProgressEvent::DownloadProgress {
+            name: self.name.clone(),
+            downloaded,
+            total,
+            speed,
+            eta_secs: eta_secs(downloaded, total, speed),
+        });
+    }
+
+    fn set_message(&self, msg: &str) {
+        self.emit(ProgressEvent::Status {
+            message: format!("{}: {}", self.name, msg),
+        });
+    }
+
+    fn set_count(&self, done: usize, total: usize) {
+        self.emit(ProgressEvent::Status {
+            message: format!("{}: {}/{}", self.name, done, total),
+        });
+    }
+
+    fn set_entry_progress(&self, progress: ExtractProgress) {
+        self.emit(ProgressEvent::FileProgress {
+            name: self.name.clone(),
+            entries_done: progress.entries_done,
+            entries_total: progress.entries_total,
+            bytes_done: progress.bytes_done,
+            bytes_total: progress.bytes_total,
+        });
+    }
+
+    fn finish(&self) {
+        if !self.finished.swap(true, Ordering::Relaxed) && self.total_bytes.is_some() {
+            self.emit(ProgressEvent::DownloadComplete {
+                name: self.name.clone(),
+            });
+        }
+    }
+
+    fn finish_with_error(&self, msg: &str) {
+        if !self.finished.swap(true, Ordering::Relaxed) {
+            self.emit(ProgressEvent::Log {
+                message: msg.to_string(),
+            });
+        }
+    }
+}
+
+impl Drop for ChannelHandle {
+    fn drop(&mut self) {
+        if !self.finished.load(Ordering::Relaxed) {
+            self.finish();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forwards_phase_counts_and_downloads() {
+        let (reporter, rx) = ChannelReporter::new();
+        reporter.phase_start(Phase::Downloading);
+        reporter.overall_set_total(2);
+        reporter.overall_inc();
+        {
+            let item = reporter.begin_item("mod.7z", Some(10));
+            item.set_bytes(10, 10, 5.0);
+        }
+        reporter.log("done");
+        drop(reporter);
+
+        let events: Vec<ProgressEvent> = rx.iter().collect();
+        assert!(
+            matches!(&events[0], ProgressEvent::PhaseChange { phase } if phase == "Downloading")
+        );
+        assert!(matches!(
+            events[1],
+            ProgressEvent::ArchiveComplete { index: 1, total: 2 }
+        ));
+        assert!(matches!(
+            events[2],
+            ProgressEvent::DownloadProgress { downloaded: 10, .. }
+        ));
+        assert!(matches!(&events[3], ProgressEvent::DownloadComplete { name } if name == "mod.7z"));
+        assert!(matches!(&events[4], ProgressEvent::Log { message } if message == "done"));
+    }
+
+    #[test]
+    fn dropped_receiver_does_not_panic() {
+        let (reporter, rx) = ChannelReporter::new();
+        drop(rx);
+        reporter.status("still running");
+        reporter.begin_item("x", None).finish();
+    }
+}
@@ -3,12 +3,16 @@
 //! Owns the `MultiProgress` display and provides a `MakeWriter` for tracing integration.
 
 use super::progress::{NullHandle, Phase, ProgressHandle, ProgressMode, ProgressReporter};
+use crate::archive::sevenzip::ExtractProgress;
 use console::style;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
+/// How often Plain mode prints an overall progress line.
+const PLAIN_REPORT_INTERVAL: Duration = Duration::from_secs(10);
+
 /// CLI reporter backed by indicatif `MultiProgress`.
 pub struct CliReporter {
     mp: MultiProgress,
@@ -27,6 +31,17 @@ pub struct CliReporter {
     /// Toggled on `phase_start`; non-active phases (GameCheck, Cleanup) keep
     /// the status line hidden so it doesn't render across the whole pipeline.
     status_visible: AtomicBool,
+    /// Plain-mode overall counters. Plain mode has no bar to carry position,
+    /// so we track it here and print a periodic summary line instead.
+    plain_done: AtomicU64,
+    plain_total: AtomicU64,
+    plain_clock: Mutex<PlainClock>,
+}
+
+/// Timing state for Plain-mode periodic progress lines.
+struct PlainClock {
+    phase_started: Instant,
+    last_report: Instant,
 }
 
 impl CliReporter {
@@ -72,6 +87,12 @@ impl CliReporter {
             bar_speeds: Arc::new(bar_speeds),
             ticker_shutdown: Arc::new(AtomicBool::new(false)),
             status_visible: AtomicBool::new(false),
+            plain_done: AtomicU64::new(0),
+            plain_total: AtomicU64::new(0),
+            plain_clock: Mutex::new(PlainClock {
+                phase_started: Instant::now(),
+                last_report: Instant::now(),
+            }),
         });
 
         if mode == ProgressMode::Full {
@@ -188,7 +209,7 @@ impl CliReporter {
         pb.set_style(
             ProgressStyle::default_bar()
                 .template(
-                    "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({percent}%) | {msg}",
+                    "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({percent}%) {per_sec} ETA {eta} | {msg}",
                 )
                 .unwrap()
                 .progress_chars("=>-"),
@@ -198,6 +219,26 @@ impl CliReporter {
         pb
     }
 
+    /// Print a Plain-mode progress line if the report interval has elapsed
+    /// (or unconditionally with `force`, e.g. when the phase completes).
+    fn plain_report(&self, force: bool) {
+        let total = self.plain_total.load(Ordering::Relaxed);
+        if total == 0 {
+            return;
+        }
+        let done = self.plain_done.load(Ordering::Relaxed);
+        let elapsed = {
+            let mut clock = self.plain_clock.lock().expect("plain clock lock");
+            let now = Instant::now();
+            if !force && now.duration_since(clock.last_report) < PLAIN_REPORT_INTERVAL {
+                return;
+            }
+            clock.last_report = now;
+            now.duration_since(clock.phase_started)
+        };
+        let _ = self.mp.println(plain_progress_line(done, total, elapsed));
+    }
+
     fn spawn_system_ticker(this: &Arc<Self>) {
         let system_status = this.system_status.clone();
         let bar_speeds = Arc::clone(&this.bar_speeds);
@@ -283,7 +324,7 @@ fn read_total_memory_bytes() -> Option<u64> {
     None
 }
 
-fn format_bytes(bytes: u64) -> String {
+pub(crate) fn format_bytes(bytes: u64) -> String {
     const UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB"];
     let mut value = bytes as f64;
     let mut idx = 0;
@@ -298,6 +339,38 @@ fn format_bytes(bytes: u64) -> String {
     }
 }
 
+/// Format a Plain-mode progress line: `Progress: 120/500 (24%) | 3.2/s | ETA 1m 58s`.
+fn plain_progress_line(done: u64, total: u64, elapsed: Duration) -> String {
+    let percent = if total > 0 {
+        done.min(total) * 100 / total
+    } else {
+        0
+    };
+    let secs = elapsed.as_secs_f64();
+    let rate = if secs > 0.0 { done as f64 / secs } else { 0.0 };
+    let eta = if rate > 0.0 && done < total {
+        format_duration((total - done) as f64 / rate)
+    } else {
+        "-".to_string()
+    };
+    format!(
+        "Progress: {}/{} ({}%) | {:.1}/s | ETA {}",
+        done, total, percent, rate, eta
+    )
+}
+
+fn format_duration(secs: f64) -> String {
+    let secs = secs.round() as u64;
+    let (h, m, s) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if h > 0 {
+        format!("{}h {}m", h, m)
+    } else if m > 0 {
+        format!("{}m {}s", m, s)
+    } else {
+        format!("{}s", s)
+    }
+}
+
 fn blank_style() -> ProgressStyle {
     ProgressStyle::default_bar().template("").unwrap()
 }
@@ -343,6 +416,11 @@ impl ProgressReporter for CliReporter {
     fn overall_set_total(&self, total: u64) {
         if self.mode == ProgressMode::Plain {
             let _ = self.mp.println(format!("Total: {}", total));
+            self.plain_total.store(total, Ordering::Relaxed);
+            self.plain_done.store(0, Ordering::Relaxed);
+            let mut clock = self.plain_clock.lock().expect("plain clock lock");
+            clock.phase_started = Instant::now();
+            clock.last_report = clock.phase_started;
             return;
         }
         let pb = self.ensure_overall();
@@ -352,6 +430,8 @@ impl ProgressReporter for CliReporter {
 
     fn overall_inc(&self) {
         if self.mode == ProgressMode::Plain {
+            self.plain_done.fetch_add(1, Ordering::Relaxed);
+            self.plain_report(false);
             return;
         }
         let pb = self.ensure_overall();
@@ -369,6 +449,8 @@ impl ProgressReporter for CliReporter {
 
     fn overall_finish(&self) {
         if self.mode == ProgressMode::Plain {
+            self.plain_report(true);
+            self.plain_total.store(0, Ordering::Relaxed);
             return;
         }
         let mut guard = self.overall.lock().expect("overall lock");
@@ -418,6 +500,7 @@ impl ProgressReporter for CliReporter {
             Arc::new(CliHandle {
                 reporter: self as *const CliReporter,
                 bar_index: index,
+                name: name.to_string(),
                 finished: AtomicBool::new(false),
             })
         } else {
@@ -532,6 +615,7 @@ struct CliHandle {
     // We can't store Arc<CliReporter> because begin_item takes &self.
     reporter: *const CliReporter,
     bar_index: usize,
+    name: String,
     finished: AtomicBool,
 }
 
@@ -568,6 +652,17 @@ impl ProgressHandle for CliHandle {
         bar.set_position(done as u64);
     }
 
+    fn set_entry_progress(&self, progress: ExtractProgress) {
+        self.bar().set_message(format!(
+            "{} {}/{} files, {}/{}",
+            active_label(&self.name),
+            progress.entries_done,
+            progress.entries_total,
+            format_bytes(progress.bytes_done),
+            format_bytes(progress.bytes_total)
+        ));
+    }
+
     fn finish(&self) {
         if !self.finished.swap(true, Ordering::Relaxed) {
             self.reporter().release_pool_bar(self.bar_index);
@@ -674,3 +769,21 @@ impl std::io::Write for CliWriter {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_line_reports_rate_and_eta() {
+        let line = plain_progress_line(120, 500, Duration::from_secs(60));
+        assert_eq!(line, "Progress: 120/500 (24%) | 2.0/s | ETA 3m 10s");
+    }
+
+    #[test]
+    fn plain_line_without_rate_has_no_eta() {
+        let line = plain_progress_line(0, 10, Duration::ZERO);
+        assert_eq!(line, "Progress: 0/10 (0%) | 0.0/s | ETA -");
+        assert_eq!(format_duration(7260.0), "2h 1m");
+    }
+}
@@ -1,11 +1,36 @@
 //! Newline-delimited JSON progress reporter for external drivers.
+//!
+//! `clf3 install --progress json` (or `--jackify`) writes one
+//! [`ProgressEvent`] per line to stdout; everything meant for a human goes
+//! to stderr. Each object has a `type` naming the event, plus its fields:
+//!
+//! | `type` | Fields |
+//! |---|---|
+//! | `PhaseChange` | `phase` |
+//! | `Status` | `message` |
+//! | `Log` | `message` (the lines the CLI prints above its bars) |
+//! | `DownloadProgress` | `name`, `downloaded`, `total` (bytes), `speed` (bytes/s), `eta_secs` (null if unknown) |
+//! | `DownloadComplete` | `name` |
+//! | `DownloadSkipped` | `count`, `total_size` |
+//! | `ArchiveComplete` | `index` (1-based), `total` |
+//! | `FileProgress` | `name`, `entries_done`, `entries_total`, `bytes_done`, `bytes_total` |
+//! | `DirectiveComplete` | `index` (1-based), `total` |
+//! | `DirectivePhaseStarted` | `directive_type`, `total` |
+//! | `InstallComplete` | `succeeded`, `archives_downloaded`, `archives_skipped`, `archives_manual`, `archives_failed`, `directives_completed`, `directives_failed` |
+//! | `InstallError` | `message` |
+//!
+//! Once the install starts, the run ends with one `InstallComplete` or
+//! `InstallError`; earlier failures (bad arguments, an unreadable
+//! .wabbajack) only reach stderr and the exit code. Readers should ignore
+//! types they don't know, as new ones may be added.
 
 use std::io::{self, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
 use super::config::{ProgressCallback, ProgressEvent};
-use super::progress::{NullHandle, Phase, ProgressHandle, ProgressReporter};
+use super::progress::{eta_secs, NullHandle, Phase, ProgressHandle, ProgressReporter};
+use crate::archive::sevenzip::ExtractProgress;
 
 #[derive(Clone)]
 pub struct JsonEventWriter {
@@ -129,6 +154,9 @@ impl ProgressReporter for JsonReporter {
     }
 
     fn log(&self, msg: &str) {
+        self.emit(ProgressEvent::Log {
+            message: msg.to_string(),
+        });
         self.write_detail(msg);
     }
 
@@ -148,15 +176,17 @@ struct JsonProgressHandle {
 
 impl ProgressHandle for JsonProgressHandle {
     fn set_bytes(&self, downloaded: u64, total: u64, speed: f64) {
+        let total = if total > 0 {
+            total
+        } else {
+            self.total_bytes.unwrap_or(0)
+        };
         self.writer.emit(ProgressEvent::DownloadProgress {
             name: self.name.clone(),
             downloaded,
-            total: if total > 0 {
-                total
-            } else {
-                self.total_bytes.unwrap_or(0)
-            },
+            total,
             speed,
+            eta_secs: eta_secs(downloaded, total, speed),
         });
     }
 
@@ -168,6 +198,16 @@ impl ProgressHandle for JsonProgressHandle {
 
     fn set_count(&self, _done: usize, _total: usize) {}
 
+    fn set_entry_progress(&self, progress: ExtractProgress) {
+        self.writer.emit(ProgressEvent::FileProgress {
+            name: self.name.clone(),
+            entries_done: progress.entries_done,
+            entries_total: progress.entries_total,
+            bytes_done: progress.bytes_done,
+            bytes_total: progress.bytes_total,
+        });
+    }
+
     fn finish(&self) {
         if !self.finished.swap(true, Ordering::Relaxed) {
             if self.total_bytes.is_some() {
@@ -194,3 +234,40 @@ impl Drop for JsonProgressHandle {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_serialize_with_documented_shape() {
+        let log = ProgressEvent::Log {
+            message: "Installation complete!".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(log).unwrap(),
+            serde_json::json!({"type": "Log", "message": "Installation complete!"})
+        );
+        let progress = ProgressEvent::DownloadProgress {
+            name: "a.7z".to_string(),
+            downloaded: 5,
+            total: 10,
+            speed: 2.5,
+            eta_secs: None,
+        };
+        assert_eq!(
+            serde_json::to_value(progress).unwrap(),
+            serde_json::json!({
+                "type": "DownloadProgress", "name": "a.7z", "downloaded": 5,
+                "total": 10, "speed": 2.5, "eta_secs": null
+            })
+        );
+        let error = ProgressEvent::InstallError {
+            message: "disk full".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_string(&error).unwrap(),
+            r#"{"type":"InstallError","message":"disk full"}"#
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! Post-install download purge (`--purge-downloads`)
+//!
+//! A finished install no longer needs its archives, but they're kept by
+//! default because re-installs and updates reuse them. Users short on disk
+//! can opt into deleting either leftovers this modlist doesn't use (e.g.
+//! archives from an older version) or everything in the downloads folder.
+//! Only top-level files are touched; hidden files and subdirectories are left
+//! alone.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// What to delete from the downloads folder after a successful install.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PurgeDownloads {
+    /// Keep every download (default).
+    #[default]
+    Keep,
+    /// Delete files the installed modlist doesn't reference.
+    Unreferenced,
+    /// Delete every download, including this modlist's archives.
+    All,
+}
+
+/// Sidecars written next to an archive; they go with it.
+const COMPANION_EXTS: &[&str] = &["clf3hash", "meta"];
+
+/// The archive a sidecar belongs to (`foo.7z.clf3hash` -> `foo.7z`).
+pub(crate) fn companion_of(name: &str) -> Option<&str> {
+    COMPANION_EXTS
+        .iter()
+        .find_map(|ext| name.strip_suffix(ext)?.strip_suffix('.'))
+}
+
+/// Files in `downloads_dir` that `mode` would delete, with their total size.
+/// `referenced` holds the archive file names the modlist uses.
+pub fn purge_candidates(
+    downloads_dir: &Path,
+    referenced: &HashSet<String>,
+    mode: PurgeDownloads,
+) -> std::io::Result<(Vec<PathBuf>, u64)> {
+    let mut files = Vec::new();
+    let mut total = 0;
+    if mode == PurgeDownloads::Keep {
+        return Ok((files, total));
+    }
+    for entry in std::fs::read_dir(downloads_dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with('.') || !entry.file_type()?.is_file() {
+            continue;
+        }
+        let owner = companion_of(&name).unwrap_or(&name);
+        if mode == PurgeDownloads::Unreferenced && referenced.contains(owner) {
+            continue;
+        }
+        total += entry.metadata()?.len();
+        files.push(entry.path());
+    }
+    files.sort();
+    Ok((files, total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn candidates_follow_mode_and_keep_sidecars_with_archives() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        for name in [
+            "used.7z",
+            "used.7z.clf3hash",
+            "old.zip",
+            "old.zip.meta",
+            ".hidden",
+        ] {
+            std::fs::write(dir.join(name), b"1234").unwrap();
+        }
+        std::fs::create_dir(dir.join("sub")).unwrap();
+        let referenced: HashSet<String> = ["used.7z".to_string()].into();
+
+        let names = |mode| {
+            let (files, total) = purge_candidates(dir, &referenced, mode).unwrap();
+            let names: Vec<String> = files
+                .iter()
+                .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+                .collect();
+            (names, total)
+        };
+
+        assert_eq!(names(PurgeDownloads::Keep), (vec![], 0));
+        assert_eq!(
+            names(PurgeDownloads::Unreferenced),
+            (vec!["old.zip".into(), "old.zip.meta".into()], 8)
+        );
+        assert_eq!(names(PurgeDownloads::All).0.len(), 4);
+        assert_eq!(names(PurgeDownloads::All).1, 16);
+    }
+}
@@ -0,0 +1,169 @@
+//! Windows runtimes a modlist needs in its Wine prefix.
+//!
+//! Wabbajack has no structured field for this, so requirements are read from
+//! what authors do provide: the description, an inline readme and the names
+//! of bundled installers (`VC_redist.x64.exe`, `windowsdesktop-runtime-*`).
+//! Each runtime maps to the winetricks verb that installs it, and is
+//! considered present if winetricks logged that verb or the installer left
+//! its usual registry key or files in the prefix.
+
+use crate::modlist::Modlist;
+use std::path::Path;
+
+/// A redistributable runtime a modlist can depend on.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Runtime {
+    pub name: &'static str,
+    /// Winetricks (and protontricks) verb that installs it.
+    pub winetricks: &'static str,
+    /// Official download page.
+    pub url: &'static str,
+    /// Lowercase phrases that mention it in modlist text or archive names.
+    keywords: &'static [&'static str],
+    /// Lowercase registry key fragments, as written in `system.reg`.
+    registry_keys: &'static [&'static str],
+    /// Paths under `drive_c` its installer creates.
+    files: &'static [&'static str],
+}
+
+pub const RUNTIMES: &[Runtime] = &[
+    Runtime {
+        name: "Visual C++ 2015-2022 Redistributable",
+        winetricks: "vcrun2022",
+        url: "https://aka.ms/vs/17/release/vc_redist.x64.exe",
+        keywords: &[
+            "visual c++",
+            "vc++ redist",
+            "vc_redist",
+            "vcredist",
+            "vcrun",
+        ],
+        registry_keys: &["microsoft\\\\visualstudio\\\\14.0\\\\vc\\\\runtimes"],
+        files: &[],
+    },
+    Runtime {
+        name: ".NET 8 Desktop Runtime",
+        winetricks: "dotnetdesktop8",
+        url: "https://dotnet.microsoft.com/download/dotnet/8.0",
+        keywords: &[
+            ".net 8",
+            ".net desktop runtime",
+            "dotnet 8",
+            "dotnetdesktop",
+            "windowsdesktop-runtime",
+        ],
+        registry_keys: &[],
+        files: &["Program Files/dotnet/shared/Microsoft.WindowsDesktop.App"],
+    },
+    Runtime {
+        name: ".NET Framework 4.8",
+        winetricks: "dotnet48",
+        url: "https://dotnet.microsoft.com/download/dotnet-framework/net48",
+        keywords: &[".net framework", "ndp48", "dotnet48"],
+        registry_keys: &["microsoft\\\\net framework setup\\\\ndp\\\\v4\\\\full"],
+        files: &[],
+    },
+    Runtime {
+        name: "DirectX 9 (June 2010) Runtime",
+        winetricks: "d3dx9",
+        url: "https://www.microsoft.com/en-us/download/details.aspx?id=8109",
+        keywords: &["d3dx9", "directx 9", "directx end-user runtime", "dxsetup"],
+        registry_keys: &["\"d3dx9_43\"=\"native"],
+        files: &[],
+    },
+];
+
+/// Runtimes mentioned in `text`, in [`RUNTIMES`] order.
+pub fn runtimes_in_text(text: &str) -> Vec<&'static Runtime> {
+    let text = text.to_lowercase();
+    RUNTIMES
+        .iter()
+        .filter(|runtime| runtime.keywords.iter().any(|k| text.contains(k)))
+        .collect()
+}
+
+/// Runtimes the author lists in the modlist's description or readme, or
+/// bundles an installer for.
+pub fn required_runtimes(modlist: &Modlist) -> Vec<&'static Runtime> {
+    let mut text = format!("{}\n{}", modlist.description, modlist.readme);
+    for archive in &modlist.archives {
+        text.push('\n');
+        text.push_str(&archive.name);
+    }
+    runtimes_in_text(&text)
+}
+
+/// Whether `runtime` is installed in the Wine prefix at `prefix`.
+pub fn is_installed(runtime: &Runtime, prefix: &Path) -> bool {
+    let logged = std::fs::read_to_string(prefix.join("winetricks.log"))
+        .is_ok_and(|log| log.lines().any(|verb| verb.trim() == runtime.winetricks));
+    if logged {
+        return true;
+    }
+    let drive_c = prefix.join("drive_c");
+    if runtime.files.iter().any(|f| drive_c.join(f).exists()) {
+        return true;
+    }
+    if runtime.registry_keys.is_empty() {
+        return false;
+    }
+    ["system.reg", "user.reg"].iter().any(|file| {
+        std::fs::read_to_string(prefix.join(file)).is_ok_and(|reg| {
+            let reg = reg.to_lowercase();
+            runtime.registry_keys.iter().any(|key| reg.contains(key))
+        })
+    })
+}
+
+/// Command that installs `missing` into the prefix at `prefix`.
+pub fn winetricks_command(prefix: &Path, missing: &[&Runtime]) -> String {
+    let verbs: Vec<_> = missing.iter().map(|r| r.winetricks).collect();
+    format!(
+        "WINEPREFIX=\"{}\" winetricks -q {}",
+        prefix.display(),
+        verbs.join(" ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runtimes_are_found_in_text_and_archive_names() {
+        let found = runtimes_in_text(
+            "Requires the latest Visual C++ redistributable.\nwindowsdesktop-runtime-8.0.8-win-x64.exe",
+        );
+        let verbs: Vec<_> = found.iter().map(|r| r.winetricks).collect();
+        assert_eq!(verbs, ["vcrun2022", "dotnetdesktop8"]);
+        assert!(runtimes_in_text("Uses DirectX 11 and an ENB").is_empty());
+    }
+
+    #[test]
+    fn installed_runtimes_are_detected_in_prefix() {
+        let tmp = tempfile::tempdir().unwrap();
+        let prefix = tmp.path();
+        let [vcrun, dotnet, framework, d3dx9] = [0, 1, 2, 3].map(|i| &RUNTIMES[i]);
+        assert!(!is_installed(vcrun, prefix));
+
+        std::fs::write(prefix.join("winetricks.log"), "corefonts\nvcrun2022\n").unwrap();
+        std::fs::write(
+            prefix.join("system.reg"),
+            "[Software\\\\Microsoft\\\\NET Framework Setup\\\\NDP\\\\v4\\\\Full] 1700000000\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(
+            prefix.join("drive_c/Program Files/dotnet/shared/Microsoft.WindowsDesktop.App"),
+        )
+        .unwrap();
+
+        assert!(is_installed(vcrun, prefix));
+        assert!(is_installed(dotnet, prefix));
+        assert!(is_installed(framework, prefix));
+        assert!(!is_installed(d3dx9, prefix));
+        assert_eq!(
+            winetricks_command(Path::new("/pfx"), &[d3dx9]),
+            "WINEPREFIX=\"/pfx\" winetricks -q d3dx9"
+        );
+    }
+}
@@ -0,0 +1,485 @@
+//! Non-destructive setup checks run before an install starts.
+//!
+//! Each check mirrors a failure the installer would otherwise hit minutes in
+//! (wrong game dir, full disk, non-Premium Nexus account, ...) and carries a
+//! hint on how to fix it. The GUI's "Validate Setup" button runs these; the
+//! only thing written is a probe file that is removed straight away.
+
+use crate::downloaders::NexusDownloader;
+use crate::game_finder::{
+    cached_protons, compare_runtime, find_compatdata, installed_game_version, runtime_edition,
+    validate_game_path, GameType, RuntimeMatch,
+};
+use crate::installer::game_preflight::required_game_version;
+use crate::installer::requirements::{
+    is_installed, required_runtimes, winetricks_command, Runtime,
+};
+use crate::modlist::Modlist;
+use std::ffi::CString;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+/// Outcome of one setup check.
+#[derive(Debug, Clone)]
+pub struct SetupCheck {
+    pub name: &'static str,
+    pub passed: bool,
+    /// What was found (shown for passes and failures alike).
+    pub detail: String,
+    /// How to fix a failure.
+    pub fix: Option<String>,
+    /// Page with more help, if there is one.
+    pub fix_url: Option<&'static str>,
+}
+
+impl SetupCheck {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            passed: true,
+            detail: detail.into(),
+            fix: None,
+            fix_url: None,
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            passed: false,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+            fix_url: None,
+        }
+    }
+
+    fn with_url(mut self, url: &'static str) -> Self {
+        self.fix_url = Some(url);
+        self
+    }
+}
+
+/// Everything the checks need, gathered by the caller.
+#[derive(Debug, Clone, Default)]
+pub struct SetupCheckInput {
+    /// Game the modlist targets, if known.
+    pub game: Option<GameType>,
+    /// Game directory that will be used (explicit or detected).
+    pub game_dir: Option<PathBuf>,
+    /// Game version the modlist was built against, if known.
+    pub game_version: Option<String>,
+    pub downloads_dir: PathBuf,
+    pub install_dir: PathBuf,
+    /// Total archive size in bytes (0 = unknown).
+    pub download_size: u64,
+    /// Total installed size in bytes (0 = unknown).
+    pub install_size: u64,
+    /// Number of installed files (0 = unknown).
+    pub file_count: u64,
+    pub nexus_api_key: String,
+    /// The modlist needs Tale of Two Wastelands installed first.
+    pub requires_ttw: bool,
+    /// TTW installer/MPI or an existing TTW output is configured.
+    pub ttw_configured: bool,
+    /// Windows runtimes the modlist author asks for.
+    pub runtimes: Vec<&'static Runtime>,
+    /// Wine prefix the modlist will run in. Defaults to the game's Proton
+    /// prefix.
+    pub wine_prefix: Option<PathBuf>,
+}
+
+impl SetupCheckInput {
+    /// Fill in the game, sizes and TTW requirement from a parsed modlist.
+    pub fn apply_modlist(&mut self, modlist: &Modlist) {
+        self.game = GameType::from_wabbajack_name(&modlist.game_type);
+        self.game_version = required_game_version(modlist);
+        self.download_size = modlist.archives.iter().map(|a| a.size).sum();
+        self.install_size = modlist.directives.iter().map(|d| d.size()).sum();
+        self.file_count = modlist.directives.len() as u64;
+        self.requires_ttw = modlist.requires_ttw().required;
+        self.runtimes = required_runtimes(modlist);
+    }
+}
+
+/// Run every check. Only the Nexus check touches the network.
+pub async fn run_setup_checks(input: &SetupCheckInput) -> Vec<SetupCheck> {
+    let mut checks = vec![check_game(input.game, input.game_dir.as_deref())];
+    if checks[0].passed {
+        checks.extend(check_runtime(input));
+    }
+    checks.push(check_writable("Downloads directory", &input.downloads_dir));
+    checks.push(check_writable("Install directory", &input.install_dir));
+    checks.extend(check_disk_space(input));
+    checks.push(check_nexus(&input.nexus_api_key).await);
+    checks.push(check_proton());
+    checks.push(check_limits(input.file_count));
+    if input.requires_ttw {
+        checks.push(check_ttw(input.ttw_configured));
+    }
+    if !input.runtimes.is_empty() {
+        checks.push(check_runtimes(input));
+    }
+    checks
+}
+
+fn check_game(game: Option<GameType>, game_dir: Option<&Path>) -> SetupCheck {
+    const NAME: &str = "Game installation";
+    let Some(game) = game else {
+        return SetupCheck::pass(NAME, "Unknown game type; skipped");
+    };
+    let Some(dir) = game_dir else {
+        return SetupCheck::fail(
+            NAME,
+            format!("{} not found in Steam or Heroic", game),
+            format!(
+                "Install {} through Steam or Heroic, or set a game directory in Settings",
+                game
+            ),
+        );
+    };
+    match validate_game_path(game, dir) {
+        Ok(()) => SetupCheck::pass(NAME, format!("{} at {}", game, dir.display())),
+        Err(e) => SetupCheck::fail(
+            NAME,
+            e.to_string(),
+            "Verify the game files in your launcher, or point Settings > Game at a \
+             clean install",
+        ),
+    }
+}
+
+/// Installed runtime vs the one the modlist was built for. Skipped when the
+/// game's version can't be read.
+fn check_runtime(input: &SetupCheckInput) -> Option<SetupCheck> {
+    const NAME: &str = "Game version";
+    let game = input.game?;
+    let installed = installed_game_version(game, input.game_dir.as_deref()?)?;
+    let label = |v: &str| match runtime_edition(game, v) {
+        Some(edition) => format!("{} ({})", v, edition),
+        None => v.to_string(),
+    };
+    let Some(required) = input.game_version.as_deref() else {
+        return Some(SetupCheck::pass(
+            NAME,
+            format!("Installed {}", label(&installed)),
+        ));
+    };
+    let detail = format!(
+        "Installed {}, modlist built for {}",
+        label(&installed),
+        label(required)
+    );
+    Some(match compare_runtime(game, required, &installed) {
+        RuntimeMatch::Compatible | RuntimeMatch::DifferentPatch => SetupCheck::pass(NAME, detail),
+        RuntimeMatch::WrongEdition { fix, .. } => SetupCheck::fail(NAME, detail, fix),
+    })
+}
+
+fn check_writable(name: &'static str, dir: &Path) -> SetupCheck {
+    if dir.as_os_str().is_empty() {
+        return SetupCheck::fail(name, "Not set", "Choose a directory");
+    }
+    let Some(existing) = dir.ancestors().find(|p| p.exists()) else {
+        return SetupCheck::fail(
+            name,
+            format!("{}: no existing parent directory", dir.display()),
+            "Pick a directory on a mounted drive",
+        );
+    };
+    match probe_writable(existing) {
+        Ok(()) if existing == dir => {
+            SetupCheck::pass(name, format!("{} is writable", dir.display()))
+        }
+        Ok(()) => SetupCheck::pass(
+            name,
+            format!(
+                "{} will be created ({} is writable)",
+                dir.display(),
+                existing.display()
+            ),
+        ),
+        Err(e) => SetupCheck::fail(
+            name,
+            format!("{}: {}", existing.display(), e),
+            "Pick a directory you own, or fix its permissions",
+        ),
+    }
+}
+
+/// Write and remove a probe file in the existing directory `dir`.
+fn probe_writable(dir: &Path) -> std::io::Result<()> {
+    if !dir.is_dir() {
+        return Err(std::io::Error::other("not a directory"));
+    }
+    let probe = dir.join(".clf3-write-test");
+    std::fs::write(&probe, b"")?;
+    std::fs::remove_file(&probe)
+}
+
+/// Free bytes on the filesystem holding `path` (or its nearest existing
+/// ancestor, for directories that will be created later).
+#[allow(clippy::unnecessary_cast)] // statvfs field widths differ across targets
+pub fn available_space(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|p| p.exists())?;
+    let c_path = CString::new(existing.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+fn device_of(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|p| p.exists())?;
+    std::fs::metadata(existing).ok().map(|m| m.dev())
+}
+
+/// One check per filesystem. Downloads and install sharing a disk need room
+/// for both.
+fn check_disk_space(input: &SetupCheckInput) -> Vec<SetupCheck> {
+    const NAME: &str = "Disk space";
+    if input.download_size == 0 && input.install_size == 0 {
+        return vec![SetupCheck::pass(NAME, "Modlist size unknown; skipped")];
+    }
+
+    let same_disk = device_of(&input.downloads_dir).is_some()
+        && device_of(&input.downloads_dir) == device_of(&input.install_dir);
+    let targets: Vec<(&Path, u64)> = if same_disk {
+        vec![(
+            input.install_dir.as_path(),
+            input.download_size + input.install_size,
+        )]
+    } else {
+        vec![
+            (input.downloads_dir.as_path(), input.download_size),
+            (input.install_dir.as_path(), input.install_size),
+        ]
+    };
+
+    targets
+        .into_iter()
+        .map(|(dir, needed)| match available_space(dir) {
+            None => SetupCheck::pass(NAME, format!("Could not query {}", dir.display())),
+            Some(free) if free >= needed => SetupCheck::pass(
+                NAME,
+                format!(
+                    "{} free on {} ({} needed)",
+                    format_gb(free),
+                    dir.display(),
+                    format_gb(needed)
+                ),
+            ),
+            Some(free) => SetupCheck::fail(
+                NAME,
+                format!(
+                    "Only {} free on {}, {} needed",
+                    format_gb(free),
+                    dir.display(),
+                    format_gb(needed)
+                ),
+                "Free up space or choose directories on a larger drive",
+            ),
+        })
+        .collect()
+}
+
+fn format_gb(bytes: u64) -> String {
+    format!("{:.1} GB", bytes as f64 / 1_000_000_000.0)
+}
+
+async fn check_nexus(api_key: &str) -> SetupCheck {
+    const NAME: &str = "Nexus API key";
+    const KEY_PAGE: &str = "https://www.nexusmods.com/users/myaccount?tab=api";
+    if api_key.trim().is_empty() {
+        return SetupCheck::fail(NAME, "Not set", "Paste your personal API key in Settings")
+            .with_url(KEY_PAGE);
+    }
+    let result = match NexusDownloader::new(api_key) {
+        Ok(nexus) => nexus.validate().await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(info) if info.is_premium => {
+            SetupCheck::pass(NAME, format!("Valid, Premium ({})", info.name))
+        }
+        Ok(info) => SetupCheck::fail(
+            NAME,
+            format!("Valid, but {} is not Premium", info.name),
+            "Automated Nexus downloads need a Premium account",
+        )
+        .with_url("https://www.nexusmods.com/premium"),
+        Err(e) => SetupCheck::fail(
+            NAME,
+            format!("{:#}", e),
+            "Generate a new API key and paste it in Settings",
+        )
+        .with_url(KEY_PAGE),
+    }
+}
+
+fn check_proton() -> SetupCheck {
+    const NAME: &str = "Proton";
+    let protons = cached_protons();
+    match protons.first() {
+        Some(p) => SetupCheck::pass(NAME, format!("{} found", p.name)),
+        None => SetupCheck::fail(
+            NAME,
+            "No Proton 10+ found in Steam",
+            "Install Proton Experimental or GE-Proton 10+ to launch the modlist through Steam",
+        ),
+    }
+}
+
+fn check_limits(file_count: u64) -> SetupCheck {
+    const NAME: &str = "System limits";
+    let warnings = super::limits::check_limits(file_count);
+    if warnings.is_empty() {
+        return SetupCheck::pass(NAME, "Open-file and inotify limits are high enough");
+    }
+    let problems: Vec<_> = warnings.iter().map(|w| w.problem.as_str()).collect();
+    let fixes: Vec<_> = warnings.iter().map(|w| w.fix.as_str()).collect();
+    SetupCheck::fail(NAME, problems.join("; "), fixes.join("\n"))
+}
+
+fn check_ttw(configured: bool) -> SetupCheck {
+    const NAME: &str = "Tale of Two Wastelands";
+    if configured {
+        SetupCheck::pass(NAME, "TTW paths configured")
+    } else {
+        SetupCheck::fail(
+            NAME,
+            "This modlist requires TTW, but no TTW installer or output is set",
+            "Set the TTW installer, MPI file and Fallout 3 paths in settings.json",
+        )
+        .with_url("https://taleoftwowastelands.com/")
+    }
+}
+
+/// Runtimes the modlist needs vs what the Wine prefix has.
+fn check_runtimes(input: &SetupCheckInput) -> SetupCheck {
+    const NAME: &str = "Windows runtimes";
+    let names = |runtimes: &[&Runtime]| {
+        let names: Vec<_> = runtimes.iter().map(|r| r.name).collect();
+        names.join(", ")
+    };
+    let verbs: Vec<_> = input.runtimes.iter().map(|r| r.winetricks).collect();
+    let app_id = input.game.and_then(|g| g.app_ids().first().copied());
+    let prefix = input.wine_prefix.clone().or_else(|| {
+        let game = input.game?;
+        game.app_ids()
+            .iter()
+            .find_map(|id| find_compatdata(&id.to_string()))
+    });
+
+    let Some(prefix) = prefix else {
+        let fix = match (input.game, app_id) {
+            (Some(game), Some(id)) => format!(
+                "Launch {} once through Steam to create its prefix, then run: \
+                 protontricks {} -q {}",
+                game,
+                id,
+                verbs.join(" ")
+            ),
+            _ => format!(
+                "Install them in the prefix you run Mod Organizer 2 from: winetricks -q {}",
+                verbs.join(" ")
+            ),
+        };
+        return SetupCheck::fail(
+            NAME,
+            format!(
+                "Needs {}; no Wine prefix found to check",
+                names(&input.runtimes)
+            ),
+            fix,
+        );
+    };
+
+    let missing: Vec<&Runtime> = input
+        .runtimes
+        .iter()
+        .copied()
+        .filter(|r| !is_installed(r, &prefix))
+        .collect();
+    if missing.is_empty() {
+        return SetupCheck::pass(
+            NAME,
+            format!(
+                "{} installed in {}",
+                names(&input.runtimes),
+                prefix.display()
+            ),
+        );
+    }
+    let downloads: Vec<_> = missing
+        .iter()
+        .map(|r| format!("{}: {}", r.name, r.url))
+        .collect();
+    SetupCheck::fail(
+        NAME,
+        format!("Missing from {}: {}", prefix.display(), names(&missing)),
+        format!(
+            "Run: {}\nOr run these installers inside the prefix:\n{}",
+            winetricks_command(&prefix, &missing),
+            downloads.join("\n")
+        ),
+    )
+    .with_url(missing[0].url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writable_check_probes_without_creating_dirs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let nested = tmp.path().join("a/b");
+        let check = check_writable("Downloads directory", &nested);
+        assert!(check.passed);
+        assert!(check.detail.contains("will be created"));
+        assert!(!tmp.path().join("a").exists());
+        assert!(!tmp.path().join(".clf3-write-test").exists());
+        assert!(check_writable("Downloads directory", tmp.path()).passed);
+        assert!(!check_writable("Install directory", Path::new("")).passed);
+    }
+
+    #[test]
+    fn disk_space_is_checked_per_filesystem() {
+        let tmp = tempfile::tempdir().unwrap();
+        let free = available_space(tmp.path()).unwrap();
+        let mut input = SetupCheckInput {
+            downloads_dir: tmp.path().join("downloads"),
+            install_dir: tmp.path().join("install"),
+            download_size: 1,
+            install_size: 1,
+            ..Default::default()
+        };
+        let checks = check_disk_space(&input);
+        assert_eq!(checks.len(), 1, "same disk is one combined check");
+        assert!(checks[0].passed);
+
+        input.install_size = free;
+        assert!(!check_disk_space(&input)[0].passed);
+    }
+
+    #[test]
+    fn runtimes_are_checked_in_the_wine_prefix() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("winetricks.log"), "vcrun2022\n").unwrap();
+        let runtimes = crate::installer::requirements::runtimes_in_text("VC++ redist, d3dx9");
+        let mut input = SetupCheckInput {
+            runtimes,
+            wine_prefix: Some(tmp.path().to_path_buf()),
+            ..Default::default()
+        };
+        let check = check_runtimes(&input);
+        assert!(!check.passed);
+        assert!(check.detail.ends_with("DirectX 9 (June 2010) Runtime"));
+        assert!(check.fix.unwrap().contains("winetricks -q d3dx9"));
+
+        input.runtimes.pop();
+        assert!(check_runtimes(&input).passed);
+    }
+}
@@ -288,6 +288,20 @@ mod tests {
         assert!(!sidecar_valid(&output, "abc123"));
     }
 
+    #[test]
+    fn test_archive_hash_invalidated_by_size_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("mod.7z");
+        fs::write(&archive, b"archive bytes").unwrap();
+
+        write_archive_hash(&archive, "xyz=").unwrap();
+        assert!(archive_hash_valid(&archive, "xyz="));
+        assert!(!archive_hash_valid(&archive, "other="));
+
+        fs::write(&archive, b"archive bytes, now longer").unwrap();
+        assert!(!archive_hash_valid(&archive, "xyz="));
+    }
+
     #[test]
     fn test_manifest_roundtrip() {
         let dir = tempfile::tempdir().unwrap();
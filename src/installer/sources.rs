@@ -0,0 +1,215 @@
+//! Resolve where each archive of a modlist can be downloaded from.
+//!
+//! For embedders that run their own download UI or scheduler: every archive
+//! comes back with its source state, size and hash plus the direct URL the
+//! downloaders would fetch, worked out the same way but without transferring
+//! the archive itself.
+
+use super::downloader::{
+    is_mediafire_url, is_moddb_url, is_yandex_url, resolve_moddb_download_url,
+};
+use crate::downloaders::{
+    parse_header_lines, GoogleDriveDownloader, HttpClient, MediaFireDownloader, NexusDownloader,
+    WabbajackCdnDownloader, YandexDownloader,
+};
+use crate::modlist::{Archive, DownloadState, Modlist};
+use anyhow::Result;
+use futures::stream::{self, StreamExt};
+
+/// Archives resolved at once
+const RESOLVE_CONCURRENCY: usize = 8;
+
+/// How an archive can be fetched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolvedSource {
+    /// A plain GET of `url` with `headers` returns the archive. Nexus,
+    /// MediaFire, Google Drive and Yandex links expire, so fetch them soon.
+    Direct {
+        url: String,
+        headers: Vec<(String, String)>,
+    },
+    /// Wabbajack CDN file served as parts to be written in order.
+    WabbajackCdn { parts: Vec<String> },
+    /// Copied from the game installation rather than downloaded.
+    GameFile { game: String, file: String },
+    /// Needs a browser or a login session (Mega, LoversLab, other manual
+    /// sources); `url` is the page to open.
+    Manual { url: String },
+    /// Resolving failed, e.g. no Nexus API key or the file was removed.
+    Unresolved { reason: String },
+}
+
+/// One archive of a modlist with its resolved source.
+#[derive(Debug)]
+pub struct ResolvedArchive<'a> {
+    pub name: &'a str,
+    pub hash: &'a str,
+    pub size: u64,
+    pub state: &'a DownloadState,
+    pub source: ResolvedSource,
+}
+
+/// Clients the per-source resolvers need, created once per call.
+struct Resolvers {
+    http: HttpClient,
+    nexus: Option<NexusDownloader>,
+    gdrive: GoogleDriveDownloader,
+    mediafire: MediaFireDownloader,
+    yandex: YandexDownloader,
+    cdn: WabbajackCdnDownloader,
+}
+
+/// Resolve every archive in `modlist`, in modlist order, without downloading
+/// any of them. Nexus links need a Premium `nexus_api_key`; with an empty
+/// key Nexus archives come back [`ResolvedSource::Unresolved`].
+///
+/// Only creating the HTTP clients can fail; a source that can't be resolved
+/// is reported per archive.
+pub async fn resolve_archive_sources<'a>(
+    modlist: &'a Modlist,
+    nexus_api_key: &str,
+) -> Result<Vec<ResolvedArchive<'a>>> {
+    let nexus_api_key = nexus_api_key.trim();
+    let resolvers = Resolvers {
+        http: HttpClient::new()?,
+        nexus: if nexus_api_key.is_empty() {
+            None
+        } else {
+            Some(NexusDownloader::new(nexus_api_key)?)
+        },
+        gdrive: GoogleDriveDownloader::new()?,
+        mediafire: MediaFireDownloader::new()?,
+        yandex: YandexDownloader::new()?,
+        cdn: WabbajackCdnDownloader::new()?,
+    };
+    let resolvers = &resolvers;
+
+    Ok(stream::iter(&modlist.archives)
+        .map(|archive| async move {
+            let source = match resolve_source(archive, resolvers).await {
+                Ok(source) => source,
+                Err(e) => ResolvedSource::Unresolved {
+                    reason: format!("{:#}", e),
+                },
+            };
+            ResolvedArchive {
+                name: &archive.name,
+                hash: &archive.hash,
+                size: archive.size,
+                state: &archive.state,
+                source,
+            }
+        })
+        .buffered(RESOLVE_CONCURRENCY)
+        .collect()
+        .await)
+}
+
+fn direct(url: String) -> ResolvedSource {
+    ResolvedSource::Direct {
+        url,
+        headers: Vec::new(),
+    }
+}
+
+async fn resolve_source(archive: &Archive, r: &Resolvers) -> Result<ResolvedSource> {
+    Ok(match &archive.state {
+        DownloadState::Nexus(state) => {
+            let Some(nexus) = &r.nexus else {
+                return Ok(ResolvedSource::Unresolved {
+                    reason: "Nexus API key not set".to_string(),
+                });
+            };
+            let domain = NexusDownloader::game_domain(&state.game_name);
+            direct(
+                nexus
+                    .get_download_link(domain, state.mod_id, state.file_id)
+                    .await?,
+            )
+        }
+        DownloadState::Http(state) => ResolvedSource::Direct {
+            url: state.url.clone(),
+            headers: parse_header_lines(&state.headers),
+        },
+        DownloadState::GoogleDrive(state) => {
+            direct(r.gdrive.get_download_url(&state.id, archive.size).await?)
+        }
+        DownloadState::MediaFire(state) => direct(r.mediafire.get_download_url(&state.url).await?),
+        DownloadState::WabbajackCDN(state) => {
+            let definition = r.cdn.get_definition(&state.url).await?;
+            ResolvedSource::WabbajackCdn {
+                parts: WabbajackCdnDownloader::get_part_urls(&state.url, &definition),
+            }
+        }
+        DownloadState::GameFileSource(state) => ResolvedSource::GameFile {
+            game: state.game.clone(),
+            file: state.game_file.clone(),
+        },
+        DownloadState::Mega(state) => ResolvedSource::Manual {
+            url: state.url.clone(),
+        },
+        DownloadState::Manual(state) => {
+            let url = &state.url;
+            if is_moddb_url(url) {
+                direct(resolve_moddb_download_url(&r.http, url).await?)
+            } else if is_mediafire_url(url) {
+                direct(r.mediafire.get_download_url(url).await?)
+            } else if is_yandex_url(url) {
+                direct(r.yandex.get_download_url(url, Some(&archive.name)).await?)
+            } else {
+                // LoversLab and Mega need a session or client-side
+                // decryption; everything else is a page for the user.
+                ResolvedSource::Manual { url: url.clone() }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn offline_sources_resolve_without_network() {
+        let modlist: Modlist = serde_json::from_str(
+            r#"{
+                "Name": "Test", "Version": "1.0", "WabbajackVersion": "3.0",
+                "GameType": "SkyrimSpecialEdition", "IsNSFW": false, "Directives": [],
+                "Archives": [
+                    {"Hash": "a", "Meta": "", "Name": "a.7z", "Size": 4,
+                     "State": {"$type": "HttpDownloader, Wabbajack.Lib",
+                               "Url": "https://x/a.7z", "Headers": ["Referer: https://x/"]}},
+                    {"Hash": "b", "Meta": "", "Name": "b.7z", "Size": 5,
+                     "State": {"$type": "NexusDownloader, Wabbajack.Lib",
+                               "GameName": "SkyrimSpecialEdition", "ModID": 1, "FileID": 2,
+                               "ImageURL": null, "IsNSFW": false}},
+                    {"Hash": "c", "Meta": "", "Name": "c.7z", "Size": 6,
+                     "State": {"$type": "MegaDownloader, Wabbajack.Lib",
+                               "Url": "https://mega.nz/file/abc"}}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let resolved = resolve_archive_sources(&modlist, "").await.unwrap();
+        let names: Vec<_> = resolved.iter().map(|a| (a.name, a.size)).collect();
+        assert_eq!(names, [("a.7z", 4), ("b.7z", 5), ("c.7z", 6)]);
+        assert_eq!(
+            resolved[0].source,
+            ResolvedSource::Direct {
+                url: "https://x/a.7z".to_string(),
+                headers: vec![("Referer".to_string(), "https://x/".to_string())],
+            }
+        );
+        assert!(matches!(
+            resolved[1].source,
+            ResolvedSource::Unresolved { .. }
+        ));
+        assert_eq!(
+            resolved[2].source,
+            ResolvedSource::Manual {
+                url: "https://mega.nz/file/abc".to_string()
+            }
+        );
+    }
+}
@@ -0,0 +1,108 @@
+//! Free space guard for the download and extract phases
+//!
+//! The preflight check sizes the disk once, but other programs can fill it
+//! during a long install. Before each download and extraction the target
+//! filesystem is checked; below a safety margin the worker pauses until the
+//! user frees space, rather than hitting ENOSPC halfway through a write and
+//! leaving a truncated file behind.
+
+use super::progress::ProgressReporter;
+use super::progress_cli::format_bytes;
+use super::setup_check::available_space;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+/// Free space kept in reserve on the downloads and install disks.
+pub const MIN_FREE_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Times a download may hit a full disk before it fails. Free space can look
+/// fine while writes still fail (a per-user quota, or other workers filling
+/// the disk again), so waiting alone could loop forever.
+pub const MAX_DISK_FULL_RETRIES: u32 = 3;
+
+/// Free bytes on `dir`'s filesystem if fewer than `needed`. Unknown free
+/// space never pauses the install.
+pub fn shortfall(dir: &Path, needed: u64) -> Option<u64> {
+    available_space(dir).filter(|&free| free < needed)
+}
+
+/// Whether `err` was caused by a full disk or quota.
+pub fn is_disk_full(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        cause
+            .downcast_ref::<std::io::Error>()
+            .and_then(|e| e.raw_os_error())
+            .is_some_and(|code| matches!(code, libc::ENOSPC | libc::EDQUOT))
+    })
+}
+
+fn paused_message(dir: &Path, free: u64, needed: u64) -> String {
+    format!(
+        "Paused: only {} free on {} (need {}). Free up space and the install resumes automatically.",
+        format_bytes(free),
+        dir.display(),
+        format_bytes(needed)
+    )
+}
+
+fn resumed_message(dir: &Path) -> String {
+    format!("Free space available on {}, resuming", dir.display())
+}
+
+/// Block until `dir`'s filesystem has `needed` bytes free. Returns true if it
+/// had to wait.
+pub fn wait_for_space_blocking(
+    dir: &Path,
+    needed: u64,
+    reporter: &Arc<dyn ProgressReporter>,
+) -> bool {
+    let Some(free) = shortfall(dir, needed) else {
+        return false;
+    };
+    let message = paused_message(dir, free, needed);
+    warn!("{}", message);
+    reporter.log(&message);
+    while shortfall(dir, needed).is_some() {
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    reporter.log(&resumed_message(dir));
+    true
+}
+
+/// Async version of [`wait_for_space_blocking`] for the download workers.
+pub async fn wait_for_space(dir: &Path, needed: u64, reporter: &Arc<dyn ProgressReporter>) -> bool {
+    let Some(free) = shortfall(dir, needed) else {
+        return false;
+    };
+    let message = paused_message(dir, free, needed);
+    warn!("{}", message);
+    reporter.log(&message);
+    while shortfall(dir, needed).is_some() {
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    reporter.log(&resumed_message(dir));
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn shortfall_and_disk_full_detection() {
+        let dir = std::env::temp_dir();
+        assert!(shortfall(&dir, 0).is_none());
+        assert!(shortfall(&dir, u64::MAX).is_some());
+
+        let err = anyhow::Error::new(std::io::Error::from_raw_os_error(libc::ENOSPC))
+            .context("Failed to write foo.7z");
+        assert!(is_disk_full(&err));
+        let err = anyhow::anyhow!("connection reset").context("Failed to download foo.7z");
+        assert!(!is_disk_full(&err));
+    }
+}
@@ -0,0 +1,174 @@
+//! Read-only "what's left" report (`clf3 status`).
+//!
+//! Compares a modlist against a downloads and install directory without
+//! touching either: archives are checked by name and size, directives go
+//! through the same pre-validation pass the installer runs (with hashing
+//! off, so nothing is deleted).
+//!
+//! `interrupted_install` uses the same check to tell the GUI whether an
+//! install was stopped part way, so it can offer to resume it.
+
+use crate::installer::pipeline::extract_bsa_temp_id;
+use crate::installer::prevalidation;
+use crate::installer::progress::{NullReporter, ProgressReporter};
+use crate::modlist::{db_matches_wabbajack, InstallManifest, ModlistDb};
+use crate::paths;
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Remaining work for one directive type.
+#[derive(Debug, Clone, Serialize)]
+pub struct DirectiveTypeStatus {
+    pub directive_type: String,
+    pub total: usize,
+    pub done: usize,
+}
+
+/// Snapshot of how far an install has got.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct InstallStatus {
+    /// Archives that have to be downloaded (game-file sources excluded).
+    pub archives_total: usize,
+    pub archives_present: usize,
+    /// Names of archives not in the downloads dir with the expected size.
+    pub missing_archives: Vec<String>,
+    pub download_bytes_remaining: u64,
+    pub directives_total: usize,
+    pub directives_done: usize,
+    /// Output bytes still to write. BSA staging files are left out; the
+    /// archive they build is counted instead.
+    pub install_bytes_remaining: u64,
+    pub by_type: Vec<DirectiveTypeStatus>,
+}
+
+impl InstallStatus {
+    pub fn is_complete(&self) -> bool {
+        self.missing_archives.is_empty() && self.directives_done == self.directives_total
+    }
+}
+
+/// Whether `name` is in the downloads dir with the expected size (0 =
+/// unknown size, any size accepted).
+pub(crate) fn archive_present(downloads_dir: &Path, name: &str, expected_size: u64) -> bool {
+    std::fs::metadata(downloads_dir.join(name))
+        .is_ok_and(|m| m.is_file() && (expected_size == 0 || m.len() == expected_size))
+}
+
+/// Map of normalized relative path -> size for every file under `output_dir`.
+pub(crate) fn scan_output_files(output_dir: &Path) -> HashMap<String, u64> {
+    let mut existing_files = HashMap::new();
+    if output_dir.exists() {
+        for entry in walkdir::WalkDir::new(output_dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            if entry.file_type().is_file() {
+                if let Ok(rel_path) = entry.path().strip_prefix(output_dir) {
+                    let normalized = paths::normalize_for_lookup(&rel_path.to_string_lossy());
+                    if let Ok(meta) = entry.metadata() {
+                        existing_files.insert(normalized, meta.len());
+                    }
+                }
+            }
+        }
+    }
+    existing_files
+}
+
+/// Check `db` against the downloads and install dirs. Never writes to either.
+pub fn check_status(
+    db: &ModlistDb,
+    downloads_dir: &Path,
+    output_dir: &Path,
+) -> Result<InstallStatus> {
+    let mut status = InstallStatus::default();
+
+    for archive in db.get_all_archives()? {
+        if archive.state_json.contains("GameFileSourceDownloader") {
+            continue;
+        }
+        status.archives_total += 1;
+        let expected = archive.size.max(0) as u64;
+        if archive_present(downloads_dir, &archive.name, expected) {
+            status.archives_present += 1;
+        } else {
+            status.download_bytes_remaining += expected;
+            status.missing_archives.push(archive.name);
+        }
+    }
+    status.missing_archives.sort();
+
+    let existing = scan_output_files(output_dir);
+    let reporter: Arc<dyn ProgressReporter> = Arc::new(NullReporter);
+    let result = prevalidation::run_prevalidation(db, &existing, output_dir, false, &reporter)?;
+
+    for d in db.get_all_directives_summary()? {
+        if !result.skip_set.contains(&d.id) && extract_bsa_temp_id(&d.to_path).is_none() {
+            status.install_bytes_remaining += d.size;
+        }
+    }
+
+    let mut by_type: Vec<_> = result
+        .type_stats
+        .into_iter()
+        .filter(|(_, s)| s.total > 0)
+        .map(|(directive_type, s)| DirectiveTypeStatus {
+            directive_type,
+            total: s.total,
+            done: s.already_valid,
+        })
+        .collect();
+    by_type.sort_by(|a, b| a.directive_type.cmp(&b.directive_type));
+    status.directives_total = by_type.iter().map(|t| t.total).sum();
+    status.directives_done = by_type.iter().map(|t| t.done).sum();
+    status.by_type = by_type;
+
+    Ok(status)
+}
+
+/// How far an unfinished install of `wabbajack_path` into `output_dir` got.
+///
+/// `None` unless the installer's saved state is for this exact .wabbajack,
+/// the dir doesn't hold a finished install, and some work was already done.
+pub fn interrupted_install(
+    wabbajack_path: &Path,
+    downloads_dir: &Path,
+    output_dir: &Path,
+) -> Result<Option<InstallStatus>> {
+    let db_path = super::state_db_path(wabbajack_path);
+    if !db_path.is_file() || !wabbajack_path.is_file() {
+        return Ok(None);
+    }
+    if InstallManifest::load_from(output_dir)?.is_some() {
+        return Ok(None);
+    }
+    let db = ModlistDb::open_shared(&db_path)?;
+    if !db_matches_wabbajack(&db, wabbajack_path)? {
+        return Ok(None);
+    }
+
+    let status = check_status(&db, downloads_dir, output_dir)?;
+    let started = status.directives_done > 0 || status.archives_present > 0;
+    Ok((started && !status.is_complete()).then_some(status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn scan_output_files_uses_lookup_keys() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::create_dir_all(tmp.path().join("mods/A")).unwrap();
+        fs::write(tmp.path().join("mods/A/Plugin.esp"), b"1234").unwrap();
+
+        let files = scan_output_files(tmp.path());
+        let key = paths::normalize_for_lookup("mods/A/Plugin.esp");
+        assert_eq!(files.get(&key), Some(&4));
+        assert!(scan_output_files(&tmp.path().join("missing")).is_empty());
+    }
+}
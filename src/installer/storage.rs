@@ -0,0 +1,171 @@
+//! Storage-aware extraction concurrency.
+//!
+//! Many extraction threads on a spinning disk make the heads seek between
+//! archives and files, and end up slower than a couple of threads would be.
+//! On SSD/NVMe more parallelism keeps helping. The volume type is read from
+//! sysfs (`queue/rotational`) for the downloads and install dirs; `--storage`
+//! overrides it when detection gets it wrong or can't tell.
+
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+/// Kind of disk a directory lives on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageKind {
+    /// Rotational disk: seeks are expensive, keep extraction mostly serial.
+    Hdd,
+    /// SSD/NVMe: parallel extraction scales with the CPU.
+    Ssd,
+}
+
+impl std::fmt::Display for StorageKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageKind::Hdd => write!(f, "HDD"),
+            StorageKind::Ssd => write!(f, "SSD"),
+        }
+    }
+}
+
+/// Extraction concurrency picked for a storage kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtractTuning {
+    pub install_workers: usize,
+    pub sevenzip_workers: usize,
+}
+
+/// Concurrency defaults for `storage` on a machine with `threads` CPU threads.
+///
+/// `None` (couldn't tell) gets a middle ground: half the threads, so an HDD
+/// isn't thrashed too badly and an SSD still gets most of the speed.
+pub fn tune(storage: Option<StorageKind>, threads: usize) -> ExtractTuning {
+    let threads = threads.max(1);
+    match storage {
+        Some(StorageKind::Hdd) => ExtractTuning {
+            install_workers: threads.min(2),
+            sevenzip_workers: 1,
+        },
+        Some(StorageKind::Ssd) => ExtractTuning {
+            install_workers: threads,
+            sevenzip_workers: threads,
+        },
+        None => {
+            let half = (threads / 2).max(1);
+            ExtractTuning {
+                install_workers: half,
+                sevenzip_workers: half,
+            }
+        }
+    }
+}
+
+/// Storage kind for an extraction reading from `downloads_dir` and writing to
+/// `install_dir`. One HDD on either side is enough to make it seek-bound.
+pub fn detect_for_install(downloads_dir: &Path, install_dir: &Path) -> Option<StorageKind> {
+    match (detect(downloads_dir), detect(install_dir)) {
+        (Some(StorageKind::Hdd), _) | (_, Some(StorageKind::Hdd)) => Some(StorageKind::Hdd),
+        (Some(StorageKind::Ssd), Some(StorageKind::Ssd)) => Some(StorageKind::Ssd),
+        _ => None,
+    }
+}
+
+/// Storage kind of the disk holding `path` (or its nearest existing
+/// ancestor). `None` if sysfs doesn't say, e.g. network or FUSE mounts.
+pub fn detect(path: &Path) -> Option<StorageKind> {
+    let existing = path.ancestors().find(|p| p.exists())?;
+    let dev = fs::metadata(existing).ok()?.dev();
+
+    // btrfs and other multi-device filesystems report an anonymous device
+    // number with no sysfs entry; fall back to the mount's source device.
+    let sysfs_dir = fs::canonicalize(format!(
+        "/sys/dev/block/{}:{}",
+        libc::major(dev),
+        libc::minor(dev)
+    ))
+    .ok()
+    .or_else(|| {
+        let canonical = fs::canonicalize(existing).ok()?;
+        let mountinfo = fs::read_to_string("/proc/self/mountinfo").ok()?;
+        let source = mount_source(&mountinfo, &canonical)?;
+        let device = fs::canonicalize(source).ok()?;
+        fs::canonicalize(Path::new("/sys/class/block").join(device.file_name()?)).ok()
+    })?;
+
+    rotational(&sysfs_dir)
+}
+
+/// Read `queue/rotational` for a block device dir. Partitions don't have a
+/// queue of their own, so look at the parent disk for those.
+fn rotational(sysfs_dir: &Path) -> Option<StorageKind> {
+    let read = |dir: &Path| fs::read_to_string(dir.join("queue/rotational")).ok();
+    let value = read(sysfs_dir).or_else(|| read(sysfs_dir.parent()?))?;
+    match value.trim() {
+        "1" => Some(StorageKind::Hdd),
+        "0" => Some(StorageKind::Ssd),
+        _ => None,
+    }
+}
+
+/// Source device (e.g. `/dev/nvme0n1p2`) of the mount containing `path`,
+/// from `/proc/self/mountinfo` contents. The longest matching mount point
+/// wins; `None` if that mount isn't backed by a device (tmpfs, FUSE, ...).
+fn mount_source(mountinfo: &str, path: &Path) -> Option<PathBuf> {
+    mountinfo
+        .lines()
+        .filter_map(|line| {
+            let (mount, rest) = line.split_once(" - ")?;
+            let mount_point = unescape_mount_path(mount.split(' ').nth(4)?);
+            let source = rest.split(' ').nth(1)?;
+            path.starts_with(&mount_point)
+                .then_some((mount_point, source))
+        })
+        .max_by_key(|(mount_point, _)| mount_point.as_os_str().len())
+        .map(|(_, source)| source)
+        .filter(|source| source.starts_with("/dev/"))
+        .map(PathBuf::from)
+}
+
+/// Undo mountinfo's octal escapes for space, tab, newline and backslash.
+fn unescape_mount_path(raw: &str) -> PathBuf {
+    PathBuf::from(
+        raw.replace("\\040", " ")
+            .replace("\\011", "\t")
+            .replace("\\012", "\n")
+            .replace("\\134", "\\"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tune_and_mount_source() {
+        assert_eq!(
+            tune(Some(StorageKind::Hdd), 16),
+            ExtractTuning {
+                install_workers: 2,
+                sevenzip_workers: 1
+            }
+        );
+        assert_eq!(tune(Some(StorageKind::Ssd), 16).install_workers, 16);
+        assert_eq!(tune(None, 16).install_workers, 8);
+        assert_eq!(tune(None, 1).sevenzip_workers, 1);
+
+        let mountinfo = "\
+22 1 0:21 / / rw,relatime - btrfs /dev/nvme0n1p2 rw
+40 22 8:17 / /mnt/Games\\040HDD rw,relatime - ext4 /dev/sdb1 rw
+41 22 0:50 / /tmp rw - tmpfs tmpfs rw";
+        assert_eq!(
+            mount_source(mountinfo, Path::new("/mnt/Games HDD/Modlists/Tuxborn")),
+            Some(PathBuf::from("/dev/sdb1"))
+        );
+        assert_eq!(
+            mount_source(mountinfo, Path::new("/home/user/Modlists")),
+            Some(PathBuf::from("/dev/nvme0n1p2"))
+        );
+        // tmpfs has no backing device.
+        assert_eq!(mount_source(mountinfo, Path::new("/tmp/x")), None);
+    }
+}
@@ -41,6 +41,7 @@ use super::processor::{
     should_preload_patch_blobs, store_patched_output_in_cache, ProcessContext,
 };
 
+use super::failures::DirectiveFailures;
 use super::progress::ProgressReporter;
 
 use anyhow::Result;
@@ -228,6 +229,7 @@ pub(crate) fn process_single_archive_fused(
     ctx: &ProcessContext,
     extra_needed_paths: &[String],
     live_progress: Option<&AtomicUsize>,
+    extract_progress: Option<sevenzip::ExtractProgressFn>,
 ) -> Result<ArchiveResult> {
     const MAX_LOGGED_FAILURES: usize = 100;
 
@@ -444,7 +446,9 @@ pub(crate) fn process_single_archive_fused(
             // BSA/BA2: extract each needed file individually (preserving path structure)
             extract_bsa_files_to_temp(archive_path, &needed_paths, &extract_dir);
         } else if !needed_paths.is_empty() {
-            if let Err(e) = extract_archive_to_temp(archive_path, &needed_paths, &extract_dir) {
+            if let Err(e) =
+                extract_archive_to_temp(archive_path, &needed_paths, &extract_dir, extract_progress)
+            {
                 error!("FAIL: Cannot extract {}: {}", archive_path.display(), e);
             }
         }
@@ -637,6 +641,7 @@ pub(crate) fn process_single_archive_fused(
                 }
                 Err(msg) => {
                     error!("{}", msg);
+                    ctx.failures.record("FromArchive", &msg);
                     failed_count += 1;
                 }
             }
@@ -657,6 +662,10 @@ pub(crate) fn process_single_archive_fused(
                 if count < MAX_LOGGED_FAILURES {
                     error!("FAIL [{}]: not found in archive: {}", id, path_in_archive);
                 }
+                ctx.failures.record(
+                    "FromArchive",
+                    format!("not found in archive: {}", path_in_archive),
+                );
                 failed_count += 1;
                 continue;
             }
@@ -743,6 +752,7 @@ pub(crate) fn process_single_archive_fused(
                 if failed_count < MAX_LOGGED_FAILURES {
                     error!("FAIL [{}]: {}", id, msg);
                 }
+                ctx.failures.record("PatchedFromArchive", &msg);
                 failed_count += 1;
             }
         }
@@ -849,17 +859,26 @@ fn extract_bsa_files_to_temp(archive_path: &Path, needed_paths: &[String], temp_
 
 /// Extract archive (7z/ZIP/RAR) to temp directory with fallback chain.
 /// Tries selective extraction first, falls back to full extraction.
+/// `on_progress` sees every extracted entry, for progress inside big archives.
 fn extract_archive_to_temp(
     archive_path: &Path,
     needed_paths: &[String],
     temp_dir: &Path,
+    on_progress: Option<sevenzip::ExtractProgressFn>,
 ) -> Result<()> {
+    let extract_all = || match on_progress {
+        Some(on_progress) => {
+            sevenzip::extract_all_with_progress(archive_path, temp_dir, on_progress)
+        }
+        None => sevenzip::extract_all(archive_path, temp_dir),
+    };
+
     if needed_paths.is_empty() {
         debug!(
             "Extraction strategy: archive={}, needed_files=0, mode=full",
             archive_path.display()
         );
-        return sevenzip::extract_all(archive_path, temp_dir).map(|_| ());
+        return extract_all().map(|_| ());
     }
 
     debug!(
@@ -868,24 +887,29 @@ fn extract_archive_to_temp(
         needed_paths.len()
     );
 
-    sevenzip::extract_files_case_insensitive(archive_path, needed_paths, temp_dir)
-        .map(|_| ())
-        .or_else(|selective_err| {
-            warn!(
-                "Selective extraction failed for {}, falling back to full: {}",
-                archive_path.display(),
-                selective_err
-            );
-            sevenzip::extract_all(archive_path, temp_dir)
-                .map(|_| ())
-                .map_err(|full_err| {
-                    anyhow::anyhow!(
-                        "selective extraction failed: {}; full fallback failed: {}",
-                        selective_err,
-                        full_err
-                    )
-                })
+    let selective = match on_progress {
+        Some(on_progress) => sevenzip::extract_files_case_insensitive_with_progress(
+            archive_path,
+            needed_paths,
+            temp_dir,
+            on_progress,
+        ),
+        None => sevenzip::extract_files_case_insensitive(archive_path, needed_paths, temp_dir),
+    };
+    selective.map(|_| ()).or_else(|selective_err| {
+        warn!(
+            "Selective extraction failed for {}, falling back to full: {}",
+            archive_path.display(),
+            selective_err
+        );
+        extract_all().map(|_| ()).map_err(|full_err| {
+            anyhow::anyhow!(
+                "selective extraction failed: {}; full fallback failed: {}",
+                selective_err,
+                full_err
+            )
         })
+    })
 }
 
 /// Finalize staged files: rename to output (same FS), verify size, cleanup temp.
@@ -899,6 +923,7 @@ pub(crate) fn finalize_archive(
     logged_failures: &Arc<AtomicUsize>,
     _reporter: &Arc<dyn ProgressReporter>,
     dir_cache: &crate::paths::DirCache,
+    failures: &DirectiveFailures,
 ) -> FinalizeStats {
     const MAX_LOGGED_FAILURES: usize = 100;
 
@@ -928,6 +953,7 @@ pub(crate) fn finalize_archive(
                     e
                 );
             }
+            failures.record("FromArchive", format!("cannot create dirs for {}: {}", sf.output_path.display(), e));
             failed_atomic.fetch_add(1, Ordering::Relaxed);
             return;
         }
@@ -954,6 +980,7 @@ pub(crate) fn finalize_archive(
                         e
                     );
                 }
+                failures.record("FromArchive", format!("staged file missing: {}: {}", sf.temp_path.display(), e));
                 failed_atomic.fetch_add(1, Ordering::Relaxed);
                 return;
             }
@@ -978,6 +1005,7 @@ pub(crate) fn finalize_archive(
                             sf.directive_id, sf.expected_size, sf.expected_hash, src_size, actual_hash
                         );
                     }
+                    failures.record("FromArchive", format!("size+hash mismatch for {}", sf.output_path.display()));
                     failed_atomic.fetch_add(1, Ordering::Relaxed);
                     return;
                 }
@@ -989,6 +1017,7 @@ pub(crate) fn finalize_archive(
                             sf.directive_id, sf.expected_size, src_size, e
                         );
                     }
+                    failures.record("FromArchive", format!("size mismatch and hash compute failed: {}", e));
                     failed_atomic.fetch_add(1, Ordering::Relaxed);
                     return;
                 }
@@ -1039,6 +1068,7 @@ pub(crate) fn finalize_archive(
                     sf.directive_id, e
                 );
             }
+            failures.record("FromArchive", format!("move/copy failed for {}: {}", sf.output_path.display(), e));
             failed_atomic.fetch_add(1, Ordering::Relaxed);
             return;
         }
@@ -1413,15 +1443,15 @@ pub(crate) fn truncate_name(name: &str, max_len: usize) -> String {
 }
 
 /// Process whole-file directives (archive IS the file, just copy it).
-/// Returns indices of directives that failed (for retry).
+/// Returns the index and error of each directive that failed (for retry).
 pub(crate) fn process_whole_file_directives(
     directives: &[(i64, FromArchiveDirective)],
     ctx: &ProcessContext,
     extracted: &Arc<AtomicUsize>,
     skipped: &Arc<AtomicUsize>,
     failed: &Arc<AtomicUsize>,
-) -> Vec<usize> {
-    let failed_indices: std::sync::Mutex<Vec<usize>> = std::sync::Mutex::new(Vec::new());
+) -> Vec<(usize, String)> {
+    let failed_indices: std::sync::Mutex<Vec<(usize, String)>> = std::sync::Mutex::new(Vec::new());
 
     directives
         .par_iter()
@@ -1445,10 +1475,10 @@ pub(crate) fn process_whole_file_directives(
                         id, archive_hash, directive.to
                     );
                     failed.fetch_add(1, Ordering::Relaxed);
-                    failed_indices
-                        .lock()
-                        .expect("failed_indices lock")
-                        .push(idx);
+                    failed_indices.lock().expect("failed_indices lock").push((
+                        idx,
+                        format!("whole-file archive not found: {}", directive.to),
+                    ));
                     return;
                 }
             };
@@ -1463,20 +1493,27 @@ pub(crate) fn process_whole_file_directives(
                         e
                     );
                     failed.fetch_add(1, Ordering::Relaxed);
-                    failed_indices
-                        .lock()
-                        .expect("failed_indices lock")
-                        .push(idx);
+                    failed_indices.lock().expect("failed_indices lock").push((
+                        idx,
+                        format!(
+                            "whole-file archive unreadable: {}: {}",
+                            archive_path.display(),
+                            e
+                        ),
+                    ));
                     return;
                 }
             };
 
             if archive_size != directive.size {
                 failed.fetch_add(1, Ordering::Relaxed);
-                failed_indices
-                    .lock()
-                    .expect("failed_indices lock")
-                    .push(idx);
+                failed_indices.lock().expect("failed_indices lock").push((
+                    idx,
+                    format!(
+                        "whole-file size mismatch {} vs {}",
+                        archive_size, directive.size
+                    ),
+                ));
                 error!(
                     "FAIL [{}]: whole-file size mismatch {} vs {}",
                     id, archive_size, directive.size
@@ -1490,7 +1527,7 @@ pub(crate) fn process_whole_file_directives(
                 failed_indices
                     .lock()
                     .expect("failed_indices lock")
-                    .push(idx);
+                    .push((idx, format!("cannot create parent dirs: {}", e)));
                 error!("FAIL [{}]: cannot create parent dirs: {}", id, e);
                 return;
             }
@@ -1500,7 +1537,7 @@ pub(crate) fn process_whole_file_directives(
                 failed_indices
                     .lock()
                     .expect("failed_indices lock")
-                    .push(idx);
+                    .push((idx, format!("copy failed: {}", e)));
                 error!("FAIL [{}]: copy failed: {}", id, e);
                 return;
             }
@@ -1885,11 +1922,19 @@ fn process_spilled_dds_batched(jobs: Vec<SpilledDdsJob>, ctx: &ProcessContext) -
                             } else {
                                 fail_count += 1;
                                 warn!("DDS BC7 write fail [{}]: {}", job.id, job.directive.to);
+                                ctx.failures.record(
+                                    "TransformedTexture",
+                                    format!("write failed: {}", job.directive.to),
+                                );
                             }
                         }
                         Err(e) => {
                             fail_count += 1;
                             warn!("DDS BC7 fail [{}]: {} — {:#}", job.id, job.directive.to, e);
+                            ctx.failures.record(
+                                "TransformedTexture",
+                                format!("{}: {:#}", job.directive.to, e),
+                            );
                         }
                     }
                 }
@@ -1957,6 +2002,10 @@ fn process_spilled_dds_batched(jobs: Vec<SpilledDdsJob>, ctx: &ProcessContext) -
                             job.directive.to,
                             e
                         );
+                        ctx.failures.record(
+                            "TransformedTexture",
+                            format!("{}: {:#}", job.directive.to, e),
+                        );
                     }
                 }
             }
@@ -2309,6 +2358,10 @@ pub(crate) fn process_archive_direct(
                             data.len(),
                             actual_hash
                         );
+                        ctx.failures.record(
+                            "FromArchive",
+                            format!("size+hash mismatch: {}", target.output_path.display()),
+                        );
                         failed_count.fetch_add(1, Ordering::Relaxed);
                         continue;
                     }
@@ -2432,6 +2485,10 @@ pub(crate) fn process_bsa_archive(
                             actual_hash
                         );
                     }
+                    ctx.failures.record(
+                        "FromArchive",
+                        format!("BSA size+hash mismatch: {}", directive.to),
+                    );
                     failed.fetch_add(1, Ordering::Relaxed);
                     continue;
                 }
@@ -2443,6 +2500,10 @@ pub(crate) fn process_bsa_archive(
 
             let out_path = paths::join_windows_path(output_dir, &directive.to);
             if let Err(e) = ctx.dir_cache.ensure_parent_dirs(&out_path) {
+                ctx.failures.record(
+                    "FromArchive",
+                    format!("cannot create dirs for {}: {}", out_path.display(), e),
+                );
                 failed.fetch_add(1, Ordering::Relaxed);
                 error!(
                     "FAIL [{}]: cannot create dirs for {}: {}",
@@ -2454,6 +2515,10 @@ pub(crate) fn process_bsa_archive(
             }
 
             if let Err(e) = fs::write(&out_path, &data) {
+                ctx.failures.record(
+                    "FromArchive",
+                    format!("write error for {}: {}", directive.to, e),
+                );
                 failed.fetch_add(1, Ordering::Relaxed);
                 error!("FAIL [{}]: write error: {}", id, e);
                 continue;
@@ -2487,6 +2552,15 @@ pub(crate) fn process_bsa_archive(
                 e
             );
         }
+        ctx.failures.record_many(
+            "FromArchive",
+            to_process.len(),
+            format!(
+                "BSA batch extraction error for {}: {}",
+                archive_path.display(),
+                e
+            ),
+        );
         failed.fetch_add(to_process.len(), Ordering::Relaxed);
     }
 }
@@ -2539,7 +2613,8 @@ pub(crate) fn process_bsa_patched_directives(
         path_to_directives.entry(normalized).or_default().push(item);
     }
 
-    // Read basis files from BSA in one batch, apply patches inline
+    // Read basis files from BSA in one batch; patches against the same file
+    // share one basis copy and run in parallel.
     let path_to_directives = &path_to_directives;
     if let Err(e) = bsa::extract_archive_batch(archive_path, &wanted_paths, |path, data| {
         let lookup = path.replace('\\', "/").to_lowercase();
@@ -2547,35 +2622,43 @@ pub(crate) fn process_bsa_patched_directives(
             return Ok(());
         };
 
-        for &(id, directive) in directive_list {
-            // Write basis to temp file for mmap-based patching
-            let temp_dir = match tempfile::tempdir_in(output_dir) {
-                Ok(d) => d,
-                Err(e) => {
-                    let count = logged_failures.fetch_add(1, Ordering::Relaxed);
-                    if count < MAX_LOGGED_FAILURES {
-                        tracing::error!("FAIL [{}]: cannot create temp dir: {}", id, e);
-                    }
-                    failed.fetch_add(1, Ordering::Relaxed);
-                    continue;
-                }
-            };
-
-            let basis_temp = temp_dir.path().join("basis");
-            if let Err(e) = fs::write(&basis_temp, &data) {
-                let count = logged_failures.fetch_add(1, Ordering::Relaxed);
-                if count < MAX_LOGGED_FAILURES {
-                    tracing::error!("FAIL [{}]: cannot write basis temp: {}", id, e);
-                }
-                failed.fetch_add(1, Ordering::Relaxed);
-                continue;
+        // Write the basis once; every patch built against this file reads it.
+        let basis_failed = |what: &str, e: &dyn std::fmt::Display| {
+            let count = logged_failures.fetch_add(1, Ordering::Relaxed);
+            if count < MAX_LOGGED_FAILURES {
+                tracing::error!("FAIL: cannot {} for {}: {}", what, path, e);
+            }
+            ctx.failures.record_many(
+                "PatchedFromArchive",
+                directive_list.len(),
+                format!("cannot {} for {}: {}", what, path, e),
+            );
+            failed.fetch_add(directive_list.len(), Ordering::Relaxed);
+        };
+        let temp_dir = match tempfile::tempdir_in(output_dir) {
+            Ok(d) => d,
+            Err(e) => {
+                basis_failed("create temp dir", &e);
+                return Ok(());
             }
+        };
+        let basis_temp = temp_dir.path().join("basis");
+        if let Err(e) = fs::write(&basis_temp, &data) {
+            basis_failed("write basis temp", &e);
+            return Ok(());
+        }
 
+        // Patches sharing a basis are independent; each checks its own output.
+        directive_list.par_iter().for_each(|&&(id, directive)| {
             let final_output = paths::join_windows_path(output_dir, &directive.to);
             if let Err(e) = ctx.dir_cache.ensure_parent_dirs(&final_output) {
+                ctx.failures.record(
+                    "PatchedFromArchive",
+                    format!("cannot create dirs for {}: {}", directive.to, e),
+                );
                 failed.fetch_add(1, Ordering::Relaxed);
                 tracing::error!("FAIL [{}]: cannot create dirs: {}", id, e);
-                continue;
+                return;
             }
 
             // Apply patch: basis + delta → final output
@@ -2613,10 +2696,14 @@ pub(crate) fn process_bsa_patched_directives(
                     if count < MAX_LOGGED_FAILURES {
                         tracing::error!("FAIL [{}]: patch apply failed: {}", id, e);
                     }
+                    ctx.failures.record(
+                        "PatchedFromArchive",
+                        format!("patch apply failed for {}: {:#}", directive.to, e),
+                    );
                     failed.fetch_add(1, Ordering::Relaxed);
                 }
             }
-        }
+        });
         Ok(())
     }) {
         let count = logged_failures.fetch_add(1, Ordering::Relaxed);
@@ -2627,6 +2714,15 @@ pub(crate) fn process_bsa_patched_directives(
                 e
             );
         }
+        ctx.failures.record_many(
+            "PatchedFromArchive",
+            to_process.len(),
+            format!(
+                "BSA patch batch error for {}: {}",
+                archive_path.display(),
+                e
+            ),
+        );
         failed.fetch_add(to_process.len(), Ordering::Relaxed);
     }
 }
@@ -2708,7 +2804,7 @@ pub(crate) fn process_spilled_dds_jobs_with_progress(
 
     use crate::installer::handlers::texture::is_fallback_mode;
     use crate::textures::{
-        init_gpu, process_texture_batch, process_texture_with_fallback, OutputFormat, TextureJob,
+        process_texture_batch, process_texture_with_fallback, OutputFormat, TextureJob,
     };
 
     let total_tex = dds_jobs.len();
@@ -2737,7 +2833,7 @@ pub(crate) fn process_spilled_dds_jobs_with_progress(
     ));
 
     // Initialize GPU for BC7
-    let _ = init_gpu();
+    super::processor::init_texture_encoder(ctx.config.reporter.as_ref());
 
     let dds_ok = AtomicUsize::new(0);
     let dds_fail = AtomicUsize::new(0);
@@ -6,12 +6,14 @@
 pub mod archive;
 pub mod bsa;
 pub mod downloaders;
+pub mod error;
 pub mod fluorine;
 pub mod game_finder;
 pub mod gpu;
 pub mod hash;
 pub mod installer;
 pub mod modlist;
+pub mod notify;
 pub mod octodiff;
 pub mod paths;
 pub mod settings;
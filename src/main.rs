@@ -13,11 +13,13 @@ mod archive;
 mod browser_gui;
 mod bsa;
 mod downloaders;
+mod error;
 mod fluorine;
 mod game_finder;
 mod hash;
 mod installer;
 mod modlist;
+mod notify;
 mod octodiff;
 mod paths;
 mod settings;
@@ -25,9 +27,11 @@ mod textures;
 
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand, ValueEnum};
+// Only the INI fix is needed here; the library already builds the module.
+use clf3::gpu;
 use installer::{
     CliReporter, ExtractStrategy, InstallConfig, Installer, JsonEventWriter, JsonReporter,
-    ProgressCallback, ProgressMode, ProgressReporter,
+    ProgressCallback, ProgressEvent, ProgressMode, ProgressReporter,
 };
 
 /// CLI-facing enum for the `--extract` flag. Maps to the internal
@@ -58,6 +62,144 @@ enum ProgressModeArg {
     Full,
     /// Line-oriented human-readable progress.
     Plain,
+    /// Newline-delimited JSON events on stdout for other programs; human
+    /// output goes to stderr. Same as `--jackify`.
+    Json,
+}
+
+/// CLI-facing enum for `--purge-downloads`.
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum PurgeDownloadsArg {
+    /// Delete downloads this modlist doesn't use (older versions, other lists).
+    Unreferenced,
+    /// Delete every download, including this modlist's archives.
+    All,
+}
+
+impl From<PurgeDownloadsArg> for installer::PurgeDownloads {
+    fn from(arg: PurgeDownloadsArg) -> Self {
+        match arg {
+            PurgeDownloadsArg::Unreferenced => installer::PurgeDownloads::Unreferenced,
+            PurgeDownloadsArg::All => installer::PurgeDownloads::All,
+        }
+    }
+}
+
+/// CLI-facing enum for `--phase`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum PhaseArg {
+    /// Download and verify archives (same as --only-downloads).
+    Downloads,
+    /// Also extract and patch archive contents into the install dir.
+    Extract,
+    /// Also write inline files, transform textures and build BSAs.
+    Directives,
+    /// The whole install, including cleanup and MO2 meta.ini files.
+    Mo2,
+}
+
+impl From<PhaseArg> for installer::InstallPhase {
+    fn from(arg: PhaseArg) -> Self {
+        match arg {
+            PhaseArg::Downloads => installer::InstallPhase::Downloads,
+            PhaseArg::Extract => installer::InstallPhase::Extract,
+            PhaseArg::Directives => installer::InstallPhase::Directives,
+            PhaseArg::Mo2 => installer::InstallPhase::Mo2,
+        }
+    }
+}
+
+/// CLI-facing enum for `--link-mode`.
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum LinkModeArg {
+    /// Every file is a separate copy.
+    Copy,
+    /// Copy-on-write clones (btrfs/XFS). Safe; edits stay separate.
+    Reflink,
+}
+
+impl From<LinkModeArg> for installer::LinkMode {
+    fn from(arg: LinkModeArg) -> Self {
+        match arg {
+            LinkModeArg::Copy => installer::LinkMode::Copy,
+            LinkModeArg::Reflink => installer::LinkMode::Reflink,
+        }
+    }
+}
+
+/// CLI-facing enum for `--texture-encoder`.
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum TextureEncoderArg {
+    /// A GPU when one works, the CPU otherwise.
+    Auto,
+    /// Always the CPU (slower; for headless machines and VMs).
+    Cpu,
+    /// Always a GPU; the install refuses to start without a usable one.
+    Gpu,
+}
+
+impl From<TextureEncoderArg> for textures::EncoderMode {
+    fn from(arg: TextureEncoderArg) -> Self {
+        match arg {
+            TextureEncoderArg::Auto => textures::EncoderMode::Auto,
+            TextureEncoderArg::Cpu => textures::EncoderMode::Cpu,
+            TextureEncoderArg::Gpu => textures::EncoderMode::Gpu,
+        }
+    }
+}
+
+/// CLI-facing enum for `--storage`.
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum StorageArg {
+    /// Detect from the downloads and install disks.
+    Auto,
+    /// Spinning disk: extract few archives at once to avoid seek thrashing.
+    Hdd,
+    /// SSD/NVMe: extract as many archives in parallel as there are CPU threads.
+    Ssd,
+}
+
+/// CLI-facing enum for `--download-order`.
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum DownloadOrderArg {
+    /// Modlist order (reproducible).
+    Manifest,
+    /// Smallest archives first, so extraction starts sooner.
+    Smallest,
+    /// Largest archives first, so no huge file is left for last.
+    Largest,
+}
+
+/// CLI-facing enum for `--on-hash-mismatch`.
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum HashMismatchArg {
+    /// Delete the file and download it again.
+    Delete,
+    /// Move the file to `.bad/` in the downloads folder with a note, then
+    /// download it again.
+    Quarantine,
+    /// Leave the file where it is and fail the archive.
+    Keep,
+}
+
+impl From<HashMismatchArg> for installer::HashMismatchAction {
+    fn from(arg: HashMismatchArg) -> Self {
+        match arg {
+            HashMismatchArg::Delete => installer::HashMismatchAction::Delete,
+            HashMismatchArg::Quarantine => installer::HashMismatchAction::Quarantine,
+            HashMismatchArg::Keep => installer::HashMismatchAction::Keep,
+        }
+    }
+}
+
+impl From<DownloadOrderArg> for installer::DownloadOrder {
+    fn from(arg: DownloadOrderArg) -> Self {
+        match arg {
+            DownloadOrderArg::Manifest => installer::DownloadOrder::Manifest,
+            DownloadOrderArg::Smallest => installer::DownloadOrder::Smallest,
+            DownloadOrderArg::Largest => installer::DownloadOrder::Largest,
+        }
+    }
 }
 
 impl From<ProgressModeArg> for ProgressMode {
@@ -65,7 +207,8 @@ impl From<ProgressModeArg> for ProgressMode {
         match arg {
             ProgressModeArg::Auto => ProgressMode::Auto,
             ProgressModeArg::Full => ProgressMode::Full,
-            ProgressModeArg::Plain => ProgressMode::Plain,
+            // JSON output replaces the CLI reporter rather than rendering it.
+            ProgressModeArg::Plain | ProgressModeArg::Json => ProgressMode::Plain,
         }
     }
 }
@@ -90,6 +233,12 @@ struct Cli {
     /// Enable verbose logging (use RUST_LOG=debug for more detail)
     #[arg(short, long, global = true)]
     verbose: bool,
+
+    /// Work from cached data only: the browser shows the cached gallery,
+    /// and installs refuse to start unless every archive is already
+    /// downloaded. Nexus credentials are not checked.
+    #[arg(long, global = true, env = "CLF3_OFFLINE")]
+    offline: bool,
 }
 
 #[derive(Subcommand)]
@@ -100,17 +249,39 @@ enum Commands {
 
     /// Install a Wabbajack modlist
     Install {
-        /// Path or URL to the .wabbajack file
-        wabbajack_file: String,
+        /// Path or URL to the .wabbajack file. Left out with --from-gallery,
+        /// in which case the positional arguments are the downloads and
+        /// install directories.
+        #[arg(required_unless_present = "from_gallery")]
+        wabbajack_file: Option<String>,
+
+        /// Install a modlist from the Wabbajack gallery by machine name (or
+        /// a title unique enough to match one list), instead of a
+        /// .wabbajack path or URL. Its .wabbajack is downloaded and checked
+        /// against the gallery's hash first.
+        #[arg(long, value_name = "MACHINE_NAME")]
+        from_gallery: Option<String>,
 
         /// Directory for downloaded archives
-        downloads: PathBuf,
+        downloads: Option<PathBuf>,
 
         /// Installation target directory (where mods will be installed)
-        output: PathBuf,
+        output: Option<PathBuf>,
+
+        /// Directory for downloaded archives, as a flag.
+        ///
+        /// Precedence for downloads, output and game directories: command
+        /// line > environment > `default_*_dir` in settings.json (see
+        /// `clf3 config`).
+        #[arg(long, env = "CLF3_DOWNLOADS_DIR", value_name = "DIR")]
+        downloads_dir: Option<PathBuf>,
+
+        /// Installation target directory, as a flag
+        #[arg(short = 'o', long, env = "CLF3_OUTPUT_DIR", value_name = "DIR")]
+        output_dir: Option<PathBuf>,
 
         /// Game installation directory (overrides auto-detection)
-        #[arg(short, long)]
+        #[arg(short, long, env = "CLF3_GAME_DIR")]
         game: Option<PathBuf>,
 
         /// Nexus Mods API key (overrides saved setting)
@@ -126,6 +297,7 @@ enum Commands {
         concurrent: Option<usize>,
 
         /// Maximum parallel workers for extraction/install phase
+        /// (default: picked from --storage)
         #[arg(long)]
         install_workers: Option<usize>,
 
@@ -133,10 +305,17 @@ enum Commands {
         #[arg(long)]
         bsa_workers: Option<usize>,
 
-        /// Maximum number of 7z archives processed concurrently (default: 1)
+        /// Maximum number of 7z archives processed concurrently
+        /// (default: picked from --storage)
         #[arg(long)]
         sevenzip_workers: Option<usize>,
 
+        /// Disk type of the downloads/install dirs, used to pick extraction
+        /// concurrency: few workers on HDD, one per CPU thread on SSD. When
+        /// it can't be detected, half the CPU threads are used
+        #[arg(long, value_enum, default_value_t = StorageArg::Auto)]
+        storage: StorageArg,
+
         /// LoversLab email (overrides saved setting)
         #[arg(long, env = "LOVERSLAB_EMAIL")]
         ll_email: Option<String>,
@@ -157,6 +336,12 @@ enum Commands {
         #[arg(long, value_enum, default_value_t = ExtractStrategyArg::Streaming)]
         extract: ExtractStrategyArg,
 
+        /// Don't overlap downloading and extraction: download everything,
+        /// then extract. Same as `--extract phased`; for debugging and
+        /// reproducible timings.
+        #[arg(long, conflicts_with = "extract")]
+        no_pipeline: bool,
+
         /// Progress output mode.
         #[arg(long, value_enum, default_value_t = ProgressModeArg::Auto)]
         progress: ProgressModeArg,
@@ -173,11 +358,203 @@ enum Commands {
         #[arg(long)]
         report_json: Option<PathBuf>,
 
-        /// Emit newline-delimited JSON progress events to stdout.
+        /// Emit newline-delimited JSON progress events to stdout. Same as
+        /// `--progress json`.
         ///
         /// Human-readable detail output is written to stderr in this mode.
         #[arg(long)]
         jackify: bool,
+
+        /// Only install mods whose folder name, MO2 separator (category) or
+        /// source archive name contains this text. Repeatable.
+        ///
+        /// Partial installs are unsupported by modlist authors.
+        #[arg(long, value_name = "PATTERN")]
+        include: Vec<String>,
+
+        /// Skip mods whose folder name, MO2 separator (category) or source
+        /// archive name contains this text. Repeatable. Skipped mods'
+        /// archives are not downloaded.
+        #[arg(long, value_name = "PATTERN")]
+        exclude: Vec<String>,
+
+        /// Download and hash-verify every needed archive, then stop before
+        /// any extraction. A later normal install reuses the verified
+        /// archives without re-downloading or re-hashing them.
+        #[arg(long)]
+        only_downloads: bool,
+
+        /// Stop after this phase, for debugging or staging an install in
+        /// steps. Earlier phases always run (finished work is reused), so
+        /// `extract` still downloads anything missing. The install manifest
+        /// is only written once `mo2` completes.
+        #[arg(
+            long,
+            value_enum,
+            value_name = "PHASE",
+            conflicts_with = "only_downloads"
+        )]
+        phase: Option<PhaseArg>,
+
+        /// Ignore cached archive hashes and re-hash every existing archive.
+        ///
+        /// Normally an archive whose size and modification time match its
+        /// `.clf3hash` sidecar is trusted without reading it again.
+        #[arg(long)]
+        force_rehash: bool,
+
+        /// Hash-check existing output files before skipping them.
+        ///
+        /// By default an existing output with the expected size is skipped.
+        /// With this flag its hash must match too; mismatches are deleted
+        /// and re-extracted.
+        #[arg(long)]
+        verify_outputs: bool,
+
+        /// Forget the progress saved by an earlier, unfinished run of this
+        /// modlist and start over. Downloads are kept; existing output files
+        /// are hash-checked as with `--verify-outputs`.
+        #[arg(long)]
+        fresh: bool,
+
+        /// JSON file mapping archive hashes to alternate download URLs,
+        /// tried in order when an archive's primary source fails.
+        #[arg(long, value_name = "FILE")]
+        mirrors: Option<PathBuf>,
+
+        /// Directory of archives named by their hash (hex or base64, any
+        /// extension), e.g. a shared NAS. Matches are linked or copied into
+        /// the downloads folder before anything is downloaded.
+        #[arg(long, value_name = "DIR")]
+        archive_store: Option<PathBuf>,
+
+        /// GPU adapter index for texture encoding (see `clf3 list-gpu`).
+        /// Defaults to the GPU saved with `clf3 select-gpu` or the GUI.
+        #[arg(long, value_name = "INDEX")]
+        gpu: Option<usize>,
+
+        /// Device that encodes textures. Defaults to the mode saved with
+        /// `clf3 select-gpu auto|cpu|gpu`.
+        #[arg(long, value_enum, value_name = "MODE")]
+        texture_encoder: Option<TextureEncoderArg>,
+
+        /// Reconnect and resume a download that averages under 10 KB/s for
+        /// this many seconds.
+        #[arg(long, value_name = "SECS", default_value_t = 180)]
+        stall_timeout: u64,
+
+        /// Cap the combined download speed, in KB/s (0 = unlimited).
+        /// Default: unlimited, or 2048 on a metered connection.
+        #[arg(long, value_name = "KB/S")]
+        max_download_speed: Option<u64>,
+
+        /// Treat the connection as metered: show how much will be downloaded
+        /// and ask first, and default to 2 concurrent downloads at 2 MB/s.
+        /// Also on with the `metered_connection` setting, or when
+        /// NetworkManager reports a metered connection and
+        /// `detect_metered_connection` is set.
+        #[arg(long)]
+        metered: bool,
+
+        /// Don't ask before downloading on a metered connection.
+        #[arg(long)]
+        accept_metered: bool,
+
+        /// Retry a failed download this many times after the first attempt
+        /// (network errors, truncated files, hash mismatches). 0 fails fast,
+        /// including on rate limits.
+        #[arg(long, value_name = "N", default_value_t = 2)]
+        max_retries: u32,
+
+        /// Run the whole install again, up to N times with a growing delay,
+        /// while downloads fail for transient reasons (network errors,
+        /// corrupt files). Completed work is skipped. Stops early when only
+        /// manual or unfixable downloads are left, and exits non-zero if the
+        /// install still isn't complete.
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        auto_retry: u32,
+
+        /// Fail an archive on a hash mismatch instead of re-downloading it.
+        #[arg(long)]
+        no_hash_retry: bool,
+
+        /// What to do with a download that fails its hash check: `delete`
+        /// it, `quarantine` it in the downloads folder's `.bad/` with a note
+        /// of both hashes, or `keep` it in place for inspection. `delete`
+        /// and `quarantine` download the archive again.
+        #[arg(long, value_enum, value_name = "ACTION", default_value_t = HashMismatchArg::Delete)]
+        on_hash_mismatch: HashMismatchArg,
+
+        /// Order in which missing archives are downloaded.
+        #[arg(long, value_enum, value_name = "ORDER", default_value_t = DownloadOrderArg::Manifest)]
+        download_order: DownloadOrderArg,
+
+        /// Nexus download server (Premium): `default`, `fastest` (probed
+        /// once per run) or a server name such as `Amsterdam`. Defaults to
+        /// the `nexus_cdn` setting.
+        #[arg(long, value_name = "SERVER")]
+        nexus_cdn: Option<String>,
+
+        /// After a fully successful install, delete downloads to free disk
+        /// space. Without a value, deletes files this modlist doesn't use;
+        /// `all` deletes its archives too. Careful if the downloads folder is
+        /// shared with other modlists. Default: keep everything.
+        #[arg(
+            long,
+            value_name = "MODE",
+            num_args = 0..=1,
+            default_missing_value = "unreferenced"
+        )]
+        purge_downloads: Option<PurgeDownloadsArg>,
+
+        /// Install even if the output directory already holds a different
+        /// modlist, without asking.
+        #[arg(long)]
+        force: bool,
+
+        /// After a successful install, set up the Steam shortcut that points
+        /// into the install dir: run it with `--proton` and restart Steam so
+        /// it's ready to launch. The shortcut itself comes from a launcher
+        /// integration such as NaK. Progress is reported as a "Steam Setup"
+        /// phase (a `phase_change` event with --jackify).
+        #[arg(long, requires = "proton")]
+        setup_steam: bool,
+
+        /// Proton for --setup-steam, by name as listed by `clf3 list-games`
+        /// (e.g. `GE-Proton10-20`, `Proton Experimental`).
+        #[arg(long, value_name = "NAME", requires = "setup_steam")]
+        proton: Option<String>,
+
+        /// Existing Wine/Proton prefix MO2 will run in (the prefix itself or
+        /// a Proton `compatdata/<appid>` dir). Paths inside its drive_c are
+        /// written to ModOrganizer.ini and other configs as `C:` paths
+        /// instead of `Z:`; install into the prefix's drive_c to use them.
+        #[arg(long, value_name = "DIR")]
+        wine_prefix: Option<PathBuf>,
+
+        /// Experimental: after installing, make identical textures, meshes,
+        /// sounds and BSAs share disk space with copy-on-write clones
+        /// (btrfs, XFS, bcachefs). Plugins and configs are never linked.
+        #[arg(long, value_enum, value_name = "MODE", default_value_t = LinkModeArg::Copy)]
+        link_mode: LinkModeArg,
+
+        /// MO2 profile (preset) to select after installing, for modlists
+        /// that ship several (see `clf3 info`). Defaults to the one picked
+        /// for this modlist last time, then the author's choice.
+        #[arg(long, value_name = "NAME")]
+        profile: Option<String>,
+
+        /// After installing, write which mods overwrite which loose files
+        /// (winner per file, by MO2 priority) to clf3-conflicts.txt in the
+        /// install dir
+        #[arg(long)]
+        report_conflicts: bool,
+
+        /// Expected hash of a .wabbajack given by URL (Wabbajack's base64
+        /// xxHash64, as listed in the gallery). The download is checked
+        /// against it and a cached copy that doesn't match is fetched again
+        #[arg(long, value_name = "HASH")]
+        wabbajack_hash: Option<String>,
     },
 
     /// Download a .wabbajack file from the Wabbajack CDN
@@ -217,19 +594,168 @@ enum Commands {
         json: bool,
     },
 
+    /// Encode a sample texture on the CPU and every GPU and report throughput,
+    /// to help choose between `select-gpu cpu` and a GPU
+    BenchmarkTextures {
+        /// Emit the results as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List the games and Protons CLF3 detects (Steam and Heroic), for
+    /// diagnosing "game not found" problems
+    ListGames {
+        /// Emit the scan as JSON (for bug reports).
+        #[arg(long)]
+        json: bool,
+    },
+
     /// Select a GPU for texture encoding (use list-gpu to see indices)
     SelectGpu {
-        /// GPU index from list-gpu (or "auto" for automatic selection)
+        /// GPU index from list-gpu, "auto" for automatic selection, "cpu" to
+        /// encode on the CPU, or "gpu" to require a GPU (no CPU fallback)
         index: String,
     },
 
-    /// Show current saved settings
-    Config,
+    /// Show current saved settings, or export/import them
+    Config {
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
+    },
 
     /// Show information about a Wabbajack modlist
     Info {
+        /// Path or URL to the .wabbajack file
+        wabbajack_file: String,
+
+        /// Also check that every directive's archive and inline data exist
+        /// and every archive has a download source. Exits non-zero on
+        /// problems. `install` always runs this check.
+        #[arg(long)]
+        validate: bool,
+
+        /// Expected hash of a .wabbajack given by URL (Wabbajack's base64
+        /// xxHash64, as listed in the gallery)
+        #[arg(long, value_name = "HASH")]
+        wabbajack_hash: Option<String>,
+    },
+
+    /// Report what an install still needs (missing archives, unfinished
+    /// outputs) without downloading or writing anything
+    Status {
         /// Path to the .wabbajack file
         wabbajack_file: PathBuf,
+
+        /// Directory for downloaded archives
+        #[arg(long, env = "CLF3_DOWNLOADS_DIR", value_name = "DIR")]
+        downloads_dir: Option<PathBuf>,
+
+        /// Installation target directory
+        #[arg(short = 'o', long, env = "CLF3_OUTPUT_DIR", value_name = "DIR")]
+        output_dir: Option<PathBuf>,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Re-hash a finished install against its modlist and list missing,
+    /// edited and corrupted files. Exits with status 1 if any are found.
+    /// Ctrl+C stops hashing and reports the files checked so far
+    Check {
+        /// Install directory (must contain .clf3-install.json)
+        install_dir: PathBuf,
+
+        /// .wabbajack file to check against, if the one recorded at install
+        /// time has moved
+        #[arg(long, value_name = "FILE")]
+        wabbajack_file: Option<PathBuf>,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Re-run the steps after a finished install without reinstalling: set
+    /// the GPU name in game INIs, register with Fluorine (if enabled) and
+    /// report the Steam shortcut
+    Finalize {
+        /// Install directory
+        install_dir: PathBuf,
+
+        /// Quit and restart Steam afterwards so it lists new shortcuts
+        #[arg(long)]
+        restart_steam: bool,
+
+        /// Also run the install's Steam shortcut with this Proton (restarts
+        /// Steam), as `install --setup-steam --proton` does
+        #[arg(long, value_name = "NAME")]
+        proton: Option<String>,
+    },
+
+    /// Open the pages of archives still missing from the downloads dir in
+    /// the browser, a batch at a time, for downloading by hand (no Nexus
+    /// Premium, manual sources)
+    OpenDownloads {
+        /// Path to the .wabbajack file
+        wabbajack_file: PathBuf,
+
+        /// Directory for downloaded archives (browser downloads must be saved here)
+        #[arg(long, env = "CLF3_DOWNLOADS_DIR", value_name = "DIR")]
+        downloads_dir: Option<PathBuf>,
+
+        /// Pages to open per batch
+        #[arg(long, value_name = "N", default_value_t = 5)]
+        batch: usize,
+
+        /// Delay between opening pages, so the browser keeps up
+        #[arg(long, value_name = "MS", default_value_t = 750)]
+        delay_ms: u64,
+
+        /// Only open Nexus mod pages
+        #[arg(long)]
+        nexus_only: bool,
+
+        /// Also open pages for sources the installer downloads itself
+        /// (direct links, Wabbajack CDN, Mega, ...). By default only Nexus
+        /// and manual archives are opened
+        #[arg(long, conflicts_with = "nexus_only")]
+        all_sources: bool,
+    },
+
+    /// Delete leftovers of interrupted runs: partial downloads, archives
+    /// of the wrong size and extraction temp dirs. Don't run it while an
+    /// install is in progress
+    Clean {
+        /// Directory for downloaded archives
+        #[arg(long, env = "CLF3_DOWNLOADS_DIR", value_name = "DIR")]
+        downloads_dir: Option<PathBuf>,
+
+        /// Install directory to clear of temp dirs (recorded installs are
+        /// always included)
+        #[arg(short = 'o', long, env = "CLF3_OUTPUT_DIR", value_name = "DIR")]
+        output_dir: Option<PathBuf>,
+
+        /// Another directory to clear of extraction temp dirs
+        #[arg(long, value_name = "DIR")]
+        temp_dir: Option<PathBuf>,
+
+        /// .wabbajack file whose archives count as known, besides those of
+        /// recorded installs (repeatable)
+        #[arg(long = "modlist", value_name = "FILE")]
+        modlists: Vec<PathBuf>,
+
+        /// Also delete archives no known modlist uses
+        #[arg(long)]
+        unreferenced: bool,
+
+        /// List what would be deleted without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Delete without asking
+        #[arg(short = 'y', long)]
+        yes: bool,
     },
 
     /// Fluorine Manager integration (auto-register finished installs).
@@ -240,6 +766,28 @@ enum Commands {
 
 }
 
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Write settings to a portable file for another machine. Credentials
+    /// and this machine's install records are left out.
+    Export {
+        /// File to write
+        output: PathBuf,
+
+        /// Also export the Nexus API key, LoversLab login and HTTP login
+        /// headers.
+        #[arg(long)]
+        include_secrets: bool,
+    },
+
+    /// Replace settings with a file from `config export`. Credentials the
+    /// file doesn't have are kept.
+    Import {
+        /// File written by `config export`
+        input: PathBuf,
+    },
+}
+
 #[derive(Subcommand)]
 enum FluorineAction {
     /// Show whether Fluorine is detected and where.
@@ -251,6 +799,12 @@ enum FluorineAction {
         /// Override the install destination directory.
         #[arg(long)]
         dest: Option<PathBuf>,
+
+        /// Release tag to install instead of the latest (e.g. `v0.2.0`).
+        /// Saved in settings and used for later auto-downloads; pass an
+        /// empty string to go back to the latest release.
+        #[arg(long, value_name = "TAG")]
+        version: Option<String>,
     },
 
     /// Register an existing install directory as a Fluorine portable instance.
@@ -314,10 +868,15 @@ enum ModlistAction {
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    // Set up file logging (always enabled) next to the executable
-    let log_dir = std::env::current_exe()
-        .ok()
-        .and_then(|p| p.parent().map(|p| p.to_path_buf()))
+    // Set up file logging (always enabled) in the CLF3 logs dir, or next to
+    // the executable if that can't be determined or created
+    let log_dir = paths::logs_dir()
+        .filter(|dir| std::fs::create_dir_all(dir).is_ok())
+        .or_else(|| {
+            std::env::current_exe()
+                .ok()
+                .and_then(|p| p.parent().map(|p| p.to_path_buf()))
+        })
         .unwrap_or_else(|| PathBuf::from("."));
 
     // Create timestamped log filename
@@ -345,7 +904,20 @@ async fn main() -> Result<()> {
     // Default to opening the browser when invoked with no subcommand — makes
     // double-click from a desktop / file manager Just Work.
     let command = cli.command.unwrap_or(Commands::Browser);
-    let json_progress = matches!(&command, Commands::Install { jackify: true, .. });
+    let offline = cli.offline;
+    let startup_settings = settings::Settings::load();
+    downloaders::set_user_agent(&startup_settings.user_agent);
+    game_finder::set_steam_preference(game_finder::SteamPreference::parse(
+        &startup_settings.steam_install,
+    ));
+    let json_progress = matches!(
+        &command,
+        Commands::Install { jackify: true, .. }
+            | Commands::Install {
+                progress: ProgressModeArg::Json,
+                ..
+            }
+    );
 
     let requested_progress_mode = if json_progress {
         ProgressMode::Plain
@@ -393,6 +965,9 @@ async fn main() -> Result<()> {
 
     let log_path = log_dir.join(&log_filename);
     tracing::info!("CLF3 started, logging to {}", log_path.display());
+    if let Some(limit) = installer::limits::raise_open_files_limit() {
+        tracing::info!("Open-file limit: {}", limit);
+    }
 
     // Panic hook: log panics from ANY thread to the log file before aborting.
     let panic_log = log_path.clone();
@@ -496,12 +1071,19 @@ async fn main() -> Result<()> {
 
     match command {
         Commands::Browser => {
-            if let Err(e) = browser_gui::launch_browser() {
+            if let Err(e) = browser_gui::launch_browser(offline, log_path) {
                 eprintln!("Browser GUI error: {}", e);
                 std::process::exit(1);
             }
         }
 
+        Commands::SetApiKey { key } if offline => {
+            let mut settings = settings::Settings::load();
+            settings.nexus_api_key = key;
+            settings.save()?;
+            println!("API key saved without verification (offline).");
+        }
+
         Commands::SetApiKey { key } => {
             println!("Verifying Nexus API key...");
             let nexus = downloaders::NexusDownloader::new(&key)?;
@@ -555,24 +1137,124 @@ async fn main() -> Result<()> {
                     );
                 }
                 let settings = settings::Settings::load();
-                match settings.gpu_index {
-                    Some(idx) => println!("\nCurrently selected: [{}] {}", idx, settings.gpu_name),
-                    None => println!("\nCurrently selected: auto"),
+                println!("\nCurrently selected: {}", settings.gpu_label());
+            }
+        }
+
+        Commands::ListGames { json } => {
+            let scan = game_finder::detect_all_games();
+            let protons = game_finder::find_steam_protons();
+            let version = |game: &game_finder::Game| {
+                game.game_type()
+                    .and_then(|t| game_finder::installed_game_version(t, &game.install_path))
+            };
+
+            if json {
+                let games: Vec<_> = scan
+                    .games
+                    .iter()
+                    .map(|g| {
+                        serde_json::json!({
+                            "name": g.name,
+                            "app_id": g.app_id,
+                            "launcher": g.launcher.display_name(),
+                            "install_path": g.install_path,
+                            "prefix_path": g.prefix_path,
+                            "game_type": g.game_type().map(|t| t.to_string()),
+                            "version": version(g),
+                        })
+                    })
+                    .collect();
+                let protons: Vec<_> = protons
+                    .iter()
+                    .map(|p| serde_json::json!({ "name": p.name, "path": p.path }))
+                    .collect();
+                let payload = serde_json::json!({ "games": games, "protons": protons });
+                println!("{}", serde_json::to_string_pretty(&payload)?);
+            } else {
+                println!(
+                    "Games ({} Steam, {} Heroic):",
+                    scan.steam_count, scan.heroic_count
+                );
+                if scan.games.is_empty() {
+                    println!("  (none found)");
+                }
+                for game in &scan.games {
+                    println!(
+                        "  {} [{} {}] {}",
+                        game.name,
+                        game.launcher.display_name(),
+                        game.app_id,
+                        version(game).as_deref().unwrap_or("")
+                    );
+                    println!("      {}", game.install_path.display());
+                }
+
+                println!("\nProton (10+):");
+                if protons.is_empty() {
+                    println!("  (none found)");
+                }
+                for proton in &protons {
+                    println!("  {}  {}", proton.name, proton.path.display());
                 }
             }
         }
 
+        Commands::BenchmarkTextures { json } => {
+            if !json {
+                println!("Benchmarking BC7 encoding (this takes a few seconds)...");
+            }
+            let results = textures::run_benchmark();
+            if json {
+                println!("{}", serde_json::to_string_pretty(&results)?);
+            } else {
+                for r in &results {
+                    let index = r
+                        .adapter_index
+                        .map(|i| format!("[{}] ", i))
+                        .unwrap_or_default();
+                    match &r.error {
+                        None => println!(
+                            "  {:>8.1} MP/s  {}{}",
+                            r.megapixels_per_sec, index, r.device
+                        ),
+                        Some(e) => println!("    failed     {}{}: {}", index, r.device, e),
+                    }
+                }
+                let settings = settings::Settings::load();
+                println!("\nCurrently selected: {}", settings.gpu_label());
+            }
+        }
+
         Commands::SelectGpu { index } => {
-            if index == "auto" {
+            let mode = match index.as_str() {
+                "auto" => Some(textures::EncoderMode::Auto),
+                "cpu" => Some(textures::EncoderMode::Cpu),
+                "gpu" => Some(textures::EncoderMode::Gpu),
+                _ => None,
+            };
+            if let Some(mode) = mode {
                 let mut settings = settings::Settings::load();
                 settings.gpu_index = None;
                 settings.gpu_name = String::new();
+                settings.texture_encoder = mode;
                 settings.save()?;
-                println!("GPU selection set to: auto (recommended)");
+                match mode {
+                    textures::EncoderMode::Auto => {
+                        println!("GPU selection set to: auto (recommended)")
+                    }
+                    textures::EncoderMode::Cpu => {
+                        println!("Textures will be encoded on the CPU (slower than a GPU)")
+                    }
+                    textures::EncoderMode::Gpu => println!(
+                        "Textures will be encoded on the best GPU; installs fail if none works"
+                    ),
+                }
             } else {
                 let idx: usize = index.parse().map_err(|_| {
                     anyhow::anyhow!(
-                        "Invalid GPU index '{}'. Use a number from list-gpu or 'auto'.",
+                        "Invalid GPU index '{}'. Use a number from list-gpu, 'auto', 'cpu' \
+                         or 'gpu'.",
                         index
                     )
                 })?;
@@ -589,6 +1271,9 @@ async fn main() -> Result<()> {
                 let mut settings = settings::Settings::load();
                 settings.gpu_index = Some(idx);
                 settings.gpu_name = gpu.name.clone();
+                if settings.texture_encoder == textures::EncoderMode::Cpu {
+                    settings.texture_encoder = textures::EncoderMode::Auto;
+                }
                 settings.save()?;
                 println!(
                     "GPU selected: [{}] {} ({}, {})",
@@ -597,7 +1282,13 @@ async fn main() -> Result<()> {
             }
         }
 
-        Commands::Config => {
+        Commands::Config {
+            action: Some(action),
+        } => {
+            run_config_action(action)?;
+        }
+
+        Commands::Config { action: None } => {
             let settings = settings::Settings::load();
             let path = settings::Settings::settings_path()
                 .map(|p| p.display().to_string())
@@ -620,22 +1311,59 @@ async fn main() -> Result<()> {
                     settings.loverslab_email.as_str()
                 }
             );
+            println!("GPU:              {}", settings.gpu_label());
+            println!(
+                "Notifications:    {}",
+                if settings.disable_notifications {
+                    "off"
+                } else {
+                    "on"
+                }
+            );
             println!(
-                "GPU:              {}",
-                match settings.gpu_index {
-                    Some(idx) => format!("[{}] {}", idx, settings.gpu_name),
-                    None => "auto".into(),
+                "Nexus server:     {}",
+                downloaders::NexusCdn::parse(&settings.nexus_cdn)
+            );
+            println!("User-Agent:       {}", downloaders::user_agent());
+            println!(
+                "Steam:            {} ({})",
+                game_finder::steam_preference(),
+                match game_finder::active_steam_path() {
+                    Some(path) if game_finder::active_steam_is_flatpak() => {
+                        format!("Flatpak, {}", path.display())
+                    }
+                    Some(path) => path.display().to_string(),
+                    None => "not found".to_string(),
                 }
             );
             if !settings.patch_cache_dir.is_empty() {
                 println!("Patch cache:      {}", settings.patch_cache_dir);
             }
+            let or_unset = |v: &str| {
+                if v.is_empty() {
+                    "(not set)".to_string()
+                } else {
+                    v.to_string()
+                }
+            };
+            println!(
+                "Downloads dir:    {}",
+                or_unset(&settings.default_downloads_dir)
+            );
+            println!(
+                "Install dir:      {}",
+                or_unset(&settings.default_install_dir)
+            );
+            println!("Game dir:         {}", or_unset(&settings.default_game_dir));
         }
 
         Commands::Install {
             wabbajack_file,
+            from_gallery,
             downloads,
             output,
+            downloads_dir,
+            output_dir,
             game,
             nexus_key,
             nexus_oauth_token,
@@ -643,263 +1371,890 @@ async fn main() -> Result<()> {
             install_workers,
             bsa_workers,
             sevenzip_workers,
+            storage,
             ll_email,
             ll_password,
             extract,
-            progress: _,
+            no_pipeline,
+            progress,
             machine_name,
             report_json,
             jackify,
+            include,
+            exclude,
+            only_downloads,
+            phase,
+            force_rehash,
+            verify_outputs,
+            fresh,
+            mirrors,
+            archive_store,
+            gpu,
+            texture_encoder,
+            stall_timeout,
+            max_download_speed,
+            metered,
+            accept_metered,
+            max_retries,
+            auto_retry,
+            no_hash_retry,
+            on_hash_mismatch,
+            download_order,
+            nexus_cdn,
+            purge_downloads,
+            force,
+            setup_steam: _,
+            proton,
+            wine_prefix,
+            link_mode,
+            profile,
+            report_conflicts,
+            wabbajack_hash,
         } => {
-            let detail = |message: String| {
-                if jackify {
-                    eprintln!("{}", message);
+            // `--jackify` is the older spelling of `--progress json`.
+            let json_events = jackify || matches!(progress, ProgressModeArg::Json);
+            let json_writer = json_events.then(JsonEventWriter::stdout);
+            // Every error exit ends a JSON stream with InstallError, so
+            // frontends always get a terminal event.
+            let result: Result<()> = async {
+                let detail = |message: String| {
+                    if json_events {
+                        eprintln!("{}", message);
+                    } else {
+                        println!("{}", message);
+                    }
+                };
+
+                let (wabbajack_file, downloads, output, wabbajack_hash, machine_name) =
+                    match from_gallery {
+                        Some(query) => {
+                            // No .wabbajack argument, so the positionals shift
+                            // down by one.
+                            if output.is_some() {
+                                anyhow::bail!(
+                                    "With --from-gallery, pass only the downloads and install \
+                                     directories"
+                                );
+                            }
+                            if let Some(first) = wabbajack_file.as_deref().filter(|first| {
+                                is_wabbajack_url(first)
+                                    || first.to_lowercase().ends_with(".wabbajack")
+                            }) {
+                                anyhow::bail!(
+                                    "With --from-gallery, leave out the modlist file: '{}' would be \
+                                     used as the downloads directory",
+                                    first
+                                );
+                            }
+                            let (metadata, url) = find_gallery_modlist(&query, offline).await?;
+                            detail(format!(
+                                "Gallery modlist: {} {} by {} ({})",
+                                metadata.title,
+                                metadata.version,
+                                metadata.author,
+                                metadata.machine_name
+                            ));
+                            (
+                                url,
+                                wabbajack_file.map(PathBuf::from),
+                                downloads,
+                                wabbajack_hash.or(metadata.download_hash().map(str::to_string)),
+                                machine_name.or(Some(metadata.machine_name)),
+                            )
+                        }
+                        None => (
+                            wabbajack_file.context("No .wabbajack file given")?,
+                            downloads,
+                            output,
+                            wabbajack_hash,
+                            machine_name,
+                        ),
+                    };
+
+                // Remember the original CLI argument as a URL if it was one — it
+                // ends up recorded in `.clf3-install.json` so `modlist update`
+                // can fall back to it later.
+                let original_wabbajack_url = if is_wabbajack_url(&wabbajack_file) {
+                    Some(wabbajack_file.clone())
+                } else {
+                    None
+                };
+
+                // If wabbajack_file is a URL, download it first.
+                let wabbajack_file = if original_wabbajack_url.is_some() {
+                    fetch_wabbajack_from_url(
+                        &wabbajack_file,
+                        wabbajack_hash.as_deref(),
+                        json_events,
+                        offline,
+                    )
+                    .await?
                 } else {
-                    println!("{}", message);
+                    PathBuf::from(&wabbajack_file)
+                };
+
+                let settings = settings::Settings::load();
+
+                // Directories: CLI arg > env var > saved default.
+                let downloads = downloads
+                    .or(downloads_dir)
+                    .or_else(|| settings.default_downloads_path())
+                    .context(
+                        "No downloads directory given. Pass one on the command line or set \
+                         default_downloads_dir in settings.json",
+                    )?;
+                let output = output
+                    .or(output_dir)
+                    .or_else(|| settings.default_install_path())
+                    .context(
+                        "No install directory given. Pass one with -o or set \
+                         default_install_dir in settings.json",
+                    )?;
+                let game = game.or_else(|| settings.default_game_path());
+
+                let nexus_oauth_token = nexus_oauth_token.filter(|token| !token.trim().is_empty());
+
+                // Resolve Nexus credentials: CLI/env OAuth token > CLI/env API key > saved API key.
+                let nexus_key = nexus_key
+                    .or_else(|| {
+                        if settings.nexus_api_key.is_empty() {
+                            None
+                        } else {
+                            Some(settings.nexus_api_key.clone())
+                        }
+                    })
+                    .or_else(|| {
+                        (nexus_oauth_token.is_some() || offline).then(String::new)
+                    })
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Nexus API key or OAuth token required. Set an API key with `clf3 set-api-key YOUR_KEY` or pass --nexus-oauth-token"
+                        )
+                    })?;
+
+                // Resolve LL credentials: CLI arg > env var > saved settings
+                let ll_email = ll_email.unwrap_or_else(|| settings.loverslab_email.clone());
+                let ll_password =
+                    ll_password.unwrap_or_else(|| settings.loverslab_password.clone());
+
+                // Game dir: CLI arg > auto-detect from modlist
+                let game_dir = match game {
+                    Some(g) => {
+                        // Fail before any downloads if --game points at the
+                        // wrong game or an incomplete install.
+                        let missing_dlc = validate_explicit_game_dir(&wabbajack_file, &g)?;
+                        if !missing_dlc.is_empty() {
+                            detail(format!("DLC not installed: {}", missing_dlc.join(", ")));
+                        }
+                        g
+                    }
+                    None => {
+                        // Try to auto-detect game path from the modlist's game type,
+                        // preferring installs whose game files actually match the
+                        // modlist's expected hashes (Steam first, then Heroic/GOG).
+                        match auto_detect_game_dir(&wabbajack_file) {
+                            Some((p, store)) => {
+                                detail(format!(
+                                    "Auto-detected game directory: {} ({})",
+                                    p.display(),
+                                    store
+                                ));
+                                p
+                            }
+                            None => {
+                                anyhow::bail!(
+                                    "Could not auto-detect a game directory with matching files. \
+                                     Specify one with --game PATH"
+                                );
+                            }
+                        }
+                    }
+                };
+
+                // An unknown --proton should fail now, not after the download.
+                let steam_proton = proton.as_deref().map(find_proton).transpose()?;
+
+                // Default to CPU thread count
+                let thread_count = std::thread::available_parallelism()
+                    .map(|n| n.get())
+                    .unwrap_or(4);
+                let metered =
+                    !offline && installer::metered::connection_is_metered(metered, &settings);
+                let concurrent = concurrent
+                    .unwrap_or(if metered {
+                        installer::metered::METERED_CONCURRENT_DOWNLOADS
+                    } else {
+                        thread_count
+                    })
+                    .max(1);
+                let max_download_speed = match max_download_speed {
+                    Some(0) => None,
+                    Some(kb) => Some(kb * 1024),
+                    None => metered.then_some(installer::metered::METERED_SPEED_LIMIT),
+                };
+                // Keep each download's share of the cap above the stall floor.
+                let concurrent = match max_download_speed {
+                    Some(speed) => concurrent.min(downloaders::max_concurrent_for_speed(speed)),
+                    None => concurrent,
+                };
+                let storage_kind = match storage {
+                    StorageArg::Auto => installer::storage::detect_for_install(&downloads, &output),
+                    StorageArg::Hdd => Some(installer::storage::StorageKind::Hdd),
+                    StorageArg::Ssd => Some(installer::storage::StorageKind::Ssd),
+                };
+                let tuning = installer::storage::tune(storage_kind, thread_count);
+                let install_workers = install_workers.unwrap_or(tuning.install_workers).max(1);
+                let bsa_workers = bsa_workers.unwrap_or(1).max(1);
+                let sevenzip_workers = sevenzip_workers.unwrap_or(tuning.sevenzip_workers).max(1);
+
+                detail("CLF3 - Wabbajack Modlist Installer".to_string());
+                detail(format!("Concurrent downloads: {}", concurrent));
+                if let Some(speed) = max_download_speed {
+                    detail(format!(
+                        "Download speed cap: {}/s",
+                        installer::progress_cli::format_bytes(speed)
+                    ));
+                }
+                detail(match storage_kind {
+                    Some(kind) => format!("Storage: {}", kind),
+                    None => {
+                        "Storage: unknown (pass --storage hdd or ssd to tune extraction)".to_string()
+                    }
+                });
+                detail(format!(
+                    "Install workers: {} (BSA archives in parallel: {})",
+                    install_workers, bsa_workers
+                ));
+                detail(format!("7z archives in parallel: {}", sevenzip_workers));
+                let wine_prefix = wine_prefix.map(|p| paths::wine_prefix_root(&p));
+                if let Some(prefix) = &wine_prefix {
+                    detail(format!("Wine prefix: {}", prefix.display()));
+                }
+                detail(String::new());
+
+                if metered {
+                    let modlist = modlist::parse_wabbajack_file(&wabbajack_file)?;
+                    let (count, bytes) =
+                        installer::metered::pending_downloads(&modlist, &downloads);
+                    let size = installer::progress_cli::format_bytes(bytes);
+                    detail(format!(
+                        "Metered connection: {} archives ({}) to download",
+                        count, size
+                    ));
+                    if count > 0 && !accept_metered && !confirm_metered_download(&size) {
+                        anyhow::bail!(
+                            "Not downloading {} over a metered connection. Pass --accept-metered \
+                             to go ahead",
+                            size
+                        );
+                    }
                 }
-            };
 
-            // Remember the original CLI argument as a URL if it was one — it
-            // ends up recorded in `.clf3-install.json` so `modlist update`
-            // can fall back to it later.
-            let original_wabbajack_url = if wabbajack_file.starts_with("http://")
-                || wabbajack_file.starts_with("https://")
-            {
-                Some(wabbajack_file.clone())
-            } else {
-                None
-            };
+                let patch_cache_dir = if settings.patch_cache_dir.is_empty() {
+                    None
+                } else {
+                    Some(PathBuf::from(&settings.patch_cache_dir))
+                };
 
-            // If wabbajack_file is a URL, download it first.
-            let wabbajack_file = if original_wabbajack_url.is_some() {
-                fetch_wabbajack_from_url(&wabbajack_file, jackify).await?
-            } else {
-                PathBuf::from(&wabbajack_file)
-            };
+                // Best-effort machine_name resolution. Explicit `--machine-name`
+                // wins. Otherwise fall back to a unique entry in
+                // `Settings::browser_list_paths` whose install_dir matches
+                // `output` — the GUI populates this when the user picks paths
+                // from the gallery, so most "from gallery" installs land here.
+                let resolved_machine_name = machine_name
+                    .clone()
+                    .filter(|s| !s.is_empty())
+                    .or_else(|| derive_machine_name_from_settings(&settings, &output));
+
+                let install_dir_for_fluorine = output.clone();
+                let downloads_dir_for_summary = downloads.clone();
+                let wabbajack_for_summary = wabbajack_file.clone();
+
+                let mirrors = match mirrors {
+                    Some(path) => {
+                        let map = installer::MirrorMap::load(&path)?;
+                        detail(format!(
+                            "Mirror file: {} ({} archives)",
+                            path.display(),
+                            map.len()
+                        ));
+                        map
+                    }
+                    None => installer::MirrorMap::default(),
+                };
+                let archive_store = match archive_store {
+                    Some(path) => {
+                        let store = installer::ArchiveStore::open(&path)?;
+                        detail(format!(
+                            "Archive store: {} ({} archives)",
+                            path.display(),
+                            store.len()
+                        ));
+                        Some(store)
+                    }
+                    None => None,
+                };
 
-            let settings = settings::Settings::load();
+                let last_phase = if only_downloads {
+                    installer::InstallPhase::Downloads
+                } else {
+                    phase.map(Into::into).unwrap_or_default()
+                };
+                let only_downloads = last_phase == installer::InstallPhase::Downloads;
+                let stopped_early = last_phase != installer::InstallPhase::Mo2;
+
+                let mod_filter = installer::ModFilter::new(include, exclude);
+                if !mod_filter.is_empty() {
+                    detail(String::new());
+                    detail("!!! PARTIAL INSTALL REQUESTED (--include/--exclude) !!!".to_string());
+                    detail(
+                        "Skipping mods can break load order and patches. This is NOT supported \
+                         by the modlist author — do not report problems with this install to them."
+                            .to_string(),
+                    );
+                    detail(String::new());
+                }
 
-            let nexus_oauth_token = nexus_oauth_token.filter(|token| !token.trim().is_empty());
+                let (progress_callback, active_reporter): (
+                    Option<ProgressCallback>,
+                    Arc<dyn ProgressReporter>,
+                ) = match &json_writer {
+                    Some(writer) => {
+                        let callback = JsonReporter::download_skipped_callback(writer.clone());
+                        (
+                            Some(callback),
+                            JsonReporter::new(writer.clone()) as Arc<dyn ProgressReporter>,
+                        )
+                    }
+                    None => (None, cli_reporter.clone() as Arc<dyn ProgressReporter>),
+                };
 
-            // Resolve Nexus credentials: CLI/env OAuth token > CLI/env API key > saved API key.
-            let nexus_key = nexus_key
-                .or_else(|| {
-                    if settings.nexus_api_key.is_empty() {
-                        None
+                let mut config = InstallConfig {
+                    wabbajack_path: wabbajack_file,
+                    output_dir: output,
+                    downloads_dir: downloads,
+                    game_dir,
+                    wine_prefix,
+                    nexus_api_key: nexus_key,
+                    nexus_oauth_token,
+                    max_concurrent_downloads: concurrent,
+                    max_install_workers: install_workers,
+                    max_parallel_bsa_archives: bsa_workers,
+                    max_parallel_7z_archives: sevenzip_workers,
+                    patch_cache_dir,
+                    progress_callback,
+                    reporter: active_reporter.clone(),
+                    loverslab_email: ll_email,
+                    loverslab_password: ll_password,
+                    extract_strategy: if no_pipeline {
+                        ExtractStrategy::Phased
                     } else {
-                        Some(settings.nexus_api_key.clone())
+                        extract.into()
+                    },
+                    machine_name: resolved_machine_name,
+                    wabbajack_url: original_wabbajack_url,
+                    mod_filter,
+                    force_rehash,
+                    verify_outputs: verify_outputs || fresh,
+                    mirrors,
+                    archive_store,
+                    gpu_index: gpu.or(settings.gpu_index),
+                    texture_encoder: match (texture_encoder, gpu, settings.texture_encoder) {
+                        (Some(mode), _, _) => mode.into(),
+                        // An explicit --gpu overrides a saved CPU-only choice.
+                        (None, Some(_), textures::EncoderMode::Cpu) => textures::EncoderMode::Auto,
+                        (None, _, mode) => mode,
+                    },
+                    stall_timeout: std::time::Duration::from_secs(stall_timeout),
+                    max_download_speed,
+                    retry: installer::RetryPolicy {
+                        max_retries,
+                        retry_hash_mismatch: !no_hash_retry,
+                    },
+                    on_hash_mismatch: on_hash_mismatch.into(),
+                    download_order: download_order.into(),
+                    nexus_cdn: downloaders::NexusCdn::parse(
+                        nexus_cdn.as_deref().unwrap_or(&settings.nexus_cdn),
+                    ),
+                    purge_downloads: purge_downloads.map(Into::into).unwrap_or_default(),
+                    force,
+                    link_mode: link_mode.into(),
+                    last_phase,
+                    profile,
+                    report_conflicts,
+                    offline,
+                    http_headers: downloaders::HostHeaders::new(&settings.http_headers),
+                };
+
+                if fresh
+                    && config
+                        .discard_saved_state()
+                        .context("Failed to discard saved install progress")?
+                {
+                    active_reporter.log("Starting fresh: discarded progress from the previous run");
+                }
+
+                let mut installer = match Installer::new(config.clone()) {
+                    Err(e) if !json_events && confirm_install_over(&e) => {
+                        config.force = true;
+                        Installer::new(config.clone())?
                     }
-                })
-                .or_else(|| {
-                    nexus_oauth_token.is_some().then(String::new)
-                })
-                .ok_or_else(|| {
-                    anyhow::anyhow!(
-                        "Nexus API key or OAuth token required. Set an API key with `clf3 set-api-key YOUR_KEY` or pass --nexus-oauth-token"
-                    )
-                })?;
+                    result => result?,
+                };
+                // JSON frontends show their own completion UI.
+                let notify = !json_events && !settings.disable_notifications;
+                let modlist_name = installer.modlist_name();
+                let mut attempt = 0;
+                let run = loop {
+                    let run = if only_downloads {
+                        installer.run_downloads_only().await
+                    } else {
+                        installer.run_pipelined().await
+                    };
+                    let retryable = run.as_ref().map_or(0, |stats| stats.retryable_failures());
+                    if retryable == 0 || attempt == auto_retry {
+                        break run;
+                    }
+                    attempt += 1;
+                    let delay = auto_retry_delay(attempt);
+                    active_reporter.log(&format!(
+                        "\n{} download(s) failed with errors that may clear up; running the \
+                         install again in {}s (retry {}/{})",
+                        retryable,
+                        delay.as_secs(),
+                        attempt,
+                        auto_retry
+                    ));
+                    tokio::time::sleep(delay).await;
+                    // Let go of the modlist database before opening it again.
+                    drop(installer);
+                    installer = Installer::new(config.clone())?;
+                };
+                let stats = match run {
+                    Ok(stats) => stats,
+                    Err(e) => {
+                        if notify {
+                            notify::install_finished(
+                                &modlist_name,
+                                &notify::InstallOutcome::Failed(e.to_string()),
+                            );
+                        }
+                        if installer::limits::is_too_many_open_files(e.inner()) {
+                            active_reporter.log(installer::limits::OPEN_FILES_FIX);
+                        }
+                        return Err(e.into());
+                    }
+                };
 
-            // Resolve LL credentials: CLI arg > env var > saved settings
-            let ll_email = ll_email.unwrap_or_else(|| settings.loverslab_email.clone());
-            let ll_password = ll_password.unwrap_or_else(|| settings.loverslab_password.clone());
-
-            // Game dir: CLI arg > auto-detect from modlist
-            let game_dir = match game {
-                Some(g) => g,
-                None => {
-                    // Try to auto-detect game path from the modlist's game type,
-                    // preferring installs whose game files actually match the
-                    // modlist's expected hashes (Steam first, then Heroic/GOG).
-                    match auto_detect_game_dir(&wabbajack_file) {
-                        Some((p, store)) => {
-                            detail(format!(
-                                "Auto-detected game directory: {} ({})",
-                                p.display(),
-                                store
-                            ));
-                            p
+                let reporter = active_reporter.as_ref();
+                let total_processed =
+                    stats.directives_completed + stats.directives_skipped + stats.directives_failed;
+
+                reporter.log("\n=== Installation Summary ===");
+                reporter.log(&format!(
+                    "Downloads:  {} downloaded, {} skipped, {} manual, {} failed",
+                    stats.archives_downloaded,
+                    stats.archives_skipped,
+                    stats.archives_manual,
+                    stats.archives_failed
+                ));
+                if !only_downloads {
+                    reporter.log(&format!(
+                        "Directives: {} new, {} existing, {} failed ({} total)",
+                        stats.directives_completed,
+                        stats.directives_skipped,
+                        stats.directives_failed,
+                        total_processed
+                    ));
+                    for f in stats.failure_breakdown() {
+                        reporter.log(&format!(
+                            "  {} {} failed: {}",
+                            f.count, f.directive_type, f.first_error
+                        ));
+                        if let Some(hint) = f.hint() {
+                            reporter.log(&format!("    Likely cause: {}", hint));
                         }
-                        None => {
-                            anyhow::bail!(
-                                "Could not auto-detect a game directory with matching files. \
-                                 Specify one with --game PATH"
-                            );
+                    }
+                }
+
+                if !stats.manual_downloads.is_empty() {
+                    reporter.log(&format!(
+                        "\n=== Manual Downloads Needed ({}) ===",
+                        stats.manual_downloads.len()
+                    ));
+                    for (i, md) in stats.manual_downloads.iter().enumerate() {
+                        reporter.log(&format!("{}. {}", i + 1, md.name));
+                        reporter.log(&format!("   URL: {}", md.url));
+                        reporter.log(&format!("   Size: {} bytes", md.expected_size));
+                        reporter.log(&format!(
+                            "   Save as: {}",
+                            downloads_dir_for_summary.join(&md.name).display()
+                        ));
+                        if let Some(ref prompt) = md.prompt {
+                            reporter.log(&format!("   Note: {}", prompt));
                         }
                     }
+                    reporter.log(
+                        "\nDownload each file in a browser, save it under the exact name shown, \
+                         then run the same install command again; finished files are picked up \
+                         and verified.",
+                    );
+                    let removed = stats.removed_source_count();
+                    if removed > 0 {
+                        reporter.log(&format!(
+                            "\n{} of these were removed or archived on Nexus by their authors. \
+                             Look for alternatives or ask the modlist author.",
+                            removed
+                        ));
+                    }
                 }
-            };
 
-            // Default to CPU thread count
-            let thread_count = std::thread::available_parallelism()
-                .map(|n| n.get())
-                .unwrap_or(4);
-            let concurrent = concurrent.unwrap_or(thread_count).max(1);
-            let install_workers = install_workers.unwrap_or(thread_count).max(1);
-            let bsa_workers = bsa_workers.unwrap_or(1).max(1);
-            let sevenzip_workers = sevenzip_workers.unwrap_or(thread_count).max(1);
-
-            detail("CLF3 - Wabbajack Modlist Installer".to_string());
-            detail(format!("Concurrent downloads: {}", concurrent));
-            detail(format!(
-                "Install workers: {} (BSA archives in parallel: {})",
-                install_workers, bsa_workers
-            ));
-            detail(format!("7z archives in parallel: {}", sevenzip_workers));
-            detail(String::new());
+                if !stats.failed_downloads.is_empty() {
+                    reporter.log(&format!(
+                        "\n=== Failed Downloads ({}) ===",
+                        stats.failed_downloads.len()
+                    ));
+                    for (i, fd) in stats.failed_downloads.iter().enumerate() {
+                        reporter.log(&format!("{}. {}", i + 1, fd.name));
+                        reporter.log(&format!("   URL: {}", fd.url));
+                        reporter.log(&format!("   Error: {}", fd.error));
+                    }
+                }
 
-            let patch_cache_dir = if settings.patch_cache_dir.is_empty() {
-                None
-            } else {
-                Some(PathBuf::from(&settings.patch_cache_dir))
-            };
+                if stats.archives_manual > 0 || stats.archives_failed > 0 {
+                    reporter.log(&open_downloads_hint(
+                        &wabbajack_for_summary,
+                        &downloads_dir_for_summary,
+                    ));
+                }
 
-            // Best-effort machine_name resolution. Explicit `--machine-name`
-            // wins. Otherwise fall back to a unique entry in
-            // `Settings::browser_list_paths` whose install_dir matches
-            // `output` — the GUI populates this when the user picks paths
-            // from the gallery, so most "from gallery" installs land here.
-            let resolved_machine_name = machine_name
-                .clone()
-                .filter(|s| !s.is_empty())
-                .or_else(|| derive_machine_name_from_settings(&settings, &output));
-
-            let install_dir_for_fluorine = output.clone();
-
-            let (progress_callback, active_reporter): (
-                Option<ProgressCallback>,
-                Arc<dyn ProgressReporter>,
-            ) = if jackify {
-                let writer = JsonEventWriter::stdout();
-                let callback = JsonReporter::download_skipped_callback(writer.clone());
-                (
-                    Some(callback),
-                    JsonReporter::new(writer) as Arc<dyn ProgressReporter>,
-                )
-            } else {
-                (None, cli_reporter.clone() as Arc<dyn ProgressReporter>)
-            };
+                let installation_succeeded = stats.archives_manual == 0
+                    && stats.archives_failed == 0
+                    && stats.directives_failed == 0;
 
-            let config = InstallConfig {
-                wabbajack_path: wabbajack_file,
-                output_dir: output,
-                downloads_dir: downloads,
-                game_dir,
-                nexus_api_key: nexus_key,
-                nexus_oauth_token,
-                max_concurrent_downloads: concurrent,
-                max_install_workers: install_workers,
-                max_parallel_bsa_archives: bsa_workers,
-                max_parallel_7z_archives: sevenzip_workers,
-                patch_cache_dir,
-                progress_callback,
-                reporter: active_reporter.clone(),
-                loverslab_email: ll_email,
-                loverslab_password: ll_password,
-                extract_strategy: extract.into(),
-                machine_name: resolved_machine_name,
-                wabbajack_url: original_wabbajack_url,
-            };
+                if notify {
+                    let outcome = if stats.archives_manual > 0 {
+                        notify::InstallOutcome::NeedsManualDownloads(stats.archives_manual)
+                    } else if !installation_succeeded {
+                        notify::InstallOutcome::Incomplete {
+                            failed: stats.archives_failed + stats.directives_failed,
+                        }
+                    } else if only_downloads || stopped_early {
+                        notify::InstallOutcome::Stopped
+                    } else {
+                        notify::InstallOutcome::Complete
+                    };
+                    notify::install_finished(&modlist_name, &outcome);
+                }
 
-            let mut installer = Installer::new(config)?;
-            let stats = installer.run_pipelined().await?;
+                if stats.archives_manual > 0 || stats.archives_failed > 0 {
+                    reporter.log("\nSome archives need manual download. Fix issues and run again.");
+                } else if stats.directives_failed > 0 {
+                    reporter.log("\nSome directives failed. Check the log file for details.");
+                } else if only_downloads {
+                    reporter.log(
+                        "\nAll archives downloaded and verified. Run again without \
+                         --only-downloads to install.",
+                    );
+                } else if stopped_early {
+                    reporter.log(&format!(
+                        "\nStopped after the {:?} phase. Run again without --phase to finish \
+                         the install.",
+                        last_phase
+                    ));
+                } else {
+                    reporter.log("\nInstallation complete!");
+                }
 
-            let reporter = active_reporter.as_ref();
-            let total_processed =
-                stats.directives_completed + stats.directives_skipped + stats.directives_failed;
+                // Only on a clean install so we don't add half-broken instances
+                // to the user's Fluorine sidebar.
+                if installation_succeeded && !stopped_early {
+                    run_launcher_integration(&settings, &install_dir_for_fluorine, reporter).await;
+                }
 
-            reporter.log("\n=== Installation Summary ===");
-            reporter.log(&format!(
-                "Downloads:  {} downloaded, {} skipped, {} manual, {} failed",
-                stats.archives_downloaded,
-                stats.archives_skipped,
-                stats.archives_manual,
-                stats.archives_failed
-            ));
-            reporter.log(&format!(
-                "Directives: {} new, {} existing, {} failed ({} total)",
-                stats.directives_completed,
-                stats.directives_skipped,
-                stats.directives_failed,
-                total_processed
-            ));
+                // Optional structured report for external tooling.
+                if let Some(report_path) = report_json {
+                    let content = serde_json::to_string_pretty(&stats)
+                        .context("Failed to serialize InstallStats")?;
+                    std::fs::write(&report_path, content).with_context(|| {
+                        format!("Failed to write report to {}", report_path.display())
+                    })?;
+                    reporter.log(&format!(
+                        "Wrote install report to {}",
+                        report_path.display()
+                    ));
+                }
 
-            if !stats.manual_downloads.is_empty() {
-                reporter.log(&format!(
-                    "\n=== Manual Downloads Needed ({}) ===",
-                    stats.manual_downloads.len()
-                ));
-                for (i, md) in stats.manual_downloads.iter().enumerate() {
-                    reporter.log(&format!("{}. {}", i + 1, md.name));
-                    reporter.log(&format!("   URL: {}", md.url));
-                    reporter.log(&format!("   Size: {} bytes", md.expected_size));
-                    if let Some(ref prompt) = md.prompt {
-                        reporter.log(&format!("   Note: {}", prompt));
+                if let Some(proton) = &steam_proton {
+                    if installation_succeeded && !stopped_early {
+                        run_steam_setup(&install_dir_for_fluorine, proton, reporter).await?;
+                    } else {
+                        reporter.log("\nSkipping Steam setup: the install didn't finish");
                     }
                 }
+
+                if let Some(writer) = &json_writer {
+                    writer.emit(ProgressEvent::InstallComplete {
+                        succeeded: installation_succeeded,
+                        archives_downloaded: stats.archives_downloaded,
+                        archives_skipped: stats.archives_skipped,
+                        archives_manual: stats.archives_manual,
+                        archives_failed: stats.archives_failed,
+                        directives_completed: stats.directives_completed,
+                        directives_failed: stats.directives_failed,
+                    });
+                }
+
+                // Scripts using --auto-retry need to know it gave up.
+                if auto_retry > 0 && !installation_succeeded {
+                    std::process::exit(1);
+                }
+                anyhow::Ok(())
+            }
+            .await;
+            if let (Err(e), Some(writer)) = (&result, &json_writer) {
+                writer.emit(ProgressEvent::InstallError {
+                    message: format!("{:#}", e),
+                });
             }
+            result?;
+        }
 
-            if !stats.failed_downloads.is_empty() {
-                reporter.log(&format!(
-                    "\n=== Failed Downloads ({}) ===",
-                    stats.failed_downloads.len()
-                ));
-                for (i, fd) in stats.failed_downloads.iter().enumerate() {
-                    reporter.log(&format!("{}. {}", i + 1, fd.name));
-                    reporter.log(&format!("   URL: {}", fd.url));
-                    reporter.log(&format!("   Error: {}", fd.error));
+        Commands::Modlist { action } => {
+            run_modlist_action(action, offline).await?;
+        }
+
+        Commands::Fetch { url, output } => {
+            run_fetch_command(&url, &output).await?;
+        }
+
+        Commands::Status {
+            wabbajack_file,
+            downloads_dir,
+            output_dir,
+            json,
+        } => {
+            let settings = settings::Settings::load();
+            let downloads = downloads_dir
+                .or_else(|| settings.default_downloads_path())
+                .context("No downloads directory given. Pass --downloads-dir")?;
+            let output = output_dir
+                .or_else(|| settings.default_install_path())
+                .context("No install directory given. Pass -o")?;
+
+            let parsed = modlist::parse_wabbajack_file(&wabbajack_file)?;
+            let mut db = modlist::ModlistDb::in_memory()?;
+            db.import_modlist(&parsed)?;
+            let status = installer::status::check_status(&db, &downloads, &output)?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&status)?);
+            } else {
+                print_install_status(&parsed.name, &status);
+            }
+        }
+
+        Commands::Check {
+            install_dir,
+            wabbajack_file,
+            json,
+        } => {
+            let cancel = tokio_util::sync::CancellationToken::new();
+            tokio::spawn({
+                let cancel = cancel.clone();
+                async move {
+                    if tokio::signal::ctrl_c().await.is_ok() {
+                        cancel.cancel();
+                    }
                 }
+            });
+
+            let pb = indicatif::ProgressBar::new(0);
+            pb.set_style(
+                indicatif::ProgressStyle::default_bar()
+                    .template("Hashing {msg} [{bar:40}] {bytes}/{total_bytes} ({eta})")
+                    .expect("valid template")
+                    .progress_chars("=> "),
+            );
+            let pb_progress = pb.clone();
+            let (name, report) = tokio::task::spawn_blocking(move || {
+                installer::integrity::check_install(
+                    &install_dir,
+                    wabbajack_file,
+                    &|p| {
+                        pb_progress.set_length(p.bytes_total);
+                        pb_progress.set_position(p.bytes_done);
+                        pb_progress
+                            .set_message(format!("{}/{} files", p.files_done, p.files_total));
+                    },
+                    &cancel,
+                )
+            })
+            .await
+            .context("Check task failed")??;
+            pb.finish_and_clear();
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print!("{}", report.render(&name));
             }
+            if report.unchecked > 0 {
+                std::process::exit(130);
+            }
+            if !report.issues.is_empty() {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Finalize {
+            install_dir,
+            restart_steam,
+            proton,
+        } => {
+            if !install_dir.join("ModOrganizer.ini").is_file() {
+                anyhow::bail!(
+                    "{} is not a finished install (no ModOrganizer.ini)",
+                    install_dir.display()
+                );
+            }
+            let settings = settings::Settings::load();
+            let reporter: &dyn ProgressReporter = cli_reporter.as_ref();
+
+            reporter.log("=== GPU Settings ===");
+            match gpu::fix_ini_gpu_settings(&install_dir) {
+                Ok(0) => reporter.log("No INI files needed a GPU name change"),
+                Ok(n) => reporter.log(&format!("Set the GPU name in {} INI files", n)),
+                Err(e) => reporter.log(&format!("GPU INI fix skipped: {:#}", e)),
+            }
+
+            run_launcher_integration(&settings, &install_dir, reporter).await;
+
+            if let Some(proton) = proton {
+                run_steam_setup(&install_dir, &find_proton(&proton)?, reporter).await?;
+            } else if restart_steam {
+                reporter.log("\nRestarting Steam...");
+                tokio::task::spawn_blocking(|| {
+                    game_finder::restart_steam(std::time::Duration::from_secs(30))
+                })
+                .await??;
+                reporter.log("Steam restarted");
+            }
+        }
+
+        Commands::OpenDownloads {
+            wabbajack_file,
+            downloads_dir,
+            batch,
+            delay_ms,
+            nexus_only,
+            all_sources,
+        } => {
+            let settings = settings::Settings::load();
+            let downloads = downloads_dir
+                .or_else(|| settings.default_downloads_path())
+                .context("No downloads directory given. Pass --downloads-dir")?;
+            let parsed = modlist::parse_wabbajack_file(&wabbajack_file)?;
+            let queue = installer::browser_queue::BrowserQueue::new(
+                &parsed.archives,
+                &downloads,
+                batch,
+                if nexus_only {
+                    installer::browser_queue::QueueSources::Nexus
+                } else if all_sources {
+                    installer::browser_queue::QueueSources::All
+                } else {
+                    installer::browser_queue::QueueSources::Browser
+                },
+            );
+            run_browser_queue(
+                queue,
+                &downloads,
+                std::time::Duration::from_millis(delay_ms),
+            )
+            .await?;
+        }
 
-            let installation_succeeded = stats.archives_manual == 0
-                && stats.archives_failed == 0
-                && stats.directives_failed == 0;
+        Commands::Clean {
+            downloads_dir,
+            output_dir,
+            temp_dir,
+            modlists,
+            unreferenced,
+            dry_run,
+            yes,
+        } => {
+            use installer::progress_cli::format_bytes;
 
-            if stats.archives_manual > 0 || stats.archives_failed > 0 {
-                reporter.log("\nSome archives need manual download. Fix issues and run again.");
-            } else if stats.directives_failed > 0 {
-                reporter.log("\nSome directives failed. Check the log file for details.");
-            } else {
-                reporter.log("\nInstallation complete!");
+            let settings = settings::Settings::load();
+            let downloads = downloads_dir
+                .or_else(|| settings.default_downloads_path())
+                .context("No downloads directory given. Pass --downloads-dir")?;
+            let known = installer::clean::known_archives(&modlists, &settings);
+            if unreferenced && known.is_empty() {
+                anyhow::bail!(
+                    "No known modlists to keep archives for; --unreferenced would delete \
+                     every download. Pass --modlist"
+                );
             }
-
-            // Fluorine auto-registration. Only runs on a clean install so we
-            // don't add half-broken instances to the user's Fluorine sidebar.
-            if installation_succeeded && settings.add_to_fluorine {
-                if let Err(e) =
-                    ensure_fluorine_and_register(&settings, &install_dir_for_fluorine).await
-                {
-                    reporter.log(&format!("\nFluorine integration failed: {}", e));
-                } else {
-                    reporter.log(&format!(
-                        "\nRegistered '{}' as a Fluorine portable instance.",
-                        install_dir_for_fluorine.display()
-                    ));
-                }
+            if !known.modlists.is_empty() {
+                println!("Known modlists: {}", known.modlists.join(", "));
             }
 
-            // Optional structured report for external tooling.
-            if let Some(report_path) = report_json {
-                let content = serde_json::to_string_pretty(&stats)
-                    .context("Failed to serialize InstallStats")?;
-                std::fs::write(&report_path, content).with_context(|| {
-                    format!("Failed to write report to {}", report_path.display())
-                })?;
-                reporter.log(&format!(
-                    "Wrote install report to {}",
-                    report_path.display()
-                ));
+            let mut temp_dirs: Vec<PathBuf> = settings
+                .installed_modlists
+                .values()
+                .map(|record| PathBuf::from(&record.install_dir))
+                .chain(output_dir)
+                .chain(temp_dir)
+                .collect();
+            temp_dirs.sort();
+            temp_dirs.dedup();
+            temp_dirs.retain(|dir| *dir != downloads);
+            let temp_dirs: Vec<&std::path::Path> = temp_dirs.iter().map(|d| d.as_path()).collect();
+
+            let stale = installer::clean::find_stale(&downloads, &temp_dirs, &known, unreferenced)
+                .with_context(|| format!("Failed to scan {}", downloads.display()))?;
+            if stale.is_empty() {
+                println!("Nothing to clean.");
+                return Ok(());
             }
-        }
-
-        Commands::Modlist { action } => {
-            run_modlist_action(action).await?;
-        }
+            for entry in &stale {
+                println!(
+                    "  {:>10}  {:<16} {}",
+                    format_bytes(entry.size),
+                    entry.kind.to_string(),
+                    entry.path.display()
+                );
+            }
+            let total: u64 = stale.iter().map(|e| e.size).sum();
+            println!(
+                "{} entries, {} reclaimable",
+                stale.len(),
+                format_bytes(total)
+            );
 
-        Commands::Fetch { url, output } => {
-            run_fetch_command(&url, &output).await?;
+            if dry_run {
+                return Ok(());
+            }
+            if !yes && !confirm_clean() {
+                anyhow::bail!("Nothing deleted. Pass --yes to delete without asking");
+            }
+            let (removed, freed) = installer::clean::remove_stale(&stale);
+            println!("Deleted {} entries, freed {}", removed, format_bytes(freed));
         }
 
-        Commands::Info { wabbajack_file } => {
+        Commands::Info {
+            wabbajack_file,
+            validate,
+            wabbajack_hash,
+        } => {
+            let wabbajack_file = if is_wabbajack_url(&wabbajack_file) {
+                fetch_wabbajack_from_url(&wabbajack_file, wabbajack_hash.as_deref(), false, offline)
+                    .await?
+            } else {
+                PathBuf::from(&wabbajack_file)
+            };
             println!("Parsing {}...\n", wabbajack_file.display());
 
             let modlist = modlist::parse_wabbajack_file(&wabbajack_file)?;
@@ -919,7 +2274,11 @@ async fn main() -> Result<()> {
             println!("Directives:        {}", modlist.directives.len());
 
             let mut type_counts = std::collections::HashMap::with_capacity(10);
-            for directive in &modlist.directives {
+            for directive in modlist
+                .directives
+                .iter()
+                .filter(|d| !matches!(d, modlist::Directive::Unsupported(_)))
+            {
                 *type_counts.entry(directive.directive_type()).or_insert(0) += 1;
             }
 
@@ -930,6 +2289,27 @@ async fn main() -> Result<()> {
                 println!("{:>8}  {}", count, dtype);
             }
 
+            let unsupported = modlist.unsupported_directives();
+            if !unsupported.is_empty() {
+                println!("\n=== Unsupported Directives (will not be installed) ===");
+                for (dtype, count) in unsupported {
+                    println!("{:>8}  {}", count, dtype);
+                }
+            }
+
+            let profiles = installer::profiles::read_profiles(&wabbajack_file, &modlist)?;
+            if profiles.has_choice() {
+                println!("\n=== MO2 Profiles (select with install --profile) ===");
+                for name in &profiles.names {
+                    let recommended = profiles.recommended.as_deref() == Some(name.as_str());
+                    println!(
+                        "  {}{}",
+                        name,
+                        if recommended { "  (recommended)" } else { "" }
+                    );
+                }
+            }
+
             let mut source_counts = std::collections::HashMap::with_capacity(10);
             for archive in &modlist.archives {
                 let source_type = match &archive.state {
@@ -951,6 +2331,16 @@ async fn main() -> Result<()> {
             for (source, count) in counts {
                 println!("{:>8}  {}", count, source);
             }
+
+            if validate {
+                println!("\n=== Validation ===");
+                let report = modlist::validate::validate_wabbajack(&wabbajack_file, &modlist)?;
+                if !report.is_ok() {
+                    print!("{}", report.render());
+                    anyhow::bail!("{} structural problems found", report.problems.len());
+                }
+                println!("No structural problems found");
+            }
         }
 
         Commands::Fluorine { action } => {
@@ -992,10 +2382,12 @@ async fn run_fetch_command(url: &str, output: &std::path::Path) -> Result<()> {
 
 
 /// Make sure a Fluorine install is available, downloading the latest release
-/// if not, then register `install_dir` as a portable instance.
+/// if not, then register `install_dir` as a portable instance. Each step is
+/// reported so a slow download doesn't look like a hang.
 async fn ensure_fluorine_and_register(
     settings: &settings::Settings,
     install_dir: &std::path::Path,
+    reporter: &dyn ProgressReporter,
 ) -> Result<()> {
     let override_path = if settings.fluorine_path.is_empty() {
         None
@@ -1003,15 +2395,397 @@ async fn ensure_fluorine_and_register(
         Some(settings.fluorine_path.as_str())
     };
 
-    if fluorine::detect(override_path).is_none() {
-        tracing::info!("Fluorine not detected — downloading the latest release");
-        fluorine::download_latest(None).await?;
+    reporter.status("Looking for Fluorine...");
+    match fluorine::detect(override_path) {
+        Some(install) => reporter.log(&format!(
+            "Using Fluorine at {} ({})",
+            install.binary.display(),
+            install.source
+        )),
+        None => {
+            reporter.status("Fluorine not found; downloading and extracting it...");
+            let download =
+                fluorine::download_release(None, pinned_fluorine_version(settings)).await?;
+            reporter.log(&format!(
+                "Installed Fluorine {} (from {}) to {}",
+                download.version,
+                download.source,
+                download.root.display()
+            ));
+        }
+    }
+
+    // Fluorine silently ignores instances without an MO2 config, so check
+    // before claiming the instance is ready.
+    reporter.status("Checking ModOrganizer.ini...");
+    if !install_dir.join("ModOrganizer.ini").is_file() {
+        anyhow::bail!(
+            "{} has no ModOrganizer.ini; Fluorine can't open it as an instance",
+            install_dir.display()
+        );
     }
 
+    reporter.status("Registering portable instance...");
     fluorine::register_portable_instance(install_dir, false)?;
     Ok(())
 }
 
+/// Interactive loop for `clf3 open-downloads`: open a batch, wait until
+/// it has arrived (or Enter skips ahead), repeat.
+async fn run_browser_queue(
+    mut queue: installer::browser_queue::BrowserQueue,
+    downloads_dir: &std::path::Path,
+    delay: std::time::Duration,
+) -> Result<()> {
+    use installer::browser_queue::open_in_browser;
+    use std::io::Write;
+
+    if queue.total() == 0 {
+        println!(
+            "Nothing to download: every archive is already in {}",
+            downloads_dir.display()
+        );
+        return Ok(());
+    }
+    println!(
+        "{} archive(s) to download in {} batch(es). Save each file into {} under the \
+         name shown.",
+        queue.total(),
+        queue.batch_count(),
+        downloads_dir.display()
+    );
+
+    // Enter skips to the next batch without waiting for the current one.
+    let (enter_tx, mut enter_rx) = tokio::sync::mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        let mut line = String::new();
+        while std::io::stdin().read_line(&mut line).is_ok_and(|n| n > 0) {
+            line.clear();
+            if enter_tx.send(()).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut batch_no = 0;
+    loop {
+        let batch = queue.next_batch();
+        if batch.is_empty() {
+            break;
+        }
+        batch_no += 1;
+        println!("\n=== Batch {}/{} ===", batch_no, queue.batch_count());
+        for item in &batch {
+            println!(
+                "  {}  ({})",
+                item.name,
+                installer::progress_cli::format_bytes(item.expected_size)
+            );
+            if let Err(e) = open_in_browser(&item.url) {
+                println!(
+                    "    Could not open the browser ({:#}). Open it yourself: {}",
+                    e, item.url
+                );
+            }
+            tokio::time::sleep(delay).await;
+        }
+        println!("Waiting for this batch (press Enter to open the next one now)...");
+
+        loop {
+            let done = batch
+                .iter()
+                .filter(|item| queue.is_downloaded(item))
+                .count();
+            print!(
+                "\r  Batch {}/{}: {}/{} downloaded, {} left overall   ",
+                batch_no,
+                queue.batch_count(),
+                done,
+                batch.len(),
+                queue.remaining()
+            );
+            let _ = std::io::stdout().flush();
+            if done == batch.len() {
+                break;
+            }
+            tokio::select! {
+                _ = enter_rx.recv() => break,
+                _ = tokio::time::sleep(std::time::Duration::from_secs(2)) => {}
+            }
+        }
+        println!();
+    }
+
+    let missing = queue.still_pending();
+    if missing.is_empty() {
+        println!("\nAll archives downloaded. Run the install again to continue.");
+    } else {
+        println!("\nStill missing ({}):", missing.len());
+        for item in missing {
+            println!("  {}  {}", item.name, item.url);
+        }
+    }
+    Ok(())
+}
+
+/// Human-readable output for `clf3 status`.
+fn print_install_status(name: &str, status: &installer::InstallStatus) {
+    use installer::progress_cli::format_bytes;
+
+    println!("=== {} ===", name);
+    println!(
+        "Archives:    {}/{} present, {} to download",
+        status.archives_present,
+        status.archives_total,
+        format_bytes(status.download_bytes_remaining)
+    );
+    println!(
+        "Directives:  {}/{} done, ~{} left to write",
+        status.directives_done,
+        status.directives_total,
+        format_bytes(status.install_bytes_remaining)
+    );
+    for t in &status.by_type {
+        println!("  {:<25} {:>7} / {:>7}", t.directive_type, t.done, t.total);
+    }
+
+    if !status.missing_archives.is_empty() {
+        println!("\nMissing archives:");
+        for name in status.missing_archives.iter().take(20) {
+            println!("  {}", name);
+        }
+        if status.missing_archives.len() > 20 {
+            println!("  ... and {} more", status.missing_archives.len() - 20);
+        }
+    }
+
+    if status.is_complete() {
+        println!("\nInstall is complete.");
+    }
+}
+
+/// How to open the download pages (exact Nexus file, manual source) of the
+/// archives a run couldn't fetch.
+fn open_downloads_hint(wabbajack: &std::path::Path, downloads: &std::path::Path) -> String {
+    format!(
+        "\nTo open their download pages in your browser, a batch at a time:\n  \
+         clf3 open-downloads {} --downloads-dir {}",
+        browser_gui::shell_quote(&wabbajack.display().to_string()),
+        browser_gui::shell_quote(&downloads.display().to_string())
+    )
+}
+
+/// Fluorine registration (when enabled in settings) and the Steam shortcut
+/// report, the last steps of an install. Failures are logged, not returned.
+async fn run_launcher_integration(
+    settings: &settings::Settings,
+    install_dir: &std::path::Path,
+    reporter: &dyn ProgressReporter,
+) {
+    if settings.add_to_fluorine {
+        reporter.log("\n=== Fluorine Setup ===");
+        if let Err(e) = ensure_fluorine_and_register(settings, install_dir, reporter).await {
+            reporter.log(&format!("Fluorine integration failed: {:#}", e));
+        } else {
+            reporter.log(&format!(
+                "Registered '{}' as a Fluorine portable instance. \
+                 Ready to launch from Fluorine.",
+                install_dir.display()
+            ));
+        }
+    }
+
+    report_steam_shortcut(install_dir, reporter);
+}
+
+/// Gallery entry for `install --from-gallery` and its .wabbajack URL.
+/// `query` is a machine name, or part of a title that matches one list.
+/// Falls back to the cached gallery offline or when it can't be fetched.
+async fn find_gallery_modlist(
+    query: &str,
+    offline: bool,
+) -> Result<(modlist::ModlistMetadata, String)> {
+    let mut browser = modlist::ModlistBrowser::new()?;
+    let fetched = !offline
+        && match browser.fetch_modlists().await {
+            Ok(_) => {
+                let _ = browser.save_cache();
+                true
+            }
+            Err(e) => {
+                eprintln!(
+                    "Could not fetch the modlist gallery ({:#}), using the cached copy{}",
+                    e,
+                    modlist::ModlistBrowser::cache_age_secs()
+                        .map(|age| format!(" (updated {})", modlist::format_age(age)))
+                        .unwrap_or_default()
+                );
+                false
+            }
+        };
+    if !fetched && !browser.load_cache()? {
+        anyhow::bail!("No cached modlist gallery. Open `clf3 browser` once while online");
+    }
+
+    let gallery = browser.modlists();
+    let machine_name = modlist::update::resolve_query(query, gallery, &[])?;
+    let metadata = gallery
+        .iter()
+        .find(|m| m.machine_name == machine_name)
+        .cloned()
+        .with_context(|| format!("Modlist '{}' is not in the gallery", machine_name))?;
+    let Some(url) = metadata.download_url().map(str::to_string) else {
+        anyhow::bail!(
+            "{} ({}) has no download link in the gallery{}",
+            metadata.title,
+            machine_name,
+            if metadata.force_down {
+                "; its author marked it down"
+            } else {
+                ""
+            }
+        );
+    };
+    Ok((metadata, url))
+}
+
+/// Installed Proton (10+) called `name`, by display or config name,
+/// ignoring case.
+fn find_proton(name: &str) -> Result<game_finder::SteamProton> {
+    let protons = game_finder::find_steam_protons();
+    if let Some(proton) = protons
+        .iter()
+        .find(|p| p.name.eq_ignore_ascii_case(name) || p.config_name.eq_ignore_ascii_case(name))
+    {
+        return Ok(proton.clone());
+    }
+    let installed: Vec<_> = protons.iter().map(|p| p.name.as_str()).collect();
+    anyhow::bail!(
+        "Proton '{}' not found. Installed Protons (10+): {}",
+        name,
+        if installed.is_empty() {
+            "none".to_string()
+        } else {
+            installed.join(", ")
+        }
+    )
+}
+
+/// `--setup-steam`: make the Steam shortcut that points into `install_dir`
+/// run with `proton`, restarting Steam so the change sticks and the
+/// shortcut is listed. The shortcut has to exist already; CLF3 doesn't add
+/// one itself.
+async fn run_steam_setup(
+    install_dir: &std::path::Path,
+    proton: &game_finder::SteamProton,
+    reporter: &dyn ProgressReporter,
+) -> Result<()> {
+    reporter.phase_start(installer::Phase::SteamSetup);
+    let install_dir = install_dir
+        .canonicalize()
+        .unwrap_or_else(|_| install_dir.to_path_buf());
+    let shortcut = game_finder::find_shortcut(&install_dir.to_string_lossy())
+        .with_context(|| {
+            format!(
+                "No Steam shortcut points into {}. Add ModOrganizer.exe to Steam as a \
+                 non-Steam game (NaK can do this), then run `clf3 finalize --proton`",
+                install_dir.display()
+            )
+        })?
+        .shortcut;
+
+    // Steam rewrites config.vdf on exit, so it has to be closed for the edit.
+    if game_finder::steam_is_running() {
+        reporter.log("Quitting Steam...");
+        tokio::task::spawn_blocking(|| game_finder::quit_steam(std::time::Duration::from_secs(30)))
+            .await??;
+    }
+    game_finder::set_shortcut_proton(shortcut.app_id, &proton.config_name)?;
+    reporter.log(&format!(
+        "Set '{}' (AppID {}) to run with {}",
+        shortcut.name, shortcut.app_id, proton.name
+    ));
+    game_finder::start_steam()?;
+    reporter.log("Started Steam. The shortcut is ready to launch from your library.");
+    Ok(())
+}
+
+/// Tell the user where to find the Steam shortcut for `install_dir`, if a
+/// launcher integration (e.g. NaK) added one. Reads shortcuts.vdf so the
+/// AppID and launch options reported are what Steam will actually use.
+fn report_steam_shortcut(install_dir: &std::path::Path, reporter: &dyn ProgressReporter) {
+    let install_dir = install_dir
+        .canonicalize()
+        .unwrap_or_else(|_| install_dir.to_path_buf());
+    let Some(found) = game_finder::find_shortcut(&install_dir.to_string_lossy()) else {
+        tracing::debug!("No Steam shortcut points into {}", install_dir.display());
+        return;
+    };
+    tracing::debug!("Found Steam shortcut in {}", found.vdf_path.display());
+    let shortcut = &found.shortcut;
+    reporter.log("\n=== Steam Shortcut ===");
+    reporter.log(&format!(
+        "Look for '{}' in your Steam library (AppID {})",
+        shortcut.name, shortcut.app_id
+    ));
+    if !shortcut.launch_options.is_empty() {
+        reporter.log(&format!("Launch options: {}", shortcut.launch_options));
+    }
+    reporter.log(&format!(
+        "Proton prefix: {}{}",
+        found.compatdata.display(),
+        if found.compatdata.exists() {
+            ""
+        } else {
+            " (created on first launch)"
+        }
+    ));
+    if game_finder::steam_is_running() {
+        reporter.log(
+            "Steam is running and may not have picked up the shortcut yet. If it isn't \
+             listed, quit Steam completely (Steam > Exit) and start it again.",
+        );
+    }
+}
+
+fn run_config_action(action: ConfigAction) -> Result<()> {
+    match action {
+        ConfigAction::Export {
+            output,
+            include_secrets,
+        } => {
+            let json = settings::Settings::load().export_json(include_secrets)?;
+            std::fs::write(&output, json)
+                .with_context(|| format!("Failed to write {}", output.display()))?;
+            println!("Exported settings to {}", output.display());
+            if include_secrets {
+                println!("The file contains your credentials; keep it private.");
+            }
+        }
+
+        ConfigAction::Import { input } => {
+            let content = std::fs::read_to_string(&input)
+                .with_context(|| format!("Failed to read {}", input.display()))?;
+            let imported = settings::Settings::from_export(&content)?;
+
+            let missing = imported.missing_paths();
+            if !missing.is_empty() {
+                println!("Warning: these paths don't exist on this machine:");
+                for (name, path) in &missing {
+                    println!("  {:<22} {}", name, path);
+                }
+                println!("Update them with the GUI settings or by re-importing.");
+            }
+
+            let mut settings = settings::Settings::load();
+            settings.apply_import(imported);
+            settings.save()?;
+            println!("Imported settings from {}", input.display());
+        }
+    }
+    Ok(())
+}
+
 async fn run_fluorine_action(action: FluorineAction) -> Result<()> {
     match action {
         FluorineAction::Status => {
@@ -1030,6 +2804,10 @@ async fn run_fluorine_action(action: FluorineAction) -> Result<()> {
                     "disabled"
                 }
             );
+            println!(
+                "Release:                {}",
+                pinned_fluorine_version(&settings).unwrap_or("latest")
+            );
             match fluorine::detect(override_path) {
                 Some(install) => println!(
                     "Detected:               {} (via {})",
@@ -1043,10 +2821,24 @@ async fn run_fluorine_action(action: FluorineAction) -> Result<()> {
             }
         }
 
-        FluorineAction::Install { dest } => {
-            println!("Downloading latest Fluorine release...");
-            let path = fluorine::download_latest(dest).await?;
-            println!("Installed Fluorine to: {}", path.display());
+        FluorineAction::Install { dest, version } => {
+            let mut settings = settings::Settings::load();
+            if let Some(version) = version {
+                settings.fluorine_version = version.trim().to_string();
+                settings.save()?;
+            }
+            let version = pinned_fluorine_version(&settings);
+            println!(
+                "Downloading Fluorine {}...",
+                version.unwrap_or("(latest release)")
+            );
+            let download = fluorine::download_release(dest, version).await?;
+            println!(
+                "Installed Fluorine {} (from {}) to: {}",
+                download.version,
+                download.source,
+                download.root.display()
+            );
         }
 
         FluorineAction::Register {
@@ -1119,15 +2911,36 @@ async fn ensure_fluorine_available() -> Result<fluorine::FluorineInstall> {
     if let Some(install) = fluorine::detect(override_path) {
         return Ok(install);
     }
-    tracing::info!("Fluorine not detected — downloading the latest release");
-    fluorine::download_latest(None).await?;
+    tracing::info!("Fluorine not detected — downloading it");
+    fluorine::download_release(None, pinned_fluorine_version(&settings)).await?;
     fluorine::detect(override_path)
         .ok_or_else(|| anyhow::anyhow!("Downloaded Fluorine but could not detect the binary"))
 }
 
+/// Fluorine release pinned in settings, or `None` for the latest.
+fn pinned_fluorine_version(settings: &settings::Settings) -> Option<&str> {
+    Some(settings.fluorine_version.as_str()).filter(|v| !v.is_empty())
+}
+
+/// Whether a `.wabbajack` argument is a URL rather than a local path.
+fn is_wabbajack_url(arg: &str) -> bool {
+    arg.starts_with("http://") || arg.starts_with("https://")
+}
+
 /// Download a .wabbajack file from a URL into the CLF3 cache, returning the
-/// resolved local path. Reuses the existing cache file if present.
-async fn fetch_wabbajack_from_url(url: &str, details_to_stderr: bool) -> Result<PathBuf> {
+/// resolved local path. Reuses the cached file unless it is broken or doesn't
+/// match `expected_hash` (the gallery's hash of the file, when known).
+///
+/// Wabbajack CDN URLs are fetched part by part, anything else as a plain
+/// (resumable) HTTP download. The file only lands in the cache once it has
+/// been checked to be a .wabbajack, so an interrupted or bogus download is
+/// never reused.
+async fn fetch_wabbajack_from_url(
+    url: &str,
+    expected_hash: Option<&str>,
+    details_to_stderr: bool,
+    offline: bool,
+) -> Result<PathBuf> {
     let detail = |message: String| {
         if details_to_stderr {
             eprintln!("{}", message);
@@ -1136,47 +2949,102 @@ async fn fetch_wabbajack_from_url(url: &str, details_to_stderr: bool) -> Result<
         }
     };
 
-    let cache_dir = dirs::cache_dir()
-        .unwrap_or_else(|| PathBuf::from("/tmp"))
-        .join("clf3")
-        .join("modlists");
+    let cache_dir =
+        paths::modlist_cache_dir().unwrap_or_else(|| PathBuf::from("/tmp/clf3/modlists"));
     std::fs::create_dir_all(&cache_dir)?;
 
     let filename = cache_filename_from_wabbajack_url(url);
     let dest = cache_dir.join(&filename);
 
-    let cached = std::fs::metadata(&dest).ok().filter(|m| m.len() > 0);
-    if let Some(meta) = cached {
-        detail(format!(
-            "Using cached .wabbajack file: {} ({} MiB)",
-            dest.display(),
-            meta.len() / (1024 * 1024)
-        ));
-    } else {
-        detail("Downloading .wabbajack file from URL...".to_string());
-        let cdn = downloaders::wabbajack_cdn::WabbajackCdnDownloader::new()?;
-        let pb = indicatif::ProgressBar::new(0);
-        pb.set_style(
-            indicatif::ProgressStyle::default_bar()
-                .template("{msg} [{bar:40}] {bytes}/{total_bytes}")
-                .expect("valid template")
-                .progress_chars("=> "),
+    if let Some(meta) = std::fs::metadata(&dest).ok().filter(|m| m.len() > 0) {
+        let usable = match modlist::check_wabbajack_file(&dest) {
+            Err(e) => {
+                detail(format!(
+                    "Cached .wabbajack file is unusable ({:#}), downloading it again",
+                    e
+                ));
+                false
+            }
+            Ok(()) => match expected_hash {
+                Some(expected) if hash::compute_file_hash(&dest)? != expected => {
+                    detail(
+                        "Cached .wabbajack file does not match the expected hash, \
+                         downloading it again"
+                            .to_string(),
+                    );
+                    false
+                }
+                _ => true,
+            },
+        };
+        if usable {
+            detail(format!(
+                "Using cached .wabbajack file: {} ({} MiB)",
+                dest.display(),
+                meta.len() / (1024 * 1024)
+            ));
+            return Ok(dest);
+        }
+        std::fs::remove_file(&dest)?;
+    }
+
+    if offline {
+        anyhow::bail!(
+            "Offline mode: {} has not been downloaded before. Pass a local .wabbajack file",
+            url
         );
-        pb.set_message("Downloading");
+    }
 
-        let pb_clone = pb.clone();
-        cdn.download_with_progress(url, &dest, 0, move |downloaded, total| {
-            if pb_clone.length() == Some(0) && total > 0 {
-                pb_clone.set_length(total);
-            }
-            pb_clone.set_position(downloaded);
-        })
-        .await?;
-        pb.finish_with_message("Downloaded");
+    detail("Downloading .wabbajack file from URL...".to_string());
+    let part = cache_dir.join(format!("{}.part", filename));
+    let pb = indicatif::ProgressBar::new(0);
+    pb.set_style(
+        indicatif::ProgressStyle::default_bar()
+            .template("{msg} [{bar:40}] {bytes}/{total_bytes}")
+            .expect("valid template")
+            .progress_chars("=> "),
+    );
+    pb.set_message("Downloading");
+
+    let pb_clone = pb.clone();
+    let on_progress = move |downloaded: u64, total: u64| {
+        if pb_clone.length() == Some(0) && total > 0 {
+            pb_clone.set_length(total);
+        }
+        pb_clone.set_position(downloaded);
+    };
+    if downloaders::WabbajackCdnDownloader::is_cdn_url(url) {
+        let cdn = downloaders::WabbajackCdnDownloader::new()?;
+        cdn.download_with_progress(url, &part, 0, on_progress)
+            .await?;
+    } else {
+        let client = downloaders::HttpClient::new()?;
+        let callback: downloaders::ProgressCallback =
+            Box::new(move |downloaded, total, _speed| on_progress(downloaded, total));
+        downloaders::download_file_with_callback(&client, url, &part, None, Some(&callback))
+            .await?;
+    }
+    pb.finish_with_message("Downloaded");
 
-        detail(format!("Saved to: {}", dest.display()));
+    if let Err(e) = modlist::check_wabbajack_file(&part) {
+        let _ = std::fs::remove_file(&part);
+        return Err(e.context(format!("{} did not return a .wabbajack file", url)));
+    }
+    if let Some(expected) = expected_hash {
+        let actual = hash::compute_file_hash(&part)?;
+        if actual != expected {
+            let _ = std::fs::remove_file(&part);
+            anyhow::bail!(
+                "Downloaded .wabbajack does not match the expected hash (expected {}, got {})",
+                expected,
+                actual
+            );
+        }
     }
+    std::fs::rename(&part, &dest)
+        .with_context(|| format!("Failed to move download to {}", dest.display()))?;
 
+    detail(format!("Saved to: {}", dest.display()));
     Ok(dest)
 }
 
@@ -1233,23 +3101,32 @@ fn derive_machine_name_from_settings(
 }
 
 /// Top-level dispatcher for `clf3 modlist <action>`.
-async fn run_modlist_action(action: ModlistAction) -> Result<()> {
+async fn run_modlist_action(action: ModlistAction, offline: bool) -> Result<()> {
     match action {
-        ModlistAction::Check { name, json } => run_modlist_check(name, json).await,
+        ModlistAction::Check { name, json } => run_modlist_check(name, json, offline).await,
+        ModlistAction::Update { .. } if offline => {
+            anyhow::bail!("`modlist update` downloads the new version and can't run offline")
+        }
         ModlistAction::Update { name, yes } => run_modlist_update(name, yes).await,
     }
 }
 
 /// `clf3 modlist check`: report which installs have a newer gallery version.
-async fn run_modlist_check(name: Option<String>, json: bool) -> Result<()> {
+async fn run_modlist_check(name: Option<String>, json: bool, offline: bool) -> Result<()> {
     let settings = settings::Settings::load();
     let installs = modlist::update::discover_installs(&settings);
 
     let mut browser = modlist::ModlistBrowser::new()?;
-    browser
-        .fetch_modlists()
-        .await
-        .context("Failed to fetch modlist gallery")?;
+    if offline {
+        if !browser.load_cache()? {
+            anyhow::bail!("Offline mode: no cached modlist gallery. Open the browser once online");
+        }
+    } else {
+        browser
+            .fetch_modlists()
+            .await
+            .context("Failed to fetch modlist gallery")?;
+    }
     let gallery = browser.modlists();
 
     let filtered: Vec<modlist::update::InstallRecord> = match &name {
@@ -1378,7 +3255,8 @@ async fn run_modlist_update(name: String, yes: bool) -> Result<()> {
         .ok_or_else(|| anyhow::anyhow!("Gallery entry for '{}' has no download URL", machine_name))?
         .to_string();
     println!("Fetching latest .wabbajack from {} ...", download_url);
-    let wabbajack_path = fetch_wabbajack_from_url(&download_url, false).await?;
+    let wabbajack_path =
+        fetch_wabbajack_from_url(&download_url, metadata.download_hash(), false, false).await?;
 
     // Resolve API keys + game dir like the normal install path.
     let nexus_oauth_token = std::env::var("NEXUS_OAUTH_TOKEN")
@@ -1396,9 +3274,14 @@ async fn run_modlist_update(name: String, yes: bool) -> Result<()> {
         )
     })?;
 
-    let game_dir = match auto_detect_game_dir(&wabbajack_path) {
+    // Same precedence as `clf3 install`: saved default, then auto-detect.
+    let game_dir = match settings
+        .default_game_path()
+        .map(|p| (p, "saved default"))
+        .or_else(|| auto_detect_game_dir(&wabbajack_path))
+    {
         Some((p, store)) => {
-            println!("Auto-detected game directory: {} ({})", p.display(), store);
+            println!("Game directory: {} ({})", p.display(), store);
             p
         }
         None => anyhow::bail!(
@@ -1410,6 +3293,14 @@ async fn run_modlist_update(name: String, yes: bool) -> Result<()> {
     let thread_count = std::thread::available_parallelism()
         .map(|n| n.get())
         .unwrap_or(4);
+    let metered = installer::metered::connection_is_metered(false, &settings);
+    if metered {
+        println!(
+            "Metered connection: downloading {} archives at a time at up to {}/s",
+            installer::metered::METERED_CONCURRENT_DOWNLOADS,
+            installer::progress_cli::format_bytes(installer::metered::METERED_SPEED_LIMIT)
+        );
+    }
 
     let cli_reporter = CliReporter::new(16, ProgressMode::Plain);
 
@@ -1420,13 +3311,22 @@ async fn run_modlist_update(name: String, yes: bool) -> Result<()> {
     };
 
     let config = InstallConfig {
-        wabbajack_path,
+        wabbajack_path: wabbajack_path.clone(),
         output_dir: install_dir.clone(),
         downloads_dir: downloads_dir.clone(),
         game_dir,
+        // Keep writing paths the way the previous install did.
+        wine_prefix: modlist::InstallManifest::load_from(&install_dir)
+            .ok()
+            .flatten()
+            .and_then(|m| m.wine_prefix),
         nexus_api_key: nexus_key,
         nexus_oauth_token,
-        max_concurrent_downloads: thread_count,
+        max_concurrent_downloads: if metered {
+            installer::metered::METERED_CONCURRENT_DOWNLOADS
+        } else {
+            thread_count
+        },
         max_install_workers: thread_count,
         max_parallel_bsa_archives: 1,
         max_parallel_7z_archives: thread_count,
@@ -1438,6 +3338,28 @@ async fn run_modlist_update(name: String, yes: bool) -> Result<()> {
         extract_strategy: installer::ExtractStrategy::Streaming,
         machine_name: Some(machine_name.clone()),
         wabbajack_url: Some(download_url),
+        mod_filter: installer::ModFilter::default(),
+        force_rehash: false,
+        verify_outputs: false,
+        mirrors: installer::MirrorMap::default(),
+        archive_store: None,
+        gpu_index: settings.gpu_index,
+        texture_encoder: settings.texture_encoder,
+        stall_timeout: downloaders::STALL_TIMEOUT,
+        max_download_speed: metered.then_some(installer::metered::METERED_SPEED_LIMIT),
+        retry: installer::RetryPolicy::default(),
+        on_hash_mismatch: installer::HashMismatchAction::Delete,
+        download_order: installer::DownloadOrder::Manifest,
+        nexus_cdn: downloaders::NexusCdn::parse(&settings.nexus_cdn),
+        purge_downloads: installer::PurgeDownloads::Keep,
+        // Updating the install the manifest points at, so it's ours.
+        force: true,
+        link_mode: installer::LinkMode::Copy,
+        last_phase: installer::InstallPhase::Mo2,
+        profile: None,
+        report_conflicts: false,
+        offline: false,
+        http_headers: downloaders::HostHeaders::new(&settings.http_headers),
     };
 
     let mut installer = Installer::new(config)?;
@@ -1462,6 +3384,14 @@ async fn run_modlist_update(name: String, yes: bool) -> Result<()> {
                 println!("   Note: {}", prompt);
             }
         }
+        let removed = stats.removed_source_count();
+        if removed > 0 {
+            println!(
+                "\n{} of these were removed or archived on Nexus by their authors. \
+                 Look for alternatives or ask the modlist author.",
+                removed
+            );
+        }
     }
 
     if !stats.failed_downloads.is_empty() {
@@ -1476,6 +3406,10 @@ async fn run_modlist_update(name: String, yes: bool) -> Result<()> {
         }
     }
 
+    if stats.archives_manual > 0 || stats.archives_failed > 0 {
+        println!("{}", open_downloads_hint(&wabbajack_path, &downloads_dir));
+    }
+
     if installation_succeeded {
         println!(
             "\nUpdate complete: '{}' is now at version {}.",
@@ -1493,6 +3427,55 @@ async fn run_modlist_update(name: String, yes: bool) -> Result<()> {
     Ok(())
 }
 
+/// When `err` is an install into another modlist's directory and we're on a
+/// terminal, show the conflict and ask whether to install over it anyway.
+fn confirm_install_over(err: &installer::InstallError) -> bool {
+    let Some(conflict @ installer::ConfigError::InstallDirInUse { .. }) =
+        err.inner().downcast_ref::<installer::ConfigError>()
+    else {
+        return false;
+    };
+    if !std::io::stdin().is_terminal() {
+        return false;
+    }
+    eprintln!("WARNING: existing installation\n{}", conflict);
+    eprint!("Install over it anyway? [y/N] ");
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer).is_ok()
+        && matches!(answer.trim(), "y" | "Y" | "yes" | "Yes")
+}
+
+/// Wait before `--auto-retry` attempt `attempt` (1-based): 30s, doubling
+/// each time, at most 10 minutes.
+fn auto_retry_delay(attempt: u32) -> std::time::Duration {
+    let secs = 30u64.saturating_mul(1 << attempt.saturating_sub(1).min(5));
+    std::time::Duration::from_secs(secs.min(600))
+}
+
+/// Ask on the terminal before downloading `size` over a metered connection.
+/// No terminal means no answer, which is a no.
+fn confirm_metered_download(size: &str) -> bool {
+    if !std::io::stdin().is_terminal() {
+        return false;
+    }
+    eprint!("Download {} over a metered connection? [y/N] ", size);
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer).is_ok()
+        && matches!(answer.trim(), "y" | "Y" | "yes" | "Yes")
+}
+
+/// Ask on the terminal before `clean` deletes anything. No terminal means
+/// no answer, which is a no.
+fn confirm_clean() -> bool {
+    if !std::io::stdin().is_terminal() {
+        return false;
+    }
+    eprint!("Delete these? [y/N] ");
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer).is_ok()
+        && matches!(answer.trim(), "y" | "Y" | "yes" | "Yes")
+}
+
 /// Try to auto-detect the game installation directory from the modlist's game type.
 ///
 /// Strategy:
@@ -1555,6 +3538,17 @@ fn auto_detect_game_dir(wabbajack_path: &std::path::Path) -> Option<(PathBuf, &'
             }
         }
     }
+    // Heroic installs with IDs not in KNOWN_GAMES (Epic, localized store
+    // variants) are matched by the files in their install directory.
+    let game_type = game_finder::GameType::from_wabbajack_name(&modlist.game_type);
+    if let Some(target) = game_type {
+        for hg in &heroic_games {
+            let seen = candidates.iter().any(|(p, _)| *p == hg.install_path);
+            if !seen && hg.game_type() == Some(target) {
+                candidates.push((hg.install_path.clone(), hg.launcher.display_name()));
+            }
+        }
+    }
 
     if candidates.is_empty() {
         tracing::warn!(
@@ -1572,6 +3566,13 @@ fn auto_detect_game_dir(wabbajack_path: &std::path::Path) -> Option<(PathBuf, &'
     let mut first_fallback: Option<(PathBuf, &'static str)> = None;
 
     for (path, store) in &candidates {
+        if let Some(game) = game_type {
+            if let Err(e) = game_finder::validate_game_path(game, path) {
+                tracing::warn!("Skipping {} install: {}", store, e);
+                continue;
+            }
+        }
+
         let report = check_game_files_from_modlist(&modlist, path);
 
         if report.total == 0 {
@@ -1639,6 +3640,25 @@ fn auto_detect_game_dir(wabbajack_path: &std::path::Path) -> Option<(PathBuf, &'
     first_fallback
 }
 
+/// Validate a user-supplied `--game` directory against the modlist's game
+/// type, returning the names of any official DLC that isn't installed.
+/// Unknown game types are left to the installer's own checks.
+fn validate_explicit_game_dir(
+    wabbajack_path: &std::path::Path,
+    game_dir: &std::path::Path,
+) -> Result<Vec<&'static str>> {
+    let modlist = modlist::parse_wabbajack_file(wabbajack_path)?;
+    let Some(game) = game_finder::GameType::from_wabbajack_name(&modlist.game_type) else {
+        return Ok(Vec::new());
+    };
+    game_finder::validate_game_path(game, game_dir)?;
+    Ok(game_finder::dlc_report(game, game_dir)
+        .into_iter()
+        .filter(|d| !d.present)
+        .map(|d| d.name)
+        .collect())
+}
+
 /// Simple percent-decoding for URL filenames (e.g. %20 -> space).
 fn urlencoded_decode(s: &str) -> String {
     let mut result = String::with_capacity(s.len());
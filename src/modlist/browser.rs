@@ -7,6 +7,8 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 
 const REPOSITORIES_URL: &str =
@@ -16,6 +18,66 @@ const FEATURED_URL: &str =
 const SEARCH_INDEX_URL: &str =
     "https://raw.githubusercontent.com/wabbajack-tools/mod-lists/refs/heads/master/reports/searchIndex.json";
 
+/// Age after which the cached gallery is fetched again: on startup, and by
+/// the GUI while it stays open.
+pub const CACHE_MAX_AGE_SECS: u64 = 60 * 60;
+
+/// Waits before each automatic retry of a gallery fetch that failed for a
+/// reason that may clear up.
+pub const FETCH_RETRY_DELAYS_SECS: [u64; 3] = [2, 5, 15];
+
+/// Why fetching the gallery failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchFailure {
+    /// No connection, DNS failure or timeout.
+    Connectivity,
+    /// The host answered 5xx.
+    Server,
+    /// GitHub is rate-limiting this address (HTTP 429).
+    RateLimited,
+    /// The host refused the request (HTTP 401/403).
+    Denied,
+    /// Any other HTTP error, or a reply that wasn't a gallery.
+    Invalid,
+}
+
+impl FetchFailure {
+    /// Classify an error from [`ModlistBrowser::fetch_modlists`].
+    pub fn of(err: &anyhow::Error) -> Self {
+        let Some(e) = err
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<reqwest::Error>())
+        else {
+            return Self::Invalid;
+        };
+        match e.status().map(|s| s.as_u16()) {
+            Some(429) => Self::RateLimited,
+            Some(401 | 403) => Self::Denied,
+            Some(500..=599) => Self::Server,
+            Some(_) => Self::Invalid,
+            None if e.is_decode() => Self::Invalid,
+            None => Self::Connectivity,
+        }
+    }
+
+    /// Worth retrying straight away. Rate limits and refusals aren't: more
+    /// requests only prolong them.
+    pub fn is_retryable(self) -> bool {
+        matches!(self, Self::Connectivity | Self::Server)
+    }
+
+    /// What went wrong, for the gallery banner.
+    pub fn message(self) -> &'static str {
+        match self {
+            Self::Connectivity => "Can't reach the modlist gallery (network problem)",
+            Self::Server => "The modlist gallery server had an error",
+            Self::RateLimited => "GitHub is rate-limiting gallery requests; try again later",
+            Self::Denied => "The modlist gallery host refused the request",
+            Self::Invalid => "The modlist gallery sent something unexpected",
+        }
+    }
+}
+
 /// Wabbajack's generated index of Mod Organizer mod names per modlist.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SearchIndex {
@@ -104,6 +166,9 @@ pub struct ModlistMetadata {
     pub download_metadata: Option<DownloadMetadata>,
     #[serde(default)]
     pub version: String,
+    /// ISO-8601 time of the last update, when the repository lists one.
+    #[serde(rename = "dateUpdated", default)]
+    pub date_updated: String,
     // Fields we populate ourselves (included in cache for quick loading)
     #[serde(default)]
     pub repository_name: String,
@@ -120,6 +185,14 @@ impl ModlistMetadata {
             .filter(|s| !s.is_empty())
     }
 
+    /// xxHash64 of the .wabbajack file (Wabbajack base64 form), if listed.
+    pub fn download_hash(&self) -> Option<&str> {
+        self.download_metadata
+            .as_ref()
+            .map(|d| d.hash.as_str())
+            .filter(|s| !s.is_empty())
+    }
+
     pub fn image_url(&self) -> Option<&str> {
         self.links
             .as_ref()
@@ -168,18 +241,76 @@ impl ModlistMetadata {
     }
 }
 
+/// Order of the gallery list.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModlistSort {
+    /// The order the repositories list them in.
+    #[default]
+    Gallery,
+    Name,
+    Author,
+    /// Smallest download first.
+    DownloadSize,
+    /// Smallest install first.
+    InstallSize,
+    /// Most recently updated first; lists without a date go last.
+    Updated,
+}
+
+impl ModlistSort {
+    pub const ALL: [ModlistSort; 6] = [
+        ModlistSort::Gallery,
+        ModlistSort::Name,
+        ModlistSort::Author,
+        ModlistSort::DownloadSize,
+        ModlistSort::InstallSize,
+        ModlistSort::Updated,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ModlistSort::Gallery => "Gallery order",
+            ModlistSort::Name => "Name",
+            ModlistSort::Author => "Author",
+            ModlistSort::DownloadSize => "Download size",
+            ModlistSort::InstallSize => "Install size",
+            ModlistSort::Updated => "Recently updated",
+        }
+    }
+
+    /// Sort in place. Stable, so ties keep gallery order.
+    pub fn apply(&self, modlists: &mut [ModlistMetadata]) {
+        match self {
+            ModlistSort::Gallery => {}
+            ModlistSort::Name => modlists.sort_by_cached_key(|m| m.title.to_lowercase()),
+            ModlistSort::Author => modlists.sort_by_cached_key(|m| m.author.to_lowercase()),
+            ModlistSort::DownloadSize => modlists.sort_by_key(|m| m.download_size()),
+            ModlistSort::InstallSize => modlists.sort_by_key(|m| m.installed_size()),
+            ModlistSort::Updated => modlists.sort_by(|a, b| {
+                a.date_updated
+                    .is_empty()
+                    .cmp(&b.date_updated.is_empty())
+                    .then_with(|| b.date_updated.cmp(&a.date_updated))
+            }),
+        }
+    }
+}
+
 /// Browser for fetching and searching modlists
 pub struct ModlistBrowser {
     client: Client,
     modlists: Vec<ModlistMetadata>,
     featured_names: Vec<String>,
+    /// Machine names loaded before the last fetch that it no longer has.
+    removed: Vec<String>,
 }
 
 impl ModlistBrowser {
     /// Create a new modlist browser
     pub fn new() -> Result<Self> {
         let client = Client::builder()
-            .user_agent(concat!("clf3/", env!("CARGO_PKG_VERSION")))
+            .user_agent(crate::downloaders::user_agent())
             .timeout(std::time::Duration::from_secs(30))
             .build()
             .context("Failed to create HTTP client")?;
@@ -188,6 +319,7 @@ impl ModlistBrowser {
             client,
             modlists: Vec::new(),
             featured_names: Vec::new(),
+            removed: Vec::new(),
         })
     }
 
@@ -201,7 +333,9 @@ impl ModlistBrowser {
             .get(REPOSITORIES_URL)
             .send()
             .await
-            .context("Failed to fetch repositories.json")?;
+            .context("Failed to fetch repositories.json")?
+            .error_for_status()
+            .context("repositories.json returned an error")?;
 
         let repos: HashMap<String, String> = response
             .json()
@@ -210,6 +344,11 @@ impl ModlistBrowser {
 
         info!("Found {} repositories", repos.len());
 
+        // Sorted, so the same list wins every time two repositories share a
+        // machine name.
+        let mut repos: Vec<_> = repos.into_iter().collect();
+        repos.sort();
+
         // Fetch modlists from each repository
         let mut all_modlists = Vec::new();
 
@@ -259,6 +398,18 @@ impl ModlistBrowser {
             available_count,
             unavailable_count
         );
+        for name in dedupe_machine_names(&mut all_modlists) {
+            warn!(
+                "Several gallery lists use the machine name '{}'; keeping the first",
+                name
+            );
+        }
+        // Whatever was loaded before (usually the cache) may name lists that
+        // have since been taken down; they're dropped with it.
+        self.removed = removed_names(&self.modlists, &all_modlists);
+        if !self.removed.is_empty() {
+            info!("No longer in the gallery: {}", self.removed.join(", "));
+        }
         self.modlists = all_modlists;
 
         // Fetch featured list
@@ -269,6 +420,42 @@ impl ModlistBrowser {
         Ok(&self.modlists)
     }
 
+    /// [`fetch_modlists`](Self::fetch_modlists), retried after each of
+    /// [`FETCH_RETRY_DELAYS_SECS`] while the failure
+    /// [is retryable](FetchFailure::is_retryable). `on_retry(retry, delay,
+    /// failure)` is called before each wait, `retry` counting from 1.
+    pub async fn fetch_modlists_with_retry(
+        &mut self,
+        mut on_retry: impl FnMut(usize, Duration, FetchFailure),
+    ) -> Result<&[ModlistMetadata]> {
+        let mut retry = 0;
+        loop {
+            let result = self.fetch_modlists().await.map(|_| ());
+            let err = match result {
+                Ok(()) => return Ok(&self.modlists),
+                Err(e) => e,
+            };
+            let failure = FetchFailure::of(&err);
+            let Some(&delay) = FETCH_RETRY_DELAYS_SECS.get(retry) else {
+                return Err(err);
+            };
+            if !failure.is_retryable() {
+                return Err(err);
+            }
+            retry += 1;
+            let delay = Duration::from_secs(delay);
+            warn!(
+                "Gallery fetch failed ({:#}); retry {}/{} in {}s",
+                err,
+                retry,
+                FETCH_RETRY_DELAYS_SECS.len(),
+                delay.as_secs()
+            );
+            on_retry(retry, delay, failure);
+            tokio::time::sleep(delay).await;
+        }
+    }
+
     async fn fetch_featured_names(&self) -> Result<Vec<String>> {
         let response = self
             .client
@@ -300,7 +487,7 @@ impl ModlistBrowser {
     }
 
     fn search_index_cache_path() -> Result<PathBuf> {
-        Ok(Self::cache_dir()?.join("searchIndex.json"))
+        Self::cache_file(crate::paths::search_index_file())
     }
 
     pub fn save_search_index_cache(index: &SearchIndex) -> Result<()> {
@@ -346,6 +533,12 @@ impl ModlistBrowser {
         &self.modlists
     }
 
+    /// Machine names of lists that were loaded (e.g. from the cache) before
+    /// the last `fetch_modlists` but aren't in the gallery anymore.
+    pub fn removed_modlists(&self) -> &[String] {
+        &self.removed
+    }
+
     pub fn games(&self) -> Vec<&str> {
         let mut games: Vec<&str> = self
             .modlists
@@ -364,16 +557,27 @@ impl ModlistBrowser {
         metadata: &ModlistMetadata,
         output_dir: &std::path::Path,
     ) -> Result<PathBuf> {
-        self.download_modlist_with_progress(metadata, output_dir, |_, _| {})
-            .await
+        self.download_modlist_with_progress(
+            metadata,
+            output_dir,
+            &CancellationToken::new(),
+            |_, _| {},
+        )
+        .await
     }
 
     /// Download a modlist .wabbajack file with progress callback
     /// Progress callback receives (bytes_downloaded, total_bytes)
+    ///
+    /// Cancelling `cancel` (e.g. from a dialog's Cancel/close) aborts the
+    /// in-flight part requests and deletes the partial file. The file is also
+    /// deleted on failure: it is pre-allocated to full size, so a leftover
+    /// would pass the size-based cache check next time.
     pub async fn download_modlist_with_progress<F>(
         &self,
         metadata: &ModlistMetadata,
         output_dir: &std::path::Path,
+        cancel: &CancellationToken,
         progress_callback: F,
     ) -> Result<PathBuf>
     where
@@ -420,11 +624,26 @@ impl ModlistBrowser {
 
         info!("Downloading {} to {:?}", metadata.title, output_path);
 
-        // Use the CDN downloader for chunked parallel downloads
+        // Use the CDN downloader for chunked parallel downloads. Dropping the
+        // download future on cancel drops every in-flight part request.
         let cdn_downloader = WabbajackCdnDownloader::new()?;
-        let bytes_downloaded = cdn_downloader
-            .download_with_progress(download_url, &output_path, expected_size, progress_callback)
-            .await?;
+        let result = tokio::select! {
+            biased;
+            _ = cancel.cancelled() => Err(anyhow::anyhow!("Download of {} cancelled", metadata.title)),
+            r = cdn_downloader.download_with_progress(
+                download_url,
+                &output_path,
+                expected_size,
+                progress_callback,
+            ) => r,
+        };
+        let bytes_downloaded = match result {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                let _ = std::fs::remove_file(&output_path);
+                return Err(e);
+            }
+        };
 
         info!("Downloaded {} ({} bytes)", metadata.title, bytes_downloaded);
 
@@ -443,19 +662,18 @@ impl ModlistBrowser {
             .find(|m| m.title.eq_ignore_ascii_case(title))
     }
 
-    /// Get the cache directory for modlist metadata
-    fn cache_dir() -> Result<PathBuf> {
-        let cache_dir = dirs::cache_dir()
-            .context("Could not determine cache directory")?
-            .join("clf3")
-            .join("modlists");
-        std::fs::create_dir_all(&cache_dir)?;
-        Ok(cache_dir)
+    /// Resolve a modlist metadata cache file, creating its directory
+    fn cache_file(path: Option<PathBuf>) -> Result<PathBuf> {
+        let path = path.context("Could not determine cache directory")?;
+        if let Some(cache_dir) = path.parent() {
+            std::fs::create_dir_all(cache_dir)?;
+        }
+        Ok(path)
     }
 
     /// Save current modlists to cache
     pub fn save_cache(&self) -> Result<()> {
-        let cache_path = Self::cache_dir()?.join("modlists.json");
+        let cache_path = Self::cache_file(crate::paths::modlist_cache_file())?;
         let json = serde_json::to_string(&self.modlists)?;
         std::fs::write(&cache_path, json)?;
         info!("Saved {} modlists to cache", self.modlists.len());
@@ -464,7 +682,7 @@ impl ModlistBrowser {
 
     /// Load modlists from cache (returns empty vec if no cache exists)
     pub fn load_cache(&mut self) -> Result<bool> {
-        let cache_path = Self::cache_dir()?.join("modlists.json");
+        let cache_path = Self::cache_file(crate::paths::modlist_cache_file())?;
         if !cache_path.exists() {
             return Ok(false);
         }
@@ -475,16 +693,15 @@ impl ModlistBrowser {
         Ok(true)
     }
 
-    /// Check if cache exists and is recent (less than 1 hour old)
+    /// Check if cache exists and is younger than `CACHE_MAX_AGE_SECS`
     pub fn has_recent_cache() -> bool {
-        if let Ok(cache_dir) = Self::cache_dir() {
-            let cache_path = cache_dir.join("modlists.json");
+        if let Ok(cache_path) = Self::cache_file(crate::paths::modlist_cache_file()) {
             if let Ok(metadata) = std::fs::metadata(&cache_path) {
                 if let Ok(modified) = metadata.modified() {
                     let age = std::time::SystemTime::now()
                         .duration_since(modified)
                         .unwrap_or_default();
-                    return age.as_secs() < 3600; // 1 hour
+                    return age.as_secs() < CACHE_MAX_AGE_SECS;
                 }
             }
         }
@@ -493,8 +710,7 @@ impl ModlistBrowser {
 
     /// Get cache age in seconds (or None if no cache)
     pub fn cache_age_secs() -> Option<u64> {
-        let cache_dir = Self::cache_dir().ok()?;
-        let cache_path = cache_dir.join("modlists.json");
+        let cache_path = Self::cache_file(crate::paths::modlist_cache_file()).ok()?;
         let metadata = std::fs::metadata(&cache_path).ok()?;
         let modified = metadata.modified().ok()?;
         let age = std::time::SystemTime::now().duration_since(modified).ok()?;
@@ -502,10 +718,61 @@ impl ModlistBrowser {
     }
 }
 
+/// Drop all but the first list for each machine name, which lookups by name
+/// couldn't tell apart. Returns the duplicated names.
+fn dedupe_machine_names(modlists: &mut Vec<ModlistMetadata>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut duplicates = Vec::new();
+    modlists.retain(|m| {
+        if m.machine_name.is_empty() || seen.insert(m.machine_name.clone()) {
+            return true;
+        }
+        if !duplicates.contains(&m.machine_name) {
+            duplicates.push(m.machine_name.clone());
+        }
+        false
+    });
+    duplicates
+}
+
+/// Machine names in `previous` that `current` doesn't have.
+fn removed_names(previous: &[ModlistMetadata], current: &[ModlistMetadata]) -> Vec<String> {
+    let current: HashSet<&str> = current.iter().map(|m| m.machine_name.as_str()).collect();
+    let mut removed: Vec<String> = previous
+        .iter()
+        .map(|m| m.machine_name.as_str())
+        .filter(|name| !name.is_empty() && !current.contains(name))
+        .map(str::to_string)
+        .collect();
+    removed.sort();
+    removed.dedup();
+    removed
+}
+
+/// Short "how long ago" for a cache age in seconds, e.g. `5 min ago`.
+pub fn format_age(secs: u64) -> String {
+    match secs {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{} min ago", secs / 60),
+        3600..=86_399 => format!("{} h ago", secs / 3600),
+        _ => format!("{} d ago", secs / 86_400),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn fetch_failures_are_classified() {
+        let parse = anyhow::anyhow!("expected value at line 1").context("Failed to parse");
+        assert_eq!(FetchFailure::of(&parse), FetchFailure::Invalid);
+        assert!(!FetchFailure::Invalid.is_retryable());
+        assert!(!FetchFailure::RateLimited.is_retryable());
+        assert!(FetchFailure::Connectivity.is_retryable());
+        assert!(FetchFailure::Server.is_retryable());
+    }
+
     #[test]
     fn search_index_applies_required_and_excluded_mods() {
         let index = SearchIndex {
@@ -523,6 +790,79 @@ mod tests {
         assert!(index.matches("unknown-list", &[], &["SkyUI".into()]));
     }
 
+    #[test]
+    fn modlist_sort_orders_lists() {
+        let list = |title: &str, size: u64, updated: &str| ModlistMetadata {
+            title: title.into(),
+            date_updated: updated.into(),
+            download_metadata: Some(DownloadMetadata {
+                size_of_archives: size,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let titles =
+            |lists: &[ModlistMetadata]| lists.iter().map(|m| m.title.clone()).collect::<Vec<_>>();
+        let mut lists = vec![
+            list("beta", 300, "2024-01-05T00:00:00Z"),
+            list("Alpha", 100, ""),
+            list("gamma", 200, "2025-03-01T00:00:00Z"),
+        ];
+
+        ModlistSort::Gallery.apply(&mut lists);
+        assert_eq!(titles(&lists), ["beta", "Alpha", "gamma"]);
+        ModlistSort::Name.apply(&mut lists);
+        assert_eq!(titles(&lists), ["Alpha", "beta", "gamma"]);
+        ModlistSort::DownloadSize.apply(&mut lists);
+        assert_eq!(titles(&lists), ["Alpha", "gamma", "beta"]);
+        ModlistSort::Updated.apply(&mut lists);
+        assert_eq!(titles(&lists), ["gamma", "beta", "Alpha"]);
+    }
+
+    #[test]
+    fn fresh_gallery_drops_duplicates_and_reports_removed_lists() {
+        let list = |name: &str, repo: &str| ModlistMetadata {
+            machine_name: name.into(),
+            repository_name: repo.into(),
+            ..Default::default()
+        };
+        let mut fresh = vec![list("a", "one"), list("b", "one"), list("a", "two")];
+        assert_eq!(dedupe_machine_names(&mut fresh), ["a"]);
+        assert_eq!(fresh.len(), 2);
+        assert_eq!(fresh[0].repository_name, "one");
+
+        let cached = vec![list("a", "one"), list("gone", "one"), list("", "one")];
+        assert_eq!(removed_names(&cached, &fresh), ["gone"]);
+        assert_eq!(format_age(30), "just now");
+        assert_eq!(format_age(7200), "2 h ago");
+    }
+
+    #[tokio::test]
+    async fn cancelled_download_removes_partial_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let metadata = ModlistMetadata {
+            title: "Test List".into(),
+            machine_name: "test-list".into(),
+            links: Some(ModlistLinks {
+                download: "https://example.invalid/test-list.wabbajack".into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let partial = dir.path().join("test-list.wabbajack");
+        std::fs::write(&partial, b"partial").unwrap();
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let browser = ModlistBrowser::new().unwrap();
+        let err = browser
+            .download_modlist_with_progress(&metadata, dir.path(), &cancel, |_, _| {})
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("cancelled"));
+        assert!(!partial.exists());
+    }
+
     #[tokio::test]
     #[ignore] // Requires network
     async fn test_fetch_modlists() {
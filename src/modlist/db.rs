@@ -11,8 +11,9 @@
 
 use anyhow::{Context, Result};
 use rusqlite::{params, Connection};
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
-use tracing::info;
+use tracing::{info, warn};
 
 use super::types::*;
 
@@ -170,6 +171,14 @@ impl ModlistDb {
             CREATE INDEX IF NOT EXISTS idx_archives_status ON archives(download_status);
             CREATE INDEX IF NOT EXISTS idx_archives_extraction ON archives(extraction_status);
 
+            -- Further modlist entries for an archive hash already in `archives`
+            CREATE TABLE IF NOT EXISTS archive_aliases (
+                hash TEXT NOT NULL,
+                name TEXT NOT NULL,
+                state_json TEXT NOT NULL,
+                PRIMARY KEY (hash, name)
+            );
+
             -- Directives
             CREATE TABLE IF NOT EXISTS directives (
                 id INTEGER PRIMARY KEY,
@@ -240,6 +249,7 @@ impl ModlistDb {
             .execute_batch(
                 "DELETE FROM directives;
              DELETE FROM archives;
+             DELETE FROM archive_aliases;
              DELETE FROM metadata;",
             )
             .context("Failed to clear database")?;
@@ -278,15 +288,29 @@ impl ModlistDb {
 
         info!("Importing {} archives", modlist.archives.len());
 
-        // Import archives
+        // Import archives. Entries repeating a hash are the same file, so
+        // only the first is downloaded; the rest become aliases (extra
+        // sources and file names for it).
+        tx.execute("DELETE FROM archive_aliases", [])?;
         {
             let mut stmt = tx.prepare(
                 "INSERT OR REPLACE INTO archives (hash, name, size, meta, state_json)
                  VALUES (?1, ?2, ?3, ?4, ?5)",
             )?;
+            let mut alias_stmt = tx.prepare(
+                "INSERT OR IGNORE INTO archive_aliases (hash, name, state_json)
+                 VALUES (?1, ?2, ?3)",
+            )?;
 
+            let mut seen = HashSet::new();
+            let mut aliases = 0usize;
             for archive in &modlist.archives {
                 let state_json = serde_json::to_string(&archive.state)?;
+                if !seen.insert(archive.hash.as_str()) {
+                    alias_stmt.execute(params![&archive.hash, &archive.name, &state_json])?;
+                    aliases += 1;
+                    continue;
+                }
                 stmt.execute(params![
                     &archive.hash,
                     &archive.name,
@@ -295,10 +319,34 @@ impl ModlistDb {
                     &state_json,
                 ])?;
             }
+            if aliases > 0 {
+                info!(
+                    "{} archives share a hash with an earlier one and will be downloaded once",
+                    aliases
+                );
+            }
         }
 
         info!("Importing {} directives", modlist.directives.len());
 
+        let no_match = modlist
+            .directives
+            .iter()
+            .filter(|d| matches!(d, Directive::NoMatch(_)))
+            .count();
+        if no_match > 0 {
+            info!(
+                "Skipping {} NoMatch/IgnoredDirectly directives (nothing to install)",
+                no_match
+            );
+        }
+        for (directive_type, count) in modlist.unsupported_directives() {
+            warn!(
+                "Unsupported directive: {} ({} occurrences) - these files will not be installed",
+                directive_type, count
+            );
+        }
+
         // Import directives
         {
             let mut stmt = tx.prepare(
@@ -306,7 +354,7 @@ impl ModlistDb {
                  VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
             )?;
 
-            for directive in &modlist.directives {
+            for directive in modlist.directives.iter().filter(|d| !d.is_skipped()) {
                 let data_json = serde_json::to_string(directive)?;
                 let archive_hash = Self::extract_archive_hash(directive);
 
@@ -335,6 +383,8 @@ impl ModlistDb {
             Directive::RemappedInlineFile(d) => &d.hash,
             Directive::TransformedTexture(d) => &d.hash,
             Directive::CreateBSA(d) => &d.hash,
+            Directive::NoMatch(d) => &d.hash,
+            Directive::Unsupported(_) => "",
         }
     }
 
@@ -822,6 +872,15 @@ pub struct ArchiveInfo {
     pub url_expires: Option<i64>,
 }
 
+/// Another modlist entry for an archive hash: same file, possibly under a
+/// different name and from a different source.
+#[derive(Debug, Clone)]
+pub struct ArchiveAlias {
+    pub hash: String,
+    pub name: String,
+    pub state_json: String,
+}
+
 /// Lightweight directive summary without full JSON deserialization.
 #[derive(Debug)]
 pub struct DirectiveSummary {
@@ -1061,6 +1120,54 @@ impl ModlistDb {
         Ok(results)
     }
 
+    /// Get the raw JSON of a single directive, regardless of status.
+    pub fn get_directive_data(&self, id: i64) -> Result<Option<String>> {
+        let mut stmt = self
+            .conn
+            .prepare_cached("SELECT data_json FROM directives WHERE id = ?1")?;
+
+        match stmt.query_row([id], |row| row.get(0)) {
+            Ok(json) => Ok(Some(json)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e).context("Failed to query directive data"),
+        }
+    }
+
+    /// Delete directives by id (used by partial installs). Returns rows removed.
+    pub fn delete_directives(&mut self, ids: &[i64]) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut removed = 0;
+        {
+            let mut stmt = tx.prepare("DELETE FROM directives WHERE id = ?1")?;
+            for id in ids {
+                removed += stmt.execute([id])?;
+            }
+        }
+        tx.commit()?;
+        Ok(removed)
+    }
+
+    /// Aliases of each archive hash that has any, in modlist order.
+    pub fn get_archive_aliases(&self) -> Result<HashMap<String, Vec<ArchiveAlias>>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT hash, name, state_json FROM archive_aliases ORDER BY rowid")?;
+        let rows = stmt.query_map([], |row| {
+            Ok(ArchiveAlias {
+                hash: row.get(0)?,
+                name: row.get(1)?,
+                state_json: row.get(2)?,
+            })
+        })?;
+
+        let mut aliases: HashMap<String, Vec<ArchiveAlias>> = HashMap::new();
+        for alias in rows {
+            let alias = alias?;
+            aliases.entry(alias.hash.clone()).or_default().push(alias);
+        }
+        Ok(aliases)
+    }
+
     pub fn get_archives_by_hashes(&self, hashes: &[String]) -> Result<Vec<ArchiveInfo>> {
         if hashes.is_empty() {
             return Ok(Vec::new());
@@ -1118,4 +1225,82 @@ mod tests {
         let value = db.get_metadata("test_key").unwrap();
         assert_eq!(value, Some("test_value".to_string()));
     }
+
+    #[test]
+    fn test_import_routes_meta_and_unknown_directives() {
+        let json = r#"{
+            "Name": "Test", "Version": "1.0", "WabbajackVersion": "3.0",
+            "GameType": "SkyrimSpecialEdition", "IsNSFW": false, "Archives": [],
+            "Directives": [
+                {"$type": "PropertyFile, Wabbajack.Lib", "To": "banner.png", "Hash": "a",
+                 "Size": 4, "SourceDataID": "00000000-0000-0000-0000-000000000001",
+                 "Type": "Banner"},
+                {"$type": "ArchiveMeta, Wabbajack.Lib", "To": "mods/A/meta.ini", "Hash": "b",
+                 "Size": 2, "SourceDataID": "00000000-0000-0000-0000-000000000002"},
+                {"$type": "NoMatch, Wabbajack.Lib", "To": "mods/A/stray.txt",
+                 "Hash": "c", "Size": 1, "Reason": "No Match in Stack"},
+                {"$type": "MergedPatch, Wabbajack.Lib", "To": "mods/B/x.esp"},
+                {"$type": "MergedPatch, Wabbajack.Lib", "To": "mods/B/y.esp"}
+            ]
+        }"#;
+        let modlist: Modlist = serde_json::from_str(json).unwrap();
+        assert!(matches!(modlist.directives[0], Directive::InlineFile(_)));
+        assert!(matches!(modlist.directives[1], Directive::InlineFile(_)));
+        assert!(matches!(modlist.directives[2], Directive::NoMatch(_)));
+        assert_eq!(
+            modlist.unsupported_directives().get("MergedPatch"),
+            Some(&2)
+        );
+
+        let mut db = ModlistDb::in_memory().unwrap();
+        db.import_modlist(&modlist).unwrap();
+        let counts = db.get_directive_type_counts().unwrap();
+        assert_eq!(counts, vec![("InlineFile".to_string(), 2)]);
+    }
+
+    #[test]
+    fn test_import_keeps_one_archive_per_hash() {
+        let json = r#"{
+            "Name": "Test", "Version": "1.0", "WabbajackVersion": "3.0",
+            "GameType": "SkyrimSpecialEdition", "IsNSFW": false, "Directives": [],
+            "Archives": [
+                {"Hash": "h1", "Meta": "", "Name": "A.7z", "Size": 3,
+                 "State": {"$type": "HttpDownloader, Wabbajack.Lib", "Url": "https://a.test/A.7z"}},
+                {"Hash": "h2", "Meta": "", "Name": "B.7z", "Size": 5,
+                 "State": {"$type": "HttpDownloader, Wabbajack.Lib", "Url": "https://b.test/B.7z"}},
+                {"Hash": "h1", "Meta": "", "Name": "A copy.7z", "Size": 3,
+                 "State": {"$type": "HttpDownloader, Wabbajack.Lib", "Url": "https://c.test/A.7z"}}
+            ]
+        }"#;
+        let modlist: Modlist = serde_json::from_str(json).unwrap();
+        let mut db = ModlistDb::in_memory().unwrap();
+        db.import_modlist(&modlist).unwrap();
+
+        let archives = db
+            .get_archives_by_hashes(&["h1".to_string(), "h2".to_string()])
+            .unwrap();
+        assert_eq!(archives.len(), 2);
+        let a = archives.iter().find(|a| a.hash == "h1").unwrap();
+        assert_eq!(a.name, "A.7z");
+
+        let aliases = db.get_archive_aliases().unwrap();
+        assert_eq!(aliases.len(), 1);
+        assert_eq!(aliases["h1"].len(), 1);
+        assert_eq!(aliases["h1"][0].name, "A copy.7z");
+        assert!(aliases["h1"][0].state_json.contains("c.test"));
+
+        // Re-importing doesn't pile up aliases.
+        db.import_modlist(&modlist).unwrap();
+        assert_eq!(db.get_archive_aliases().unwrap()["h1"].len(), 1);
+    }
+
+    #[test]
+    fn test_malformed_known_directive_is_an_error() {
+        let json = r#"{
+            "Name": "Test", "Version": "1.0", "WabbajackVersion": "3.0",
+            "GameType": "SkyrimSpecialEdition", "IsNSFW": false, "Archives": [],
+            "Directives": [{"$type": "FromArchive, Wabbajack.Lib", "To": "a.esp"}]
+        }"#;
+        assert!(serde_json::from_str::<Modlist>(json).is_err());
+    }
 }
@@ -18,6 +18,7 @@
 //!   "name": "Tuxborn",
 //!   "installed_version": "1.2.3",
 //!   "wabbajack_url": "https://.../Tuxborn.wabbajack_xxx",
+//!   "wabbajack_path": "/home/u/wj/Tuxborn.wabbajack",
 //!   "installed_at": "2026-05-15T12:34:56Z",
 //!   "downloads_dir": "/home/u/wj/downloads",
 //!   "output_dir": "/home/u/wj/tuxborn"
@@ -63,6 +64,16 @@ pub struct InstallManifest {
     #[serde(default)]
     pub wabbajack_url: Option<String>,
 
+    /// Local .wabbajack file the install was built from, so `clf3 check` can
+    /// re-read the expected output hashes. `None` for older manifests.
+    #[serde(default)]
+    pub wabbajack_path: Option<PathBuf>,
+
+    /// Wine prefix the install's paths were written for (`--wine-prefix`),
+    /// so updates keep using it. `None` for the default `Z:` paths.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wine_prefix: Option<PathBuf>,
+
     /// RFC3339 timestamp of when the install finished.
     pub installed_at: String,
 
@@ -92,6 +103,8 @@ impl InstallManifest {
             name: name.into(),
             installed_version: installed_version.into(),
             wabbajack_url,
+            wabbajack_path: None,
+            wine_prefix: None,
             installed_at: chrono::Utc::now().to_rfc3339(),
             downloads_dir,
             output_dir,
@@ -117,6 +130,15 @@ impl InstallManifest {
         Ok(Some(manifest))
     }
 
+    /// Whether this manifest records the given modlist. Gallery machine names
+    /// are compared when both sides have one; otherwise the display names.
+    pub fn is_same_modlist(&self, machine_name: &str, name: &str) -> bool {
+        if !self.machine_name.is_empty() && !machine_name.is_empty() {
+            return self.machine_name == machine_name;
+        }
+        self.name.trim().eq_ignore_ascii_case(name.trim())
+    }
+
     /// Write this manifest to its install directory, replacing any prior copy.
     pub fn save_to(&self, install_dir: &Path) -> Result<()> {
         let path = Self::path_in(install_dir);
@@ -248,6 +270,23 @@ mod tests {
         std::fs::write(dir.path().join(MANIFEST_FILENAME), json).unwrap();
         let loaded = InstallManifest::load_from(dir.path()).unwrap().unwrap();
         assert!(loaded.wabbajack_url.is_none());
+        assert!(loaded.wabbajack_path.is_none());
+    }
+
+    #[test]
+    fn same_modlist_prefers_machine_name() {
+        let manifest = InstallManifest::new(
+            "tuxborn",
+            "Tuxborn",
+            "1.2.3",
+            None,
+            PathBuf::from("/d"),
+            PathBuf::from("/o"),
+        );
+        assert!(manifest.is_same_modlist("tuxborn", "Renamed"));
+        assert!(!manifest.is_same_modlist("lorerim", "Tuxborn"));
+        assert!(manifest.is_same_modlist("", "tuxborn "));
+        assert!(!manifest.is_same_modlist("", "LoreRim"));
     }
 
     #[test]
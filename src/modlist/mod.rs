@@ -10,6 +10,7 @@ mod db;
 pub mod install_manifest;
 mod types;
 pub mod update;
+pub mod validate;
 
 #[allow(unused_imports)] // Used by lib crate (GUI)
 pub use browser::*;
@@ -84,6 +85,30 @@ pub fn parse_wabbajack_file(path: &Path) -> Result<Modlist> {
     Ok(modlist)
 }
 
+/// Check that `path` looks like a .wabbajack file (a ZIP with a `modlist`
+/// entry) without parsing it. Catches HTML error pages and other files a
+/// URL can hand back instead of the modlist.
+pub fn check_wabbajack_file(path: &Path) -> Result<()> {
+    let mut head = Vec::with_capacity(512);
+    File::open(path)
+        .with_context(|| format!("Failed to open: {}", path.display()))?
+        .take(512)
+        .read_to_end(&mut head)?;
+    if !head.starts_with(b"PK") {
+        let text = String::from_utf8_lossy(&head).to_lowercase();
+        if text.contains("<html") || text.contains("<!doctype") {
+            anyhow::bail!("Got a web page instead of a .wabbajack file");
+        }
+        anyhow::bail!("Not a .wabbajack file (not a ZIP archive)");
+    }
+    let file = File::open(path)?;
+    let archive = ZipArchive::new(BufReader::new(file)).context("Failed to read as ZIP archive")?;
+    if archive.index_for_name("modlist").is_none() {
+        anyhow::bail!("Not a .wabbajack file (ZIP archive without a 'modlist' entry)");
+    }
+    Ok(())
+}
+
 /// Open a .wabbajack file and import into a database
 /// If the database exists but is for a different wabbajack file, it will be cleared and re-imported
 pub fn import_wabbajack_to_db(wabbajack_path: &Path, db_path: &Path) -> Result<ModlistDb> {
@@ -127,6 +152,16 @@ pub fn import_wabbajack_to_db(wabbajack_path: &Path, db_path: &Path) -> Result<M
         info!("Importing modlist to database...");
         let modlist = parse_wabbajack_file(wabbajack_path)?;
 
+        let report = validate::validate_wabbajack(wabbajack_path, &modlist)?;
+        if !report.is_ok() {
+            anyhow::bail!(
+                "{} is malformed ({} problems); nothing was downloaded:\n{}",
+                wabbajack_path.display(),
+                report.problems.len(),
+                report.render().trim_end()
+            );
+        }
+
         db.import_modlist(&modlist)?;
 
         // Store fingerprint and path for future checks
@@ -145,9 +180,45 @@ pub fn import_wabbajack_to_db(wabbajack_path: &Path, db_path: &Path) -> Result<M
     Ok(db)
 }
 
+/// Whether `db` was imported from `wabbajack_path` as it is now, i.e. the
+/// next install would reuse it rather than start over.
+pub fn db_matches_wabbajack(db: &ModlistDb, wabbajack_path: &Path) -> Result<bool> {
+    let stored_fingerprint = db.get_metadata("wabbajack_fingerprint")?;
+    let stored_path = db.get_metadata("wabbajack_path")?;
+    Ok(
+        stored_path.as_deref() == Some(wabbajack_path.to_string_lossy().as_ref())
+            && stored_fingerprint == Some(calculate_file_fingerprint(wabbajack_path)?),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_check_wabbajack_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let write_zip = |name: &str, entry: &str| {
+            let path = dir.path().join(name);
+            let mut zip = zip::ZipWriter::new(File::create(&path).unwrap());
+            zip.start_file(entry, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(b"{}").unwrap();
+            zip.finish().unwrap();
+            path
+        };
+
+        assert!(check_wabbajack_file(&write_zip("list.wabbajack", "modlist")).is_ok());
+
+        let err = check_wabbajack_file(&write_zip("other.zip", "readme.txt")).unwrap_err();
+        assert!(err.to_string().contains("'modlist'"));
+
+        let html = dir.path().join("page.wabbajack");
+        fs::write(&html, "<!DOCTYPE html><html><body>Not found</body></html>").unwrap();
+        let err = check_wabbajack_file(&html).unwrap_err();
+        assert!(err.to_string().contains("web page"));
+    }
 
     // Test with actual Tuxborn file if available
     #[test]
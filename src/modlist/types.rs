@@ -8,7 +8,8 @@
 // Some methods not yet used until installation pipeline is built
 #![allow(dead_code)]
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
 
 /// Root modlist structure
 #[derive(Debug, Serialize, Deserialize)]
@@ -31,9 +32,23 @@ pub struct Modlist {
     #[serde(rename = "IsNSFW")]
     pub is_nsfw: bool,
     pub archives: Vec<Archive>,
+    #[serde(deserialize_with = "deserialize_directives")]
     pub directives: Vec<Directive>,
 }
 
+impl Modlist {
+    /// Directive types CLF3 can't install, with occurrence counts.
+    pub fn unsupported_directives(&self) -> BTreeMap<&str, usize> {
+        let mut counts = BTreeMap::new();
+        for directive in &self.directives {
+            if let Directive::Unsupported(d) = directive {
+                *counts.entry(d.directive_type.as_str()).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+}
+
 /// Archive (download) definition
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
@@ -118,6 +133,10 @@ pub struct HttpState {
     pub url: String,
     #[serde(default)]
     pub headers: Vec<String>,
+    /// Alternate URLs tried in order when `url` fails. Not part of the
+    /// upstream Wabbajack format; modlists that carry it get mirror fallback.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mirrors: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -172,6 +191,12 @@ pub enum Directive {
     PatchedFromArchive(PatchedFromArchiveDirective),
     #[serde(alias = "InlineFile, Compression.BSA")]
     #[serde(alias = "InlineFile, Wabbajack.Lib")]
+    // Banner/readme property files and archive .meta files are inline data
+    // with extra fields we don't need; they install like any inline file.
+    #[serde(alias = "PropertyFile")]
+    #[serde(alias = "PropertyFile, Wabbajack.Lib")]
+    #[serde(alias = "ArchiveMeta")]
+    #[serde(alias = "ArchiveMeta, Wabbajack.Lib")]
     InlineFile(InlineFileDirective),
     #[serde(alias = "RemappedInlineFile, Compression.BSA")]
     #[serde(alias = "RemappedInlineFile, Wabbajack.Lib")]
@@ -182,6 +207,84 @@ pub enum Directive {
     #[serde(alias = "CreateBSA, Compression.BSA")]
     #[serde(alias = "CreateBSA, Wabbajack.Lib")]
     CreateBSA(CreateBSADirective),
+    #[serde(alias = "NoMatch, Wabbajack.Lib")]
+    #[serde(alias = "IgnoredDirectly")]
+    #[serde(alias = "IgnoredDirectly, Wabbajack.Lib")]
+    NoMatch(NoMatchDirective),
+    /// A `$type` this version doesn't know. Never stored in the database.
+    #[serde(skip)]
+    Unsupported(UnsupportedDirective),
+}
+
+/// Directive type names (without the `, Assembly` suffix) that
+/// `Directive` deserializes. Anything else becomes `Directive::Unsupported`.
+const KNOWN_DIRECTIVE_TYPES: &[&str] = &[
+    "FromArchive",
+    "PatchedFromArchive",
+    "InlineFile",
+    "RemappedInlineFile",
+    "TransformedTexture",
+    "CreateBSA",
+    "PropertyFile",
+    "ArchiveMeta",
+    "NoMatch",
+    "IgnoredDirectly",
+];
+
+/// Deserialize the directive list, turning unknown `$type`s into
+/// `Directive::Unsupported` instead of failing the whole modlist. Known
+/// types still report their real parse errors.
+fn deserialize_directives<'de, D>(deserializer: D) -> Result<Vec<Directive>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct DirectivesVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for DirectivesVisitor {
+        type Value = Vec<Directive>;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("a list of directives")
+        }
+
+        // One element at a time, so only a single directive is ever
+        // buffered as a `Value`.
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: serde::de::SeqAccess<'de>,
+        {
+            use serde::de::Error;
+
+            let mut directives = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(value) = seq.next_element::<serde_json::Value>()? {
+                let type_name = value
+                    .get("$type")
+                    .and_then(|t| t.as_str())
+                    .unwrap_or_default();
+                let short_name = type_name.split(',').next().unwrap_or_default().trim();
+                if KNOWN_DIRECTIVE_TYPES.contains(&short_name) {
+                    directives.push(serde_json::from_value(value).map_err(A::Error::custom)?);
+                } else {
+                    let directive_type = if short_name.is_empty() {
+                        "(missing $type)"
+                    } else {
+                        short_name
+                    };
+                    directives.push(Directive::Unsupported(UnsupportedDirective {
+                        directive_type: directive_type.to_string(),
+                        to: value
+                            .get("To")
+                            .and_then(|t| t.as_str())
+                            .unwrap_or_default()
+                            .to_string(),
+                    }));
+                }
+            }
+            Ok(directives)
+        }
+    }
+
+    deserializer.deserialize_seq(DirectivesVisitor)
 }
 
 impl Directive {
@@ -194,6 +297,8 @@ impl Directive {
             Directive::RemappedInlineFile(d) => &d.to,
             Directive::TransformedTexture(d) => &d.to,
             Directive::CreateBSA(d) => &d.to,
+            Directive::NoMatch(d) => &d.to,
+            Directive::Unsupported(d) => &d.to,
         }
     }
 
@@ -206,6 +311,8 @@ impl Directive {
             Directive::RemappedInlineFile(d) => d.size,
             Directive::TransformedTexture(d) => d.size,
             Directive::CreateBSA(d) => d.file_states.iter().map(|f| f.size()).sum(),
+            Directive::NoMatch(d) => d.size,
+            Directive::Unsupported(_) => 0,
         }
     }
 
@@ -218,8 +325,16 @@ impl Directive {
             Directive::RemappedInlineFile(_) => "RemappedInlineFile",
             Directive::TransformedTexture(_) => "TransformedTexture",
             Directive::CreateBSA(_) => "CreateBSA",
+            Directive::NoMatch(_) => "NoMatch",
+            Directive::Unsupported(_) => "Unsupported",
         }
     }
+
+    /// True for directives that write nothing: files the compiler couldn't
+    /// match and types CLF3 doesn't support.
+    pub fn is_skipped(&self) -> bool {
+        matches!(self, Directive::NoMatch(_) | Directive::Unsupported(_))
+    }
 }
 
 /// Extract file directly from an archive
@@ -268,6 +383,28 @@ pub struct RemappedInlineFileDirective {
     pub source_data_id: uuid::Uuid,
 }
 
+/// File the modlist compiler couldn't match to any source (`NoMatch`) or
+/// was told to ignore (`IgnoredDirectly`). Nothing to install.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct NoMatchDirective {
+    pub to: String,
+    #[serde(default)]
+    pub hash: String,
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default)]
+    pub reason: String,
+}
+
+/// Directive of a type this version can't install.
+#[derive(Debug)]
+pub struct UnsupportedDirective {
+    /// `$type` without the assembly suffix, e.g. "MergedPatch".
+    pub directive_type: String,
+    pub to: String,
+}
+
 /// Texture that needs transformation (resize/recompress)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
@@ -0,0 +1,224 @@
+//! Structural checks of a parsed modlist, run before anything is downloaded.
+//!
+//! A truncated or hand-edited .wabbajack can parse fine and still reference
+//! archives it doesn't list, sources with no URL, or inline data that isn't
+//! in the zip. Those otherwise surface hours later as failed directives;
+//! here they're collected into one report up front.
+
+use super::types::{Directive, DownloadState, Modlist};
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::fmt;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use zip::ZipArchive;
+
+/// Problems listed by `ValidationReport::render` before "... and N more".
+const RENDER_LIMIT: usize = 50;
+
+/// One structural problem in a modlist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// A directive sources an archive hash that isn't in the archive list.
+    UnknownArchive { to: String, archive_hash: String },
+    /// A directive that reads from an archive doesn't say which file.
+    MissingArchivePath { to: String },
+    /// Inline file or patch data the directive needs isn't in the .wabbajack.
+    MissingEntry { to: String, entry: String },
+    /// An archive has no usable download source.
+    UnresolvableSource { archive: String, reason: String },
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::UnknownArchive { to, archive_hash } => {
+                write!(
+                    f,
+                    "{}: source archive {} is not in the archive list",
+                    to, archive_hash
+                )
+            }
+            Problem::MissingArchivePath { to } => {
+                write!(f, "{}: no path inside the source archive", to)
+            }
+            Problem::MissingEntry { to, entry } => {
+                write!(f, "{}: data {} is missing from the .wabbajack", to, entry)
+            }
+            Problem::UnresolvableSource { archive, reason } => {
+                write!(f, "archive {}: {}", archive, reason)
+            }
+        }
+    }
+}
+
+/// Result of `validate`.
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    pub problems: Vec<Problem>,
+}
+
+impl ValidationReport {
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// One problem per line, capped so a badly broken file stays readable.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for problem in self.problems.iter().take(RENDER_LIMIT) {
+            out.push_str(&format!("  - {}\n", problem));
+        }
+        if self.problems.len() > RENDER_LIMIT {
+            out.push_str(&format!(
+                "  ... and {} more\n",
+                self.problems.len() - RENDER_LIMIT
+            ));
+        }
+        out
+    }
+}
+
+/// Validate `modlist` against the entries of the .wabbajack it came from.
+pub fn validate_wabbajack(wabbajack_path: &Path, modlist: &Modlist) -> Result<ValidationReport> {
+    let file = File::open(wabbajack_path)
+        .with_context(|| format!("Failed to open: {}", wabbajack_path.display()))?;
+    let archive = ZipArchive::new(BufReader::new(file)).context("Failed to read as ZIP archive")?;
+    let entries: HashSet<&str> = archive.file_names().collect();
+    Ok(validate(modlist, &entries))
+}
+
+/// Check that every directive's archive and inline data exist and every
+/// archive has a download source. `entries` are the .wabbajack's zip entries.
+pub fn validate(modlist: &Modlist, entries: &HashSet<&str>) -> ValidationReport {
+    let mut problems = Vec::new();
+
+    for archive in &modlist.archives {
+        if let Some(reason) = unresolvable_reason(&archive.state) {
+            problems.push(Problem::UnresolvableSource {
+                archive: archive.name.clone(),
+                reason: reason.to_string(),
+            });
+        }
+    }
+
+    let archive_hashes: HashSet<&str> = modlist.archives.iter().map(|a| a.hash.as_str()).collect();
+    for directive in &modlist.directives {
+        let to = directive.to_path();
+        let (archive_hash_path, entry) = match directive {
+            Directive::FromArchive(d) => (Some(&d.archive_hash_path), None),
+            Directive::TransformedTexture(d) => (Some(&d.archive_hash_path), None),
+            Directive::PatchedFromArchive(d) => (Some(&d.archive_hash_path), Some(d.patch_id)),
+            Directive::InlineFile(d) => (None, Some(d.source_data_id)),
+            Directive::RemappedInlineFile(d) => (None, Some(d.source_data_id)),
+            Directive::CreateBSA(_) | Directive::NoMatch(_) | Directive::Unsupported(_) => {
+                (None, None)
+            }
+        };
+
+        if let Some(hash_path) = archive_hash_path {
+            match hash_path.first() {
+                Some(hash) if !archive_hashes.contains(hash.as_str()) => {
+                    problems.push(Problem::UnknownArchive {
+                        to: to.to_string(),
+                        archive_hash: hash.clone(),
+                    });
+                }
+                _ if hash_path.len() < 2 => {
+                    problems.push(Problem::MissingArchivePath { to: to.to_string() });
+                }
+                _ => {}
+            }
+        }
+        if let Some(entry) = entry.map(|id| id.to_string()) {
+            if !entries.contains(entry.as_str()) {
+                problems.push(Problem::MissingEntry {
+                    to: to.to_string(),
+                    entry,
+                });
+            }
+        }
+    }
+
+    ValidationReport { problems }
+}
+
+/// Why `state` can't be downloaded, if it can't.
+fn unresolvable_reason(state: &DownloadState) -> Option<&'static str> {
+    let blank = |s: &str| s.trim().is_empty();
+    match state {
+        DownloadState::Nexus(s) if blank(&s.game_name) || s.mod_id == 0 || s.file_id == 0 => {
+            Some("Nexus source without game, mod ID or file ID")
+        }
+        DownloadState::Http(s) if reqwest::Url::parse(s.url.trim()).is_err() => {
+            Some("HTTP source without a valid URL")
+        }
+        DownloadState::GoogleDrive(s) if blank(&s.id) => {
+            Some("Google Drive source without a file ID")
+        }
+        DownloadState::Mega(s) if blank(&s.url) => Some("MEGA source without a URL"),
+        DownloadState::MediaFire(s) if blank(&s.url) => Some("MediaFire source without a URL"),
+        DownloadState::Manual(s) if blank(&s.url) => Some("manual download without a URL"),
+        DownloadState::WabbajackCDN(s) if blank(&s.url) => {
+            Some("Wabbajack CDN source without a URL")
+        }
+        DownloadState::GameFileSource(s) if blank(&s.game_file) => {
+            Some("game file source without a file path")
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_dangling_references_and_bad_sources() {
+        let modlist: Modlist = serde_json::from_str(
+            r#"{
+                "Name": "Test", "Version": "1.0", "WabbajackVersion": "3.0",
+                "GameType": "SkyrimSpecialEdition", "IsNSFW": false,
+                "Archives": [
+                    {"Hash": "AAA=", "Meta": "", "Name": "good.7z", "Size": 1,
+                     "State": {"$type": "HttpDownloader, Wabbajack.Lib", "Url": "https://example.com/good.7z"}},
+                    {"Hash": "BBB=", "Meta": "", "Name": "bad.7z", "Size": 1,
+                     "State": {"$type": "HttpDownloader, Wabbajack.Lib", "Url": ""}}
+                ],
+                "Directives": [
+                    {"$type": "FromArchive", "To": "ok.esp", "Hash": "x", "Size": 1,
+                     "ArchiveHashPath": ["AAA=", "ok.esp"]},
+                    {"$type": "FromArchive", "To": "dangling.esp", "Hash": "x", "Size": 1,
+                     "ArchiveHashPath": ["CCC=", "dangling.esp"]},
+                    {"$type": "InlineFile", "To": "inline.ini", "Hash": "x", "Size": 1,
+                     "SourceDataID": "6f1c5a52-3c1e-4a7b-9a5c-0d2c4b1e8f00"},
+                    {"$type": "InlineFile", "To": "missing.ini", "Hash": "x", "Size": 1,
+                     "SourceDataID": "00000000-0000-0000-0000-000000000001"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let entries = HashSet::from(["modlist", "6f1c5a52-3c1e-4a7b-9a5c-0d2c4b1e8f00"]);
+
+        let report = validate(&modlist, &entries);
+        assert_eq!(
+            report.problems,
+            vec![
+                Problem::UnresolvableSource {
+                    archive: "bad.7z".into(),
+                    reason: "HTTP source without a valid URL".into(),
+                },
+                Problem::UnknownArchive {
+                    to: "dangling.esp".into(),
+                    archive_hash: "CCC=".into(),
+                },
+                Problem::MissingEntry {
+                    to: "missing.ini".into(),
+                    entry: "00000000-0000-0000-0000-000000000001".into(),
+                },
+            ]
+        );
+        assert!(report.render().contains("dangling.esp"));
+    }
+}
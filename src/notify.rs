@@ -0,0 +1,97 @@
+//! Desktop notifications when an install finishes
+//!
+//! Long installs run in the background while the user does something else.
+//! A notification (freedesktop notification service on Linux, the native
+//! notification centre elsewhere, via `notify-rust`) tells them when it's
+//! done, failed, or is waiting on manual downloads. Turned off with the
+//! `disable_notifications` setting.
+//!
+//! Sending is best-effort: a missing notification daemon is logged and
+//! otherwise ignored.
+
+use tracing::debug;
+
+/// How an install ended, for the notification text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstallOutcome {
+    /// Everything installed.
+    Complete,
+    /// Stopped on purpose (`--phase`, `--only-downloads`) without errors.
+    Stopped,
+    /// Archives the user has to download in a browser before re-running.
+    NeedsManualDownloads(usize),
+    /// Finished, but some archives or directives failed.
+    Incomplete { failed: usize },
+    /// The install aborted with this error.
+    Failed(String),
+}
+
+impl InstallOutcome {
+    fn summary(&self, modlist: &str) -> String {
+        match self {
+            InstallOutcome::Complete => format!("{} installed", modlist),
+            InstallOutcome::Stopped => format!("{} paused", modlist),
+            InstallOutcome::NeedsManualDownloads(_) => {
+                format!("{} needs manual downloads", modlist)
+            }
+            InstallOutcome::Incomplete { .. } => format!("{} finished with errors", modlist),
+            InstallOutcome::Failed(_) => format!("{} install failed", modlist),
+        }
+    }
+
+    fn body(&self) -> String {
+        match self {
+            InstallOutcome::Complete => "The modlist is ready to play.".to_string(),
+            InstallOutcome::Stopped => "Stopped after the requested phase.".to_string(),
+            InstallOutcome::NeedsManualDownloads(n) => format!(
+                "{} file(s) must be downloaded in a browser. See CLF3 for the list, \
+                 then run the install again.",
+                n
+            ),
+            InstallOutcome::Incomplete { failed } => format!(
+                "{} item(s) failed. Check the install summary and log.",
+                failed
+            ),
+            InstallOutcome::Failed(error) => error.clone(),
+        }
+    }
+
+    fn is_error(&self) -> bool {
+        matches!(
+            self,
+            InstallOutcome::Incomplete { .. } | InstallOutcome::Failed(_)
+        )
+    }
+}
+
+/// Show a desktop notification for a finished install.
+pub fn install_finished(modlist: &str, outcome: &InstallOutcome) {
+    let icon = if outcome.is_error() {
+        "dialog-error"
+    } else {
+        "dialog-information"
+    };
+    let result = notify_rust::Notification::new()
+        .appname("CLF3")
+        .summary(&outcome.summary(modlist))
+        .body(&outcome.body())
+        .icon(icon)
+        .show();
+    if let Err(e) = result {
+        debug!("Desktop notification not shown: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outcome_text_names_modlist() {
+        let outcome = InstallOutcome::NeedsManualDownloads(3);
+        assert_eq!(outcome.summary("Tuxborn"), "Tuxborn needs manual downloads");
+        assert!(outcome.body().starts_with("3 file(s)"));
+        assert!(!outcome.is_error());
+        assert!(InstallOutcome::Failed("disk full".into()).is_error());
+    }
+}
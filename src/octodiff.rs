@@ -53,6 +53,13 @@ struct Header {
     end_marker: [u8; 3],
 }
 
+/// The basis ran out before a Copy command was satisfied: it isn't the file
+/// the delta was built against. Returned inside the `UnexpectedEof`
+/// `io::Error` from [`DeltaReader`]'s `read`.
+#[derive(Debug, thiserror::Error)]
+#[error("basis file ended early")]
+pub struct BasisTooShort;
+
 /// A delta command
 #[derive(Debug)]
 enum Command {
@@ -179,7 +186,7 @@ impl<B: Read + Seek, D: Read + Seek> Read for DeltaReader<B, D> {
                     if n == 0 {
                         return Err(std::io::Error::new(
                             std::io::ErrorKind::UnexpectedEof,
-                            "basis file ended early",
+                            BasisTooShort,
                         ));
                     }
                     *remaining -= n;
@@ -232,6 +239,26 @@ mod tests {
         assert_eq!(header.hash.len(), 20);
     }
 
+    #[test]
+    fn short_basis_is_reported_as_basis_too_short() {
+        let mut delta = Vec::new();
+        delta.extend_from_slice(MAGIC);
+        delta.push(0x01);
+        delta.push(4);
+        delta.extend_from_slice(b"SHA1");
+        delta.extend_from_slice(&20i32.to_le_bytes());
+        delta.extend_from_slice(&[0u8; 20]);
+        delta.extend_from_slice(END_MARKER);
+        delta.push(CMD_COPY);
+        delta.extend_from_slice(&0i64.to_le_bytes());
+        delta.extend_from_slice(&8i64.to_le_bytes());
+
+        let mut reader = DeltaReader::new(Cursor::new(b"abc"), Cursor::new(delta)).unwrap();
+        let err = std::io::copy(&mut reader, &mut std::io::sink()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+        assert!(err.get_ref().is_some_and(|e| e.is::<BasisTooShort>()));
+    }
+
     #[test]
     #[ignore] // Run with: cargo test -- --ignored
     fn test_real_tuxborn_patch() {
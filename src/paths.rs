@@ -7,10 +7,14 @@
 //! - Preserving intended case for output paths
 //! - Unicode normalization (NFC) for consistent path matching
 //! - CP437 to UTF-8 conversion for legacy Windows archives
+//!
+//! It also owns CLF3's own directory layout (config, cache, data, logs) so
+//! nothing else recomputes those locations.
 
 // Module not yet integrated into main installation pipeline
 #![allow(dead_code)]
 
+use std::ffi::OsString;
 use std::path::{Path, PathBuf};
 use unicode_normalization::UnicodeNormalization;
 
@@ -220,6 +224,154 @@ pub fn join_windows_path(base: &Path, relative: &str) -> PathBuf {
     base.join(to_linux_path(relative))
 }
 
+/// Forward-slash form of `path` without a trailing separator.
+fn placeholder_base(path: &Path) -> String {
+    path.to_string_lossy()
+        .replace('\\', "/")
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Install-time values for Wabbajack's path placeholders.
+///
+/// Compiled config files (`ModOrganizer.ini`, tool INIs) replace the author's
+/// folders with `{--||<KIND>_MAGIC_<STYLE>||--}` tokens: `KIND` is
+/// `MO2_PATH`, `GAME_PATH` or `DOWNLOAD_PATH`, `STYLE` is `BACK` (`\`),
+/// `DOUBLE_BACK` (`\\`, INI-escaped) or `FORWARD` (`/`). Paths are written
+/// as Wine `Z:` paths so MO2 running under Proton can open them; with a
+/// Wine prefix set, paths inside its `drive_c` are written as `C:` paths.
+#[derive(Debug, Clone)]
+pub struct PathPlaceholders {
+    /// Forward-slash paths without a trailing separator.
+    install: String,
+    downloads: String,
+    game: String,
+    /// The prefix's `drive_c`, in the same form.
+    drive_c: Option<String>,
+}
+
+impl PathPlaceholders {
+    pub fn new(install_dir: &Path, downloads_dir: &Path, game_dir: &Path) -> Self {
+        Self {
+            install: placeholder_base(install_dir),
+            downloads: placeholder_base(downloads_dir),
+            game: placeholder_base(game_dir),
+            drive_c: None,
+        }
+    }
+
+    /// Write paths inside `prefix`'s `drive_c` as `C:` paths, the way MO2
+    /// running in that prefix sees them.
+    pub fn with_wine_prefix(mut self, prefix: &Path) -> Self {
+        let drive_c = prefix.join("drive_c");
+        self.drive_c = Some(drive_c.to_string_lossy().trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Windows path in the given separator style: `C:` under the prefix's
+    /// `drive_c`, `Z:` (Wine's mapping of `/`) anywhere else.
+    fn styled(&self, base: &str, separator: &str) -> String {
+        let in_drive_c = self.drive_c.as_deref().and_then(|drive_c| {
+            base.strip_prefix(drive_c)
+                .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+        });
+        match in_drive_c {
+            Some("") => format!("C:{}", separator),
+            Some(rest) => format!("C:{}", rest.replace('/', separator)),
+            None => format!("Z:{}", base.replace('/', separator)),
+        }
+    }
+
+    /// `path` as MO2 writes it in `ModOrganizer.ini` (`Z:/home/...`).
+    pub fn windows_path(&self, path: &Path) -> String {
+        self.styled(&placeholder_base(path), "/")
+    }
+
+    /// Linux path for a `Z:` path, or a `C:` path when a Wine prefix is set.
+    /// Other drives don't exist here.
+    pub fn linux_path(&self, windows: &str) -> Option<PathBuf> {
+        let windows = windows.replace('\\', "/");
+        let drive = windows.get(..2)?;
+        let rest = &windows[2..];
+        if !rest.is_empty() && !rest.starts_with('/') {
+            return None;
+        }
+        if drive.eq_ignore_ascii_case("Z:") {
+            Some(PathBuf::from(if rest.is_empty() { "/" } else { rest }))
+        } else if drive.eq_ignore_ascii_case("C:") {
+            let drive_c = self.drive_c.as_deref()?;
+            Some(PathBuf::from(format!("{}{}", drive_c, rest)))
+        } else {
+            None
+        }
+    }
+
+    /// Expand every placeholder in `content`. Text without placeholders is
+    /// returned unchanged, line endings included.
+    pub fn expand(&self, content: &str) -> String {
+        if !content.contains("{--||")
+            && !content.contains("[Game Folder Files]")
+            && !content.contains("[MO2_PATH]")
+            && !content.contains("[DOWNLOADS_PATH]")
+            && !content.contains("download_directory=")
+        {
+            return content.to_string();
+        }
+
+        let mut result = content.to_string();
+        // Wabbajack writes DOWNLOAD_PATH; DOWNLOADS_PATH is accepted for
+        // lists built by older tools.
+        let kinds = [
+            ("MO2_PATH", &self.install),
+            ("GAME_PATH", &self.game),
+            ("DOWNLOAD_PATH", &self.downloads),
+            ("DOWNLOADS_PATH", &self.downloads),
+        ];
+        let styles = [("FORWARD", "/"), ("DOUBLE_BACK", "\\\\"), ("BACK", "\\")];
+        for (kind, base) in kinds {
+            for (style, separator) in styles {
+                let token = format!("{{--||{}_MAGIC_{}||--}}", kind, style);
+                if result.contains(&token) {
+                    result = result.replace(&token, &self.styled(base, separator));
+                }
+            }
+        }
+
+        // Legacy placeholders (older Wabbajack format)
+        result = result.replace("[Game Folder Files]", &self.styled(&self.game, "\\"));
+        result = result.replace("[MO2_PATH]", &self.styled(&self.install, "\\"));
+        result = result.replace("[DOWNLOADS_PATH]", &self.styled(&self.downloads, "\\"));
+
+        // Some lists hardcode the author's download_directory=X:/... instead
+        // of a token. MO2's INI format wants it double-backslash escaped.
+        let downloads = self.styled(&self.downloads, "\\\\");
+        let mut out = String::with_capacity(result.len());
+        for line in result.split_inclusive('\n') {
+            let body = line.trim_end_matches(['\r', '\n']);
+            match body.strip_prefix("download_directory=") {
+                Some(value) if value.as_bytes().get(1) == Some(&b':') => {
+                    out.push_str("download_directory=");
+                    out.push_str(&downloads);
+                    out.push_str(&line[body.len()..]);
+                }
+                _ => out.push_str(line),
+            }
+        }
+        out
+    }
+}
+
+/// The Wine prefix at `path`: the path itself, or its `pfx` subdir when
+/// given a Proton `compatdata/<appid>` dir.
+pub fn wine_prefix_root(path: &Path) -> PathBuf {
+    let proton_pfx = path.join("pfx");
+    if !path.join("drive_c").is_dir() && proton_pfx.join("drive_c").is_dir() {
+        proton_pfx
+    } else {
+        path.to_path_buf()
+    }
+}
+
 /// Thread-safe directory creation cache.
 ///
 /// Tracks which directories have already been created so we can skip
@@ -345,10 +497,170 @@ impl DirCache {
     }
 }
 
+// ============================================================================
+// CLF3 directory layout
+// ============================================================================
+//
+// Each base dir follows the platform convention (XDG on Linux) with a `clf3`
+// subdirectory, and can be moved as a whole with an env var.
+
+/// `$<override_var>` if set, otherwise `<platform_dir>/clf3`.
+fn app_dir(override_dir: Option<OsString>, platform_dir: Option<PathBuf>) -> Option<PathBuf> {
+    match override_dir.filter(|dir| !dir.is_empty()) {
+        Some(dir) => Some(PathBuf::from(dir)),
+        None => platform_dir.map(|dir| dir.join("clf3")),
+    }
+}
+
+/// Settings and per-modlist config: `$CLF3_CONFIG_HOME`, else
+/// `$XDG_CONFIG_HOME/clf3` (`~/.config/clf3`).
+pub fn config_dir() -> Option<PathBuf> {
+    app_dir(std::env::var_os("CLF3_CONFIG_HOME"), dirs::config_dir())
+}
+
+/// Disposable caches (gallery, images, install databases):
+/// `$CLF3_CACHE_HOME`, else `$XDG_CACHE_HOME/clf3` (`~/.cache/clf3`).
+pub fn cache_dir() -> Option<PathBuf> {
+    app_dir(std::env::var_os("CLF3_CACHE_HOME"), dirs::cache_dir())
+}
+
+/// Downloaded helper tools: `$CLF3_DATA_HOME`, else `$XDG_DATA_HOME/clf3`
+/// (`~/.local/share/clf3`).
+pub fn data_dir() -> Option<PathBuf> {
+    app_dir(std::env::var_os("CLF3_DATA_HOME"), dirs::data_dir())
+}
+
+/// Session logs: `$CLF3_STATE_HOME/logs`, else `$XDG_STATE_HOME/clf3/logs`
+/// (`~/.local/state/clf3/logs`; the local data dir where there is no state dir).
+pub fn logs_dir() -> Option<PathBuf> {
+    app_dir(
+        std::env::var_os("CLF3_STATE_HOME"),
+        dirs::state_dir().or_else(dirs::data_local_dir),
+    )
+    .map(|dir| dir.join("logs"))
+}
+
+/// `settings.json`.
+pub fn settings_file() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("settings.json"))
+}
+
+/// SQLite store of per-modlist install choices.
+pub fn modlist_configs_db() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("modlist_configs.db"))
+}
+
+/// Downloaded `.wabbajack` files and gallery metadata.
+pub fn modlist_cache_dir() -> Option<PathBuf> {
+    cache_dir().map(|dir| dir.join("modlists"))
+}
+
+/// Cached gallery listing (`modlists.json`).
+pub fn modlist_cache_file() -> Option<PathBuf> {
+    modlist_cache_dir().map(|dir| dir.join("modlists.json"))
+}
+
+/// Cached gallery mod search index.
+pub fn search_index_file() -> Option<PathBuf> {
+    modlist_cache_dir().map(|dir| dir.join("searchIndex.json"))
+}
+
+/// Gallery thumbnails.
+pub fn image_cache_dir() -> Option<PathBuf> {
+    cache_dir().map(|dir| dir.join("images"))
+}
+
+/// Cache of extracted 7z/RAR entries, shared across installs.
+pub fn extraction_cache_db() -> Option<PathBuf> {
+    cache_dir().map(|dir| dir.join("extraction_cache.db"))
+}
+
+/// Install state database of one modlist.
+pub fn modlist_db(modlist_name: &str) -> Option<PathBuf> {
+    cache_dir().map(|dir| dir.join(format!("{}.db", modlist_name)))
+}
+
+/// Helper binaries CLF3 downloads (TTW installer).
+pub fn bin_dir() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("bin"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_app_dir_override() {
+        let platform = Some(PathBuf::from("/home/u/.config"));
+        assert_eq!(
+            app_dir(None, platform.clone()),
+            Some(PathBuf::from("/home/u/.config/clf3"))
+        );
+        assert_eq!(
+            app_dir(Some("/srv/clf3".into()), platform.clone()),
+            Some(PathBuf::from("/srv/clf3"))
+        );
+        assert_eq!(
+            app_dir(Some("".into()), platform),
+            Some(PathBuf::from("/home/u/.config/clf3"))
+        );
+        assert_eq!(app_dir(None, None), None);
+    }
+
+    #[test]
+    fn test_expand_mo2_ini_placeholders() {
+        let placeholders = PathPlaceholders::new(
+            Path::new("/home/u/Modlists/Tuxborn/"),
+            Path::new("/home/u/Downloads"),
+            Path::new("/home/u/.steam/steamapps/common/Skyrim Special Edition"),
+        );
+        let ini = "[General]\r\n\
+            gamePath=@ByteArray({--||GAME_PATH_MAGIC_DOUBLE_BACK||--})\r\n\
+            [Settings]\r\n\
+            download_directory=E:/Wabbajack/downloads\r\n\
+            base_directory={--||MO2_PATH_MAGIC_FORWARD||--}\r\n\
+            [customExecutables]\r\n\
+            1\\binary={--||MO2_PATH_MAGIC_BACK||--}\\tools\\xEdit.exe\r\n\
+            2\\arguments=-o:{--||DOWNLOAD_PATH_MAGIC_BACK||--}\r\n";
+
+        let expanded = placeholders.expand(ini);
+        assert_eq!(
+            expanded,
+            "[General]\r\n\
+             gamePath=@ByteArray(Z:\\\\home\\\\u\\\\.steam\\\\steamapps\\\\common\\\\Skyrim Special Edition)\r\n\
+             [Settings]\r\n\
+             download_directory=Z:\\\\home\\\\u\\\\Downloads\r\n\
+             base_directory=Z:/home/u/Modlists/Tuxborn\r\n\
+             [customExecutables]\r\n\
+             1\\binary=Z:\\home\\u\\Modlists\\Tuxborn\\tools\\xEdit.exe\r\n\
+             2\\arguments=-o:Z:\\home\\u\\Downloads\r\n"
+        );
+
+        let plain = "no tokens here\n";
+        assert_eq!(placeholders.expand(plain), plain);
+    }
+
+    #[test]
+    fn test_expand_inside_wine_prefix() {
+        let prefix = Path::new("/home/u/prefixes/skyrim");
+        let placeholders = PathPlaceholders::new(
+            &prefix.join("drive_c/Modlists/Tuxborn"),
+            Path::new("/mnt/data/downloads"),
+            &prefix.join("drive_c/Games/Skyrim Special Edition"),
+        )
+        .with_wine_prefix(prefix);
+        let ini = "gamePath=@ByteArray({--||GAME_PATH_MAGIC_DOUBLE_BACK||--})\r\n\
+            base_directory={--||MO2_PATH_MAGIC_FORWARD||--}\r\n\
+            download_directory={--||DOWNLOAD_PATH_MAGIC_DOUBLE_BACK||--}\r\n";
+
+        assert_eq!(
+            placeholders.expand(ini),
+            "gamePath=@ByteArray(C:\\\\Games\\\\Skyrim Special Edition)\r\n\
+             base_directory=C:/Modlists/Tuxborn\r\n\
+             download_directory=Z:\\\\mnt\\\\data\\\\downloads\r\n"
+        );
+    }
+
     #[test]
     fn test_to_linux_path() {
         assert_eq!(
@@ -1,10 +1,26 @@
 //! Settings management for CLF3
 //!
-//! Stores user preferences in ~/.config/clf3/settings.json
+//! Stores user preferences in ~/.config/clf3/settings.json (see
+//! `paths::settings_file` for overrides)
+//!
+//! The same file backs the GUI and the CLI. `default_downloads_dir`,
+//! `default_install_dir` and `default_game_dir` fill in `clf3 install`
+//! arguments that weren't given; precedence is command line > environment
+//! (`CLF3_DOWNLOADS_DIR`, `CLF3_OUTPUT_DIR`, `CLF3_GAME_DIR`,
+//! `NEXUS_API_KEY`) > this file.
+//!
+//! The GUI reads the same variables through `apply_env_fallbacks`, but only
+//! for fields this file leaves empty, and never writes them back to disk.
+//!
+//! `clf3 config export`/`import` move settings between machines. Exports
+//! leave out credentials unless asked, and the install records, which only
+//! describe this machine.
 
 // Used by lib crate
 #![allow(dead_code)]
 
+use crate::modlist::browser::ModlistSort;
+use crate::textures::EncoderMode;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -61,6 +77,10 @@ pub struct Settings {
     #[serde(default)]
     pub default_downloads_dir: String,
 
+    /// Default game directory (empty = auto-detect from the modlist)
+    #[serde(default)]
+    pub default_game_dir: String,
+
     /// Nexus Mods API key
     #[serde(default)]
     pub nexus_api_key: String,
@@ -73,6 +93,15 @@ pub struct Settings {
     #[serde(default)]
     pub gpu_name: String,
 
+    /// Texture encoding device: auto, cpu or gpu
+    #[serde(default)]
+    pub texture_encoder: EncoderMode,
+
+    /// Nexus download server: empty for Nexus' default, "fastest", or a
+    /// server name such as "Amsterdam" (Premium only)
+    #[serde(default)]
+    pub nexus_cdn: String,
+
     /// Path to TTW (Tale of Two Wastelands) MPI installer binary
     #[serde(default)]
     pub ttw_installer_path: String,
@@ -117,6 +146,18 @@ pub struct Settings {
     #[serde(default)]
     pub browser_show_installed_only: bool,
 
+    /// Last state of the browser favorites-only filter.
+    #[serde(default)]
+    pub browser_show_favorites_only: bool,
+
+    /// Last selected browser sort order.
+    #[serde(default)]
+    pub browser_sort: ModlistSort,
+
+    /// Starred modlists by machine name, listed first in the browser.
+    #[serde(default)]
+    pub browser_favorite_modlists: Vec<String>,
+
     /// Last selected online modlist machine name.
     #[serde(default)]
     pub browser_last_selected_modlist: Option<String>,
@@ -142,21 +183,65 @@ pub struct Settings {
     /// ~/.local/share/fluorine-manager for auto-downloads.
     #[serde(default)]
     pub fluorine_path: String,
+
+    /// Fluorine release tag to download (e.g. `v0.2.0`). Empty = latest.
+    #[serde(default)]
+    pub fluorine_version: String,
+
+    /// `User-Agent` for CLF3's own HTTP requests. Empty = `clf3/<version>`.
+    #[serde(default)]
+    pub user_agent: String,
+
+    /// Extra headers for plain HTTP sources behind a login, by host:
+    /// `{"files.example.com": ["Cookie: session=...", "Referer: ..."]}`.
+    /// Also sent to subdomains of the host.
+    #[serde(default)]
+    pub http_headers: HashMap<String, Vec<String>>,
+
+    /// Steam install to use when both a native and a Flatpak Steam exist:
+    /// "native", "flatpak", or empty to pick the running / last used one.
+    #[serde(default)]
+    pub steam_install: String,
+
+    /// Don't show a desktop notification when an install finishes.
+    #[serde(default)]
+    pub disable_notifications: bool,
+
+    /// Treat the connection as metered (see `clf3 install --metered`): ask
+    /// before downloading and keep downloads slow and few.
+    #[serde(default)]
+    pub metered_connection: bool,
+
+    /// Also treat the connection as metered whenever NetworkManager says so.
+    #[serde(default)]
+    pub detect_metered_connection: bool,
+
+    /// Values filled in by `apply_env_fallbacks`, as (index into
+    /// `ENV_FALLBACKS`, value). `save` leaves them out unless edited.
+    #[serde(skip)]
+    env_values: Vec<(usize, String)>,
 }
 
+type FieldRef = fn(&mut Settings) -> &mut String;
+
+/// Environment variables the GUI falls back to, and the field each fills.
+/// `CLF3_OUTPUT_DIR` is the CLI's name for the install directory.
+const ENV_FALLBACKS: &[(&str, FieldRef)] = &[
+    ("NEXUS_API_KEY", |s| &mut s.nexus_api_key),
+    ("CLF3_DOWNLOADS_DIR", |s| &mut s.default_downloads_dir),
+    ("CLF3_INSTALL_DIR", |s| &mut s.default_install_dir),
+    ("CLF3_OUTPUT_DIR", |s| &mut s.default_install_dir),
+];
+
 impl Settings {
     /// Get the config directory path (~/.config/clf3)
     fn config_dir() -> Result<PathBuf> {
-        let config_dir = dirs::config_dir()
-            .context("Could not determine config directory")?
-            .join("clf3");
-
-        Ok(config_dir)
+        crate::paths::config_dir().context("Could not determine config directory")
     }
 
     /// Get the settings file path
     pub fn settings_path() -> Result<PathBuf> {
-        Ok(Self::config_dir()?.join("settings.json"))
+        crate::paths::settings_file().context("Could not determine config directory")
     }
 
     /// Load settings from disk, or return defaults if not found
@@ -194,13 +279,141 @@ impl Settings {
             .with_context(|| format!("Failed to create {:?}", config_dir))?;
 
         let path = Self::settings_path()?;
-        let content = serde_json::to_string_pretty(self).context("Failed to serialize settings")?;
+        let content = self.to_json()?;
 
         std::fs::write(&path, content).with_context(|| format!("Failed to write {:?}", path))?;
 
         Ok(())
     }
 
+    /// Serialize for disk, leaving out values that only came from the
+    /// environment.
+    fn to_json(&self) -> Result<String> {
+        let mut on_disk = self.clone();
+        for (index, value) in &self.env_values {
+            let field = (ENV_FALLBACKS[*index].1)(&mut on_disk);
+            if field == value {
+                field.clear();
+            }
+        }
+        serde_json::to_string_pretty(&on_disk).context("Failed to serialize settings")
+    }
+
+    /// Serialize for `config export`. Credentials (Nexus API key, LoversLab
+    /// login, HTTP login headers) are only kept with `include_secrets`.
+    pub fn export_json(&self, include_secrets: bool) -> Result<String> {
+        let mut exported = self.clone();
+        exported.installed_modlists.clear();
+        if !include_secrets {
+            exported.nexus_api_key.clear();
+            exported.loverslab_email.clear();
+            exported.loverslab_password.clear();
+            exported.http_headers.clear();
+        }
+        exported.to_json()
+    }
+
+    /// Parse a `config export` file.
+    pub fn from_export(content: &str) -> Result<Self> {
+        serde_json::from_str(content).context("Not a CLF3 settings export")
+    }
+
+    /// Replace these settings with an imported set. Credentials the export
+    /// left out and this machine's install records are kept.
+    pub fn apply_import(&mut self, imported: Settings) {
+        let mut merged = imported;
+        if merged.nexus_api_key.is_empty() {
+            merged.nexus_api_key = std::mem::take(&mut self.nexus_api_key);
+        }
+        if merged.loverslab_email.is_empty() {
+            merged.loverslab_email = std::mem::take(&mut self.loverslab_email);
+            merged.loverslab_password = std::mem::take(&mut self.loverslab_password);
+        }
+        if merged.http_headers.is_empty() {
+            merged.http_headers = std::mem::take(&mut self.http_headers);
+        }
+        merged.installed_modlists = std::mem::take(&mut self.installed_modlists);
+        *self = merged;
+    }
+
+    /// Configured paths that don't exist on this machine, as (setting, path).
+    pub fn missing_paths(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("default_install_dir", &self.default_install_dir),
+            ("default_downloads_dir", &self.default_downloads_dir),
+            ("default_game_dir", &self.default_game_dir),
+            ("patch_cache_dir", &self.patch_cache_dir),
+            ("ttw_installer_path", &self.ttw_installer_path),
+            ("ttw_mpi_path", &self.ttw_mpi_path),
+            ("fallout3_path", &self.fallout3_path),
+            ("ttw_output_path", &self.ttw_output_path),
+            ("fluorine_path", &self.fluorine_path),
+        ]
+        .into_iter()
+        .filter(|(_, path)| non_empty_path(path).is_some_and(|p| !p.exists()))
+        .map(|(name, path)| (name, path.as_str()))
+        .collect()
+    }
+
+    /// Fill empty fields from `NEXUS_API_KEY`, `CLF3_DOWNLOADS_DIR` and
+    /// `CLF3_INSTALL_DIR`/`CLF3_OUTPUT_DIR`. Saved values always win. Returns
+    /// the variables that were used.
+    pub fn apply_env_fallbacks(&mut self) -> Vec<&'static str> {
+        self.apply_fallbacks(|var| std::env::var(var).ok())
+    }
+
+    fn apply_fallbacks(&mut self, lookup: impl Fn(&str) -> Option<String>) -> Vec<&'static str> {
+        let mut used = Vec::new();
+        for (index, &(var, field)) in ENV_FALLBACKS.iter().enumerate() {
+            let Some(value) = lookup(var).filter(|v| !v.trim().is_empty()) else {
+                continue;
+            };
+            let field = field(self);
+            if field.trim().is_empty() {
+                *field = value.clone();
+                self.env_values.push((index, value));
+                used.push(var);
+            }
+        }
+        used
+    }
+
+    /// Whether `var` supplied a value in `apply_env_fallbacks`.
+    pub fn is_from_env(&self, var: &str) -> bool {
+        self.env_values
+            .iter()
+            .any(|(index, _)| ENV_FALLBACKS[*index].0 == var)
+    }
+
+    /// Texture encoder choice for display, e.g. "CPU only", "[1] RX 7800",
+    /// "GPU only ([1] RX 7800)" or "auto".
+    pub fn gpu_label(&self) -> String {
+        let gpu = match self.gpu_index {
+            Some(idx) => format!("[{}] {}", idx, self.gpu_name),
+            None => "auto".into(),
+        };
+        match self.texture_encoder {
+            EncoderMode::Auto => gpu,
+            EncoderMode::Cpu => EncoderMode::Cpu.name().into(),
+            EncoderMode::Gpu => format!("{} ({})", EncoderMode::Gpu.name(), gpu),
+        }
+    }
+
+    /// Saved downloads directory, if set
+    pub fn default_downloads_path(&self) -> Option<PathBuf> {
+        non_empty_path(&self.default_downloads_dir)
+    }
+
+    /// Saved install directory, if set
+    pub fn default_install_path(&self) -> Option<PathBuf> {
+        non_empty_path(&self.default_install_dir)
+    }
+
+    /// Saved game directory, if set
+    pub fn default_game_path(&self) -> Option<PathBuf> {
+        non_empty_path(&self.default_game_dir)
+    }
+
     /// Check if any defaults are set
     pub fn has_defaults(&self) -> bool {
         !self.default_install_dir.is_empty()
@@ -224,6 +437,11 @@ impl Settings {
     }
 }
 
+fn non_empty_path(value: &str) -> Option<PathBuf> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| PathBuf::from(value))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -234,6 +452,21 @@ mod tests {
         assert!(settings.default_install_dir.is_empty());
         assert!(settings.nexus_api_key.is_empty());
         assert!(settings.gpu_index.is_none());
+        assert!(settings.default_game_path().is_none());
+    }
+
+    #[test]
+    fn test_default_paths_ignore_blank_values() {
+        let settings: Settings = serde_json::from_str(
+            r#"{"default_downloads_dir": "/mnt/dl", "default_install_dir": "  "}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            settings.default_downloads_path(),
+            Some(PathBuf::from("/mnt/dl"))
+        );
+        assert!(settings.default_install_path().is_none());
+        assert!(settings.default_game_path().is_none());
     }
 
     #[test]
@@ -244,29 +477,102 @@ mod tests {
             nexus_api_key: "test_key".into(),
             gpu_index: Some(0),
             gpu_name: "Test GPU".into(),
-            ttw_installer_path: String::new(),
-            ttw_mpi_path: String::new(),
-            fallout3_path: String::new(),
-            ttw_output_path: String::new(),
+            texture_encoder: EncoderMode::Gpu,
             patch_cache_dir: "/home/user/.cache/clf3/patches".into(),
-            loverslab_email: String::new(),
-            loverslab_password: String::new(),
-            browser_game_filter: String::new(),
-            browser_show_nsfw: false,
-            browser_show_unavailable: false,
-            browser_show_installed_only: false,
-            browser_last_selected_modlist: None,
-            browser_list_paths: HashMap::new(),
-            installed_modlists: HashMap::new(),
-            add_to_fluorine: false,
-            fluorine_path: String::new(),
+            browser_sort: ModlistSort::Updated,
+            browser_favorite_modlists: vec!["tuxborn".into()],
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&settings).unwrap();
         let loaded: Settings = serde_json::from_str(&json).unwrap();
 
         assert_eq!(loaded.default_install_dir, settings.default_install_dir);
+        assert_eq!(
+            loaded.browser_favorite_modlists,
+            vec!["tuxborn".to_string()]
+        );
+        assert_eq!(loaded.browser_sort, ModlistSort::Updated);
         assert_eq!(loaded.gpu_index, Some(0));
+        assert_eq!(loaded.texture_encoder, EncoderMode::Gpu);
+        assert!(json.contains(r#""texture_encoder":"gpu""#));
+    }
+
+    #[test]
+    fn test_export_import_keeps_local_secrets() {
+        let mut source = Settings {
+            nexus_api_key: "key".into(),
+            loverslab_password: "hunter2".into(),
+            default_downloads_dir: "/nonexistent/clf3-test/downloads".into(),
+            browser_favorite_modlists: vec!["tuxborn".into()],
+            ..Default::default()
+        };
+        source
+            .installed_modlists
+            .insert("tuxborn".into(), InstalledModlistRecord::default());
+
+        let exported = source.export_json(false).unwrap();
+        assert!(!exported.contains("hunter2"));
+        let imported = Settings::from_export(&exported).unwrap();
+        assert!(imported.nexus_api_key.is_empty());
+        assert!(imported.installed_modlists.is_empty());
+        assert_eq!(
+            imported.missing_paths(),
+            vec![("default_downloads_dir", "/nonexistent/clf3-test/downloads")]
+        );
+
+        let mut target = Settings {
+            nexus_api_key: "local".into(),
+            ..Default::default()
+        };
+        target.apply_import(imported);
+        assert_eq!(target.nexus_api_key, "local");
+        assert_eq!(
+            target.browser_favorite_modlists,
+            vec!["tuxborn".to_string()]
+        );
+
+        let with_secrets = Settings::from_export(&source.export_json(true).unwrap()).unwrap();
+        assert_eq!(with_secrets.nexus_api_key, "key");
+    }
+
+    #[test]
+    fn test_gpu_label_shows_encoder_mode() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.gpu_label(), "auto");
+        settings.gpu_index = Some(1);
+        settings.gpu_name = "RX 7800".into();
+        assert_eq!(settings.gpu_label(), "[1] RX 7800");
+        settings.texture_encoder = EncoderMode::Gpu;
+        assert_eq!(settings.gpu_label(), "GPU only ([1] RX 7800)");
+        settings.texture_encoder = EncoderMode::Cpu;
+        assert_eq!(settings.gpu_label(), "CPU only");
+    }
+
+    #[test]
+    fn test_env_fallbacks_fill_empty_fields_only() {
+        let mut settings = Settings {
+            default_downloads_dir: "/saved/downloads".into(),
+            ..Default::default()
+        };
+        let used = settings.apply_fallbacks(|var| match var {
+            "NEXUS_API_KEY" => Some("env_key".into()),
+            "CLF3_DOWNLOADS_DIR" => Some("/env/downloads".into()),
+            "CLF3_OUTPUT_DIR" => Some("/env/install".into()),
+            _ => None,
+        });
+        assert_eq!(used, vec!["NEXUS_API_KEY", "CLF3_OUTPUT_DIR"]);
+        assert_eq!(settings.nexus_api_key, "env_key");
+        assert_eq!(settings.default_downloads_dir, "/saved/downloads");
+        assert_eq!(settings.default_install_dir, "/env/install");
+        assert!(settings.is_from_env("NEXUS_API_KEY"));
+
+        // Env values aren't persisted; an edited value is.
+        settings.default_install_dir = "/picked/install".into();
+        let saved: Settings = serde_json::from_str(&settings.to_json().unwrap()).unwrap();
+        assert!(saved.nexus_api_key.is_empty());
+        assert_eq!(saved.default_downloads_dir, "/saved/downloads");
+        assert_eq!(saved.default_install_dir, "/picked/install");
     }
 
     #[test]
@@ -0,0 +1,128 @@
+//! BC7 encoder benchmark (`clf3 benchmark-textures`, GUI Settings)
+//!
+//! Encodes the same synthetic texture on the CPU and on every GPU wgpu
+//! reports, so users can see which device is actually fastest. Integrated
+//! GPUs and software adapters are often slower than a modern CPU.
+
+use super::gpu_encoder::{list_gpus, GpuEncoder};
+use super::processor::encode_bc7_cpu;
+use anyhow::Result;
+use rayon::prelude::*;
+use std::time::Instant;
+
+/// Edge length of the sample texture.
+const SAMPLE_SIZE: u32 = 1024;
+/// Textures encoded per device. The CPU encodes them in parallel, like the
+/// installer does.
+const SAMPLE_COUNT: usize = 8;
+
+/// Throughput of one encoding device.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BenchResult {
+    /// "CPU (N threads)" or the GPU name.
+    pub device: String,
+    /// wgpu adapter index (as used by `select-gpu`); None for the CPU.
+    pub adapter_index: Option<usize>,
+    /// Megapixels encoded per second; 0 if the device failed.
+    pub megapixels_per_sec: f64,
+    pub error: Option<String>,
+}
+
+/// Deterministic RGBA pattern with gradients and noise, so block encoders
+/// can't take shortcuts on flat colour.
+fn sample_rgba(size: u32) -> Vec<u8> {
+    let mut data = Vec::with_capacity((size * size * 4) as usize);
+    let mut seed = 0x2545_f491_u32;
+    for y in 0..size {
+        for x in 0..size {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            let noise = (seed & 0x3f) as u8;
+            data.push(((x * 255 / size) as u8).wrapping_add(noise));
+            data.push(((y * 255 / size) as u8).wrapping_add(noise));
+            data.push((((x ^ y) & 0xff) as u8).wrapping_add(noise));
+            data.push(255 - noise);
+        }
+    }
+    data
+}
+
+fn megapixels_per_sec(secs: f64) -> f64 {
+    let pixels = SAMPLE_COUNT as f64 * (SAMPLE_SIZE as f64 * SAMPLE_SIZE as f64);
+    pixels / 1_000_000.0 / secs.max(1e-9)
+}
+
+fn bench_cpu(sample: &[u8]) -> Result<f64> {
+    let start = Instant::now();
+    (0..SAMPLE_COUNT)
+        .into_par_iter()
+        .try_for_each(|_| encode_bc7_cpu(sample.to_vec(), SAMPLE_SIZE, SAMPLE_SIZE).map(drop))?;
+    Ok(megapixels_per_sec(start.elapsed().as_secs_f64()))
+}
+
+fn bench_gpu(adapter_index: usize, sample: &[u8]) -> Result<f64> {
+    let mut encoder = GpuEncoder::with_gpu_index(Some(adapter_index))?;
+    // The first encode compiles shaders and allocates buffers.
+    encoder.encode_bc7(sample, SAMPLE_SIZE, SAMPLE_SIZE)?;
+    let start = Instant::now();
+    for _ in 0..SAMPLE_COUNT {
+        encoder.encode_bc7(sample, SAMPLE_SIZE, SAMPLE_SIZE)?;
+    }
+    Ok(megapixels_per_sec(start.elapsed().as_secs_f64()))
+}
+
+fn result(device: String, adapter_index: Option<usize>, outcome: Result<f64>) -> BenchResult {
+    match outcome {
+        Ok(mps) => BenchResult {
+            device,
+            adapter_index,
+            megapixels_per_sec: mps,
+            error: None,
+        },
+        Err(e) => BenchResult {
+            device,
+            adapter_index,
+            megapixels_per_sec: 0.0,
+            error: Some(format!("{:#}", e)),
+        },
+    }
+}
+
+/// Benchmark the CPU and every GPU, fastest first. Takes a few seconds.
+pub fn run_benchmark() -> Vec<BenchResult> {
+    let sample = sample_rgba(SAMPLE_SIZE);
+    let mut results = vec![result(
+        format!("CPU ({} threads)", rayon::current_num_threads()),
+        None,
+        bench_cpu(&sample),
+    )];
+    for gpu in list_gpus() {
+        let device = format!("{} ({}, {})", gpu.name, gpu.backend, gpu.device_type);
+        let outcome = std::panic::catch_unwind(|| bench_gpu(gpu.adapter_index, &sample))
+            .unwrap_or_else(|_| Err(anyhow::anyhow!("GPU driver panicked")));
+        results.push(result(device, Some(gpu.adapter_index), outcome));
+    }
+    results.sort_by(|a, b| b.megapixels_per_sec.total_cmp(&a.megapixels_per_sec));
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_is_rgba_and_not_flat() {
+        let sample = sample_rgba(16);
+        assert_eq!(sample.len(), 16 * 16 * 4);
+        assert_ne!(sample[0..4], sample[60..64]);
+        assert_eq!(sample, sample_rgba(16));
+    }
+
+    #[test]
+    fn cpu_encodes_sample_to_bc7_blocks() {
+        let encoded = encode_bc7_cpu(sample_rgba(16), 16, 16).unwrap();
+        // BC7: 16 bytes per 4x4 block
+        assert_eq!(encoded.len(), (16 / 4) * (16 / 4) * 16);
+    }
+}
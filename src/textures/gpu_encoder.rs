@@ -109,10 +109,13 @@ impl GpuEncoder {
             }
             (idx, &adapters[idx])
         } else {
-            // Auto-select: prefer discrete GPU, then Vulkan backend
+            // Auto-select: prefer discrete GPU, then Vulkan backend. Software
+            // rasterizers (llvmpipe on headless machines) are slower than the
+            // CPU encoder, so they only count when picked explicitly.
             adapters
                 .iter()
                 .enumerate()
+                .filter(|(_, a)| a.get_info().device_type != wgpu::DeviceType::Cpu)
                 .max_by_key(|(_, a)| {
                     let info = a.get_info();
                     let mut score = 0i32;
@@ -124,7 +127,7 @@ impl GpuEncoder {
                     }
                     score
                 })
-                .unwrap()
+                .context("Only software renderers found, no hardware GPU")?
         };
 
         let adapter_info = adapter.get_info();
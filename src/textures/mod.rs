@@ -7,12 +7,14 @@
 #![allow(dead_code)]
 #![allow(unused_imports)]
 
+mod benchmark;
 mod gpu_encoder;
 mod processor;
 
+pub use benchmark::{run_benchmark, BenchResult};
 pub use gpu_encoder::{is_gpu_available, list_gpus, GpuEncoder, GpuInfo};
 pub use processor::{
-    estimate_dds_size, init_gpu, process_texture, process_texture_batch,
-    process_texture_with_fallback, resize_texture, OutputFormat, ProcessedTexture, TextureInfo,
-    TextureJob,
+    estimate_dds_size, gpu_encoder_active, init_gpu, process_texture, process_texture_batch,
+    process_texture_with_fallback, resize_texture, set_encoder_mode, set_gpu_index, EncoderMode,
+    OutputFormat, ProcessedTexture, TextureInfo, TextureJob,
 };
@@ -10,7 +10,7 @@ use image_dds::{ddsfile::Dds, ImageFormat, Mipmaps, Quality, SurfaceRgba8};
 use rayon::prelude::*;
 use std::io::Cursor;
 use std::path::Path;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use tracing::{debug, info, warn};
 
@@ -197,12 +197,94 @@ fn decode_with_directxtex(input_data: &[u8]) -> Result<RgbaImage> {
 static GPU_ENCODER: std::sync::OnceLock<Arc<Mutex<Option<GpuEncoder>>>> =
     std::sync::OnceLock::new();
 
+/// Adapter index the user picked (`usize::MAX` = auto-select).
+static PREFERRED_GPU: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+/// Choose the GPU adapter used by `init_gpu` (None = auto-select). Must be
+/// called before the encoder is first initialized to take effect.
+pub fn set_gpu_index(index: Option<usize>) {
+    PREFERRED_GPU.store(index.unwrap_or(usize::MAX), Ordering::Relaxed);
+}
+
+/// Which device encodes BC7 textures.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EncoderMode {
+    /// GPU when one works, CPU otherwise.
+    #[default]
+    Auto,
+    /// Always the CPU, even if a GPU is present.
+    Cpu,
+    /// Always a GPU; the install refuses to start without a usable one.
+    Gpu,
+}
+
+impl EncoderMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EncoderMode::Auto => "auto",
+            EncoderMode::Cpu => "CPU only",
+            EncoderMode::Gpu => "GPU only",
+        }
+    }
+}
+
+/// Current `EncoderMode` as its discriminant.
+static ENCODER_MODE: AtomicU8 = AtomicU8::new(EncoderMode::Auto as u8);
+
+/// Choose the encoding device (`select-gpu cpu`, the GUI setting). Like
+/// `set_gpu_index`, only affects an encoder not yet initialized.
+pub fn set_encoder_mode(mode: EncoderMode) {
+    ENCODER_MODE.store(mode as u8, Ordering::Relaxed);
+}
+
+fn encoder_mode() -> EncoderMode {
+    match ENCODER_MODE.load(Ordering::Relaxed) {
+        m if m == EncoderMode::Cpu as u8 => EncoderMode::Cpu,
+        m if m == EncoderMode::Gpu as u8 => EncoderMode::Gpu,
+        _ => EncoderMode::Auto,
+    }
+}
+
+/// Whether BC7 encoding currently runs on a GPU. False before `init_gpu`,
+/// in CPU-only mode, and when no usable GPU was found.
+pub fn gpu_encoder_active() -> bool {
+    get_gpu_encoder().is_some_and(|enc| {
+        enc.lock()
+            .map(|guard| guard.as_ref().is_some_and(|e| !e.is_device_lost()))
+            .unwrap_or(false)
+    })
+}
+
+/// Create an encoder on the preferred adapter, falling back to auto-select
+/// if that adapter is gone (e.g. a saved index from another machine).
+fn create_gpu_encoder() -> Result<GpuEncoder> {
+    let preferred = PREFERRED_GPU.load(Ordering::Relaxed);
+    if preferred == usize::MAX {
+        return GpuEncoder::new();
+    }
+    GpuEncoder::with_gpu_index(Some(preferred)).or_else(|e| {
+        warn!(
+            "Selected GPU {} unavailable ({}); auto-selecting",
+            preferred, e
+        );
+        GpuEncoder::new()
+    })
+}
+
 /// Initialize the global GPU encoder
 pub fn init_gpu() -> Result<()> {
     let encoder = GPU_ENCODER.get_or_init(|| Arc::new(Mutex::new(None)));
     let mut lock = encoder.lock().expect("GPU encoder lock poisoned");
+    if lock.is_none() && encoder_mode() == EncoderMode::Cpu {
+        return Ok(());
+    }
     if lock.is_none() {
-        match GpuEncoder::new() {
+        // Broken drivers can panic inside wgpu instead of returning an error;
+        // either way the CPU path takes over.
+        let created = std::panic::catch_unwind(create_gpu_encoder)
+            .unwrap_or_else(|_| Err(anyhow!("GPU driver panicked during initialization")));
+        match created {
             Ok(e) => {
                 info!(
                     "GPU encoder initialized: {} ({})",
@@ -212,7 +294,8 @@ pub fn init_gpu() -> Result<()> {
             }
             Err(e) => {
                 warn!(
-                    "GPU encoder not available: {}. BC7 will use CPU fallback.",
+                    "No usable GPU ({:#}). BC7 textures will be encoded on the CPU, \
+                     which is much slower.",
                     e
                 );
             }
@@ -235,7 +318,7 @@ fn try_reinit_gpu() -> bool {
             let should_reinit = guard.as_ref().is_some_and(|e| e.is_device_lost());
             if should_reinit {
                 info!("Attempting GPU encoder re-initialization after device loss...");
-                match GpuEncoder::new() {
+                match create_gpu_encoder() {
                     Ok(new_enc) => {
                         info!("GPU encoder re-initialized: {}", new_enc.gpu_info);
                         *guard = Some(new_enc);
@@ -450,18 +533,23 @@ fn create_bc7_dds_with_mips(
     Ok(output)
 }
 
+/// Encode one RGBA8 surface (no mips) to BC7 blocks on the CPU.
+pub(super) fn encode_bc7_cpu(rgba: Vec<u8>, width: u32, height: u32) -> Result<Vec<u8>> {
+    let rgba_image = RgbaImage::from_raw(width, height, rgba)
+        .context("Failed to create RGBA image for CPU BC7")?;
+    let surface = SurfaceRgba8::from_image(&rgba_image);
+    let encoded = surface
+        .encode(ImageFormat::BC7RgbaUnorm, Quality::Fast, Mipmaps::Disabled)
+        .context("CPU BC7 encode failed")?;
+    Ok(encoded.data.to_vec())
+}
+
 /// CPU fallback for a prepared BC7 texture (already has RGBA mip data).
 /// Used when GPU batch encoding fails (device lost, timeout, etc.)
 fn encode_prepared_bc7_cpu(texture: &PreparedBc7Texture) -> Result<ProcessedTexture> {
     let mut mip_data: Vec<Vec<u8>> = Vec::with_capacity(texture.mip_images.len());
     for (rgba, w, h) in &texture.mip_images {
-        let rgba_image = RgbaImage::from_raw(*w, *h, rgba.clone())
-            .context("Failed to create RGBA image for CPU BC7")?;
-        let surface = SurfaceRgba8::from_image(&rgba_image);
-        let encoded = surface
-            .encode(ImageFormat::BC7RgbaUnorm, Quality::Fast, Mipmaps::Disabled)
-            .context("CPU BC7 encode failed")?;
-        mip_data.push(encoded.data.to_vec());
+        mip_data.push(encode_bc7_cpu(rgba.clone(), *w, *h)?);
     }
     create_bc7_dds_with_mips(mip_data, texture.target_width, texture.target_height).map(|data| {
         ProcessedTexture {
@@ -21,7 +21,7 @@ use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use tracing::{info, warn};
 
-use crate::game_finder::{detect_all_games, GameScanResult};
+use crate::game_finder::{cached_games, GameScanResult};
 
 /// GitHub repo for TTW Linux Installer
 const TTW_INSTALLER_REPO: &str = "SulfurNitride/TTW_Linux_Installer";
@@ -102,7 +102,7 @@ pub fn precheck_ttw(modlist: &crate::modlist::Modlist) -> TtwPrecheck {
     }
 
     // Scan for installed games
-    let games = detect_all_games();
+    let games = cached_games();
     let fo3_detected = find_fallout3(&games);
     let fnv_detected = find_fallout_nv(&games);
 
@@ -480,10 +480,8 @@ where
 
 /// Get the path where we cache the TTW installer
 fn ttw_installer_cache_path() -> PathBuf {
-    dirs::data_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("clf3")
-        .join("bin")
+    crate::paths::bin_dir()
+        .unwrap_or_else(|| PathBuf::from("./clf3/bin"))
         .join(TTW_INSTALLER_BINARY)
 }
 
@@ -547,7 +545,7 @@ fn download_ttw_installer() -> Result<PathBuf> {
     info!("Fetching release info from: {}", api_url);
 
     let client = reqwest::blocking::Client::builder()
-        .user_agent("clf3")
+        .user_agent(crate::downloaders::user_agent())
         .build()
         .context("Failed to create HTTP client")?;
 
@@ -726,7 +724,7 @@ pub fn check_ttw_installer_update() -> Result<Option<String>> {
     );
 
     let client = reqwest::blocking::Client::builder()
-        .user_agent("clf3")
+        .user_agent(crate::downloaders::user_agent())
         .build()?;
 
     let response = client.get(&api_url).send()?;